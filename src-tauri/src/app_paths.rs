@@ -0,0 +1,418 @@
+//! Central resolution of every on-disk location used by Zenith.
+//! Supports a `ZENITH_DATA_DIR` override, a portable mode (data next to the
+//! binary) and moving existing data from the legacy locations to a custom root.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::command;
+
+/// Environment variable that overrides the data directory
+pub const DATA_DIR_ENV: &str = "ZENITH_DATA_DIR";
+
+/// Environment variable that enables portable mode (`1`/`true`)
+pub const PORTABLE_ENV: &str = "ZENITH_PORTABLE";
+
+/// Environment variable that requests moving legacy data on startup (`1`/`true`)
+pub const MIGRATE_ENV: &str = "ZENITH_MIGRATE_DATA";
+
+/// Marker files next to the executable that enable portable mode
+const PORTABLE_MARKERS: [&str; 2] = ["portable", "portable.txt"];
+
+/// Marker file in the data directory that requests a migration on next launch
+const MIGRATION_MARKER: &str = ".migrate-legacy-data";
+
+const DATABASE_FILE: &str = "games.db";
+
+lazy_static::lazy_static! {
+    pub static ref APP_PATHS: AppPaths = AppPaths::resolve();
+    /// Outcome of the migration run at startup, for the loading screen
+    static ref MIGRATION_OUTCOME: Mutex<Option<Result<DataMigrationReport, String>>> = Mutex::new(None);
+}
+
+/// Shorthand for the process-wide resolved paths
+pub fn app_paths() -> &'static AppPaths {
+    &APP_PATHS
+}
+
+/// Where the data directory came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDirMode {
+    /// Platform data directories (the historical layout)
+    Default,
+    /// `ZENITH_DATA_DIR` environment variable
+    EnvOverride,
+    /// `data/` folder next to the executable
+    Portable,
+}
+
+#[derive(Debug, Clone)]
+pub struct AppPaths {
+    mode: DataDirMode,
+    root: PathBuf,
+}
+
+/// Path information exposed to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppPathsInfo {
+    pub mode: DataDirMode,
+    pub data_dir: String,
+    pub cache_dir: String,
+    pub database_path: String,
+    pub profile_dir: String,
+    pub legacy_data_found: bool,
+    pub migration_pending: bool,
+}
+
+/// Outcome of moving legacy data into a custom data directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataMigrationReport {
+    pub files_moved: usize,
+    pub profile_paths_updated: usize,
+    pub skipped_reason: Option<String>,
+}
+
+impl AppPaths {
+    /// Resolve paths from the environment: `ZENITH_DATA_DIR` > portable mode > default
+    pub fn resolve() -> Self {
+        if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|v| !v.is_empty()) {
+            return Self::with_root(DataDirMode::EnvOverride, absolutize(PathBuf::from(dir)));
+        }
+
+        if let Some(exe_dir) = executable_dir() {
+            if env_flag(PORTABLE_ENV) || PORTABLE_MARKERS.iter().any(|m| exe_dir.join(m).is_file()) {
+                return Self::with_root(DataDirMode::Portable, exe_dir.join("data"));
+            }
+        }
+
+        Self::with_root(DataDirMode::Default, legacy_data_dir())
+    }
+
+    pub fn with_root(mode: DataDirMode, root: PathBuf) -> Self {
+        Self { mode, root }
+    }
+
+    /// Directory holding the SQLite database and legacy JSON cache files
+    pub fn cache_dir(&self) -> PathBuf {
+        self.root.join("cache")
+    }
+
+    /// Main SQLite database (cache, library, profile, history)
+    pub fn database_path(&self) -> PathBuf {
+        self.cache_dir().join(DATABASE_FILE)
+    }
+
+    /// Directory holding profile images
+    pub fn profile_dir(&self) -> PathBuf {
+        match self.mode {
+            // Profile images historically lived outside the launcher data folder
+            DataDirMode::Default => legacy_profile_dir(),
+            _ => self.root.join("profile"),
+        }
+    }
+
+    /// Create the cache directory and return the database path
+    pub fn ensure_database_path(&self) -> Result<PathBuf> {
+        let cache_dir = self.cache_dir();
+        fs::create_dir_all(&cache_dir)
+            .with_context(|| format!("Failed to create data directory {}", cache_dir.display()))?;
+        Ok(cache_dir.join(DATABASE_FILE))
+    }
+
+    pub fn info(&self) -> AppPathsInfo {
+        AppPathsInfo {
+            mode: self.mode,
+            data_dir: self.root.to_string_lossy().to_string(),
+            cache_dir: self.cache_dir().to_string_lossy().to_string(),
+            database_path: self.database_path().to_string_lossy().to_string(),
+            profile_dir: self.profile_dir().to_string_lossy().to_string(),
+            legacy_data_found: self.mode != DataDirMode::Default
+                && legacy_data_dir().join("cache").join(DATABASE_FILE).exists(),
+            migration_pending: self.migration_requested(),
+        }
+    }
+
+    /// Whether a migration was requested via env or the on-disk marker
+    pub fn migration_requested(&self) -> bool {
+        self.mode != DataDirMode::Default
+            && (env_flag(MIGRATE_ENV) || self.root.join(MIGRATION_MARKER).exists())
+    }
+
+    /// Request a migration on the next launch (before any database is opened)
+    pub fn schedule_migration(&self) -> Result<()> {
+        if self.mode == DataDirMode::Default {
+            anyhow::bail!("Data is already stored in the default location");
+        }
+        fs::create_dir_all(&self.root)?;
+        fs::write(self.root.join(MIGRATION_MARKER), b"")?;
+        Ok(())
+    }
+
+    /// Run a requested migration. Must be called before any database connection is opened.
+    /// The outcome is kept for `last_migration_outcome`.
+    pub fn run_pending_migration(&self) -> Option<Result<DataMigrationReport, String>> {
+        if !self.migration_requested() {
+            return None;
+        }
+
+        let outcome = self.run_migration(&legacy_data_dir().join("cache"), &legacy_profile_dir());
+        *MIGRATION_OUTCOME.lock().unwrap_or_else(|e| e.into_inner()) = Some(outcome.clone());
+        Some(outcome)
+    }
+
+    /// Migrate and clear the request marker. A failed migration keeps the marker so it is
+    /// retried on the next launch.
+    fn run_migration(&self, legacy_cache_dir: &Path, legacy_profile_dir: &Path) -> Result<DataMigrationReport, String> {
+        match self.migrate_from(legacy_cache_dir, legacy_profile_dir) {
+            Ok(report) => {
+                let _ = fs::remove_file(self.root.join(MIGRATION_MARKER));
+                match &report.skipped_reason {
+                    Some(reason) => println!("⏭️ Data migration skipped: {}", reason),
+                    None => println!(
+                        "📦 Moved {} files to {} ({} profile paths updated)",
+                        report.files_moved,
+                        self.root.display(),
+                        report.profile_paths_updated
+                    ),
+                }
+                Ok(report)
+            }
+            Err(e) => {
+                eprintln!("❌ Data migration failed, retrying on next launch: {:#}", e);
+                Err(format!("{:#}", e))
+            }
+        }
+    }
+
+    /// Move an existing cache directory and profile directory into this data directory
+    pub fn migrate_from(&self, legacy_cache_dir: &Path, legacy_profile_dir: &Path) -> Result<DataMigrationReport> {
+        let mut report = DataMigrationReport::default();
+        let target_cache_dir = self.cache_dir();
+        let target_profile_dir = self.profile_dir();
+
+        if legacy_cache_dir == target_cache_dir {
+            report.skipped_reason = Some("source and target are the same".to_string());
+            return Ok(report);
+        }
+        if !legacy_cache_dir.join(DATABASE_FILE).exists() {
+            report.skipped_reason = Some("no existing data found".to_string());
+            return Ok(report);
+        }
+        if target_cache_dir.join(DATABASE_FILE).exists() {
+            report.skipped_reason = Some(format!(
+                "{} already contains a database",
+                target_cache_dir.display()
+            ));
+            return Ok(report);
+        }
+
+        // Fold the WAL back into the main file so only games.db carries data
+        if let Ok(conn) = Connection::open(legacy_cache_dir.join(DATABASE_FILE)) {
+            let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
+        }
+
+        report.files_moved += move_dir_contents(legacy_cache_dir, &target_cache_dir)?;
+        if legacy_profile_dir.exists() && legacy_profile_dir != target_profile_dir {
+            report.files_moved += move_dir_contents(legacy_profile_dir, &target_profile_dir)?;
+        }
+
+        // Profile image paths are stored as absolute paths
+        let conn = Connection::open(target_cache_dir.join(DATABASE_FILE))?;
+        let has_profile: bool = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'user_profile'",
+            [],
+            |row| Ok(row.get::<_, i64>(0)? > 0),
+        )?;
+        if has_profile {
+            let old_prefix = legacy_profile_dir.to_string_lossy().to_string();
+            let new_prefix = target_profile_dir.to_string_lossy().to_string();
+            for column in ["banner_path", "avatar_path"] {
+                report.profile_paths_updated += conn.execute(
+                    &format!(
+                        "UPDATE user_profile SET {col} = ?2 || substr({col}, length(?1) + 1)
+                         WHERE substr({col}, 1, length(?1)) = ?1",
+                        col = column
+                    ),
+                    params![old_prefix, new_prefix],
+                )?;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// What the migration run at startup did, if one was requested
+pub fn last_migration_outcome() -> Option<Result<DataMigrationReport, String>> {
+    MIGRATION_OUTCOME.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Historical data root: `<platform data dir>/zenith-launcher`
+fn legacy_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("zenith-launcher")
+}
+
+/// Historical profile image directory: `<APPDATA | Application Support | ~/.local/share>/zenith/profile`
+fn legacy_profile_dir() -> PathBuf {
+    let app_dir = if cfg!(target_os = "windows") {
+        std::env::var("APPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(r"C:\Users\Default\AppData\Roaming"))
+    } else if cfg!(target_os = "macos") {
+        dirs::home_dir()
+            .map(|home| home.join("Library").join("Application Support"))
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+    } else {
+        dirs::home_dir()
+            .map(|home| home.join(".local").join("share"))
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+    };
+
+    app_dir.join("zenith").join("profile")
+}
+
+fn executable_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
+
+fn absolutize(path: PathBuf) -> PathBuf {
+    if path.is_absolute() {
+        path
+    } else {
+        std::env::current_dir().map(|cwd| cwd.join(&path)).unwrap_or(path)
+    }
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Move every entry of `from` into `to`, falling back to copy + delete across filesystems
+fn move_dir_contents(from: &Path, to: &Path) -> Result<usize> {
+    fs::create_dir_all(to)?;
+    let mut moved = 0;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+
+        if target.exists() {
+            println!("⚠️ Not overwriting existing {}", target.display());
+            continue;
+        }
+
+        if fs::rename(&source, &target).is_ok() {
+            moved += 1;
+            continue;
+        }
+
+        if source.is_dir() {
+            moved += move_dir_contents(&source, &target)?;
+            fs::remove_dir_all(&source)?;
+        } else {
+            fs::copy(&source, &target)
+                .with_context(|| format!("Failed to copy {}", source.display()))?;
+            fs::remove_file(&source)?;
+            moved += 1;
+        }
+    }
+
+    Ok(moved)
+}
+
+/// Get the resolved data locations
+#[command]
+pub async fn get_app_paths() -> Result<AppPathsInfo, String> {
+    Ok(app_paths().info())
+}
+
+/// Move existing data into the configured data directory on the next launch
+#[command]
+pub async fn schedule_data_migration() -> Result<String, String> {
+    app_paths()
+        .schedule_migration()
+        .map_err(|e| format!("Failed to schedule data migration: {}", e))?;
+    Ok("Data will be moved on the next launch".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_moves_database_and_rewrites_profile_paths() {
+        let legacy = tempfile::tempdir().unwrap();
+        let legacy_cache = legacy.path().join("cache");
+        let legacy_profile = legacy.path().join("profile");
+        fs::create_dir_all(&legacy_cache).unwrap();
+        fs::create_dir_all(&legacy_profile).unwrap();
+        fs::write(legacy_profile.join("avatar.jpg"), b"jpg").unwrap();
+
+        let avatar = legacy_profile.join("avatar.jpg").to_string_lossy().to_string();
+        let conn = Connection::open(legacy_cache.join(DATABASE_FILE)).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE user_profile (id INTEGER PRIMARY KEY, banner_path TEXT, avatar_path TEXT);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO user_profile (id, banner_path, avatar_path) VALUES (1, NULL, ?1)",
+            params![avatar],
+        )
+        .unwrap();
+        drop(conn);
+
+        let root = tempfile::tempdir().unwrap();
+        let paths = AppPaths::with_root(DataDirMode::EnvOverride, root.path().to_path_buf());
+        let report = paths.migrate_from(&legacy_cache, &legacy_profile).unwrap();
+
+        assert!(report.skipped_reason.is_none());
+        assert!(paths.database_path().exists());
+        assert!(paths.profile_dir().join("avatar.jpg").exists());
+        assert!(!legacy_cache.join(DATABASE_FILE).exists());
+        assert_eq!(report.profile_paths_updated, 1);
+
+        let conn = Connection::open(paths.database_path()).unwrap();
+        let stored: String = conn
+            .query_row("SELECT avatar_path FROM user_profile WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(PathBuf::from(stored), paths.profile_dir().join("avatar.jpg"));
+
+        // A second run never overwrites the migrated database
+        let again = paths.migrate_from(&legacy_cache, &legacy_profile).unwrap();
+        assert!(again.skipped_reason.is_some());
+    }
+
+    #[test]
+    fn test_failed_migration_is_retried() {
+        let legacy = tempfile::tempdir().unwrap();
+        let legacy_cache = legacy.path().join("cache");
+        fs::create_dir_all(&legacy_cache).unwrap();
+        Connection::open(legacy_cache.join(DATABASE_FILE)).unwrap();
+
+        let root = tempfile::tempdir().unwrap();
+        let paths = AppPaths::with_root(DataDirMode::EnvOverride, root.path().to_path_buf());
+        paths.schedule_migration().unwrap();
+        // A file where the cache directory should go makes the move fail
+        fs::write(paths.cache_dir(), b"").unwrap();
+
+        assert!(paths.run_migration(&legacy_cache, &legacy.path().join("profile")).is_err());
+        assert!(paths.migration_requested());
+        assert!(legacy_cache.join(DATABASE_FILE).exists());
+
+        fs::remove_file(paths.cache_dir()).unwrap();
+        let report = paths.run_migration(&legacy_cache, &legacy.path().join("profile")).unwrap();
+        assert_eq!(report.files_moved, 1);
+        assert!(!paths.migration_requested());
+    }
+}
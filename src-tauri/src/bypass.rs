@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
 use crate::steam_utils::{find_game_folder_from_acf, find_steam_installation_path};
+#[cfg(target_os = "windows")]
+use crate::steam_utils::find_app_id_for_executable;
 use crate::download::{DownloadManagerState};
 use crate::download::types::{DownloadRequest, DownloadType, DownloadStatus};
 use crate::database::history_commands::{add_download_to_history, update_download_history_completion};
+#[cfg(target_os = "windows")]
+use crate::process_monitor::track_game_process;
+use crate::exe_detection::{is_launchable, rank_executables, RankedExecutable};
 use std::fs::{self, File};
 use std::io;
 use std::path::Path;
@@ -34,6 +39,24 @@ pub struct GameExecutable {
     name: String,
     path: String,
     size_mb: f64,
+    score: i32,
+    reasons: Vec<String>,
+    product_name: Option<String>,
+    file_description: Option<String>,
+}
+
+impl From<RankedExecutable> for GameExecutable {
+    fn from(ranked: RankedExecutable) -> Self {
+        Self {
+            name: ranked.name,
+            path: ranked.path,
+            size_mb: ranked.size_mb,
+            score: ranked.score,
+            reasons: ranked.reasons,
+            product_name: ranked.metadata.product_name,
+            file_description: ranked.metadata.file_description,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -241,46 +264,18 @@ pub async fn get_game_installation_info(app_id: String) -> Result<GameInstallati
 }
 
 #[command]
-pub async fn get_game_executables(game_path: String) -> Result<Vec<GameExecutable>, String> {
+pub async fn get_game_executables(game_path: String, game_name: Option<String>) -> Result<Vec<GameExecutable>, String> {
     println!("🔍 Scanning for executable files in: {}", game_path);
 
     if !Path::new(&game_path).exists() {
         return Err("Game folder does not exist".to_string());
     }
 
-    let mut executables = Vec::new();
-
-    for entry in WalkDir::new(&game_path).max_depth(2) {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if extension == "exe" {
-                        if let Some(file_name) = path.file_name() {
-                            let file_name_str = file_name.to_string_lossy().to_string();
-                            let file_size = path.metadata().map(|m| m.len()).unwrap_or(0);
-                            let size_mb = file_size as f64 / 1_048_576.0;
-
-                            println!("📄 Found .exe: {} ({:.1} MB)", file_name_str, size_mb);
-
-                            executables.push(GameExecutable {
-                                name: file_name_str,
-                                path: path.to_string_lossy().to_string(),
-                                size_mb,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // Sort alphabetically (no priority)
-    executables.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    let executables = scan_executables(&game_path, game_name.as_deref(), None).await?;
 
     println!("🎯 Found {} executable files:", executables.len());
     for (i, exe) in executables.iter().enumerate() {
-        println!("  📄 {}: {} ({:.1} MB)", i + 1, exe.name, exe.size_mb);
+        println!("  📄 {}: {} ({:.1} MB, score {})", i + 1, exe.name, exe.size_mb, exe.score);
     }
 
     Ok(executables)
@@ -320,22 +315,25 @@ pub async fn get_bypass_notes(game_path: String) -> Result<BypassNotes, String>
                 
                 // If we found an exe in note, create exe_list with only that exe
                 if let Some(ref exe_name) = recommended_exe {
-                    let exe_path = format!("{}/{}", game_path, exe_name);
-                    if Path::new(&exe_path).exists() {
-                        if let Ok(metadata) = fs::metadata(&exe_path) {
-                            let size_mb = metadata.len() as f64 / 1_048_576.0;
-                            exe_list.push(GameExecutable {
-                                name: exe_name.clone(),
-                                path: exe_path,
-                                size_mb,
-                            });
-                            println!("🎯 Using ONLY exe from note: {} ({:.1} MB)", exe_name, size_mb);
-                        } else {
-                            println!("❌ Exe from note exists but cannot read metadata: {}", exe_name);
+                    // Free-text names are matched case-insensitively against the files actually present
+                    let file_name = Path::new(exe_name)
+                        .file_name()
+                        .map(|f| f.to_string_lossy().to_string())
+                        .unwrap_or_else(|| exe_name.clone());
+                    let matched = scan_executables(&game_path, None, Some(&file_name))
+                        .await?
+                        .into_iter()
+                        .find(|exe| exe.name.eq_ignore_ascii_case(&file_name));
+
+                    match matched {
+                        Some(exe) => {
+                            println!("🎯 Using ONLY exe from note: {} ({:.1} MB)", exe.path, exe.size_mb);
+                            exe_list.push(exe);
+                        }
+                        None => {
+                            println!("❌ Exe from note not found in game folder: {}", exe_name);
+                            // Don't fallback to scanning - if note specifies an exe, only use that
                         }
-                    } else {
-                        println!("❌ Exe from note not found at path: {}", exe_path);
-                        // Don't fallback to scanning - if note specifies an exe, only use that
                     }
                 } else {
                     println!("⚠️ No exe extracted from note content, will not show any exe");
@@ -352,7 +350,7 @@ pub async fn get_bypass_notes(game_path: String) -> Result<BypassNotes, String>
             Err(e) => {
                 println!("❌ Failed to read note.txt: {}", e);
                 // Fallback to scanning all exe
-                exe_list = scan_executables(&game_path).await?;
+                exe_list = scan_executables(&game_path, None, None).await?;
                 Ok(BypassNotes {
                     has_notes: false,
                     instructions: "".to_string(),
@@ -364,7 +362,7 @@ pub async fn get_bypass_notes(game_path: String) -> Result<BypassNotes, String>
     } else {
         println!("📝 No note.txt found, scanning for executables...");
         // Fallback to scanning all exe
-        exe_list = scan_executables(&game_path).await?;
+        exe_list = scan_executables(&game_path, None, None).await?;
         Ok(BypassNotes {
             has_notes: false,
             instructions: "".to_string(), 
@@ -374,36 +372,31 @@ pub async fn get_bypass_notes(game_path: String) -> Result<BypassNotes, String>
     }
 }
 
-async fn scan_executables(game_path: &str) -> Result<Vec<GameExecutable>, String> {
-    let mut executables = Vec::new();
-
-    for entry in WalkDir::new(game_path).max_depth(2) {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if extension == "exe" {
-                        if let Some(file_name) = path.file_name() {
-                            let file_name_str = file_name.to_string_lossy().to_string();
-                            let file_size = path.metadata().map(|m| m.len()).unwrap_or(0);
-                            let size_mb = file_size as f64 / 1_048_576.0;
-
-                            executables.push(GameExecutable {
-                                name: file_name_str,
-                                path: path.to_string_lossy().to_string(),
-                                size_mb,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-    }
+/// Ranked executables in a game folder, best match first.
+/// Without a game name, the install folder name is used for name matching.
+async fn scan_executables(
+    game_path: &str,
+    game_name: Option<&str>,
+    hint: Option<&str>,
+) -> Result<Vec<GameExecutable>, String> {
+    let game_name = game_name
+        .map(|n| n.to_string())
+        .or_else(|| {
+            Path::new(game_path)
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+        })
+        .unwrap_or_default();
+    let game_path = game_path.to_string();
+    let hint = hint.map(|h| h.to_string());
 
-    // Sort alphabetically (no priority)
-    executables.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    
-    Ok(executables)
+    let ranked = tokio::task::spawn_blocking(move || {
+        rank_executables(&game_path, &game_name, hint.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Executable scan failed: {}", e))?;
+
+    Ok(ranked.into_iter().map(GameExecutable::from).collect())
 }
 
 fn extract_recommended_exe(content: &str) -> Option<String> {
@@ -465,15 +458,21 @@ fn extract_recommended_exe(content: &str) -> Option<String> {
 pub async fn confirm_and_launch_game(
     executable_path: String,
     game_name: String,
+    app_id: Option<String>,
+    app: tauri::AppHandle,
 ) -> Result<String, String> {
     println!("🎮 User confirmed to launch game: {}", game_name);
     println!("📁 Executable path: {}", executable_path);
 
-    launch_game_executable(executable_path).await
+    launch_game_executable(executable_path, app_id, app).await
 }
 
 #[command]
-pub async fn launch_game_executable(executable_path: String) -> Result<String, String> {
+pub async fn launch_game_executable(
+    executable_path: String,
+    app_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
     println!("🚀 Attempting to launch game: {}", executable_path);
 
     // Validate file exists
@@ -484,8 +483,8 @@ pub async fn launch_game_executable(executable_path: String) -> Result<String, S
     }
 
     // Validate it's an .exe file
-    if !executable_path.to_lowercase().ends_with(".exe") {
-        let error_msg = format!("File is not an executable (.exe): {}", executable_path);
+    if !is_launchable(Path::new(&executable_path)) {
+        let error_msg = format!("File is not a launchable executable: {}", executable_path);
         println!("❌ {}", error_msg);
         return Err(error_msg);
     }
@@ -521,7 +520,12 @@ pub async fn launch_game_executable(executable_path: String) -> Result<String, S
                 println!("   Path: {}", executable_path);
                 println!("   Working Dir: {:?}", Path::new(&executable_path).parent());
 
-                // Don't wait for the game to finish, just confirm it started
+                // Track the process tree in the background for playtime
+                let app_id = app_id.or_else(|| find_app_id_for_executable(&executable_path));
+                if let Err(e) = track_game_process(app, child, app_id, executable_path.clone(), None).await {
+                    println!("⚠️ Failed to start playtime tracking: {}", e);
+                }
+
                 Ok(
                     "Game launched successfully! The game is now running with bypass enabled."
                         .to_string(),
@@ -537,6 +541,8 @@ pub async fn launch_game_executable(executable_path: String) -> Result<String, S
 
     #[cfg(not(target_os = "windows"))]
     {
+        // Playtime tracking needs a launched process
+        let _ = (app_id, app);
         Err("Game launching is only supported on Windows".to_string())
     }
 }
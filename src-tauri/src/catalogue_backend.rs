@@ -0,0 +1,284 @@
+//! Where catalogue data comes from: the `CatalogueProvider` trait, the configured endpoints
+//! (saved settings, overridden by `ZENITH_HYDRA_API_URL` / `ZENITH_METADATA_URL`) and the
+//! provider and metadata service currently in use
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::stream::{self, Stream};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tauri::{command, State};
+
+use crate::connectivity::CONNECTIVITY;
+use crate::database::operations::CacheMetadataOperations;
+use crate::database::catalogue_cache_operations::CatalogueCacheOperations;
+use crate::database::{shared_database, SharedDatabase};
+use crate::hydra_api::{CatalogueError, CataloguePageRequest, CatalogueResponse, HydraApi, PaginatedCatalogueResponse};
+use crate::metadata_index::invalidate_index;
+use crate::metadata_service::MetadataService;
+
+pub const HYDRA_API_URL_ENV: &str = "ZENITH_HYDRA_API_URL";
+pub const METADATA_URL_ENV: &str = "ZENITH_METADATA_URL";
+
+pub const DEFAULT_HYDRA_API_URL: &str = "https://hydra-api-us-east-1.losbroxas.org";
+pub const DEFAULT_METADATA_URL: &str = "https://assets.hydralauncher.gg";
+
+/// cache_metadata key holding the saved `CatalogueEndpointSettings`
+const ENDPOINTS_KEY: &str = "catalogue_endpoints";
+
+/// A source of catalogue listings and searches
+#[async_trait]
+pub trait CatalogueProvider: Send + Sync {
+    /// Base URL requests go to
+    fn base_url(&self) -> &str;
+
+    /// One unfiltered slice of the catalogue, straight from the backend
+    async fn catalogue_list(&self, limit: Option<u32>, offset: Option<u32>) -> Result<CatalogueResponse, CatalogueError>;
+
+    /// A page of results for a title and filters (cached)
+    async fn search(&self, request: &CataloguePageRequest) -> Result<PaginatedCatalogueResponse, CatalogueError>;
+}
+
+#[async_trait]
+impl CatalogueProvider for HydraApi {
+    fn base_url(&self) -> &str {
+        HydraApi::base_url(self)
+    }
+
+    async fn catalogue_list(&self, limit: Option<u32>, offset: Option<u32>) -> Result<CatalogueResponse, CatalogueError> {
+        self.get_catalogue_list(limit, offset).await
+    }
+
+    async fn search(&self, request: &CataloguePageRequest) -> Result<PaginatedCatalogueResponse, CatalogueError> {
+        self.search_page(request).await
+    }
+}
+
+/// Every page of a listing or search from `first` onwards, for bulk consumers. Ends after the
+/// last page or the first error.
+pub fn catalogue_pages(
+    provider: &dyn CatalogueProvider,
+    first: CataloguePageRequest,
+) -> impl Stream<Item = Result<PaginatedCatalogueResponse, CatalogueError>> + '_ {
+    stream::unfold(Some(first), move |request| async move {
+        let request = request?;
+        match provider.search(&request).await {
+            Ok(page) => {
+                let next = if page.pagination.has_next_page { request.next_page() } else { None };
+                Some((Ok(page), next))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
+
+/// Saved endpoint settings. `None` keeps the default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatalogueEndpointSettings {
+    pub hydra_api_url: Option<String>,
+    pub metadata_url: Option<String>,
+}
+
+impl CatalogueEndpointSettings {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        match CacheMetadataOperations::get(conn, ENDPOINTS_KEY)? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        CacheMetadataOperations::set(conn, ENDPOINTS_KEY, &serde_json::to_string(self)?)
+    }
+
+    /// Blank URLs become `None`; anything else must be an absolute http(s) URL
+    pub fn normalized(self) -> Result<Self> {
+        Ok(Self {
+            hydra_api_url: normalize_url(self.hydra_api_url)?,
+            metadata_url: normalize_url(self.metadata_url)?,
+        })
+    }
+}
+
+fn normalize_url(value: Option<String>) -> Result<Option<String>> {
+    let value = match value.map(|v| v.trim().trim_end_matches('/').to_string()) {
+        Some(v) if !v.is_empty() => v,
+        _ => return Ok(None),
+    };
+
+    let parsed = url::Url::parse(&value).map_err(|e| anyhow!("Invalid URL {}: {}", value, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(anyhow!("Invalid URL {}: only http and https are supported", value));
+    }
+    Ok(Some(value))
+}
+
+/// Where an endpoint in effect came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointSource {
+    Default,
+    Settings,
+    Environment,
+}
+
+/// The endpoints in effect, returned by `get_catalogue_endpoints`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CatalogueEndpoints {
+    pub hydra_api_url: String,
+    pub hydra_api_source: EndpointSource,
+    pub metadata_url: String,
+    pub metadata_source: EndpointSource,
+}
+
+impl CatalogueEndpoints {
+    /// Environment overrides, then saved settings, then the defaults
+    pub fn resolve(settings: &CatalogueEndpointSettings) -> Self {
+        Self::resolve_with(settings, |name| std::env::var(name).ok())
+    }
+
+    fn resolve_with(settings: &CatalogueEndpointSettings, env: impl Fn(&str) -> Option<String>) -> Self {
+        let pick = |env_name: &str, saved: &Option<String>, default: &str| {
+            // A malformed override is ignored rather than breaking the catalogue
+            match env(env_name).and_then(|v| normalize_url(Some(v)).ok().flatten()) {
+                Some(url) => (url, EndpointSource::Environment),
+                None => match saved {
+                    Some(url) => (url.clone(), EndpointSource::Settings),
+                    None => (default.to_string(), EndpointSource::Default),
+                },
+            }
+        };
+
+        let (hydra_api_url, hydra_api_source) = pick(HYDRA_API_URL_ENV, &settings.hydra_api_url, DEFAULT_HYDRA_API_URL);
+        let (metadata_url, metadata_source) = pick(METADATA_URL_ENV, &settings.metadata_url, DEFAULT_METADATA_URL);
+        Self {
+            hydra_api_url,
+            hydra_api_source,
+            metadata_url,
+            metadata_source,
+        }
+    }
+
+    /// Endpoints from the saved settings and the environment
+    pub fn current() -> Self {
+        let settings = shared_database()
+            .and_then(|db| db.with_read_connection(CatalogueEndpointSettings::load))
+            .unwrap_or_else(|e| {
+                eprintln!("⚠️  Ignoring saved catalogue endpoints: {}", e);
+                CatalogueEndpointSettings::default()
+            });
+        Self::resolve(&settings)
+    }
+}
+
+/// Catalogue provider and metadata service for `endpoints`, both caching in the shared database
+fn build_clients(endpoints: &CatalogueEndpoints) -> (Arc<dyn CatalogueProvider>, Arc<MetadataService>) {
+    let hydra = HydraApi::with_base_url(&endpoints.hydra_api_url, CONNECTIVITY.clone());
+    let metadata = MetadataService::with_base_url(&endpoints.metadata_url, CONNECTIVITY.clone());
+
+    match shared_database() {
+        Ok(db) => (Arc::new(hydra.with_cache(db.clone())), Arc::new(metadata.with_cache(db))),
+        Err(e) => {
+            eprintln!("⚠️  Catalogue cache unavailable: {}", e);
+            (Arc::new(hydra), Arc::new(metadata))
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref CLIENTS: RwLock<(Arc<dyn CatalogueProvider>, Arc<MetadataService>)> =
+        RwLock::new(build_clients(&CatalogueEndpoints::current()));
+}
+
+/// The provider catalogue commands use
+pub fn catalogue_provider() -> Arc<dyn CatalogueProvider> {
+    CLIENTS.read().unwrap_or_else(|e| e.into_inner()).0.clone()
+}
+
+/// The service filter metadata commands use
+pub fn metadata_service() -> Arc<MetadataService> {
+    CLIENTS.read().unwrap_or_else(|e| e.into_inner()).1.clone()
+}
+
+/// Point the catalogue and metadata clients at `endpoints`; requests already running finish
+/// against the old ones
+pub fn apply_endpoints(endpoints: &CatalogueEndpoints) {
+    *CLIENTS.write().unwrap_or_else(|e| e.into_inner()) = build_clients(endpoints);
+    invalidate_index();
+    println!(
+        "🔧 Catalogue endpoints: {} ({:?}), metadata {} ({:?})",
+        endpoints.hydra_api_url, endpoints.hydra_api_source, endpoints.metadata_url, endpoints.metadata_source
+    );
+}
+
+#[command]
+pub async fn get_catalogue_endpoints() -> Result<CatalogueEndpoints, String> {
+    Ok(CatalogueEndpoints::current())
+}
+
+/// Save catalogue and metadata URLs (blank or missing resets to the default) and switch to
+/// them. Environment overrides still win. The catalogue cache is cleared if the URLs in
+/// effect change, so results from one backend are never served for another.
+#[command]
+pub async fn set_catalogue_endpoints(
+    settings: CatalogueEndpointSettings,
+    db: State<'_, SharedDatabase>,
+) -> Result<CatalogueEndpoints, String> {
+    let settings = settings.normalized().map_err(|e| format!("Failed to save catalogue endpoints: {}", e))?;
+    let previous = CatalogueEndpoints::current();
+    let endpoints = CatalogueEndpoints::resolve(&settings);
+    let changed = endpoints.hydra_api_url != previous.hydra_api_url || endpoints.metadata_url != previous.metadata_url;
+
+    db.with_connection_async(move |conn| {
+        settings.save(conn)?;
+        if changed {
+            CatalogueCacheOperations::clear(conn)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Failed to save catalogue endpoints: {}", e))?;
+
+    if changed {
+        apply_endpoints(&endpoints);
+    }
+    Ok(endpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment_overrides_settings() {
+        let settings = CatalogueEndpointSettings {
+            hydra_api_url: Some("http://localhost:4000".to_string()),
+            metadata_url: None,
+        };
+
+        let endpoints = CatalogueEndpoints::resolve_with(&settings, |_| None);
+        assert_eq!(endpoints.hydra_api_url, "http://localhost:4000");
+        assert_eq!(endpoints.hydra_api_source, EndpointSource::Settings);
+        assert_eq!(endpoints.metadata_url, DEFAULT_METADATA_URL);
+        assert_eq!(endpoints.metadata_source, EndpointSource::Default);
+
+        let endpoints = CatalogueEndpoints::resolve_with(&settings, |name| match name {
+            HYDRA_API_URL_ENV => Some("http://127.0.0.1:9000/".to_string()),
+            _ => Some("not a url".to_string()),
+        });
+        assert_eq!(endpoints.hydra_api_url, "http://127.0.0.1:9000");
+        assert_eq!(endpoints.hydra_api_source, EndpointSource::Environment);
+        assert_eq!(endpoints.metadata_source, EndpointSource::Default);
+    }
+
+    #[test]
+    fn test_settings_reject_non_http_urls() {
+        let blank = CatalogueEndpointSettings { hydra_api_url: Some("  ".to_string()), metadata_url: None };
+        assert_eq!(blank.normalized().unwrap(), CatalogueEndpointSettings::default());
+
+        let file = CatalogueEndpointSettings { hydra_api_url: Some("file:///etc/passwd".to_string()), metadata_url: None };
+        assert!(file.normalized().is_err());
+    }
+}
@@ -1,5 +1,12 @@
-use crate::hydra_api::{HYDRA_API, PaginatedCatalogueResponse, SearchFilters};
+use crate::catalogue_backend::catalogue_provider;
+use crate::database::catalogue_enrichment_operations::CatalogueEnrichmentOperations;
+use crate::database::operations::CacheMetadataOperations;
+use crate::database::shared_database;
+use crate::hydra_api::{CataloguePageRequest, CatalogueSort, PaginatedCatalogueResponse, SearchFilters};
+use crate::database::catalogue_enrichment_models::LocalGameState;
+use crate::steam_utils::{cached_installed_app_ids, find_steam_installation_path};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use tauri::command;
 
 // Frontend-facing data structures
@@ -11,6 +18,17 @@ pub struct CatalogueGame {
     pub shop: String,
     pub genres: Vec<String>,
     pub library_image_url: Option<String>,
+    // Steam games only, filled in by `enrich_catalogue_games`
+    #[serde(default)]
+    pub in_library: bool,
+    #[serde(default)]
+    pub installed: bool,
+    #[serde(default)]
+    pub has_cached_details: bool,
+    #[serde(default)]
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub publisher: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,10 +47,71 @@ impl From<crate::hydra_api::HydraGame> for CatalogueGame {
             shop: hydra_game.shop,
             genres: hydra_game.genres,
             library_image_url: hydra_game.library_image_url,
+            in_library: false,
+            installed: false,
+            has_cached_details: false,
+            release_date: None,
+            publisher: None,
         }
     }
 }
 
+/// Cross-reference Steam games with what the user already has: library membership and cached
+/// details from the database, install state from the Steam libraries. Best effort; lookup
+/// failures are logged and leave the games as they are.
+pub async fn enrich_catalogue_games(games: &mut [CatalogueGame]) {
+    let app_ids: Vec<String> = games
+        .iter()
+        .filter(|game| game.shop == "steam")
+        .map(|game| game.object_id.clone())
+        .collect();
+    if app_ids.is_empty() {
+        return;
+    }
+
+    let lookup = match shared_database() {
+        Ok(db) => {
+            db.with_read_connection_async(move |conn| {
+                let states = CatalogueEnrichmentOperations::local_state(conn, &app_ids)?;
+                let steam_path = CacheMetadataOperations::get(conn, "steam_path")?;
+                Ok((states, steam_path))
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    let (states, steam_path) = match lookup {
+        Ok(lookup) => lookup,
+        Err(e) => {
+            eprintln!("⚠️  Failed to look up catalogue games locally: {}", e);
+            return;
+        }
+    };
+
+    let installed = tokio::task::spawn_blocking(move || {
+        steam_path
+            .or_else(|| find_steam_installation_path().ok())
+            .map(|path| cached_installed_app_ids(&path))
+            .unwrap_or_default()
+    })
+    .await
+    .unwrap_or_default();
+
+    apply_local_state(games, &states, &installed);
+}
+
+/// Set the local flags of Steam games from their database state and install state
+fn apply_local_state(games: &mut [CatalogueGame], states: &HashMap<String, LocalGameState>, installed: &HashSet<String>) {
+    for game in games.iter_mut().filter(|game| game.shop == "steam") {
+        let state = states.get(&game.object_id).cloned().unwrap_or_default();
+        game.in_library = state.in_library;
+        game.installed = installed.contains(&game.object_id);
+        game.has_cached_details = state.has_cached_details;
+        game.release_date = state.release_date;
+        game.publisher = state.publisher;
+    }
+}
+
 /// Get catalogue list (all games)
 /// Based on your hydra-test catalogue-list.js implementation
 #[command]
@@ -42,13 +121,14 @@ pub async fn get_catalogue_list(
 ) -> Result<CatalogueListResponse, String> {
     println!("🎮 [Command] Getting catalogue list...");
     
-    match HYDRA_API.get_catalogue_list(limit, offset).await {
+    match catalogue_provider().catalogue_list(limit, offset).await {
         Ok(response) => {
-            let games: Vec<CatalogueGame> = response
+            let mut games: Vec<CatalogueGame> = response
                 .edges
                 .into_iter()
                 .map(CatalogueGame::from)
                 .collect();
+            enrich_catalogue_games(&mut games).await;
 
             Ok(CatalogueListResponse {
                 games,
@@ -71,6 +151,7 @@ pub async fn get_paginated_catalogue(
     tags: Option<Vec<String>>,
     developers: Option<Vec<String>>,
     publishers: Option<Vec<String>>,
+    sort_by: Option<CatalogueSort>,
 ) -> Result<PaginatedCatalogueResponse, String> {
     // Fetching catalogue data
     
@@ -92,8 +173,13 @@ pub async fn get_paginated_catalogue(
     println!("   developers: {:?}", developers);
     println!("   publishers: {:?}", publishers);
 
-    match HYDRA_API.search_games_paginated_with_filters(filters, page, items_per_page).await {
-        Ok(response) => {
+    let request = CataloguePageRequest::new(&filters, page, items_per_page, sort_by.unwrap_or_default())
+        .map_err(|e| format!("Failed to fetch paginated catalogue: {}", e))?;
+
+    match catalogue_provider().search(&request).await {
+        Ok(mut response) => {
+            enrich_catalogue_games(&mut response.games).await;
+
             // Debug: Log first game data being sent to frontend
             if let Some(first_game) = response.games.first() {
                 println!(
@@ -120,6 +206,7 @@ pub async fn search_catalogue_games(
     tags: Option<Vec<String>>,
     developers: Option<Vec<String>>,
     publishers: Option<Vec<String>>,
+    sort_by: Option<CatalogueSort>,
 ) -> Result<PaginatedCatalogueResponse, String> {
     // Searching catalogue games
     
@@ -143,8 +230,13 @@ pub async fn search_catalogue_games(
     println!("   developers: {:?}", developers);
     println!("   publishers: {:?}", publishers);
 
-    match HYDRA_API.search_games_paginated_with_filters(filters, page, items_per_page).await {
-        Ok(response) => {
+    let request = CataloguePageRequest::new(&filters, page, items_per_page, sort_by.unwrap_or_default())
+        .map_err(|e| format!("Failed to search catalogue games: {}", e))?;
+
+    match catalogue_provider().search(&request).await {
+        Ok(mut response) => {
+            enrich_catalogue_games(&mut response.games).await;
+
             // Debug: Log first search result
             if let Some(first_game) = response.games.first() {
                 println!(
@@ -168,13 +260,14 @@ pub async fn get_sample_catalogue_games(limit: Option<u32>) -> Result<Vec<Catalo
     
     let limit = limit.unwrap_or(10);
     
-    match HYDRA_API.get_catalogue_list(Some(limit), Some(0)).await {
+    match catalogue_provider().catalogue_list(Some(limit), Some(0)).await {
         Ok(response) => {
-            let games: Vec<CatalogueGame> = response
+            let mut games: Vec<CatalogueGame> = response
                 .edges
                 .into_iter()
                 .map(CatalogueGame::from)
                 .collect();
+            enrich_catalogue_games(&mut games).await;
 
             println!("✅ Retrieved {} sample games", games.len());
             Ok(games)
@@ -191,10 +284,12 @@ pub async fn get_sample_catalogue_games(limit: Option<u32>) -> Result<Vec<Catalo
 pub async fn test_hydra_connection() -> Result<String, String> {
     // Testing API connection
     
-    match HYDRA_API.get_catalogue_list(Some(5), Some(0)).await {
+    let provider = catalogue_provider();
+    match provider.catalogue_list(Some(5), Some(0)).await {
         Ok(response) => {
             let message = format!(
-                "✅ Hydra API connection successful! Found {} games (total: {})",
+                "✅ Hydra API connection to {} successful! Found {} games (total: {})",
+                provider.base_url(),
                 response.edges.len(),
                 response.count
             );
@@ -202,9 +297,51 @@ pub async fn test_hydra_connection() -> Result<String, String> {
             Ok(message)
         }
         Err(e) => {
-            let error_msg = format!("❌ Hydra API connection failed: {}", e);
+            let error_msg = format!("❌ Hydra API connection to {} failed: {}", provider.base_url(), e);
             eprintln!("{}", error_msg);
             Err(error_msg)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(object_id: &str, shop: &str) -> CatalogueGame {
+        CatalogueGame::from(crate::hydra_api::HydraGame {
+            id: format!("{}-{}", shop, object_id),
+            object_id: object_id.to_string(),
+            title: format!("Game {}", object_id),
+            shop: shop.to_string(),
+            genres: Vec::new(),
+            library_image_url: None,
+            release_date: None,
+            popularity: None,
+        })
+    }
+
+    #[test]
+    fn test_local_state_flags_only_steam_games() {
+        let mut games = vec![game("220", "steam"), game("620", "steam"), game("220", "epic")];
+        let states = HashMap::from([(
+            "220".to_string(),
+            LocalGameState {
+                in_library: true,
+                has_cached_details: true,
+                release_date: Some("16 Nov, 2004".to_string()),
+                publisher: Some("Valve".to_string()),
+            },
+        )]);
+        let installed = HashSet::from(["620".to_string()]);
+
+        apply_local_state(&mut games, &states, &installed);
+
+        assert!(games[0].in_library && games[0].has_cached_details && !games[0].installed);
+        assert_eq!(games[0].publisher.as_deref(), Some("Valve"));
+        assert!(!games[1].in_library && !games[1].has_cached_details && games[1].installed);
+        assert_eq!(games[1].release_date, None);
+        // Same id on another store is left alone
+        assert!(!games[2].in_library && !games[2].installed && games[2].publisher.is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::app_paths::app_paths;
+use crate::database::SharedDatabase;
 use crate::models::{RepoType, UpdateStrategy, UpdateSource, ManifestInfo, UpdateResult};
 use anyhow::Result;
 use regex::Regex;
@@ -6,7 +8,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
-use tauri::command;
+use tauri::{command, State};
 use uuid::Uuid;
 use walkdir::WalkDir;
 use zip::ZipArchive;
@@ -272,13 +274,10 @@ fn find_lua_file_for_appid(
 
 // Profile Management Commands using SQLite
 #[command]
-pub async fn get_user_profile() -> Result<crate::database::models::UserProfile, String> {
-    use crate::database::{DatabaseManager, operations::UserProfileOperations};
+pub async fn get_user_profile(db: State<'_, SharedDatabase>) -> Result<crate::database::models::UserProfile, String> {
+    use crate::database::operations::UserProfileOperations;
     
-    let db_path = get_profile_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    let profile = db_manager.with_connection(|conn| {
+    let profile = db.with_connection_async(|conn| {
         match UserProfileOperations::get(conn)? {
             Some(profile) => Ok(profile),
             None => {
@@ -290,28 +289,29 @@ pub async fn get_user_profile() -> Result<crate::database::models::UserProfile,
                 Ok(default_profile)
             }
         }
-    }).map_err(|e| e.to_string())?;
+    }).await.map_err(|e| e.to_string())?;
     
     Ok(profile)
 }
 
 #[command]
-pub async fn save_user_profile(profile: crate::database::models::UserProfile) -> Result<(), String> {
-    use crate::database::{DatabaseManager, operations::UserProfileOperations};
-    
-    let db_path = get_profile_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
+pub async fn save_user_profile(profile: crate::database::models::UserProfile, db: State<'_, SharedDatabase>) -> Result<(), String> {
+    use crate::database::operations::UserProfileOperations;
     
-    db_manager.with_connection(|conn| {
+    db.with_connection_async(move |conn| {
         UserProfileOperations::upsert(conn, &profile)
-    }).map_err(|e| e.to_string())?;
+    }).await.map_err(|e| e.to_string())?;
     
     Ok(())
 }
 
 #[command]
-pub async fn upload_profile_image(image_data: Vec<u8>, image_type: String) -> Result<String, String> {
-    use crate::database::{DatabaseManager, operations::UserProfileOperations};
+pub async fn upload_profile_image(
+    image_data: Vec<u8>,
+    image_type: String,
+    db: State<'_, SharedDatabase>,
+) -> Result<String, String> {
+    use crate::database::operations::UserProfileOperations;
     
     println!("Starting profile image upload - type: {}, size: {} bytes", image_type, image_data.len());
     
@@ -350,16 +350,6 @@ pub async fn upload_profile_image(image_data: Vec<u8>, image_type: String) -> Re
     
     println!("Image file saved successfully, updating database...");
     // Update database with new image path
-    let db_path = get_profile_db_path().map_err(|e| {
-        println!("Failed to get database path: {}", e);
-        e.to_string()
-    })?;
-    
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| {
-        println!("Failed to create database manager: {}", e);
-        e.to_string()
-    })?;
-    
     let field_name = match image_type.as_str() {
         "banner" => "banner_path",
         "avatar" => "avatar_path",
@@ -367,9 +357,10 @@ pub async fn upload_profile_image(image_data: Vec<u8>, image_type: String) -> Re
     };
     
     println!("Updating database field: {}", field_name);
-    db_manager.with_connection(|conn| {
-        UserProfileOperations::update_field(conn, field_name, Some(&image_path.to_string_lossy().to_string()))
-    }).map_err(|e| {
+    let stored_path = image_path.to_string_lossy().to_string();
+    db.with_connection_async(move |conn| {
+        UserProfileOperations::update_field(conn, field_name, Some(&stored_path))
+    }).await.map_err(|e| {
         println!("Failed to update database: {}", e);
         format!("Database update failed: {}", e)
     })?;
@@ -418,86 +409,51 @@ pub async fn get_profile_image_base64(image_type: String) -> Result<Option<Strin
 }
 
 #[command]
-pub async fn reset_profile_to_default() -> Result<(), String> {
-    use crate::database::{DatabaseManager, operations::UserProfileOperations};
-    
-    let db_path = get_profile_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
+pub async fn reset_profile_to_default(db: State<'_, SharedDatabase>) -> Result<(), String> {
+    use crate::database::operations::UserProfileOperations;
     
-    db_manager.with_connection(|conn| {
+    db.with_connection_async(|conn| {
         // Reset to default profile
         let default_profile = crate::database::models::UserProfile::new(
                     "User".to_string()
         );
         UserProfileOperations::upsert(conn, &default_profile)
-    }).map_err(|e| e.to_string())?;
+    }).await.map_err(|e| e.to_string())?;
     
     Ok(())
 }
 
 #[command]
-pub async fn update_profile_field(field: String, value: Option<String>) -> Result<(), String> {
-    use crate::database::{DatabaseManager, operations::UserProfileOperations};
+pub async fn update_profile_field(field: String, value: Option<String>, db: State<'_, SharedDatabase>) -> Result<(), String> {
+    use crate::database::operations::UserProfileOperations;
     
-    let db_path = get_profile_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db_manager.with_connection(|conn| {
+    db.with_connection_async(move |conn| {
         UserProfileOperations::update_field(conn, &field, value.as_deref())
-    }).map_err(|e| e.to_string())?;
+    }).await.map_err(|e| e.to_string())?;
     
     Ok(())
 }
 
 // Helper functions
 fn get_profile_dir() -> Result<PathBuf, anyhow::Error> {
-    // Use platform-specific app data directory
-    let app_dir = if cfg!(target_os = "windows") {
-        std::env::var("APPDATA")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from(r"C:\Users\Default\AppData\Roaming"))
-    } else if cfg!(target_os = "macos") {
-        dirs::home_dir()
-            .map(|home| home.join("Library").join("Application Support"))
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-    } else {
-        dirs::home_dir()
-            .map(|home| home.join(".local").join("share"))
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-    };
-    
-    Ok(app_dir.join("zenith").join("profile"))
-}
-
-fn get_profile_db_path() -> Result<PathBuf, anyhow::Error> {
-    // Use the same database path as the main cache database
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    fs::create_dir_all(&cache_dir)?;
-    Ok(cache_dir.join("games.db"))
+    Ok(app_paths().profile_dir())
 }
 
 // Steam Path Management Commands
 #[command]
-pub async fn get_steam_path() -> Result<Option<String>, String> {
-    use crate::database::{DatabaseManager, operations::CacheMetadataOperations};
+pub async fn get_steam_path(db: State<'_, SharedDatabase>) -> Result<Option<String>, String> {
+    use crate::database::operations::CacheMetadataOperations;
     
-    let db_path = get_profile_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    let steam_path = db_manager.with_connection(|conn| {
+    let steam_path = db.with_read_connection_async(|conn| {
         CacheMetadataOperations::get(conn, "steam_path")
-    }).map_err(|e| e.to_string())?;
+    }).await.map_err(|e| e.to_string())?;
     
     Ok(steam_path)
 }
 
 #[command]
-pub async fn set_steam_path(path: String) -> Result<(), String> {
-    use crate::database::{DatabaseManager, operations::CacheMetadataOperations};
+pub async fn set_steam_path(path: String, db: State<'_, SharedDatabase>) -> Result<(), String> {
+    use crate::database::operations::CacheMetadataOperations;
     
     // Validate that the path exists and contains steam.exe
     let steam_exe_path = PathBuf::from(&path).join("steam.exe");
@@ -505,12 +461,9 @@ pub async fn set_steam_path(path: String) -> Result<(), String> {
         return Err(format!("Invalid Steam path: steam.exe not found in {}", path));
     }
     
-    let db_path = get_profile_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db_manager.with_connection(|conn| {
+    db.with_connection_async(move |conn| {
         CacheMetadataOperations::set(conn, "steam_path", &path)
-    }).map_err(|e| e.to_string())?;
+    }).await.map_err(|e| e.to_string())?;
     
     Ok(())
 }
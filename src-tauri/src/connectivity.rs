@@ -0,0 +1,354 @@
+//! Connectivity monitor and offline mode. Network clients check `CONNECTIVITY` before going
+//! out and report connection failures to it; while offline every service answers from the
+//! local cache only and outbound work waits in `wait_until_online`.
+
+use serde::Serialize;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::watch;
+
+use crate::database::operations::CacheMetadataOperations;
+use crate::database::shared_database;
+
+/// Event emitted with a `ConnectivityStatus` whenever the app goes offline or back online
+pub const CONNECTIVITY_CHANGED_EVENT: &str = "connectivity-changed";
+
+/// cache_metadata key persisting the user's explicit offline mode
+const OFFLINE_MODE_KEY: &str = "offline_mode";
+
+const DEFAULT_PROBE_URL: &str = "https://store.steampowered.com/";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Failed requests in a row before the network counts as unreachable, so one slow download
+/// does not take the whole app offline. A failed probe counts on its own.
+pub const FAILURES_BEFORE_OFFLINE: u32 = 3;
+
+/// Reachability is rechecked slowly while online and quickly while offline
+const ONLINE_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Payload of `connectivity-changed` and `get_connectivity_status`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityStatus {
+    /// Outbound requests are allowed (reachable and not in offline mode)
+    pub online: bool,
+    /// Result of the last probe or request
+    pub network_reachable: bool,
+    /// Offline mode switched on by the user
+    pub offline_mode: bool,
+    pub last_checked: Option<i64>,
+    pub last_changed: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+/// Returned instead of making a request while offline
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineError(pub &'static str);
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Offline: {} is unavailable until connectivity returns", self.0)
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+#[derive(Default)]
+struct ProbeState {
+    last_checked: Option<i64>,
+    last_changed: Option<i64>,
+    last_error: Option<String>,
+}
+
+pub struct ConnectivityMonitor {
+    client: reqwest::Client,
+    probe_url: String,
+    reachable: AtomicBool,
+    // Connection failures reported by requests since the last success
+    consecutive_failures: AtomicU32,
+    offline_mode: AtomicBool,
+    state: RwLock<ProbeState>,
+    // Effective online state, for waiters and the event forwarder
+    online: watch::Sender<bool>,
+}
+
+impl ConnectivityMonitor {
+    pub fn new() -> Self {
+        Self::with_probe_url(DEFAULT_PROBE_URL)
+    }
+
+    /// Monitor probing `probe_url`; point it at a closed port to simulate being offline
+    pub fn with_probe_url(probe_url: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("zenith-launcher/1.0")
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .expect("Failed to create connectivity probe client");
+
+        Self {
+            client,
+            probe_url: probe_url.to_string(),
+            // Assume online until a probe or request says otherwise
+            reachable: AtomicBool::new(true),
+            consecutive_failures: AtomicU32::new(0),
+            offline_mode: AtomicBool::new(false),
+            state: RwLock::new(ProbeState::default()),
+            online: watch::channel(true).0,
+        }
+    }
+
+    pub fn is_online(&self) -> bool {
+        !self.offline_mode.load(Ordering::SeqCst) && self.reachable.load(Ordering::SeqCst)
+    }
+
+    pub fn is_offline(&self) -> bool {
+        !self.is_online()
+    }
+
+    /// Fail fast with an `OfflineError` naming `what` instead of attempting a request
+    pub fn ensure_online(&self, what: &'static str) -> Result<(), OfflineError> {
+        if self.is_online() {
+            Ok(())
+        } else {
+            Err(OfflineError(what))
+        }
+    }
+
+    /// Switch the explicit offline mode. Returns true if the effective state changed.
+    pub fn set_offline_mode(&self, enabled: bool) -> bool {
+        self.offline_mode.store(enabled, Ordering::SeqCst);
+        self.publish()
+    }
+
+    /// A request got a response, so the network is reachable
+    pub fn record_success(&self) {
+        self.set_reachable(true, None);
+    }
+
+    /// Count a failed request against connectivity. Only connection failures and timeouts
+    /// count, since HTTP errors and bad bodies come from a reachable server, and only
+    /// `FAILURES_BEFORE_OFFLINE` of them in a row mean offline.
+    pub fn record_failure(&self, error: &reqwest::Error) {
+        if !(error.is_connect() || error.is_timeout()) {
+            return;
+        }
+        if self.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1 >= FAILURES_BEFORE_OFFLINE {
+            self.set_reachable(false, Some(error.to_string()));
+        }
+    }
+
+    /// Check reachability now (skipped in offline mode). Returns whether the network is reachable.
+    pub async fn probe(&self) -> bool {
+        if self.offline_mode.load(Ordering::SeqCst) {
+            return self.reachable.load(Ordering::SeqCst);
+        }
+
+        // Any HTTP response at all means we got through
+        match self.client.head(&self.probe_url).send().await {
+            Ok(_) => self.set_reachable(true, None),
+            Err(e) => self.set_reachable(false, Some(e.to_string())),
+        };
+        self.reachable.load(Ordering::SeqCst)
+    }
+
+    /// Resolves immediately if online, otherwise once connectivity returns
+    pub async fn wait_until_online(&self) {
+        let mut online = self.online.subscribe();
+        let _ = online.wait_for(|online| *online).await;
+    }
+
+    /// Receive the effective online state whenever it changes
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.online.subscribe()
+    }
+
+    pub fn status(&self) -> ConnectivityStatus {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        ConnectivityStatus {
+            online: self.is_online(),
+            network_reachable: self.reachable.load(Ordering::SeqCst),
+            offline_mode: self.offline_mode.load(Ordering::SeqCst),
+            last_checked: state.last_checked,
+            last_changed: state.last_changed,
+            last_error: state.last_error.clone(),
+        }
+    }
+
+    fn set_reachable(&self, reachable: bool, error: Option<String>) -> bool {
+        if reachable {
+            self.consecutive_failures.store(0, Ordering::SeqCst);
+        }
+        {
+            let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+            state.last_checked = Some(chrono::Utc::now().timestamp());
+            state.last_error = error;
+        }
+        self.reachable.store(reachable, Ordering::SeqCst);
+        self.publish()
+    }
+
+    /// Push the effective state to subscribers. Returns true if it changed.
+    fn publish(&self) -> bool {
+        let online = self.is_online();
+        let changed = self.online.send_replace(online) != online;
+        if changed {
+            let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+            state.last_changed = Some(chrono::Utc::now().timestamp());
+        }
+        changed
+    }
+}
+
+impl Default for ConnectivityMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref CONNECTIVITY: Arc<ConnectivityMonitor> = Arc::new(ConnectivityMonitor::new());
+}
+
+/// Restore the offline mode saved by `set_offline_mode`
+pub fn load_offline_mode() {
+    let saved = shared_database().and_then(|db| {
+        db.with_read_connection(|conn| CacheMetadataOperations::get(conn, OFFLINE_MODE_KEY))
+    });
+    match saved {
+        Ok(Some(value)) if value == "true" => {
+            CONNECTIVITY.set_offline_mode(true);
+            println!("📴 Starting in offline mode");
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("⚠️  Failed to load offline mode: {}", e),
+    }
+}
+
+/// Probe reachability for the lifetime of the app
+pub async fn run_connectivity_monitor() {
+    let monitor = CONNECTIVITY.clone();
+    loop {
+        let interval = if monitor.probe().await {
+            ONLINE_PROBE_INTERVAL
+        } else {
+            OFFLINE_PROBE_INTERVAL
+        };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Forward online/offline transitions to the frontend as `connectivity-changed`
+pub async fn forward_connectivity_changes(app: AppHandle) {
+    let mut changes = CONNECTIVITY.subscribe();
+    while changes.changed().await.is_ok() {
+        let status = CONNECTIVITY.status();
+        if status.online {
+            println!("🌐 Back online, resuming deferred work");
+        } else {
+            println!("📴 Offline, serving cached data only");
+        }
+        let _ = app.emit(CONNECTIVITY_CHANGED_EVENT, status);
+    }
+}
+
+#[command]
+pub async fn get_connectivity_status() -> Result<ConnectivityStatus, String> {
+    Ok(CONNECTIVITY.status())
+}
+
+/// Switch offline mode on or off; persisted across restarts
+#[command]
+pub async fn set_offline_mode(enabled: bool) -> Result<ConnectivityStatus, String> {
+    let db = shared_database().map_err(|e| format!("Failed to open database: {}", e))?;
+    db.with_connection_async(move |conn| {
+        CacheMetadataOperations::set(conn, OFFLINE_MODE_KEY, if enabled { "true" } else { "false" })
+    })
+    .await
+    .map_err(|e| format!("Failed to save offline mode: {}", e))?;
+
+    CONNECTIVITY.set_offline_mode(enabled);
+    if !enabled {
+        // Recheck now rather than trusting a reachability result from before offline mode
+        CONNECTIVITY.probe().await;
+    }
+    Ok(CONNECTIVITY.status())
+}
+
+/// Recheck reachability now
+#[command]
+pub async fn check_connectivity() -> Result<ConnectivityStatus, String> {
+    CONNECTIVITY.probe().await;
+    Ok(CONNECTIVITY.status())
+}
+
+/// A localhost URL nothing listens on, for simulating offline mode in tests
+#[cfg(test)]
+pub(crate) fn closed_port_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    format!("http://127.0.0.1:{}", port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_closed_port_probe_goes_offline_and_back() {
+        let monitor = ConnectivityMonitor::with_probe_url(&closed_port_url());
+        assert!(monitor.is_online());
+
+        assert!(!monitor.probe().await);
+        assert!(monitor.is_offline());
+        assert_eq!(monitor.ensure_online("test"), Err(OfflineError("test")));
+        assert!(monitor.status().last_error.is_some());
+
+        let waiter = {
+            let mut online = monitor.subscribe();
+            tokio::spawn(async move { online.wait_for(|online| *online).await.is_ok() })
+        };
+        monitor.record_success();
+        assert!(waiter.await.unwrap());
+        assert!(monitor.status().last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_offline_only_after_repeated_failures() {
+        let monitor = ConnectivityMonitor::with_probe_url(&closed_port_url());
+        let error = reqwest::get(closed_port_url()).await.unwrap_err();
+
+        for _ in 1..FAILURES_BEFORE_OFFLINE {
+            monitor.record_failure(&error);
+        }
+        assert!(monitor.is_online());
+
+        // A success in between starts the count over
+        monitor.record_success();
+        monitor.record_failure(&error);
+        assert!(monitor.is_online());
+
+        for _ in 1..FAILURES_BEFORE_OFFLINE {
+            monitor.record_failure(&error);
+        }
+        assert!(monitor.is_offline());
+        assert!(monitor.status().last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_offline_mode_overrides_reachability() {
+        let monitor = ConnectivityMonitor::with_probe_url(&closed_port_url());
+
+        assert!(monitor.set_offline_mode(true));
+        assert!(monitor.is_offline());
+        // No probing while the user has switched the network off
+        assert!(monitor.probe().await);
+        assert!(monitor.status().network_reachable);
+
+        assert!(monitor.set_offline_mode(false));
+        assert!(monitor.is_online());
+    }
+}
@@ -0,0 +1,30 @@
+use tauri::{command, State};
+use crate::database::{
+    backup_models::*, backup_operations::BackupOperations, SharedDatabase,
+};
+
+/// List database backups, newest first
+#[command]
+pub async fn list_backups(db: State<'_, SharedDatabase>) -> Result<Vec<BackupInfo>, String> {
+    BackupOperations::list_backups(&db.backups_dir())
+        .map_err(|e| format!("Failed to list backups: {}", e))
+}
+
+/// Take a manual backup of the database now
+#[command]
+pub async fn create_database_backup(db: State<'_, SharedDatabase>) -> Result<BackupInfo, String> {
+    db.run_blocking(|db| {
+        let dir = db.backups_dir();
+        db.with_read_connection(|conn| BackupOperations::create_backup(conn, &dir, BackupKind::Manual))
+    })
+    .await
+    .map_err(|e| format!("Failed to create backup: {}", e))
+}
+
+/// Restore the database from a backup (the current state is backed up first)
+#[command]
+pub async fn restore_backup(file_name: String, db: State<'_, SharedDatabase>) -> Result<BackupInfo, String> {
+    db.run_blocking(move |db| BackupOperations::restore_backup(db, &file_name))
+        .await
+        .map_err(|e| format!("Failed to restore backup: {}", e))
+}
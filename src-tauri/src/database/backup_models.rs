@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// Daily backups kept before the oldest is deleted
+pub const DAILY_BACKUPS_TO_KEEP: usize = 7;
+
+/// File name layout: `games-<kind>-<YYYYmmdd-HHMMSS>[-<n>].db`, where `n` counts backups of
+/// the same kind taken within one second
+pub const BACKUP_FILE_PREFIX: &str = "games-";
+pub const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const BACKUP_TIMESTAMP_LEN: usize = "YYYYmmdd-HHMMSS".len();
+
+/// Why a backup was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    Daily,
+    PreMigration,
+    PreRestore,
+    Manual,
+}
+
+impl BackupKind {
+    pub const ALL: [BackupKind; 4] = [
+        BackupKind::Daily,
+        BackupKind::PreMigration,
+        BackupKind::PreRestore,
+        BackupKind::Manual,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupKind::Daily => "daily",
+            BackupKind::PreMigration => "pre-migration",
+            BackupKind::PreRestore => "pre-restore",
+            BackupKind::Manual => "manual",
+        }
+    }
+
+    /// How many backups of this kind survive rotation
+    pub fn keep(&self) -> usize {
+        match self {
+            BackupKind::Daily => DAILY_BACKUPS_TO_KEEP,
+            BackupKind::PreMigration => 3,
+            BackupKind::PreRestore => 3,
+            BackupKind::Manual => 10,
+        }
+    }
+}
+
+/// A backup file of games.db
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    pub kind: BackupKind,
+    pub created_at: i64,
+    pub size_bytes: u64,
+}
+
+impl BackupInfo {
+    /// Parse `games-<kind>-<timestamp>[-<n>].db` into kind, timestamp and `n` (1 when absent);
+    /// other files in the folder are ignored
+    pub fn parse_file_name(file_name: &str) -> Option<(BackupKind, i64, u32)> {
+        let stem = file_name.strip_prefix(BACKUP_FILE_PREFIX)?.strip_suffix(".db")?;
+        let kind = BackupKind::ALL
+            .into_iter()
+            .find(|kind| stem.starts_with(kind.as_str()) && stem[kind.as_str().len()..].starts_with('-'))?;
+        let rest = &stem[kind.as_str().len() + 1..];
+        let timestamp = rest.get(..BACKUP_TIMESTAMP_LEN)?;
+        let sequence = match &rest[BACKUP_TIMESTAMP_LEN..] {
+            "" => 1,
+            suffix => suffix.strip_prefix('-')?.parse().ok().filter(|n| *n > 1)?,
+        };
+        let created_at = chrono::NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).ok()?;
+        Some((kind, created_at.and_utc().timestamp(), sequence))
+    }
+}
@@ -0,0 +1,280 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use super::backup_models::*;
+use super::migrations::check_database_integrity;
+use super::{DatabaseManager, SharedDatabase};
+
+/// How often the daily backup task checks whether a backup is due
+const DAILY_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+pub struct BackupOperations;
+
+impl BackupOperations {
+    /// Copy the database behind `conn` into `dir` with the SQLite online backup API
+    pub fn create_backup(conn: &Connection, dir: &Path, kind: BackupKind) -> Result<BackupInfo> {
+        fs::create_dir_all(dir)?;
+
+        let stem = format!("{}{}-{}", BACKUP_FILE_PREFIX, kind.as_str(), Utc::now().format(BACKUP_TIMESTAMP_FORMAT));
+        // Backups taken within the same second get a counter instead of overwriting each other
+        let (file_name, path) = (1..)
+            .map(|n| if n == 1 { format!("{}.db", stem) } else { format!("{}-{}.db", stem, n) })
+            .map(|file_name| {
+                let path = dir.join(&file_name);
+                (file_name, path)
+            })
+            .find(|(_, path)| !path.exists())
+            .expect("unbounded range");
+
+        // Write to a temp file first so a half-written backup never shows up in the list
+        let tmp_path = dir.join(format!("{}.tmp", file_name));
+        conn.backup(DatabaseName::Main, &tmp_path, None)?;
+        fs::rename(&tmp_path, &path)?;
+
+        Self::rotate(dir, kind)?;
+
+        let info = Self::list_backups(dir)?
+            .into_iter()
+            .find(|backup| backup.file_name == file_name)
+            .ok_or_else(|| anyhow!("Backup {} disappeared after it was written", file_name))?;
+        println!("💾 Database backup created: {}", info.file_name);
+        Ok(info)
+    }
+
+    /// All backups in `dir`, newest first
+    pub fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(dir)?.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if let Some((kind, created_at, sequence)) = BackupInfo::parse_file_name(&file_name) {
+                let info = BackupInfo {
+                    path: entry.path().to_string_lossy().to_string(),
+                    size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    file_name,
+                    kind,
+                    created_at,
+                };
+                backups.push((info, sequence));
+            }
+        }
+
+        backups.sort_by(|(a, a_seq), (b, b_seq)| {
+            (b.created_at, b_seq).cmp(&(a.created_at, a_seq)).then_with(|| b.file_name.cmp(&a.file_name))
+        });
+        Ok(backups.into_iter().map(|(info, _)| info).collect())
+    }
+
+    /// Delete the oldest backups of `kind` beyond its retention count
+    pub fn rotate(dir: &Path, kind: BackupKind) -> Result<usize> {
+        let expired: Vec<BackupInfo> = Self::list_backups(dir)?
+            .into_iter()
+            .filter(|backup| backup.kind == kind)
+            .skip(kind.keep())
+            .collect();
+
+        for backup in &expired {
+            fs::remove_file(&backup.path)?;
+        }
+        Ok(expired.len())
+    }
+
+    /// Take a daily backup if the newest one is more than a day old
+    pub fn backup_if_due(db: &DatabaseManager) -> Result<Option<BackupInfo>> {
+        let dir = db.backups_dir();
+        let newest_daily = Self::list_backups(&dir)?
+            .into_iter()
+            .find(|backup| backup.kind == BackupKind::Daily);
+
+        if let Some(backup) = newest_daily {
+            if Utc::now().timestamp() - backup.created_at < SECONDS_PER_DAY {
+                return Ok(None);
+            }
+        }
+
+        // A read connection keeps the writer free while pages are copied
+        db.with_read_connection(|conn| Self::create_backup(conn, &dir, BackupKind::Daily))
+            .map(Some)
+    }
+
+    /// Replace the live database with a backup after it passes the integrity check.
+    /// The current database is backed up first so the restore can be undone.
+    pub fn restore_backup(db: &DatabaseManager, file_name: &str) -> Result<BackupInfo> {
+        let dir = db.backups_dir();
+        let backup = Self::list_backups(&dir)?
+            .into_iter()
+            .find(|backup| backup.file_name == file_name)
+            .ok_or_else(|| anyhow!("Backup not found: {}", file_name))?;
+
+        let source = Connection::open_with_flags(&backup.path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        match check_database_integrity(&source) {
+            Ok(true) => {}
+            Ok(false) => return Err(anyhow!("Backup {} failed the integrity check", file_name)),
+            Err(e) => return Err(anyhow!("Backup {} is not a readable database: {}", file_name, e)),
+        }
+        drop(source);
+
+        db.with_connection(|conn| Self::create_backup(conn, &dir, BackupKind::PreRestore))?;
+        db.restore_from(Path::new(&backup.path))?;
+
+        println!("♻️  Database restored from {}", backup.file_name);
+        Ok(backup)
+    }
+}
+
+/// Background task keeping a rotating set of daily backups
+pub async fn run_daily_backups(db: SharedDatabase) {
+    let mut interval = tokio::time::interval(DAILY_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = db.run_blocking(BackupOperations::backup_if_due).await {
+            eprintln!("⚠️  Daily database backup failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{add_library_game, test_database};
+
+    fn library_count(db: &DatabaseManager) -> i64 {
+        db.with_read_connection(|conn| {
+            Ok(conn.query_row("SELECT COUNT(*) FROM user_library", [], |row| row.get(0))?)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        assert_eq!(
+            BackupInfo::parse_file_name("games-pre-migration-20260101-120000.db"),
+            Some((BackupKind::PreMigration, 1767268800, 1))
+        );
+        assert_eq!(
+            BackupInfo::parse_file_name("games-manual-20260101-120000-3.db"),
+            Some((BackupKind::Manual, 1767268800, 3))
+        );
+        assert_eq!(BackupInfo::parse_file_name("games-manual-20260101-120000-x.db"), None);
+        assert_eq!(BackupInfo::parse_file_name("games-daily-20260101-120000.db.tmp"), None);
+        assert_eq!(BackupInfo::parse_file_name("games.db"), None);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for day in 1..=DAILY_BACKUPS_TO_KEEP + 2 {
+            fs::write(dir.path().join(format!("games-daily-202601{:02}-000000.db", day)), b"").unwrap();
+        }
+        fs::write(dir.path().join("games-manual-20250101-000000.db"), b"").unwrap();
+
+        assert_eq!(BackupOperations::rotate(dir.path(), BackupKind::Daily).unwrap(), 2);
+
+        let backups = BackupOperations::list_backups(dir.path()).unwrap();
+        assert_eq!(backups.len(), DAILY_BACKUPS_TO_KEEP + 1);
+        assert_eq!(backups[0].file_name, format!("games-daily-202601{:02}-000000.db", DAILY_BACKUPS_TO_KEEP + 2));
+        assert!(backups.iter().any(|b| b.kind == BackupKind::Manual));
+    }
+
+    #[test]
+    fn test_backups_in_the_same_second_do_not_collide() {
+        let (dir, db) = test_database();
+        let backups_dir = dir.path().join("backups");
+
+        let created: Vec<String> = (0..3)
+            .map(|_| {
+                db.with_read_connection(|conn| BackupOperations::create_backup(conn, &backups_dir, BackupKind::Manual))
+                    .unwrap()
+                    .file_name
+            })
+            .collect();
+
+        let listed: Vec<String> = BackupOperations::list_backups(&backups_dir)
+            .unwrap()
+            .into_iter()
+            .map(|backup| backup.file_name)
+            .collect();
+        assert_eq!(listed, created.into_iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_backups_within_a_second_are_listed_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        for file_name in ["games-manual-20260101-120000.db", "games-manual-20260101-120000-10.db", "games-manual-20260101-120000-2.db"] {
+            fs::write(dir.path().join(file_name), b"").unwrap();
+        }
+
+        let listed: Vec<String> = BackupOperations::list_backups(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|backup| backup.file_name)
+            .collect();
+        assert_eq!(
+            listed,
+            ["games-manual-20260101-120000-10.db", "games-manual-20260101-120000-2.db", "games-manual-20260101-120000.db"]
+        );
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let (_dir, db) = test_database();
+        db.with_connection(|conn| add_library_game(conn, "570", "Game", None)).unwrap();
+
+        let backup = BackupOperations::backup_if_due(&db).unwrap().expect("first daily backup");
+        assert!(BackupOperations::backup_if_due(&db).unwrap().is_none());
+
+        db.with_connection(|conn| add_library_game(conn, "730", "Game", None)).unwrap();
+        assert_eq!(library_count(&db), 2);
+
+        BackupOperations::restore_backup(&db, &backup.file_name).unwrap();
+        assert_eq!(library_count(&db), 1);
+
+        // The state before the restore was kept
+        let backups = BackupOperations::list_backups(&db.backups_dir()).unwrap();
+        assert!(backups.iter().any(|b| b.kind == BackupKind::PreRestore));
+    }
+
+    #[test]
+    fn test_outdated_database_is_backed_up_before_migrating() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("games.db");
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch(include_str!("migrations/fixtures/v1.sql"))
+            .unwrap();
+
+        let db = DatabaseManager::new(db_path).unwrap();
+        let backups = BackupOperations::list_backups(&db.backups_dir()).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].kind, BackupKind::PreMigration);
+
+        // The snapshot still has the old schema
+        let snapshot = Connection::open(&backups[0].path).unwrap();
+        let version: String = snapshot
+            .query_row("SELECT value FROM cache_metadata WHERE key = 'schema_version'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, "1");
+    }
+
+    #[test]
+    fn test_corrupt_backup_is_not_restored() {
+        let (_dir, db) = test_database();
+        db.with_connection(|conn| add_library_game(conn, "570", "Game", None)).unwrap();
+
+        fs::create_dir_all(db.backups_dir()).unwrap();
+        fs::write(db.backups_dir().join("games-manual-20260101-000000.db"), b"definitely not sqlite").unwrap();
+
+        assert!(BackupOperations::restore_backup(&db, "games-manual-20260101-000000.db").is_err());
+        assert!(BackupOperations::restore_backup(&db, "../games.db").is_err());
+        assert_eq!(library_count(&db), 1);
+    }
+}
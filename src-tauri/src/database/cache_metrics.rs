@@ -0,0 +1,229 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds (ms) of the API latency histogram buckets
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Cache kinds tracked by the hit/miss counters
+pub const GAME_DETAILS: &str = "game_details";
+pub const GAME_NAME: &str = "game_name";
+pub const BYPASS_GAMES: &str = "bypass_games";
+pub const CATALOGUE: &str = "catalogue";
+pub const METADATA_RESOURCES: &str = "metadata_resources";
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LookupCounts {
+    pub fresh_hits: u64,
+    pub stale_hits: u64,
+    pub misses: u64,
+}
+
+impl LookupCounts {
+    pub fn hit_ratio(&self) -> f64 {
+        let hits = self.fresh_hits + self.stale_hits;
+        let total = hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LatencyHistogram {
+    /// Cumulative counts per `LATENCY_BUCKETS_MS` bound
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum_ms: u64,
+}
+
+impl LatencyHistogram {
+    fn observe(&mut self, elapsed_ms: u64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS_MS.len()];
+        }
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS_MS) {
+            if elapsed_ms <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum_ms += elapsed_ms;
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CircuitBreakerMetrics {
+    pub open: bool,
+    pub times_opened: u64,
+    pub times_closed: u64,
+}
+
+/// Point-in-time copy of the registry, returned by `get_cache_metrics`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheMetricsSnapshot {
+    pub started_at: i64,
+    pub lookups: BTreeMap<String, LookupCounts>,
+    pub api_latency: BTreeMap<String, LatencyHistogram>,
+    pub rate_limited: u64,
+    pub circuit_breaker: CircuitBreakerMetrics,
+    pub refresh_queue_depth: u64,
+}
+
+/// In-process registry for cache and Steam API metrics
+pub struct CacheMetrics {
+    state: Mutex<CacheMetricsSnapshot>,
+}
+
+impl Default for CacheMetrics {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(CacheMetricsSnapshot {
+                started_at: chrono::Utc::now().timestamp(),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl CacheMetrics {
+    fn update(&self, f: impl FnOnce(&mut CacheMetricsSnapshot)) {
+        f(&mut self.state.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    pub fn record_hit(&self, kind: &str, stale: bool) {
+        self.update(|state| {
+            let counts = state.lookups.entry(kind.to_string()).or_default();
+            if stale {
+                counts.stale_hits += 1;
+            } else {
+                counts.fresh_hits += 1;
+            }
+        });
+    }
+
+    pub fn record_miss(&self, kind: &str) {
+        self.update(|state| state.lookups.entry(kind.to_string()).or_default().misses += 1);
+    }
+
+    pub fn observe_latency(&self, endpoint: &str, elapsed: Duration) {
+        self.update(|state| {
+            state.api_latency.entry(endpoint.to_string()).or_default().observe(elapsed.as_millis() as u64)
+        });
+    }
+
+    pub fn record_rate_limited(&self) {
+        self.update(|state| state.rate_limited += 1);
+    }
+
+    /// Count a circuit breaker state change (no-op if the state didn't change)
+    pub fn record_circuit_breaker(&self, open: bool) {
+        self.update(|state| {
+            let breaker = &mut state.circuit_breaker;
+            if breaker.open == open {
+                return;
+            }
+            breaker.open = open;
+            if open {
+                breaker.times_opened += 1;
+            } else {
+                breaker.times_closed += 1;
+            }
+        });
+    }
+
+    pub fn set_refresh_queue_depth(&self, depth: u64) {
+        self.update(|state| state.refresh_queue_depth = depth);
+    }
+
+    pub fn snapshot(&self) -> CacheMetricsSnapshot {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Prometheus text exposition of the current values, for debugging
+    pub fn to_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut out = String::new();
+
+        out.push_str("# TYPE zenith_cache_lookups_total counter\n");
+        for (kind, counts) in &snapshot.lookups {
+            for (result, value) in [("fresh_hit", counts.fresh_hits), ("stale_hit", counts.stale_hits), ("miss", counts.misses)] {
+                let _ = writeln!(out, "zenith_cache_lookups_total{{cache=\"{}\",result=\"{}\"}} {}", kind, result, value);
+            }
+        }
+
+        out.push_str("# TYPE zenith_api_request_duration_ms histogram\n");
+        for (endpoint, histogram) in &snapshot.api_latency {
+            for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(&histogram.buckets) {
+                let _ = writeln!(out, "zenith_api_request_duration_ms_bucket{{endpoint=\"{}\",le=\"{}\"}} {}", endpoint, bound, count);
+            }
+            let _ = writeln!(out, "zenith_api_request_duration_ms_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}", endpoint, histogram.count);
+            let _ = writeln!(out, "zenith_api_request_duration_ms_sum{{endpoint=\"{}\"}} {}", endpoint, histogram.sum_ms);
+            let _ = writeln!(out, "zenith_api_request_duration_ms_count{{endpoint=\"{}\"}} {}", endpoint, histogram.count);
+        }
+
+        let breaker = &snapshot.circuit_breaker;
+        let _ = writeln!(out, "# TYPE zenith_api_rate_limited_total counter\nzenith_api_rate_limited_total {}", snapshot.rate_limited);
+        let _ = writeln!(out, "# TYPE zenith_circuit_breaker_open gauge\nzenith_circuit_breaker_open {}", breaker.open as u8);
+        let _ = writeln!(out, "# TYPE zenith_circuit_breaker_transitions_total counter");
+        let _ = writeln!(out, "zenith_circuit_breaker_transitions_total{{to=\"open\"}} {}", breaker.times_opened);
+        let _ = writeln!(out, "zenith_circuit_breaker_transitions_total{{to=\"closed\"}} {}", breaker.times_closed);
+        let _ = writeln!(out, "# TYPE zenith_refresh_queue_depth gauge\nzenith_refresh_queue_depth {}", snapshot.refresh_queue_depth);
+        out
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref CACHE_METRICS: CacheMetrics = CacheMetrics::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_and_histogram() {
+        let metrics = CacheMetrics::default();
+        metrics.record_hit(GAME_DETAILS, false);
+        metrics.record_hit(GAME_DETAILS, true);
+        metrics.record_miss(GAME_DETAILS);
+        metrics.observe_latency("appdetails", Duration::from_millis(120));
+        metrics.observe_latency("appdetails", Duration::from_millis(20_000));
+        metrics.record_circuit_breaker(true);
+        metrics.record_circuit_breaker(true);
+        metrics.record_circuit_breaker(false);
+
+        let snapshot = metrics.snapshot();
+        let details = &snapshot.lookups[GAME_DETAILS];
+        assert_eq!((details.fresh_hits, details.stale_hits, details.misses), (1, 1, 1));
+        assert!((details.hit_ratio() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        let latency = &snapshot.api_latency["appdetails"];
+        assert_eq!(latency.count, 2);
+        assert_eq!(latency.buckets[1], 0); // <= 100ms
+        assert_eq!(latency.buckets[2], 1); // <= 250ms
+        assert_eq!(latency.buckets[7], 1); // 20s only counts in +Inf
+
+        assert_eq!(snapshot.circuit_breaker.times_opened, 1);
+        assert_eq!(snapshot.circuit_breaker.times_closed, 1);
+        assert!(!snapshot.circuit_breaker.open);
+    }
+
+    #[test]
+    fn test_prometheus_format() {
+        let metrics = CacheMetrics::default();
+        metrics.record_miss(GAME_NAME);
+        metrics.record_rate_limited();
+        metrics.observe_latency("appdetails", Duration::from_millis(75));
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("zenith_cache_lookups_total{cache=\"game_name\",result=\"miss\"} 1"));
+        assert!(text.contains("zenith_api_request_duration_ms_bucket{endpoint=\"appdetails\",le=\"100\"} 1"));
+        assert!(text.contains("zenith_api_request_duration_ms_bucket{endpoint=\"appdetails\",le=\"+Inf\"} 1"));
+        assert!(text.contains("zenith_api_rate_limited_total 1"));
+    }
+}
@@ -1,16 +1,38 @@
-use anyhow::Result;
-use std::sync::Arc;
-use std::path::PathBuf;
-use tokio::sync::{Mutex, Semaphore};
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use futures::stream::{self, StreamExt};
+use tokio::sync::{broadcast, Mutex, Notify, Semaphore};
 use std::collections::HashMap;
-use std::time::Duration;
-use tokio::time::sleep;
-use crate::database::{DatabaseManager, operations::*};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use crate::connectivity::CONNECTIVITY;
+use crate::database::{operations::*, shared_database, SharedDatabase};
+use crate::database::cache_metrics::{self, CACHE_METRICS};
 use crate::database::models::{Game, GameDetailDb, BypassGame, BypassInfo};
+use crate::database::refresh_queue_models::{GameDetailsUpdate, GameMetadataUpdate, QueuedRefresh, RefreshKind};
+use crate::database::refresh_queue_operations::RefreshQueueOperations;
+use crate::database::ttl_config::{FieldTtl, TtlCategory, TtlOverrides};
+use crate::steam_store::{self, AppDetails, AppDetailsError};
 use crate::GameDetail;
 
-/// Log levels for cache operations
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// How current a cached answer is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Freshness {
+    /// Within every category's TTL
+    Fresh,
+    /// Past at least one TTL; a revalidation has been scheduled
+    Stale,
+    /// Nothing usable cached
+    Missing,
+}
+
+/// Log levels for cache operations, from least to most verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LogLevel {
     Silent,   // No logging
     Error,    // Only errors
@@ -30,8 +52,41 @@ impl Default for LogLevel {
     }
 }
 
+/// `println!`, or `eprintln!` for errors and warnings, if the service's live `log_level` allows `$level`
+macro_rules! cache_log {
+    ($service:expr, $level:ident, $($arg:tt)*) => {
+        if $service.logs(LogLevel::$level) {
+            match LogLevel::$level {
+                LogLevel::Error | LogLevel::Warn => eprintln!($($arg)*),
+                _ => println!($($arg)*),
+            }
+        }
+    };
+}
+
+/// How long an open circuit breaker rejects requests before letting one probe through
+const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// How long a queued refresh is remembered, so repeated stale hits skip the database
+const QUEUED_REFRESH_MEMO_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Position of the circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BreakerState {
+    Closed,
+    /// Rejecting requests since the given instant
+    Open(Instant),
+    /// The cool-down passed and a probe request went out at the given instant; its outcome
+    /// closes or reopens the breaker. Another probe may go if this one never reports back.
+    HalfOpen(Instant),
+}
+
+/// cache_metadata key holding the persisted CacheConfig
+const CACHE_CONFIG_KEY: &str = "cache_config";
+
 /// Configuration for cache batch processing and rate limiting
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CacheConfig {
     pub max_concurrent_requests: usize,
     pub batch_size: usize,
@@ -40,6 +95,10 @@ pub struct CacheConfig {
     pub circuit_breaker_threshold: u32,
     pub max_retries: u32,
     pub log_level: LogLevel,
+    /// Per-category TTL overrides
+    pub ttl: TtlOverrides,
+    /// Size cap of the local image cache before LRU eviction
+    pub image_cache_max_mb: u64,
 }
 
 impl Default for CacheConfig {
@@ -52,122 +111,178 @@ impl Default for CacheConfig {
             circuit_breaker_threshold: 10, // Much more tolerant - only break after 10 failures
             max_retries: 5,             // More retries before giving up
             log_level: LogLevel::default(), // Use default log level
+            ttl: TtlOverrides::default(),   // Compiled TTLs
+            image_cache_max_mb: 512,
         }
     }
 }
 
+impl CacheConfig {
+    /// Reject values that would stall or hammer the Steam API
+    pub fn validate(&self) -> Result<()> {
+        let check = |ok: bool, message: &str| if ok { Ok(()) } else { Err(anyhow!("{}", message)) };
+
+        check((1..=20).contains(&self.max_concurrent_requests), "max_concurrent_requests must be between 1 and 20")?;
+        check((1..=200).contains(&self.batch_size), "batch_size must be between 1 and 200")?;
+        check(self.batch_delay_seconds <= 600, "batch_delay_seconds must be at most 600")?;
+        check(self.request_delay_ms <= 60_000, "request_delay_ms must be at most 60000")?;
+        check((1..=100).contains(&self.circuit_breaker_threshold), "circuit_breaker_threshold must be between 1 and 100")?;
+        check(self.max_retries <= 20, "max_retries must be at most 20")?;
+        check((16..=20_480).contains(&self.image_cache_max_mb), "image_cache_max_mb must be between 16 and 20480")?;
+        self.ttl.validate().map_err(|e| anyhow!(e))
+    }
+
+    /// Persisted config, with defaults for anything not overridden
+    pub fn load(conn: &Connection) -> Result<Self> {
+        match CacheMetadataOperations::get(conn, CACHE_CONFIG_KEY)? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<()> {
+        CacheMetadataOperations::set(conn, CACHE_CONFIG_KEY, &serde_json::to_string(self)?)
+    }
+}
+
 /// SQLite-based cache service to replace the old JSON cache
 pub struct SqliteCacheService {
-    db: Arc<DatabaseManager>,
-    config: CacheConfig,
+    db: SharedDatabase,
+    // Live config, replaced by set_cache_config
+    config: Arc<RwLock<CacheConfig>>,
     // Keep in-flight requests tracking to prevent duplicate API calls
     in_flight_requests: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
     // Rate limiting
     last_request_time: Arc<Mutex<u64>>,
     // Circuit breaker for API failures
     consecutive_errors: Arc<Mutex<u32>>,
-    circuit_breaker_open: Arc<Mutex<bool>>,
+    circuit_breaker: Arc<Mutex<BreakerState>>,
+    // Refreshes queued recently, checked before going to the database
+    queued_refreshes: Arc<std::sync::Mutex<HashMap<(String, RefreshKind), Instant>>>,
     // Semaphore for concurrent request limiting
     concurrent_limit: Arc<Semaphore>,
+    // Bytes fetched by full vs per-category refreshes
+    refresh_stats: Arc<RefreshCounters>,
+    // Wakes the refresh worker when something is queued
+    refresh_notify: Arc<Notify>,
+    // Revalidated game details, forwarded to the UI as `game-details-updated`
+    details_updates: broadcast::Sender<GameDetailsUpdate>,
+}
+
+/// Counters behind `PartialRefreshStats`
+#[derive(Default)]
+struct RefreshCounters {
+    full_fetches: AtomicU64,
+    full_bytes: AtomicU64,
+    partial_fetches: AtomicU64,
+    partial_bytes: AtomicU64,
+    bytes_saved: AtomicU64,
 }
 
+/// How much per-category refreshing saved compared to refetching whole records
+#[derive(Debug, Clone, Serialize)]
+pub struct PartialRefreshStats {
+    pub full_fetches: u64,
+    pub full_bytes: u64,
+    pub partial_fetches: u64,
+    pub partial_bytes: u64,
+    /// Estimated against the average full response size seen so far
+    pub bytes_saved: u64,
+}
+
+/// Revalidated details buffered for a slow event forwarder before the oldest are dropped
+const DETAILS_UPDATE_CAPACITY: usize = 64;
+
 impl SqliteCacheService {
-    /// Create new SQLite cache service with default config
+    /// Create new SQLite cache service with the persisted config
     pub fn new() -> Result<Self> {
-        Self::with_config(CacheConfig::default())
+        let config = shared_database()?
+            .with_read_connection(CacheConfig::load)
+            .unwrap_or_else(|e| {
+                eprintln!("⚠️  Ignoring saved cache config: {}", e);
+                CacheConfig::default()
+            });
+        Self::with_config(config)
     }
 
     /// Create new SQLite cache service with custom config
     pub fn with_config(config: CacheConfig) -> Result<Self> {
-        // Create database in the same location as before
-        let cache_dir = dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("zenith-launcher")
-            .join("cache");
+        Ok(Self::with_database(shared_database()?, config))
+    }
 
-        let db_path = cache_dir.join("games.db");
-        let db = Arc::new(DatabaseManager::new(db_path)?);
+    /// Cache service on `db` instead of the process-wide database
+    fn with_database(db: SharedDatabase, config: CacheConfig) -> Self {
+        config.ttl.apply();
 
-        Ok(Self {
+        Self {
             db,
-            config: config.clone(),
+            config: Arc::new(RwLock::new(config.clone())),
             in_flight_requests: Arc::new(Mutex::new(HashMap::new())),
             last_request_time: Arc::new(Mutex::new(0)),
             consecutive_errors: Arc::new(Mutex::new(0)),
-            circuit_breaker_open: Arc::new(Mutex::new(false)),
+            circuit_breaker: Arc::new(Mutex::new(BreakerState::Closed)),
+            queued_refreshes: Arc::new(std::sync::Mutex::new(HashMap::new())),
             concurrent_limit: Arc::new(Semaphore::new(config.max_concurrent_requests)),
-        })
+            refresh_stats: Arc::new(RefreshCounters::default()),
+            refresh_notify: Arc::new(Notify::new()),
+            details_updates: broadcast::channel(DETAILS_UPDATE_CAPACITY).0,
+        }
     }
 
     /// Get game details with caching and stale-while-revalidate
     pub async fn get_game_details(&self, app_id: &str) -> Option<GameDetail> {
+        self.get_game_details_with_freshness(app_id).await.0
+    }
+
+    /// Best available cached details and how fresh they are. Stale entries are returned
+    /// as-is and revalidated in the background; `game-details-updated` fires when that lands.
+    pub async fn get_game_details_with_freshness(&self, app_id: &str) -> (Option<GameDetail>, Freshness) {
         // Check database cache first with proper error handling
-        let cached_detail = match self.db.with_connection(|conn| {
-            GameDetailOperations::get_by_id(conn, app_id)
-        }) {
+        let lookup_id = app_id.to_string();
+        let cached_detail = match self.db.with_read_connection_async(move |conn| {
+            GameDetailOperations::get_by_id(conn, &lookup_id)
+        }).await {
             Ok(detail_option) => detail_option,
             Err(e) => {
                 #[cfg(debug_assertions)]
-                eprintln!("Database error for app_id {}: {}", app_id, e);
+                cache_log!(self, Error, "Database error for app_id {}: {}", app_id, e);
                 
-                // Log error but don't crash - return None to trigger API fetch
-                return None;
+                // Log error but don't crash - report a miss to trigger API fetch
+                CACHE_METRICS.record_miss(cache_metrics::GAME_DETAILS);
+                return (None, Freshness::Missing);
             }
         };
 
-        if let Some(detail) = cached_detail {
-            if !detail.is_expired() {
-                // Fresh data - return immediately
-                // Cache hit logging disabled to reduce noise
-                // #[cfg(debug_assertions)]
-                // println!("Cache HIT (fresh) for {}", app_id);
-                
-                return Some(detail.into());
-            } else {
-                // Check what categories are expired for smarter handling
-                let expired_categories = detail.get_expired_categories();
-                let now = chrono::Utc::now().timestamp();
-                let age_days = (now - detail.cached_at) / (24 * 3600);
-                
-                // Only log stale cache hits for important debugging
+        let detail = match cached_detail {
+            Some(detail) => detail,
+            None => {
+                // Cache miss logging disabled to reduce noise
                 // #[cfg(debug_assertions)]
-                // println!("Cache HIT (stale) for {}, expired: {:?}, age: {} days", app_id, expired_categories, age_days);
-                
-                // Only force fresh fetch if data is VERY old (over 1 year) or critically expired
-                let is_critically_expired = age_days > 365 || 
-                    (expired_categories.contains(&"dynamic") && age_days > 180);
-                
-                if is_critically_expired {
-                    #[cfg(debug_assertions)]
-                    println!("Data critically expired for {} (age: {} days), forcing fresh fetch", app_id, age_days);
-                    
-                    return None; // Force API call only for critically expired data
-                } else {
-                    // For all other cases, return stale data and refresh in background
-                    // This provides better UX - users get immediate response
-                    let stale_data = detail.clone();
-                    let app_id_clone = app_id.to_string();
-                    let service_clone = Arc::new(self.clone_for_background());
-                    
-                    // Background refresh logging disabled to reduce noise
-                    // #[cfg(debug_assertions)]
-                    // println!("Returning stale data for {} and refreshing in background", app_id);
-                    
-                    tokio::spawn(async move {
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                        let _ = service_clone.refresh_game_details_background(&app_id_clone).await;
-                    });
-
-                    return Some(stale_data.into());
-                }
+                // println!("Cache MISS for {}", app_id);
+                CACHE_METRICS.record_miss(cache_metrics::GAME_DETAILS);
+                return (None, Freshness::Missing);
             }
-        } else {
-            // Cache miss logging disabled to reduce noise
-            // #[cfg(debug_assertions)]
-            // println!("Cache MISS for {}", app_id);
+        };
+
+        if !detail.is_expired() {
+            // Fresh data - return immediately
+            CACHE_METRICS.record_hit(cache_metrics::GAME_DETAILS, false);
+            return (Some(detail.into()), Freshness::Fresh);
         }
 
-        None
+        // Expired data is still served unless it is genuinely unusable (anything beats nothing offline)
+        if CONNECTIVITY.is_online() && self.validate_cache_before_invalidation(&detail) {
+            #[cfg(debug_assertions)]
+            cache_log!(self, Debug, "Cached details for {} are unusable, forcing fresh fetch", app_id);
+
+            CACHE_METRICS.record_miss(cache_metrics::GAME_DETAILS);
+            return (None, Freshness::Missing);
+        }
+
+        // Return stale data now and revalidate in background - users get an immediate response
+        CACHE_METRICS.record_hit(cache_metrics::GAME_DETAILS, true);
+        drop(self.revalidate_in_background(app_id));
+        (Some(detail.into()), Freshness::Stale)
     }
 
     /// Validate cache data before invalidation
@@ -178,14 +293,14 @@ impl SqliteCacheService {
         // Don't invalidate if data is less than 30 days old, regardless of TTL
         if age_days < 30 {
             #[cfg(debug_assertions)]
-            println!("Cache validation: Data too fresh to invalidate (age: {} days)", age_days);
+            cache_log!(self, Debug, "Cache validation: Data too fresh to invalidate (age: {} days)", age_days);
             return false;
         }
         
         // Don't invalidate if we have valid core data (name, images)
         if !data.name.is_empty() && !data.header_image.is_empty() {
             #[cfg(debug_assertions)]
-            println!("Cache validation: Core data still valid, keeping cache");
+            cache_log!(self, Debug, "Cache validation: Core data still valid, keeping cache");
             return false;
         }
         
@@ -194,22 +309,22 @@ impl SqliteCacheService {
     }
 
     /// Set game details in cache with validation
-    pub fn set_game_details(&self, app_id: String, details: GameDetail) -> Result<()> {
+    pub async fn set_game_details(&self, app_id: String, details: GameDetail) -> Result<()> {
         let db_detail: GameDetailDb = details.clone().into();
         
-        let result = self.db.with_connection(|conn| {
+        let result = self.db.with_connection_async(move |conn| {
             // First ensure the game exists in games table (for foreign key constraint)
             let game = Game::new(
                 details.app_id.clone(),
                 details.name.clone(),
                 details.header_image.clone(),
-                crate::database::ttl_config::TtlConfig::GAME_NAME, // Use proper TTL
+                FieldTtl::get_field_ttl("name"), // Use proper TTL
             );
             GameOperations::upsert(conn, &game)?;
             
             // Then insert game details
             GameDetailOperations::upsert(conn, &db_detail)
-        });
+        }).await;
 
         match result {
             Ok(_) => {
@@ -220,7 +335,7 @@ impl SqliteCacheService {
             }
             Err(e) => {
                 #[cfg(debug_assertions)]
-                eprintln!("Failed to cache game details for {}: {}", app_id, e);
+                cache_log!(self, Error, "Failed to cache game details for {}: {}", app_id, e);
                 Err(e)
             }
         }
@@ -228,14 +343,16 @@ impl SqliteCacheService {
 
     /// Get game name with caching
     pub async fn get_game_name(&self, app_id: &str) -> Option<String> {
-        let cached_game = match self.db.with_connection(|conn| {
-            GameOperations::get_by_id(conn, app_id)
-        }) {
+        let lookup_id = app_id.to_string();
+        let cached_game = match self.db.with_read_connection_async(move |conn| {
+            GameOperations::get_by_id(conn, &lookup_id)
+        }).await {
             Ok(game_option) => game_option,
             Err(e) => {
                 #[cfg(debug_assertions)]
-                eprintln!("Database error getting game name for {}: {}", app_id, e);
+                cache_log!(self, Error, "Database error getting game name for {}: {}", app_id, e);
                 
+                CACHE_METRICS.record_miss(cache_metrics::GAME_NAME);
                 return None;
             }
         };
@@ -246,27 +363,30 @@ impl SqliteCacheService {
                 // #[cfg(debug_assertions)]
                 // println!("Game name cache HIT (fresh) for {}: {}", app_id, game.name);
                 
+                CACHE_METRICS.record_hit(cache_metrics::GAME_NAME, false);
                 return Some(game.name);
             } else {
                 // Stale data - return it but refresh in background
                 let stale_name = game.name.clone();
                 
                 #[cfg(debug_assertions)]
-                println!("Game name cache HIT (stale) for {}: {}", app_id, stale_name);
+                cache_log!(self, Debug, "Game name cache HIT (stale) for {}: {}", app_id, stale_name);
                 
-                self.queue_for_refresh(app_id.to_string());
+                CACHE_METRICS.record_hit(cache_metrics::GAME_NAME, true);
+                self.queue_refresh(app_id, RefreshKind::Name).await;
                 return Some(stale_name);
             }
         } else {
             #[cfg(debug_assertions)]
-            println!("Game name cache MISS for {}", app_id);
+            cache_log!(self, Debug, "Game name cache MISS for {}", app_id);
+            CACHE_METRICS.record_miss(cache_metrics::GAME_NAME);
         }
 
         None
     }
 
     /// Set game name in cache
-    pub fn set_game_name(&self, app_id: String, name: String) -> Result<()> {
+    pub async fn set_game_name(&self, app_id: String, name: String) -> Result<()> {
         // Create a basic game entry for name caching
         let game = Game::new(
             app_id.clone(),
@@ -275,9 +395,9 @@ impl SqliteCacheService {
             604800, // 7 days TTL
         );
 
-        self.db.with_connection(|conn| {
+        self.db.with_connection_async(move |conn| {
             GameOperations::upsert(conn, &game)
-        })?;
+        }).await?;
 
         // Game name cache logging disabled to reduce noise
         // #[cfg(debug_assertions)]
@@ -294,7 +414,7 @@ impl SqliteCacheService {
         match in_flight.get(app_id) {
             Some(existing_lock) => {
                 #[cfg(debug_assertions)]
-                println!("Using existing request lock for {}", app_id);
+                cache_log!(self, Debug, "Using existing request lock for {}", app_id);
                 existing_lock.clone()
             }
             None => {
@@ -302,7 +422,7 @@ impl SqliteCacheService {
                 in_flight.insert(app_id.to_string(), lock.clone());
                 
                 #[cfg(debug_assertions)]
-                println!("Created new request lock for {}", app_id);
+                cache_log!(self, Debug, "Created new request lock for {}", app_id);
                 
                 lock
             }
@@ -316,9 +436,9 @@ impl SqliteCacheService {
         
         #[cfg(debug_assertions)]
         if removed.is_some() {
-            println!("Removed request lock for {}", app_id);
+            cache_log!(self, Debug, "Removed request lock for {}", app_id);
         } else {
-            println!("Warning: Attempted to remove non-existent lock for {}", app_id);
+            cache_log!(self, Warn, "Attempted to remove non-existent lock for {}", app_id);
         }
     }
 
@@ -339,7 +459,7 @@ impl SqliteCacheService {
                 now - *last_request
             };
 
-            let base_delay = self.config.request_delay_ms;
+            let base_delay = self.get_config().request_delay_ms;
             let backoff_multiplier = 2_u64.pow((*consecutive_errors).min(5));
             let required_delay = base_delay * backoff_multiplier;
 
@@ -355,7 +475,7 @@ impl SqliteCacheService {
 
         if let Some(delay_ms) = delay {
             if delay_ms > 500 {
-                println!("Rate limiting: waiting {}ms before next request", delay_ms);
+                cache_log!(self, Debug, "Rate limiting: waiting {}ms before next request", delay_ms);
             }
             tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
         }
@@ -366,13 +486,21 @@ impl SqliteCacheService {
         let mut consecutive_errors = self.consecutive_errors.lock().await;
         *consecutive_errors += 1;
 
-        println!("API error recorded. Consecutive errors: {}", *consecutive_errors);
+        cache_log!(self, Debug, "API error recorded. Consecutive errors: {}", *consecutive_errors);
+
+        // A failed probe reopens the breaker for another cool-down
+        let mut breaker = self.circuit_breaker.lock().await;
+        if matches!(*breaker, BreakerState::HalfOpen(_)) {
+            *breaker = BreakerState::Open(Instant::now());
+            cache_log!(self, Debug, "Circuit breaker probe failed, staying open");
+            return;
+        }
 
         // Open circuit breaker if too many errors
-        if *consecutive_errors >= 5 {
-            let mut circuit_open = self.circuit_breaker_open.lock().await;
-            *circuit_open = true;
-            println!("Circuit breaker opened due to consecutive errors");
+        if *consecutive_errors >= self.get_config().circuit_breaker_threshold {
+            *breaker = BreakerState::Open(Instant::now());
+            CACHE_METRICS.record_circuit_breaker(true);
+            cache_log!(self, Info, "Circuit breaker opened due to consecutive errors");
         }
     }
 
@@ -380,25 +508,44 @@ impl SqliteCacheService {
     pub async fn reset_error_count(&self) {
         let mut consecutive_errors = self.consecutive_errors.lock().await;
         if *consecutive_errors > 0 {
-            println!("Resetting error count from {}", *consecutive_errors);
+            cache_log!(self, Debug, "Resetting error count from {}", *consecutive_errors);
             *consecutive_errors = 0;
         }
 
-        let mut circuit_open = self.circuit_breaker_open.lock().await;
-        *circuit_open = false;
+        *self.circuit_breaker.lock().await = BreakerState::Closed;
+        CACHE_METRICS.record_circuit_breaker(false);
     }
 
-    /// Check if circuit breaker is open
+    /// Check if circuit breaker is open. Once the cool-down has passed it reports closed, so
+    /// callers go ahead and the next request probes the API.
     pub async fn is_circuit_breaker_open(&self) -> bool {
-        *self.circuit_breaker_open.lock().await
+        match *self.circuit_breaker.lock().await {
+            BreakerState::Closed => false,
+            BreakerState::Open(since) | BreakerState::HalfOpen(since) => since.elapsed() < CIRCUIT_BREAKER_COOLDOWN,
+        }
+    }
+
+    /// Whether a request may go out. After the cool-down the first caller becomes the
+    /// half-open probe; everyone else waits for its outcome.
+    async fn try_pass_circuit_breaker(&self) -> bool {
+        let mut breaker = self.circuit_breaker.lock().await;
+        match *breaker {
+            BreakerState::Closed => true,
+            BreakerState::Open(since) | BreakerState::HalfOpen(since) if since.elapsed() >= CIRCUIT_BREAKER_COOLDOWN => {
+                cache_log!(self, Debug, "Circuit breaker half-open, probing the API");
+                *breaker = BreakerState::HalfOpen(Instant::now());
+                true
+            }
+            BreakerState::Open(_) | BreakerState::HalfOpen(_) => false,
+        }
     }
 
     /// Clean up expired cache entries
-    pub fn cleanup_expired(&self) -> Result<()> {
-        let cleanup_result = self.db.cleanup_expired()?;
+    pub async fn cleanup_expired(&self) -> Result<()> {
+        let cleanup_result = self.db.run_blocking(|db| db.cleanup_expired()).await?;
         
         if cleanup_result.games_deleted > 0 || cleanup_result.details_deleted > 0 {
-            println!(
+            cache_log!(self, Debug, 
                 "Cache cleanup: {} games, {} details deleted",
                 cleanup_result.games_deleted,
                 cleanup_result.details_deleted
@@ -409,50 +556,58 @@ impl SqliteCacheService {
     }
 
     /// Get cache statistics
-    pub fn cache_stats(&self) -> Result<()> {
-        let stats = self.db.get_stats()?;
-        println!("{}", stats);
+    pub async fn cache_stats(&self) -> Result<()> {
+        let stats = self.db.run_blocking(|db| db.get_stats()).await?;
+        cache_log!(self, Debug, "{}", stats);
+
+        for (kind, counts) in CACHE_METRICS.snapshot().lookups {
+            cache_log!(self, Debug, 
+                "  {}: {} fresh, {} stale, {} misses ({:.0}% hit rate)",
+                kind, counts.fresh_hits, counts.stale_hits, counts.misses, counts.hit_ratio() * 100.0
+            );
+        }
         Ok(())
     }
 
     /// Save all data (for compatibility with old interface)
-    pub fn save_to_disk(&self) {
+    pub async fn save_to_disk(&self) {
         // SQLite auto-saves, but we can vacuum occasionally for optimization
-        if let Err(e) = self.db.vacuum() {
-            eprintln!("Failed to vacuum database: {}", e);
+        if let Err(e) = self.db.run_blocking(|db| db.vacuum()).await {
+            cache_log!(self, Error, "Failed to vacuum database: {}", e);
         }
     }
 
     /// Load from disk (for compatibility with old interface)
-    pub fn load_from_disk(&self) {
+    pub async fn load_from_disk(&self) {
         // SQLite loads automatically, but we can run cleanup
-        if let Err(e) = self.cleanup_expired() {
-            eprintln!("Failed to cleanup expired entries: {}", e);
+        if let Err(e) = self.cleanup_expired().await {
+            cache_log!(self, Error, "Failed to cleanup expired entries: {}", e);
         }
     }
 
     /// Clear all cache (for compatibility)
-    pub fn clear_cache(&self) -> Result<()> {
-        self.db.with_connection(|conn| {
+    pub async fn clear_cache(&self) -> Result<()> {
+        self.db.with_connection_async(|conn| {
             conn.execute("DELETE FROM games", [])?;
             conn.execute("DELETE FROM game_details", [])?;
             conn.execute("DELETE FROM user_library", [])?;
             Ok(())
-        })?;
+        }).await?;
 
-        println!("All cache cleared");
+        cache_log!(self, Info, "All cache cleared");
         Ok(())
     }
 
     /// Invalidate (delete) game details for a specific app_id to force fresh fetch
-    pub fn invalidate_game_details(&self, app_id: &str) -> Result<()> {
-        self.db.with_connection(|conn| {
-            conn.execute("DELETE FROM game_details WHERE app_id = ?", [app_id])?;
+    pub async fn invalidate_game_details(&self, app_id: &str) -> Result<()> {
+        let target_id = app_id.to_string();
+        self.db.with_connection_async(move |conn| {
+            conn.execute("DELETE FROM game_details WHERE app_id = ?", [&target_id])?;
             Ok(())
-        })?;
+        }).await?;
         
         #[cfg(debug_assertions)]
-        println!("Invalidated game details cache for app_id: {}", app_id);
+        cache_log!(self, Debug, "Invalidated game details cache for app_id: {}", app_id);
         
         Ok(())
     }
@@ -467,114 +622,453 @@ impl SqliteCacheService {
             in_flight_requests: self.in_flight_requests.clone(),
             last_request_time: self.last_request_time.clone(),
             consecutive_errors: self.consecutive_errors.clone(),
-            circuit_breaker_open: self.circuit_breaker_open.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
+            queued_refreshes: self.queued_refreshes.clone(),
             concurrent_limit: self.concurrent_limit.clone(),
+            refresh_stats: self.refresh_stats.clone(),
+            refresh_notify: self.refresh_notify.clone(),
+            details_updates: self.details_updates.clone(),
         }
     }
 
-    /// Queue game for background refresh
-    fn queue_for_refresh(&self, app_id: String) {
-        let service_clone = Arc::new(self.clone_for_background());
+    /// Run `work` holding the game's request lock so concurrent fetches collapse into one.
+    /// Without `wait`, returns None instead of queueing behind a fetch already in flight.
+    async fn with_request_lock<T>(&self, app_id: &str, wait: bool, work: impl Future<Output = T>) -> Option<T> {
+        let request_lock = self.get_or_create_request_lock(app_id).await;
+        let guard = if wait {
+            request_lock.lock().await
+        } else {
+            request_lock.try_lock().ok()?
+        };
+
+        let result = work.await;
+        drop(guard);
+        self.remove_request_lock(app_id).await;
+        Some(result)
+    }
+
+    /// Schedule a stale-while-revalidate refresh. Skipped if this game is already being fetched;
+    /// failures fall back to the persistent refresh queue, which retries with backoff.
+    fn revalidate_in_background(&self, app_id: &str) -> tokio::task::JoinHandle<()> {
+        let service = self.clone_for_background();
+        let app_id = app_id.to_string();
+
         tokio::spawn(async move {
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            let _ = service_clone.refresh_game_name_background(&app_id).await;
+            // The refresh worker picks this up once connectivity returns
+            if CONNECTIVITY.is_offline() {
+                service.queue_refresh(&app_id, RefreshKind::Details).await;
+                return;
+            }
+
+            let revalidated = service
+                .with_request_lock(&app_id, false, service.refresh_game_details_locked(&app_id))
+                .await;
+
+            match revalidated {
+                Some(Ok(Some(detail))) => service.publish_details_update(detail),
+                Some(Ok(None)) | None => {}
+                Some(Err(e)) => {
+                    #[cfg(debug_assertions)]
+                    cache_log!(service, Warn, "Revalidation failed for {}, queueing retry: {}", app_id, e);
+                    service.queue_refresh(&app_id, RefreshKind::Details).await;
+                }
+            }
+        })
+    }
+
+    /// Tell listeners (the `game-details-updated` forwarder) that fresh details landed
+    fn publish_details_update(&self, details: GameDetail) {
+        // No receivers just means nothing is listening yet
+        let _ = self.details_updates.send(GameDetailsUpdate {
+            app_id: details.app_id.clone(),
+            freshness: Freshness::Fresh,
+            details,
         });
     }
 
-    /// Background refresh for game details
-    async fn refresh_game_details_background(&self, app_id: &str) -> Option<GameDetail> {
-        // This would call the existing fetch_game_details_background function
-        // For now, we'll implement a placeholder
-        println!("Background refresh for game details: {}", app_id);
-        None
+    /// Receive every revalidated or background-refreshed game detail
+    pub fn subscribe_details_updates(&self) -> broadcast::Receiver<GameDetailsUpdate> {
+        self.details_updates.subscribe()
     }
 
-    /// Background refresh for game name
-    async fn refresh_game_name_background(&self, app_id: &str) -> Option<String> {
-        // This would call the existing fetch_game_name_simple function
-        // For now, we'll implement a placeholder
-        println!("Background refresh for game name: {}", app_id);
-        None
+    /// Add a game to the persistent refresh queue (no-op if already queued) and wake the worker.
+    /// Refreshes queued in the last `QUEUED_REFRESH_MEMO_TTL` are skipped without touching the
+    /// database. Returns false if the refresh could not be queued.
+    pub async fn queue_refresh(&self, app_id: &str, kind: RefreshKind) -> bool {
+        {
+            let mut queued = self.queued_refreshes.lock().unwrap_or_else(|e| e.into_inner());
+            let key = (app_id.to_string(), kind);
+            if queued.get(&key).is_some_and(|at| at.elapsed() < QUEUED_REFRESH_MEMO_TTL) {
+                return true;
+            }
+            queued.retain(|_, at| at.elapsed() < QUEUED_REFRESH_MEMO_TTL);
+            queued.insert(key, Instant::now());
+        }
+
+        let queued_id = app_id.to_string();
+        let queued = self.db.with_connection_async(move |conn| {
+            let inserted = RefreshQueueOperations::enqueue(conn, &queued_id, kind)?;
+            Ok((inserted, RefreshQueueOperations::depth(conn)?))
+        }).await;
+
+        match queued {
+            Ok((inserted, depth)) => {
+                CACHE_METRICS.set_refresh_queue_depth(depth);
+                if inserted {
+                    self.refresh_notify.notify_one();
+                }
+                true
+            }
+            Err(e) => {
+                self.forget_queued_refresh(app_id, kind);
+                cache_log!(self, Error, "Failed to queue refresh for {}: {}", app_id, e);
+                false
+            }
+        }
     }
 
-    /// Get cache configuration
-    pub fn get_config(&self) -> &CacheConfig {
-        &self.config
+    /// Drop a refresh from the in-memory memo once it left the queue, so it can be queued again
+    fn forget_queued_refresh(&self, app_id: &str, kind: RefreshKind) {
+        self.queued_refreshes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(app_id.to_string(), kind));
     }
 
-    /// Batch refresh multiple games with rate limiting and semaphore control
-    pub async fn batch_refresh_games(&self, app_ids: Vec<String>) -> Result<BatchRefreshResult> {
-        let mut result = BatchRefreshResult {
-            total_requested: app_ids.len(),
-            successfully_processed: 0,
-            failed: 0,
-            skipped_circuit_breaker: 0,
+    /// Wake the refresh worker without queueing anything (e.g. to retry after the breaker closes)
+    pub fn wake_refresh_worker(&self) {
+        self.refresh_notify.notify_one();
+    }
+
+    /// Wait until something is queued, or `timeout` passes
+    pub async fn wait_for_queued_refresh(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.refresh_notify.notified()).await;
+    }
+
+    /// Refresh one batch of due queue entries, highest priority first, calling `on_update`
+    /// as each one lands. Returns how many entries were attempted.
+    pub async fn process_refresh_queue_batch(
+        &self,
+        on_update: &(dyn Fn(GameMetadataUpdate) + Sync),
+    ) -> Result<usize> {
+        if self.is_circuit_breaker_open().await || CONNECTIVITY.is_offline() {
+            return Ok(0);
+        }
+
+        let config = self.get_config();
+        let now = chrono::Utc::now().timestamp();
+        let batch = self.db.with_read_connection_async(move |conn| {
+            RefreshQueueOperations::next_batch(conn, config.batch_size, now)
+        }).await?;
+        let attempted = batch.len();
+
+        // Each refresh still takes a `concurrent_limit` permit and is throttled
+        stream::iter(batch)
+            .for_each_concurrent(config.max_concurrent_requests, |entry| self.process_queued_refresh(entry, on_update))
+            .await;
+
+        let depth = self.db.with_read_connection_async(RefreshQueueOperations::depth).await?;
+        CACHE_METRICS.set_refresh_queue_depth(depth);
+        Ok(attempted)
+    }
+
+    async fn process_queued_refresh(&self, entry: QueuedRefresh, on_update: &(dyn Fn(GameMetadataUpdate) + Sync)) {
+        let app_id = entry.app_id.clone();
+        let kind = entry.kind;
+        let result = match kind {
+            RefreshKind::Name => self.refresh_game_name_background(&app_id).await
+                .map(|name| name.map(|name| (name, crate::header_image_for(&app_id)))),
+            RefreshKind::Details => self.refresh_game_details_background(&app_id).await
+                .map(|detail| detail.map(|detail| {
+                    let summary = (detail.name.clone(), detail.header_image.clone());
+                    self.publish_details_update(detail);
+                    summary
+                })),
+        };
+
+        let update = match result {
+            Ok(refreshed) => refreshed.map(|(name, header_image)| GameMetadataUpdate {
+                app_id: app_id.clone(),
+                kind,
+                name,
+                header_image,
+            }),
+            // Leave the entry as it is; it is retried once the breaker closes or we are back online
+            Err(_) if self.is_circuit_breaker_open().await || CONNECTIVITY.is_offline() => return,
+            Err(e) => {
+                let permanent = matches!(
+                    e.downcast_ref::<AppDetailsError>(),
+                    Some(AppDetailsError::NotFound(_) | AppDetailsError::Unsuccessful(_))
+                );
+                let (failed_id, error) = (app_id.clone(), e.to_string());
+                let now = chrono::Utc::now().timestamp();
+                let retrying = self.db.with_connection_async(move |conn| {
+                    if permanent {
+                        RefreshQueueOperations::complete(conn, &failed_id, kind)?;
+                        return Ok(false);
+                    }
+                    RefreshQueueOperations::fail(conn, &failed_id, kind, &error, now)
+                }).await;
+                if !matches!(retrying, Ok(true)) {
+                    self.forget_queued_refresh(&app_id, kind);
+                }
+
+                match retrying {
+                    Ok(true) => cache_log!(self, Warn, "⚠️  Background {} refresh failed for {}, will retry: {}", kind.as_str(), app_id, e),
+                    Ok(false) => cache_log!(self, Error, "❌ Giving up on background {} refresh for {}: {}", kind.as_str(), app_id, e),
+                    Err(db_error) => cache_log!(self, Error, "Failed to reschedule refresh for {}: {}", app_id, db_error),
+                }
+                return;
+            }
         };
 
-        // Check circuit breaker first
-        if self.is_circuit_breaker_open().await {
-            println!("Circuit breaker open - skipping batch refresh of {} games", app_ids.len());
-            result.skipped_circuit_breaker = app_ids.len();
-            return Ok(result);
+        let done_id = app_id.clone();
+        if let Err(e) = self.db.with_connection_async(move |conn| {
+            RefreshQueueOperations::complete(conn, &done_id, kind)
+        }).await {
+            cache_log!(self, Error, "Failed to dequeue refresh for {}: {}", app_id, e);
         }
+        self.forget_queued_refresh(&app_id, kind);
 
-        println!("Starting batch refresh of {} games", app_ids.len());
+        if let Some(update) = update {
+            #[cfg(debug_assertions)]
+            cache_log!(self, Info, "✅ Background {} refresh landed for {}: {}", kind.as_str(), app_id, update.name);
+            on_update(update);
+        }
+    }
 
-        // Process in chunks to avoid overwhelming Steam API
-        for (batch_num, chunk) in app_ids.chunks(self.config.batch_size).enumerate() {
-            println!("Processing batch {} of {} games", batch_num + 1, chunk.len());
-            
-            // Process each game in the chunk sequentially with rate limiting
-            for app_id in chunk {
-                // Acquire semaphore permit
-                let _permit = self.concurrent_limit.acquire().await.unwrap();
-                
-                // Simulate background refresh (placeholder)
-                println!("Background refresh for game details: {}", app_id);
-                tokio::time::sleep(Duration::from_millis(self.config.request_delay_ms)).await;
-                
-                // For now, simulate success
-                result.successfully_processed += 1;
-                
-                #[cfg(debug_assertions)]
-                println!("✅ Successfully refreshed: {}", app_id);
-                
-                // Check if we should stop due to errors
-                if self.is_circuit_breaker_open().await {
-                    println!("Circuit breaker opened mid-processing - stopping");
-                    let remaining_in_chunk = chunk.len() - (chunk.iter().position(|x| x == app_id).unwrap() + 1);
-                    let remaining_batches = app_ids.chunks(self.config.batch_size).skip(batch_num + 1).count();
-                    result.skipped_circuit_breaker = remaining_in_chunk + (remaining_batches * self.config.batch_size);
-                    return Ok(result);
+    /// Background refresh for game details, waiting behind any fetch already in flight
+    async fn refresh_game_details_background(&self, app_id: &str) -> Result<Option<GameDetail>> {
+        self.with_request_lock(app_id, true, self.refresh_game_details_locked(app_id))
+            .await
+            .unwrap_or(Ok(None))
+    }
+
+    /// Refetch only the expired categories if cached, otherwise everything.
+    /// The caller holds the game's request lock.
+    async fn refresh_game_details_locked(&self, app_id: &str) -> Result<Option<GameDetail>> {
+        if let Some(detail) = self.refresh_expired_categories(app_id).await? {
+            return Ok(Some(detail));
+        }
+
+        let fetched = self.fetch_app_details_throttled(app_id, &[]).await?;
+        self.record_full_fetch(fetched.response_bytes);
+
+        let detail = steam_store::parse_game_detail(app_id, &fetched.data);
+        self.set_game_details(app_id.to_string(), detail.clone()).await?;
+        Ok(Some(detail))
+    }
+
+    /// Fetch appdetails behind `concurrent_limit` and `throttle_request`, feeding the circuit
+    /// breaker. Callers hold the game's request lock.
+    async fn fetch_app_details_throttled(&self, app_id: &str, filters: &[&str]) -> Result<AppDetails> {
+        if !self.try_pass_circuit_breaker().await {
+            return Err(anyhow!("circuit breaker open"));
+        }
+
+        let _permit = self.concurrent_limit.acquire().await?;
+        self.throttle_request().await;
+
+        match steam_store::fetch_app_details(app_id, filters).await {
+            Ok(fetched) => {
+                self.reset_error_count().await;
+                Ok(fetched)
+            }
+            Err(e) => {
+                match e {
+                    AppDetailsError::RateLimited | AppDetailsError::Request(_) => self.record_error().await,
+                    // Nothing was sent, so the breaker learns nothing
+                    AppDetailsError::Offline => {}
+                    // Steam answered, just not with details; that is enough to close the breaker
+                    _ => self.reset_error_count().await,
                 }
+                Err(e.into())
             }
-            
-            // Delay between batches
-            if batch_num < (app_ids.len() / self.config.batch_size) {
-                println!("Batch {} completed. Waiting {} seconds before next batch...", 
-                         batch_num + 1, self.config.batch_delay_seconds);
-                sleep(Duration::from_secs(self.config.batch_delay_seconds)).await;
+        }
+    }
+
+    /// Refetch only the expired categories of a cached entry (via appdetails `filters=`)
+    /// and merge them into the existing row. Returns None if nothing is cached yet.
+    /// The caller holds the game's request lock.
+    async fn refresh_expired_categories(&self, app_id: &str) -> Result<Option<GameDetail>> {
+        let lookup_id = app_id.to_string();
+        let cached = self.db.with_read_connection_async(move |conn| {
+            GameDetailOperations::get_by_id(conn, &lookup_id)
+        }).await?;
+
+        let detail = match cached {
+            Some(detail) => detail,
+            None => return Ok(None),
+        };
+        let categories: Vec<TtlCategory> = detail.get_expired_categories()
+            .into_iter()
+            .filter_map(TtlCategory::from_key)
+            .collect();
+        if categories.is_empty() {
+            return Ok(Some(detail.into()));
+        }
+
+        let filters: Vec<&str> = categories.iter()
+            .flat_map(|category| steam_store::category_filters(*category).iter().copied())
+            .collect();
+        let fetched = self.fetch_app_details_throttled(app_id, &filters).await?;
+        self.record_partial_fetch(fetched.response_bytes);
+
+        self.apply_partial_refresh(app_id, &fetched.data, &categories).await
+    }
+
+    /// Merge a (possibly filtered) appdetails response into the cached row for `categories`
+    pub async fn apply_partial_refresh(
+        &self,
+        app_id: &str,
+        data: &serde_json::Value,
+        categories: &[TtlCategory],
+    ) -> Result<Option<GameDetail>> {
+        let target_id = app_id.to_string();
+        let data = data.clone();
+        let to_merge = categories.to_vec();
+
+        let merged = self.db.with_connection_async(move |conn| {
+            let mut detail = match GameDetailOperations::get_by_id(conn, &target_id)? {
+                Some(detail) => detail,
+                None => return Ok(None),
+            };
+            steam_store::merge_categories(&mut detail, &data, &to_merge);
+            GameDetailOperations::upsert(conn, &detail)?;
+            Ok(Some(detail))
+        }).await?;
+
+        #[cfg(debug_assertions)]
+        if merged.is_some() {
+            cache_log!(self, Info, "🔄 Partially refreshed {} ({:?})", app_id, categories);
+        }
+        Ok(merged.map(Into::into))
+    }
+
+    /// Record the size of a full appdetails response (baseline for bytes-saved estimates)
+    pub fn record_full_fetch(&self, bytes: usize) {
+        self.refresh_stats.full_fetches.fetch_add(1, Ordering::Relaxed);
+        self.refresh_stats.full_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn record_partial_fetch(&self, bytes: usize) {
+        let stats = &self.refresh_stats;
+        stats.partial_fetches.fetch_add(1, Ordering::Relaxed);
+        stats.partial_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+
+        let full_fetches = stats.full_fetches.load(Ordering::Relaxed);
+        if let Some(average_full) = stats.full_bytes.load(Ordering::Relaxed).checked_div(full_fetches) {
+            stats.bytes_saved.fetch_add(average_full.saturating_sub(bytes as u64), Ordering::Relaxed);
+        }
+    }
+
+    /// Bytes fetched and saved by per-category refreshes since startup
+    pub fn get_refresh_stats(&self) -> PartialRefreshStats {
+        let stats = &self.refresh_stats;
+        PartialRefreshStats {
+            full_fetches: stats.full_fetches.load(Ordering::Relaxed),
+            full_bytes: stats.full_bytes.load(Ordering::Relaxed),
+            partial_fetches: stats.partial_fetches.load(Ordering::Relaxed),
+            partial_bytes: stats.partial_bytes.load(Ordering::Relaxed),
+            bytes_saved: stats.bytes_saved.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Background refresh for game name (fetches just the `name` key)
+    async fn refresh_game_name_background(&self, app_id: &str) -> Result<Option<String>> {
+        let fetched = self
+            .with_request_lock(app_id, true, self.fetch_app_details_throttled(app_id, &["name"]))
+            .await
+            .unwrap_or_else(|| Err(anyhow!("request lock unavailable")))?;
+        self.record_partial_fetch(fetched.response_bytes);
+
+        let name = match fetched.data.get("name").and_then(|name| name.as_str()) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => return Ok(None),
+        };
+        self.set_game_name(app_id.to_string(), name.clone()).await?;
+        Ok(Some(name))
+    }
+
+    /// Whether messages at `level` pass the configured log level
+    fn logs(&self, level: LogLevel) -> bool {
+        level <= self.config.read().unwrap_or_else(|e| e.into_inner()).log_level
+    }
+
+    /// Get cache configuration
+    pub fn get_config(&self) -> CacheConfig {
+        self.config.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Validate, persist and apply a new configuration without restarting
+    pub async fn update_config(&self, config: CacheConfig) -> Result<CacheConfig> {
+        config.validate()?;
+
+        let to_save = config.clone();
+        self.db.with_connection_async(move |conn| to_save.save(conn)).await?;
+
+        let previous = std::mem::replace(&mut *self.config.write().unwrap_or_else(|e| e.into_inner()), config.clone());
+        self.resize_concurrent_limit(previous.max_concurrent_requests, config.max_concurrent_requests);
+        config.ttl.apply();
+
+        cache_log!(self, Info, "⚙️  Cache config updated");
+        Ok(config)
+    }
+
+    /// Grow or shrink the request semaphore to a new concurrency limit
+    fn resize_concurrent_limit(&self, from: usize, to: usize) {
+        if to > from {
+            self.concurrent_limit.add_permits(to - from);
+        } else if from > to {
+            let excess = from - to;
+            let forgotten = self.concurrent_limit.forget_permits(excess);
+            if forgotten < excess {
+                // The rest are held by in-flight requests; retire them as they come back
+                let semaphore = self.concurrent_limit.clone();
+                tokio::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned((excess - forgotten) as u32).await {
+                        permits.forget();
+                    }
+                });
             }
         }
-        
-        println!("Batch refresh completed: {}/{} successful, {} failed, {} skipped", 
-                 result.successfully_processed, result.total_requested, 
-                 result.failed, result.skipped_circuit_breaker);
-        
-        Ok(result)
+    }
+
+    /// Queue full details refreshes for the background worker and wake it. The worker applies
+    /// the rate limits, batching and circuit breaker; this only reports what was queued.
+    pub async fn batch_refresh_games(&self, app_ids: Vec<String>) -> BatchRefreshResult {
+        let mut result = BatchRefreshResult {
+            total_requested: app_ids.len(),
+            queued: 0,
+            failed: 0,
+        };
+
+        for app_id in &app_ids {
+            if self.queue_refresh(app_id, RefreshKind::Details).await {
+                result.queued += 1;
+            } else {
+                result.failed += 1;
+            }
+        }
+        self.wake_refresh_worker();
+
+        cache_log!(self, Info, "Batch refresh: {}/{} games queued, {} failed",
+                 result.queued, result.total_requested, result.failed);
+        result
     }
 
     /// Smart refresh games based on granular TTL priority and staleness
-    pub async fn smart_refresh_library(&self, library_games: Vec<String>) -> Result<BatchRefreshResult> {
+    pub async fn smart_refresh_library(&self, library_games: Vec<String>) -> BatchRefreshResult {
         let mut refresh_queue: Vec<(String, u8, Vec<String>)> = Vec::new(); // (app_id, priority, expired_categories)
         
-        println!("Analyzing {} library games for granular TTL smart refresh", library_games.len());
+        cache_log!(self, Debug, "Analyzing {} library games for granular TTL smart refresh", library_games.len());
         
         // Categorize games by granular expiry
         for app_id in library_games {
-            if let Ok(Some(detail)) = self.db.with_connection(|conn| {
-                GameDetailOperations::get_by_id(conn, &app_id)
-            }) {
+            let lookup_id = app_id.clone();
+            if let Ok(Some(detail)) = self.db.with_read_connection_async(move |conn| {
+                GameDetailOperations::get_by_id(conn, &lookup_id)
+            }).await {
                 let expired_categories = detail.get_expired_categories();
                 
                 if expired_categories.is_empty() {
@@ -608,30 +1102,30 @@ impl SqliteCacheService {
         let medium_priority = refresh_queue.iter().filter(|(_, p, _)| *p == 1).count();
         let low_priority = refresh_queue.iter().filter(|(_, p, _)| *p == 2).count();
         
-        println!("Smart refresh priority breakdown:");
-        println!("  🔴 High (Dynamic expired): {} games", high_priority);
-        println!("  🟡 Medium (Semi-static expired): {} games", medium_priority);
-        println!("  🟢 Low (Static expired): {} games", low_priority);
+        cache_log!(self, Debug, "Smart refresh priority breakdown:");
+        cache_log!(self, Debug, "  🔴 High (Dynamic expired): {} games", high_priority);
+        cache_log!(self, Debug, "  🟡 Medium (Semi-static expired): {} games", medium_priority);
+        cache_log!(self, Debug, "  🟢 Low (Static expired): {} games", low_priority);
         
         // Extract app_ids for batch processing
         let app_ids: Vec<String> = refresh_queue.into_iter()
             .map(|(app_id, _, _)| app_id)
             .collect();
         
-        println!("Smart refresh: {} games queued (granular TTL priority)", app_ids.len());
+        cache_log!(self, Info, "Smart refresh: {} games queued (granular TTL priority)", app_ids.len());
         
         // Use batch processing
         self.batch_refresh_games(app_ids).await
     }
 
     /// Get games that need refresh based on granular TTL
-    pub fn get_games_needing_refresh(&self) -> Result<Vec<(String, Vec<String>)>> {
+    pub async fn get_games_needing_refresh(&self) -> Result<Vec<(String, Vec<String>)>> {
         let mut games_needing_refresh = Vec::new();
         
         // Get games with any expired category
-        let expired_games = self.db.with_connection(|conn| {
+        let expired_games = self.db.with_read_connection_async(|conn| {
             GameDetailOperations::get_any_expired(conn)
-        })?;
+        }).await?;
         
         for game in expired_games {
             let expired_categories = game.get_expired_categories();
@@ -642,7 +1136,7 @@ impl SqliteCacheService {
     }
 
     /// Cleanup expired data by category (more efficient than full refresh)
-    pub fn cleanup_expired_by_category(&self) -> Result<GranularCleanupResult> {
+    pub async fn cleanup_expired_by_category(&self) -> Result<GranularCleanupResult> {
         let mut result = GranularCleanupResult {
             dynamic_expired: 0,
             semistatic_expired: 0,
@@ -650,19 +1144,17 @@ impl SqliteCacheService {
             total_cleaned: 0,
         };
         
-        self.db.with_connection(|conn| {
-            // Count expired by category
-            result.dynamic_expired = GameDetailOperations::get_dynamic_expired(conn)?.len();
-            
-            // For now, we'll just log the counts (actual cleanup would require partial updates)
-            println!("Granular TTL cleanup analysis:");
-            println!("  Dynamic data expired: {} games", result.dynamic_expired);
-            
-            // Note: Actual selective cleanup would require more complex SQL updates
-            // For now, we maintain existing behavior but with better insights
-            
-            Ok(())
-        })?;
+        // Count expired by category
+        result.dynamic_expired = self.db.with_read_connection_async(|conn| {
+            Ok(GameDetailOperations::get_dynamic_expired(conn)?.len())
+        }).await?;
+        
+        // For now, we'll just log the counts (actual cleanup would require partial updates)
+        cache_log!(self, Debug, "Granular TTL cleanup analysis:");
+        cache_log!(self, Debug, "  Dynamic data expired: {} games", result.dynamic_expired);
+        
+        // Note: Actual selective cleanup would require more complex SQL updates
+        // For now, we maintain existing behavior but with better insights
         
         Ok(result)
     }
@@ -672,17 +1164,25 @@ impl SqliteCacheService {
     /// Get all bypass games from cache with 1 month TTL
     pub async fn get_bypass_games(&self) -> Result<Vec<BypassGame>> {
         // Try to get from cache first
-        let cached_games = self.db.with_connection(|conn| {
+        let cached_games = self.db.with_read_connection_async(|conn| {
             BypassGameOperations::get_all(conn)
-        })?;
+        }).await?;
 
         // Check if we have valid cached data
         if !cached_games.is_empty() && !cached_games.iter().any(|game| game.is_expired()) {
             // Bypass cache hit logging disabled to reduce noise
             // println!("Bypass games cache HIT: {} games", cached_games.len());
+            CACHE_METRICS.record_hit(cache_metrics::BYPASS_GAMES, false);
             return Ok(cached_games);
         }
 
+        // Offline, an expired list is better than an error
+        if !cached_games.is_empty() && CONNECTIVITY.is_offline() {
+            CACHE_METRICS.record_hit(cache_metrics::BYPASS_GAMES, true);
+            return Ok(cached_games);
+        }
+        CACHE_METRICS.record_miss(cache_metrics::BYPASS_GAMES);
+
         // Only log when loading from external source
         // println!("Bypass games cache MISS or expired - loading from fallback JSON");
         
@@ -695,11 +1195,11 @@ impl SqliteCacheService {
         // Try to fetch from GitHub API first
         let json_data = match self.fetch_bypass_games_from_github().await {
             Ok(data) => {
-                println!("Successfully fetched bypass games from GitHub API");
+                cache_log!(self, Info, "Successfully fetched bypass games from GitHub API");
                 data
             }
             Err(e) => {
-                eprintln!("Failed to fetch from GitHub API: {}", e);
+                cache_log!(self, Warn, "Failed to fetch from GitHub API: {}", e);
                 // Return error instead of fallback - GitHub API should be the single source of truth
                 return Err(anyhow::anyhow!("Unable to fetch bypass games from GitHub API: {}. Please check your internet connection.", e));
             }
@@ -747,34 +1247,36 @@ impl SqliteCacheService {
         }
 
         // Cache the data
-        self.db.with_connection(|conn| {
+        let games_to_cache = bypass_games.clone();
+        self.db.with_connection_async(move |conn| {
             // Clear existing data first
             BypassGameOperations::clear_all(conn)?;
             
             // Insert new data
-            for game in &bypass_games {
+            for game in &games_to_cache {
                 BypassGameOperations::insert(conn, game)?;
             }
             
             Ok(())
-        })?;
+        }).await?;
 
-        println!("Bypass games cached successfully: {} games", bypass_games.len());
+        cache_log!(self, Info, "Bypass games cached successfully: {} games", bypass_games.len());
         Ok(bypass_games)
     }
 
     /// Force refresh bypass games cache (useful for updates)
     pub async fn refresh_bypass_games(&self) -> Result<Vec<BypassGame>> {
-        println!("Force refreshing bypass games cache...");
+        cache_log!(self, Info, "Force refreshing bypass games cache...");
         self.load_bypass_games_from_json().await
     }
 
     /// Get bypass game by app_id
     pub async fn get_bypass_game(&self, app_id: &str) -> Result<Option<BypassGame>> {
         // Check cache first
-        let cached_game = self.db.with_connection(|conn| {
-            BypassGameOperations::get_by_id(conn, app_id)
-        })?;
+        let lookup_id = app_id.to_string();
+        let cached_game = self.db.with_read_connection_async(move |conn| {
+            BypassGameOperations::get_by_id(conn, &lookup_id)
+        }).await?;
 
         if let Some(game) = cached_game {
             if !game.is_expired() {
@@ -793,13 +1295,18 @@ impl SqliteCacheService {
         
         // GitHub API URL for the bypass games JSON file
         let github_api_url = "https://api.github.com/repos/itsbintang/bypass-games-api/contents/bypassGames.json";
+        CONNECTIVITY.ensure_online("the bypass games list")?;
         
         let client = reqwest::Client::new();
+        let started = std::time::Instant::now();
         let response = client
             .get(github_api_url)
             .header("User-Agent", "Zenith-Launcher")
             .send()
-            .await?;
+            .await
+            .inspect_err(|e| CONNECTIVITY.record_failure(e))?;
+        CONNECTIVITY.record_success();
+        CACHE_METRICS.observe_latency("github_bypass_games", started.elapsed());
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API returned error: {}", response.status()));
@@ -892,9 +1399,8 @@ where
 #[derive(Debug)]
 pub struct BatchRefreshResult {
     pub total_requested: usize,
-    pub successfully_processed: usize,
+    pub queued: usize,
     pub failed: usize,
-    pub skipped_circuit_breaker: usize,
 }
 
 /// Result of granular TTL cleanup operation
@@ -911,3 +1417,213 @@ lazy_static::lazy_static! {
         Arc::new(SqliteCacheService::new().expect("Failed to initialize SQLite cache service"))
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+
+    fn test_service(config: CacheConfig) -> (tempfile::TempDir, SqliteCacheService) {
+        let (dir, db) = test_database();
+        (dir, SqliteCacheService::with_database(db, config))
+    }
+
+    /// Pretend the breaker opened (or probed) a full cool-down ago
+    async fn expire_cool_down(service: &SqliteCacheService) {
+        let long_ago = Instant::now() - CIRCUIT_BREAKER_COOLDOWN;
+        let mut breaker = service.circuit_breaker.lock().await;
+        *breaker = match *breaker {
+            BreakerState::Open(_) => BreakerState::Open(long_ago),
+            BreakerState::HalfOpen(_) => BreakerState::HalfOpen(long_ago),
+            BreakerState::Closed => BreakerState::Closed,
+        };
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(CacheConfig::default().validate().is_ok());
+        assert!(CacheConfig { max_concurrent_requests: 0, ..Default::default() }.validate().is_err());
+        assert!(CacheConfig { batch_size: 1000, ..Default::default() }.validate().is_err());
+
+        let bad_ttl = TtlOverrides { dynamic: Some(10), ..Default::default() };
+        assert!(CacheConfig { ttl: bad_ttl, ..Default::default() }.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_log_level_follows_live_config() {
+        let (_dir, service) = test_service(CacheConfig { log_level: LogLevel::Warn, ..Default::default() });
+        assert!(service.logs(LogLevel::Error));
+        assert!(service.logs(LogLevel::Warn));
+        assert!(!service.logs(LogLevel::Info));
+
+        service.update_config(CacheConfig { log_level: LogLevel::Silent, ..Default::default() }).await.unwrap();
+        assert!(!service.logs(LogLevel::Error));
+    }
+
+    #[test]
+    fn test_config_persists_in_cache_metadata() {
+        let (_dir, db) = test_database();
+        assert_eq!(db.with_read_connection(CacheConfig::load).unwrap(), CacheConfig::default());
+
+        let config = CacheConfig {
+            batch_size: 5,
+            log_level: LogLevel::Debug,
+            ttl: TtlOverrides { semistatic: Some(86400), ..Default::default() },
+            ..Default::default()
+        };
+        db.with_connection(|conn| config.save(conn)).unwrap();
+        assert_eq!(db.with_read_connection(CacheConfig::load).unwrap(), config);
+
+        // Keys missing from an older saved config fall back to defaults
+        db.with_connection(|conn| CacheMetadataOperations::set(conn, CACHE_CONFIG_KEY, r#"{"batch_size": 7}"#))
+            .unwrap();
+        let loaded = db.with_read_connection(CacheConfig::load).unwrap();
+        assert_eq!(loaded.batch_size, 7);
+        assert_eq!(loaded.max_concurrent_requests, CacheConfig::default().max_concurrent_requests);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_probes_after_cool_down() {
+        let (_dir, service) = test_service(CacheConfig { circuit_breaker_threshold: 2, ..Default::default() });
+
+        service.record_error().await;
+        assert!(!service.is_circuit_breaker_open().await);
+        service.record_error().await;
+        assert!(service.is_circuit_breaker_open().await);
+        assert!(!service.try_pass_circuit_breaker().await);
+
+        // After the cool-down one probe goes out while everything else keeps waiting
+        expire_cool_down(&service).await;
+        assert!(!service.is_circuit_breaker_open().await);
+        assert!(service.try_pass_circuit_breaker().await);
+        assert!(!service.try_pass_circuit_breaker().await);
+        assert!(service.is_circuit_breaker_open().await);
+
+        // A failed probe reopens it for another cool-down
+        service.record_error().await;
+        assert!(matches!(*service.circuit_breaker.lock().await, BreakerState::Open(_)));
+        assert!(!service.try_pass_circuit_breaker().await);
+
+        // A probe that never reports back does not keep it half-open forever
+        expire_cool_down(&service).await;
+        assert!(service.try_pass_circuit_breaker().await);
+        expire_cool_down(&service).await;
+        assert!(service.try_pass_circuit_breaker().await);
+
+        // A successful probe closes it
+        service.reset_error_count().await;
+        assert!(!service.is_circuit_breaker_open().await);
+        assert!(service.try_pass_circuit_breaker().await);
+        assert!(service.try_pass_circuit_breaker().await);
+    }
+
+    #[tokio::test]
+    async fn test_queue_refresh_skips_recently_queued() {
+        let (_dir, service) = test_service(CacheConfig::default());
+        let queued = |service: &SqliteCacheService| {
+            service.db.with_read_connection(RefreshQueueOperations::depth).unwrap()
+        };
+
+        service.queue_refresh("620", RefreshKind::Name).await;
+        service.queue_refresh("620", RefreshKind::Details).await;
+        assert_eq!(queued(&service), 2);
+
+        // Repeated stale hits are answered from memory, without a database round trip
+        service.db.with_connection(|conn| RefreshQueueOperations::complete(conn, "620", RefreshKind::Name)).unwrap();
+        service.queue_refresh("620", RefreshKind::Name).await;
+        assert_eq!(queued(&service), 1);
+
+        // Once the refresh has left the queue it can be queued again
+        service.forget_queued_refresh("620", RefreshKind::Name);
+        service.queue_refresh("620", RefreshKind::Name).await;
+        assert_eq!(queued(&service), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_refresh_queues_details() {
+        let (_dir, service) = test_service(CacheConfig::default());
+        service.queue_refresh("620", RefreshKind::Details).await;
+
+        let result = service.batch_refresh_games(vec!["620".to_string(), "400".to_string()]).await;
+        assert_eq!((result.total_requested, result.queued, result.failed), (2, 2, 0));
+
+        let now = chrono::Utc::now().timestamp();
+        let pending = service.db.with_read_connection(|conn| RefreshQueueOperations::next_batch(conn, 10, now)).unwrap();
+        let mut queued: Vec<(&str, RefreshKind)> = pending.iter().map(|e| (e.app_id.as_str(), e.kind)).collect();
+        queued.sort_by_key(|(app_id, _)| *app_id);
+        assert_eq!(queued, vec![("400", RefreshKind::Details), ("620", RefreshKind::Details)]);
+    }
+
+    fn cached_detail(app_id: &str, name: &str) -> GameDetail {
+        steam_store::parse_game_detail(app_id, &serde_json::json!({ "name": name, "publishers": ["Valve"] }))
+    }
+
+    #[tokio::test]
+    async fn test_details_freshness() {
+        let (_dir, service) = test_service(CacheConfig::default());
+
+        let (detail, freshness) = service.get_game_details_with_freshness("620").await;
+        assert!(detail.is_none());
+        assert_eq!(freshness, Freshness::Missing);
+
+        service.set_game_details("620".to_string(), cached_detail("620", "Portal 2")).await.unwrap();
+        let (detail, freshness) = service.get_game_details_with_freshness("620").await;
+        assert_eq!(freshness, Freshness::Fresh);
+        assert_eq!(detail.unwrap().name, "Portal 2");
+
+        // Past its TTL it is still served, while a revalidation runs behind it. Holding the
+        // game's request lock keeps that revalidation from reaching the network.
+        service.db.with_connection(|conn| {
+            conn.execute("UPDATE game_details SET dynamic_expires_at = 0 WHERE app_id = '620'", [])?;
+            Ok(())
+        })
+        .unwrap();
+        let request_lock = service.get_or_create_request_lock("620").await;
+        let _in_flight = request_lock.lock().await;
+        let (detail, freshness) = service.get_game_details_with_freshness("620").await;
+        assert_eq!(freshness, Freshness::Stale);
+        assert_eq!(detail.unwrap().name, "Portal 2");
+    }
+
+    #[tokio::test]
+    async fn test_revalidation_skips_games_already_being_fetched() {
+        let (_dir, service) = test_service(CacheConfig::default());
+        let mut updates = service.subscribe_details_updates();
+
+        let request_lock = service.get_or_create_request_lock("620").await;
+        let in_flight = request_lock.lock().await;
+        service.revalidate_in_background("620").await.unwrap();
+        drop(in_flight);
+
+        // The fetch already in flight answers for it: nothing published, nothing queued
+        assert!(matches!(updates.try_recv(), Err(broadcast::error::TryRecvError::Empty)));
+        if CONNECTIVITY.is_online() {
+            assert_eq!(service.db.with_read_connection(RefreshQueueOperations::depth).unwrap(), 0);
+        }
+
+        // The work of a collapsed request never runs
+        let ran = std::sync::atomic::AtomicBool::new(false);
+        let _in_flight = request_lock.lock().await;
+        let skipped = service
+            .with_request_lock("620", false, async { ran.store(true, Ordering::SeqCst) })
+            .await;
+        assert!(skipped.is_none());
+        assert!(!ran.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_revalidated_details_are_broadcast() {
+        let (_dir, service) = test_service(CacheConfig::default());
+        let mut first = service.subscribe_details_updates();
+        let mut second = service.clone_for_background().subscribe_details_updates();
+
+        service.publish_details_update(cached_detail("620", "Portal 2"));
+
+        for updates in [&mut first, &mut second] {
+            let update = updates.recv().await.unwrap();
+            assert_eq!(update.app_id, "620");
+            assert_eq!(update.freshness, Freshness::Fresh);
+            assert_eq!(update.details.name, "Portal 2");
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use tauri::{command, State};
+use crate::database::{
+    catalogue_cache_models::CatalogueCacheStats, catalogue_cache_operations::CatalogueCacheOperations, SharedDatabase,
+};
+
+/// Cached catalogue pages, searches and metadata files
+#[command]
+pub async fn get_catalogue_cache_stats(db: State<'_, SharedDatabase>) -> Result<CatalogueCacheStats, String> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_read_connection_async(move |conn| CatalogueCacheOperations::stats(conn, now))
+        .await
+        .map_err(|e| format!("Failed to get catalogue cache stats: {}", e))
+}
+
+/// Forget every cached catalogue page, search and metadata file
+#[command]
+pub async fn clear_catalogue_cache(db: State<'_, SharedDatabase>) -> Result<usize, String> {
+    db.with_connection_async(CatalogueCacheOperations::clear)
+        .await
+        .map_err(|e| format!("Failed to clear catalogue cache: {}", e))
+}
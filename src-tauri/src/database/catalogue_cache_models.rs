@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use super::ttl_config::TtlConfig;
+
+/// Cached searches kept for offline use; beyond this the least recently used are pruned
+pub const MAX_CACHED_SEARCHES: usize = 500;
+
+/// Cached catalogue pages kept for offline use, pruned the same way
+pub const MAX_CACHED_PAGES: usize = 200;
+
+/// What a cached catalogue response answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogueCacheKind {
+    /// A plain catalogue page (no title or filters)
+    Page,
+    /// A title search or filtered listing
+    Search,
+}
+
+impl CatalogueCacheKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CatalogueCacheKind::Page => "page",
+            CatalogueCacheKind::Search => "search",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "page" => Some(CatalogueCacheKind::Page),
+            "search" => Some(CatalogueCacheKind::Search),
+            _ => None,
+        }
+    }
+
+    /// How many entries of this kind are kept before the least recently used are pruned
+    pub fn max_cached(&self) -> usize {
+        match self {
+            CatalogueCacheKind::Page => MAX_CACHED_PAGES,
+            CatalogueCacheKind::Search => MAX_CACHED_SEARCHES,
+        }
+    }
+
+    pub fn ttl(&self) -> i64 {
+        match self {
+            CatalogueCacheKind::Page => TtlConfig::CATALOGUE_PAGE,
+            CatalogueCacheKind::Search => TtlConfig::CATALOGUE_SEARCH,
+        }
+    }
+}
+
+/// A row of `catalogue_cache`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedCatalogueResponse {
+    pub cache_key: String,
+    pub kind: CatalogueCacheKind,
+    pub response_json: String,
+    pub etag: Option<String>,
+    pub cached_at: i64,
+    pub expires_at: i64,
+    pub last_accessed: i64,
+}
+
+impl CachedCatalogueResponse {
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() > self.expires_at
+    }
+}
+
+/// A row of `metadata_resources`: one of the filter metadata JSON files, verbatim
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedMetadataResource {
+    pub name: String,
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub cached_at: i64,
+    pub expires_at: i64,
+}
+
+impl CachedMetadataResource {
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() > self.expires_at
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CatalogueCacheStats {
+    pub pages: i64,
+    pub searches: i64,
+    pub metadata_resources: i64,
+    pub expired: i64,
+    pub total_bytes: i64,
+}
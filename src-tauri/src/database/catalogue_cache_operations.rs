@@ -0,0 +1,210 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use super::catalogue_cache_models::{
+    CachedCatalogueResponse, CachedMetadataResource, CatalogueCacheKind, CatalogueCacheStats,
+};
+
+pub struct CatalogueCacheOperations;
+
+impl CatalogueCacheOperations {
+    pub fn get(conn: &Connection, cache_key: &str) -> Result<Option<CachedCatalogueResponse>> {
+        Ok(conn
+            .query_row(
+                "SELECT cache_key, kind, response_json, etag, cached_at, expires_at, last_accessed
+                 FROM catalogue_cache WHERE cache_key = ?1",
+                [cache_key],
+                Self::from_row,
+            )
+            .optional()?)
+    }
+
+    pub fn upsert(conn: &Connection, entry: &CachedCatalogueResponse) -> Result<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO catalogue_cache
+                (cache_key, kind, response_json, etag, cached_at, expires_at, last_accessed)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                entry.cache_key,
+                entry.kind.as_str(),
+                entry.response_json,
+                entry.etag,
+                entry.cached_at,
+                entry.expires_at,
+                entry.last_accessed,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn touch(conn: &Connection, cache_key: &str, now: i64) -> Result<()> {
+        conn.execute(
+            "UPDATE catalogue_cache SET last_accessed = ?2 WHERE cache_key = ?1",
+            params![cache_key, now],
+        )?;
+        Ok(())
+    }
+
+    /// Keep a response the server confirmed unchanged (304) for another TTL
+    pub fn revalidated(conn: &Connection, cache_key: &str, now: i64, expires_at: i64) -> Result<()> {
+        conn.execute(
+            "UPDATE catalogue_cache SET cached_at = ?2, expires_at = ?3, last_accessed = ?2 WHERE cache_key = ?1",
+            params![cache_key, now, expires_at],
+        )?;
+        Ok(())
+    }
+
+    /// Raw bodies of every cached page and search, expired or not
+    pub fn response_bodies(conn: &Connection) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare("SELECT response_json FROM catalogue_cache")?;
+        let bodies = stmt.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(bodies)
+    }
+
+    /// Drop the least recently used entries of `kind` beyond `keep`
+    pub fn prune(conn: &Connection, kind: CatalogueCacheKind, keep: usize) -> Result<usize> {
+        Ok(conn.execute(
+            "DELETE FROM catalogue_cache WHERE cache_key IN (
+                SELECT cache_key FROM catalogue_cache WHERE kind = ?1
+                ORDER BY last_accessed DESC LIMIT -1 OFFSET ?2
+             )",
+            params![kind.as_str(), keep as i64],
+        )?)
+    }
+
+    pub fn get_resource(conn: &Connection, name: &str) -> Result<Option<CachedMetadataResource>> {
+        Ok(conn
+            .query_row(
+                "SELECT name, body, etag, last_modified, cached_at, expires_at FROM metadata_resources WHERE name = ?1",
+                [name],
+                |row| {
+                    Ok(CachedMetadataResource {
+                        name: row.get(0)?,
+                        body: row.get(1)?,
+                        etag: row.get(2)?,
+                        last_modified: row.get(3)?,
+                        cached_at: row.get(4)?,
+                        expires_at: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    pub fn upsert_resource(conn: &Connection, resource: &CachedMetadataResource) -> Result<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata_resources (name, body, etag, last_modified, cached_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                resource.name,
+                resource.body,
+                resource.etag,
+                resource.last_modified,
+                resource.cached_at,
+                resource.expires_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Keep a resource the server confirmed unchanged (304) for another TTL
+    pub fn resource_revalidated(conn: &Connection, name: &str, now: i64, expires_at: i64) -> Result<()> {
+        conn.execute(
+            "UPDATE metadata_resources SET cached_at = ?2, expires_at = ?3 WHERE name = ?1",
+            params![name, now, expires_at],
+        )?;
+        Ok(())
+    }
+
+    /// Remove every cached page, search and metadata file. Returns how many rows went.
+    pub fn clear(conn: &Connection) -> Result<usize> {
+        let tx = conn.unchecked_transaction()?;
+        let removed = tx.execute("DELETE FROM catalogue_cache", [])? + tx.execute("DELETE FROM metadata_resources", [])?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    pub fn stats(conn: &Connection, now: i64) -> Result<CatalogueCacheStats> {
+        let (pages, searches, expired, catalogue_bytes): (i64, i64, i64, i64) = conn.query_row(
+            "SELECT COALESCE(SUM(kind = 'page'), 0), COALESCE(SUM(kind = 'search'), 0),
+                    COALESCE(SUM(expires_at < ?1), 0), COALESCE(SUM(LENGTH(response_json)), 0)
+             FROM catalogue_cache",
+            [now],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        let (metadata_resources, expired_resources, resource_bytes): (i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(expires_at < ?1), 0), COALESCE(SUM(LENGTH(body)), 0) FROM metadata_resources",
+            [now],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        Ok(CatalogueCacheStats {
+            pages,
+            searches,
+            metadata_resources,
+            expired: expired + expired_resources,
+            total_bytes: catalogue_bytes + resource_bytes,
+        })
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<CachedCatalogueResponse> {
+        let kind: String = row.get(1)?;
+        Ok(CachedCatalogueResponse {
+            cache_key: row.get(0)?,
+            kind: CatalogueCacheKind::parse(&kind).unwrap_or(CatalogueCacheKind::Search),
+            response_json: row.get(2)?,
+            etag: row.get(3)?,
+            cached_at: row.get(4)?,
+            expires_at: row.get(5)?,
+            last_accessed: row.get(6)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+
+    fn search(cache_key: &str, last_accessed: i64) -> CachedCatalogueResponse {
+        CachedCatalogueResponse {
+            cache_key: cache_key.to_string(),
+            kind: CatalogueCacheKind::Search,
+            response_json: r#"{"edges":[],"count":0}"#.to_string(),
+            etag: None,
+            cached_at: last_accessed,
+            expires_at: last_accessed + 60,
+            last_accessed,
+        }
+    }
+
+    #[test]
+    fn test_prune_keeps_most_recently_used_of_each_kind() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            for (key, accessed) in [("page-1", 0), ("page-2", 5)] {
+                let page = CachedCatalogueResponse { kind: CatalogueCacheKind::Page, ..search(key, accessed) };
+                CatalogueCacheOperations::upsert(conn, &page)?;
+            }
+            for (key, accessed) in [("a", 1), ("b", 2), ("c", 3)] {
+                CatalogueCacheOperations::upsert(conn, &search(key, accessed))?;
+            }
+            CatalogueCacheOperations::touch(conn, "a", 10)?;
+
+            assert_eq!(CatalogueCacheOperations::prune(conn, CatalogueCacheKind::Search, 2)?, 1);
+            assert!(CatalogueCacheOperations::get(conn, "b")?.is_none());
+            assert!(CatalogueCacheOperations::get(conn, "a")?.is_some());
+            assert!(CatalogueCacheOperations::get(conn, "page-1")?.is_some());
+
+            assert_eq!(CatalogueCacheOperations::prune(conn, CatalogueCacheKind::Page, 1)?, 1);
+            assert!(CatalogueCacheOperations::get(conn, "page-1")?.is_none());
+            assert!(CatalogueCacheOperations::get(conn, "page-2")?.is_some());
+
+            CatalogueCacheOperations::revalidated(conn, "c", 100, 200)?;
+            let stats = CatalogueCacheOperations::stats(conn, 150)?;
+            assert_eq!((stats.pages, stats.searches, stats.expired), (1, 2, 2));
+            Ok(())
+        })
+        .unwrap();
+    }
+}
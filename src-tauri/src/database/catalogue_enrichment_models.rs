@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// What the local database knows about a Steam app shown in the catalogue
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocalGameState {
+    pub in_library: bool,
+    /// A `game_details` row exists, fresh or not
+    pub has_cached_details: bool,
+    pub release_date: Option<String>,
+    pub publisher: Option<String>,
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use rusqlite::{params_from_iter, Connection};
+use std::collections::HashMap;
+use super::catalogue_enrichment_models::LocalGameState;
+
+pub struct CatalogueEnrichmentOperations;
+
+impl CatalogueEnrichmentOperations {
+    /// Library membership and cached details for each app id. Apps the database knows
+    /// nothing about are left out.
+    pub fn local_state(conn: &Connection, app_ids: &[String]) -> Result<HashMap<String, LocalGameState>> {
+        if app_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let placeholders = vec!["(?)"; app_ids.len()].join(", ");
+        let sql = format!(
+            "WITH ids(app_id) AS (VALUES {})
+             SELECT ids.app_id, l.app_id IS NOT NULL, d.app_id IS NOT NULL,
+                    NULLIF(d.release_date, ''), NULLIF(d.publisher, '')
+             FROM ids
+             LEFT JOIN user_library l ON l.app_id = ids.app_id
+             LEFT JOIN game_details d ON d.app_id = ids.app_id
+             WHERE l.app_id IS NOT NULL OR d.app_id IS NOT NULL",
+            placeholders
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(app_ids), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                LocalGameState {
+                    in_library: row.get(1)?,
+                    has_cached_details: row.get(2)?,
+                    release_date: row.get(3)?,
+                    publisher: row.get(4)?,
+                },
+            ))
+        })?;
+
+        let mut states = HashMap::new();
+        for row in rows {
+            let (app_id, state) = row?;
+            states.insert(app_id, state);
+        }
+        Ok(states)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+
+    #[test]
+    fn test_local_state_joins_library_and_details() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            conn.execute_batch(
+                "INSERT INTO games (app_id, name, cached_at, expires_at) VALUES ('220', 'Half-Life 2', 0, 0), ('620', 'Portal 2', 0, 0);
+                 INSERT INTO user_library (app_id) VALUES ('220');
+                 INSERT INTO game_details (app_id, name, release_date, publisher, cached_at, expires_at)
+                     VALUES ('220', 'Half-Life 2', '16 Nov, 2004', 'Valve', 0, 0),
+                            ('620', 'Portal 2', '', '', 0, 0);",
+            )?;
+
+            let ids: Vec<String> = ["220", "620", "550"].iter().map(|id| id.to_string()).collect();
+            let states = CatalogueEnrichmentOperations::local_state(conn, &ids)?;
+
+            assert_eq!(
+                states["220"],
+                LocalGameState {
+                    in_library: true,
+                    has_cached_details: true,
+                    release_date: Some("16 Nov, 2004".to_string()),
+                    publisher: Some("Valve".to_string()),
+                }
+            );
+            assert_eq!(
+                states["620"],
+                LocalGameState { has_cached_details: true, ..Default::default() }
+            );
+            assert!(!states.contains_key("550"));
+            Ok(())
+        })
+        .unwrap();
+    }
+}
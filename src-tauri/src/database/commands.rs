@@ -1,10 +1,14 @@
 /// Tauri commands for database operations and migration
 /// These commands will be exposed to the frontend for testing and management
 
-use tauri::command;
+use tauri::{command, State};
 use crate::database::{
+    cache_metrics::{CacheMetricsSnapshot, CACHE_METRICS},
+    cache_service::{CacheConfig, LogLevel, PartialRefreshStats},
     migration_utils::{auto_migrate_if_needed, CacheMigrator},
-    DatabaseManager,
+    migrations::{self, SchemaMigrationStatus, SchemaRollbackReport},
+    ttl_config::{TtlCategory, TtlOverrides},
+    SharedDatabase,
 };
 use anyhow::Result;
 
@@ -31,41 +35,54 @@ pub async fn get_migration_status() -> Result<String, String> {
     Ok(status.to_string())
 }
 
+/// Applied schema migrations and a dry run of the pending ones
 #[command]
-pub async fn get_database_stats() -> Result<DatabaseStats, String> {
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    let db_path = cache_dir.join("games.db");
-    let db = DatabaseManager::new(db_path.clone()).map_err(|e| e.to_string())?;
-    
-    let stats = db.get_stats().map_err(|e| e.to_string())?;
+pub async fn get_schema_migration_status(db: State<'_, SharedDatabase>) -> Result<SchemaMigrationStatus, String> {
+    db.run_blocking(|db| db.with_connection(migrations::migration_status))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Revert schema migrations down to `target_version` (re-applied on next start). The down
+/// scripts drop data, so `confirm` must be set and a backup is taken first.
+#[command]
+pub async fn rollback_schema_migrations(
+    target_version: i32,
+    confirm: bool,
+    db: State<'_, SharedDatabase>,
+) -> Result<SchemaRollbackReport, String> {
+    if !confirm {
+        return Err("Rolling back migrations deletes data; confirm to continue".to_string());
+    }
+
+    db.run_blocking(move |db| {
+        let backups_dir = db.backups_dir();
+        db.with_connection(|conn| migrations::rollback_to(conn, &backups_dir, target_version))
+    })
+    .await
+    .map_err(|e| format!("Failed to roll back migrations: {}", e))
+}
+
+#[command]
+pub async fn get_database_stats(db: State<'_, SharedDatabase>) -> Result<DatabaseStats, String> {
+    let stats = db.run_blocking(|db| db.get_stats()).await.map_err(|e| e.to_string())?;
     
     Ok(DatabaseStats {
         games_count: stats.games_count,
         game_details_count: stats.game_details_count,
         library_count: stats.library_count,
         file_size_mb: stats.file_size_bytes as f64 / 1024.0 / 1024.0,
-        database_exists: db_path.exists(),
+        database_exists: db.db_path().exists(),
     })
 }
 
 #[command]
-pub async fn debug_cache_entry(app_id: String) -> Result<String, String> {
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    let db_path = cache_dir.join("games.db");
-    let db = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    let result = db.with_connection(|conn| {
+pub async fn debug_cache_entry(app_id: String, db: State<'_, SharedDatabase>) -> Result<String, String> {
+    let id = app_id.clone();
+    let result = db.with_read_connection_async(move |conn| {
         use crate::database::operations::GameDetailOperations;
-        GameDetailOperations::get_by_id(conn, &app_id)
-    }).map_err(|e| e.to_string())?;
+        GameDetailOperations::get_by_id(conn, &id)
+    }).await.map_err(|e| e.to_string())?;
     
     match result {
         Some(detail) => {
@@ -109,35 +126,19 @@ pub async fn debug_cache_entry(app_id: String) -> Result<String, String> {
 }
 
 #[command]
-pub async fn force_clear_cache() -> Result<String, String> {
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    let db_path = cache_dir.join("games.db");
-    let db = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db.with_connection(|conn| {
+pub async fn force_clear_cache(db: State<'_, SharedDatabase>) -> Result<String, String> {
+    db.with_connection_async(|conn| {
         conn.execute("DELETE FROM game_details", [])?;
         conn.execute("DELETE FROM games", [])?;
         Ok(())
-    }).map_err(|e: anyhow::Error| e.to_string())?;
+    }).await.map_err(|e: anyhow::Error| e.to_string())?;
     
     Ok("Cache cleared successfully".to_string())
 }
 
 #[command]
-pub async fn cleanup_expired_cache() -> Result<String, String> {
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    let db_path = cache_dir.join("games.db");
-    let db = DatabaseManager::new(db_path.clone()).map_err(|e| e.to_string())?;
-    
-    let cleanup_result = db.cleanup_expired().map_err(|e| e.to_string())?;
+pub async fn cleanup_expired_cache(db: State<'_, SharedDatabase>) -> Result<String, String> {
+    let cleanup_result = db.run_blocking(|db| db.cleanup_expired()).await.map_err(|e| e.to_string())?;
     
     Ok(format!(
         "Cleanup completed: {} games, {} details removed",
@@ -146,16 +147,8 @@ pub async fn cleanup_expired_cache() -> Result<String, String> {
 }
 
 #[command]
-pub async fn vacuum_database() -> Result<String, String> {
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    let db_path = cache_dir.join("games.db");
-    let db = DatabaseManager::new(db_path.clone()).map_err(|e| e.to_string())?;
-    
-    db.vacuum().map_err(|e| e.to_string())?;
+pub async fn vacuum_database(db: State<'_, SharedDatabase>) -> Result<String, String> {
+    db.run_blocking(|db| db.vacuum()).await.map_err(|e| e.to_string())?;
     
     Ok("Database vacuum completed".to_string())
 }
@@ -165,7 +158,7 @@ pub async fn test_sqlite_connection() -> Result<String, String> {
     let service = crate::database::cache_service::SqliteCacheService::new().map_err(|e| e.to_string())?;
     
     // Try to perform a simple operation
-    match service.clear_cache() {
+    match service.clear_cache().await {
         Ok(_) => Ok("SQLite connection test successful".to_string()),
         Err(e) => Err(format!("SQLite connection test failed: {}", e)),
     }
@@ -188,38 +181,33 @@ pub struct DatabaseStats {
     pub database_exists: bool,
 }
 
-/// Safely refresh multiple games using batch processing
+/// Queue multiple games for the background refresh worker
 #[command]
 pub async fn batch_refresh_games(app_ids: Vec<String>) -> Result<String, String> {
     use crate::database::legacy_adapter::SQLITE_GAME_CACHE_ADAPTER;
     
-    let result = SQLITE_GAME_CACHE_ADAPTER.batch_refresh_games(app_ids).await
-        .map_err(|e| e.to_string())?;
+    let result = SQLITE_GAME_CACHE_ADAPTER.batch_refresh_games(app_ids).await;
     
     Ok(format!(
-        "Batch refresh completed: {}/{} successful, {} failed, {} skipped due to circuit breaker",
-        result.successfully_processed,
+        "Batch refresh queued: {}/{} games queued, {} failed",
+        result.queued,
         result.total_requested,
-        result.failed,
-        result.skipped_circuit_breaker
+        result.failed
     ))
 }
 
-/// Smart refresh library games based on staleness priority
+/// Queue stale library games for the background refresh worker
 #[command]
 pub async fn smart_refresh_library(app_ids: Vec<String>) -> Result<String, String> {
     use crate::database::legacy_adapter::SQLITE_GAME_CACHE_ADAPTER;
     
-    let result = SQLITE_GAME_CACHE_ADAPTER.smart_refresh_library(app_ids).await
-        .map_err(|e| e.to_string())?;
+    let result = SQLITE_GAME_CACHE_ADAPTER.smart_refresh_library(app_ids).await;
     
     Ok(format!(
-        "Smart library refresh completed: {}/{} games processed ({} successful, {} failed, {} skipped)",
-        result.successfully_processed + result.failed,
+        "Smart library refresh queued: {}/{} stale games queued, {} failed",
+        result.queued,
         result.total_requested,
-        result.successfully_processed,
-        result.failed,
-        result.skipped_circuit_breaker
+        result.failed
     ))
 }
 
@@ -229,17 +217,65 @@ pub async fn get_cache_config() -> Result<CacheConfigInfo, String> {
     use crate::database::legacy_adapter::SQLITE_GAME_CACHE_ADAPTER;
     
     let config = SQLITE_GAME_CACHE_ADAPTER.sqlite_service().get_config();
-    
-    Ok(CacheConfigInfo {
-        max_concurrent_requests: config.max_concurrent_requests,
-        batch_size: config.batch_size,
-        batch_delay_seconds: config.batch_delay_seconds,
-        request_delay_ms: config.request_delay_ms,
-        circuit_breaker_threshold: config.circuit_breaker_threshold,
-        max_retries: config.max_retries,
+    Ok(CacheConfigInfo::from(config))
+}
+
+/// Update cache configuration; changes are validated, persisted and applied immediately
+#[command]
+pub async fn set_cache_config(update: CacheConfigUpdate) -> Result<CacheConfigInfo, String> {
+    use crate::database::legacy_adapter::SQLITE_GAME_CACHE_ADAPTER;
+
+    let service = SQLITE_GAME_CACHE_ADAPTER.sqlite_service();
+    let mut config = service.get_config();
+    let image_cap_changed = update.image_cache_max_mb.is_some();
+    update.apply_to(&mut config);
+
+    let config = service.update_config(config).await
+        .map_err(|e| format!("Failed to update cache config: {}", e))?;
+
+    // A smaller image cache cap takes effect right away
+    if image_cap_changed {
+        tauri::async_runtime::spawn(async {
+            if let Err(e) = crate::image_cache::IMAGE_CACHE.evict_to_limit().await {
+                eprintln!("⚠️  Image cache eviction failed: {}", e);
+            }
+        });
+    }
+    Ok(CacheConfigInfo::from(config))
+}
+
+/// Bytes fetched and saved by per-category (partial) game detail refreshes
+#[command]
+pub async fn get_partial_refresh_stats() -> Result<PartialRefreshStats, String> {
+    use crate::database::legacy_adapter::SQLITE_GAME_CACHE_ADAPTER;
+
+    Ok(SQLITE_GAME_CACHE_ADAPTER.sqlite_service().get_refresh_stats())
+}
+
+/// Cache hit/miss counters, Steam API latency, rate limiting and circuit breaker metrics
+#[command]
+pub async fn get_cache_metrics() -> Result<CacheMetricsInfo, String> {
+    use crate::database::legacy_adapter::SQLITE_GAME_CACHE_ADAPTER;
+
+    Ok(CacheMetricsInfo {
+        metrics: CACHE_METRICS.snapshot(),
+        partial_refresh: SQLITE_GAME_CACHE_ADAPTER.sqlite_service().get_refresh_stats(),
     })
 }
 
+/// Same metrics in Prometheus text format, for debugging
+#[command]
+pub async fn get_cache_metrics_prometheus() -> Result<String, String> {
+    Ok(CACHE_METRICS.to_prometheus())
+}
+
+#[derive(serde::Serialize)]
+pub struct CacheMetricsInfo {
+    #[serde(flatten)]
+    pub metrics: CacheMetricsSnapshot,
+    pub partial_refresh: PartialRefreshStats,
+}
+
 /// Cache configuration info for frontend
 #[derive(serde::Serialize)]
 pub struct CacheConfigInfo {
@@ -249,6 +285,62 @@ pub struct CacheConfigInfo {
     pub request_delay_ms: u64,
     pub circuit_breaker_threshold: u32,
     pub max_retries: u32,
+    pub log_level: LogLevel,
+    /// Overrides as set by the user (null = compiled default)
+    pub ttl_overrides: TtlOverrides,
+    /// TTLs in effect per category, in seconds
+    pub ttl_dynamic_seconds: i64,
+    pub ttl_semistatic_seconds: i64,
+    pub ttl_static_seconds: i64,
+    pub image_cache_max_mb: u64,
+}
+
+impl From<CacheConfig> for CacheConfigInfo {
+    fn from(config: CacheConfig) -> Self {
+        Self {
+            max_concurrent_requests: config.max_concurrent_requests,
+            batch_size: config.batch_size,
+            batch_delay_seconds: config.batch_delay_seconds,
+            request_delay_ms: config.request_delay_ms,
+            circuit_breaker_threshold: config.circuit_breaker_threshold,
+            max_retries: config.max_retries,
+            log_level: config.log_level,
+            ttl_dynamic_seconds: config.ttl.category_ttl(TtlCategory::Dynamic),
+            ttl_semistatic_seconds: config.ttl.category_ttl(TtlCategory::SemiStatic),
+            ttl_static_seconds: config.ttl.category_ttl(TtlCategory::Static),
+            ttl_overrides: config.ttl,
+            image_cache_max_mb: config.image_cache_max_mb,
+        }
+    }
+}
+
+/// Partial cache configuration update; omitted fields keep their current value
+#[derive(serde::Deserialize, Default)]
+pub struct CacheConfigUpdate {
+    pub max_concurrent_requests: Option<usize>,
+    pub batch_size: Option<usize>,
+    pub batch_delay_seconds: Option<u64>,
+    pub request_delay_ms: Option<u64>,
+    pub circuit_breaker_threshold: Option<u32>,
+    pub max_retries: Option<u32>,
+    pub log_level: Option<LogLevel>,
+    /// Replaces all TTL overrides; null categories go back to the compiled default
+    pub ttl_overrides: Option<TtlOverrides>,
+    pub image_cache_max_mb: Option<u64>,
+}
+
+impl CacheConfigUpdate {
+    fn apply_to(self, config: &mut CacheConfig) {
+        if let Some(value) = self.max_concurrent_requests { config.max_concurrent_requests = value; }
+        if let Some(value) = self.batch_size { config.batch_size = value; }
+        if let Some(value) = self.batch_delay_seconds { config.batch_delay_seconds = value; }
+        if let Some(value) = self.request_delay_ms { config.request_delay_ms = value; }
+        if let Some(value) = self.circuit_breaker_threshold { config.circuit_breaker_threshold = value; }
+        if let Some(value) = self.max_retries { config.max_retries = value; }
+        if let Some(value) = self.log_level { config.log_level = value; }
+        if let Some(value) = self.ttl_overrides { config.ttl = value; }
+        if let Some(value) = self.image_cache_max_mb { config.image_cache_max_mb = value; }
+    }
 }
 
 // ============= BYPASS GAMES COMMANDS =============
@@ -314,18 +406,10 @@ pub async fn refresh_bypass_games_cache() -> Result<String, String> {
 
 /// Clear bypass games cache specifically
 #[command]
-pub async fn clear_bypass_games_cache() -> Result<String, String> {
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    let db_path = cache_dir.join("games.db");
-    let db = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db.with_connection(|conn| {
+pub async fn clear_bypass_games_cache(db: State<'_, SharedDatabase>) -> Result<String, String> {
+    db.with_connection_async(|conn| {
         crate::database::operations::BypassGameOperations::clear_all(conn)
-    }).map_err(|e: anyhow::Error| e.to_string())?;
+    }).await.map_err(|e: anyhow::Error| e.to_string())?;
     
     Ok("Bypass games cache cleared successfully".to_string())
 }
@@ -359,42 +443,25 @@ pub async fn get_bypass_game_by_id(app_id: String) -> Result<Option<FrontendBypa
 
 /// Get bypass games cache statistics
 #[command]
-pub async fn get_bypass_games_cache_stats() -> Result<BypassGamesCacheStats, String> {
-    use crate::database::DatabaseManager;
-    use std::path::PathBuf;
-    
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    let db_path = cache_dir.join("games.db");
-    
-    match DatabaseManager::new(db_path) {
-        Ok(db) => {
-            match db.with_connection(|conn| {
-                use crate::database::operations::BypassGameOperations;
-                
-                let total_games = BypassGameOperations::count(conn)?;
-                let expired_games = BypassGameOperations::get_expired(conn)?.len() as u32;
-                let valid_games = total_games - expired_games;
-                
-                Ok(BypassGamesCacheStats {
-                    total_games,
-                    valid_games,
-                    expired_games,
-                    cache_hit_rate: if total_games > 0 {
-                        (valid_games as f64 / total_games as f64) * 100.0
-                    } else {
-                        0.0
-                    },
-                })
-            }) {
-                Ok(stats) => Ok(stats),
-                Err(e) => Err(format!("Database error: {}", e)),
-            }
-        }
-        Err(e) => Err(format!("Failed to connect to database: {}", e)),
-    }
+pub async fn get_bypass_games_cache_stats(db: State<'_, SharedDatabase>) -> Result<BypassGamesCacheStats, String> {
+    db.with_read_connection_async(|conn| {
+        use crate::database::operations::BypassGameOperations;
+        
+        let total_games = BypassGameOperations::count(conn)?;
+        let expired_games = BypassGameOperations::get_expired(conn)?.len() as u32;
+        let valid_games = total_games - expired_games;
+        
+        Ok(BypassGamesCacheStats {
+            total_games,
+            valid_games,
+            expired_games,
+            cache_hit_rate: if total_games > 0 {
+                (valid_games as f64 / total_games as f64) * 100.0
+            } else {
+                0.0
+            },
+        })
+    }).await.map_err(|e| format!("Database error: {}", e))
 }
 
 #[derive(serde::Serialize, Debug)]
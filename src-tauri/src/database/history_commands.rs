@@ -1,18 +1,7 @@
-use tauri::command;
-use crate::database::{history_models::*, history_operations::DownloadHistoryOperations, DatabaseManager};
-use std::path::PathBuf;
-use std::fs;
-
-fn get_history_db_path() -> Result<PathBuf, anyhow::Error> {
-    // Use the same database path as the main cache database
-    let cache_dir = dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("zenith-launcher")
-        .join("cache");
-    
-    fs::create_dir_all(&cache_dir)?;
-    Ok(cache_dir.join("games.db"))
-}
+use tauri::{command, State};
+use crate::database::{
+    history_models::*, history_operations::DownloadHistoryOperations, shared_database, SharedDatabase,
+};
 
 /// Get download history with pagination and filtering
 #[command]
@@ -20,28 +9,21 @@ pub async fn get_download_history(
     limit: Option<u32>,
     offset: Option<u32>,
     filter_type: Option<String>, // 'bypass', 'regular', or None for all
+    db: State<'_, SharedDatabase>,
 ) -> Result<Vec<DownloadHistorySummary>, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    let filter = filter_type.as_deref();
-    
-    db_manager.with_connection(|conn| {
-        DownloadHistoryOperations::get_history_summary(conn, limit, offset, filter)
+    db.with_read_connection_async(move |conn| {
+        DownloadHistoryOperations::get_history_summary(conn, limit, offset, filter_type.as_deref())
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to get download history: {}", e))
+    }).await.map_err(|e| format!("Failed to get download history: {}", e))
 }
 
 /// Get download history statistics
 #[command]
-pub async fn get_download_history_stats() -> Result<HistoryStats, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db_manager.with_connection(|conn| {
+pub async fn get_download_history_stats(db: State<'_, SharedDatabase>) -> Result<HistoryStats, String> {
+    db.with_read_connection_async(move |conn| {
         DownloadHistoryOperations::get_history_stats(conn)
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to get history stats: {}", e))
+    }).await.map_err(|e| format!("Failed to get history stats: {}", e))
 }
 
 /// Search download history
@@ -49,56 +31,43 @@ pub async fn get_download_history_stats() -> Result<HistoryStats, String> {
 pub async fn search_download_history(
     search_term: String,
     limit: Option<u32>,
+    db: State<'_, SharedDatabase>,
 ) -> Result<Vec<DownloadHistorySummary>, String> {
     if search_term.trim().is_empty() {
         return Ok(Vec::new());
     }
     
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db_manager.with_connection(|conn| {
+    db.with_read_connection_async(move |conn| {
         DownloadHistoryOperations::search_history(conn, &search_term, limit)
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to search history: {}", e))
+    }).await.map_err(|e| format!("Failed to search history: {}", e))
 }
 
 /// Get full download history entry by ID
 #[command]
-pub async fn get_download_history_entry(id: i64) -> Result<Option<DownloadHistoryEntry>, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db_manager.with_connection(|conn| {
+pub async fn get_download_history_entry(id: i64, db: State<'_, SharedDatabase>) -> Result<Option<DownloadHistoryEntry>, String> {
+    db.with_read_connection_async(move |conn| {
         DownloadHistoryOperations::get_download_by_id(conn, id)
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to get download entry: {}", e))
+    }).await.map_err(|e| format!("Failed to get download entry: {}", e))
 }
 
 /// Delete download history entry
 #[command]
-pub async fn delete_download_history_entry(id: i64) -> Result<bool, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    db_manager.with_connection(|conn| {
+pub async fn delete_download_history_entry(id: i64, db: State<'_, SharedDatabase>) -> Result<bool, String> {
+    db.with_connection_async(move |conn| {
         DownloadHistoryOperations::delete_history_entry(conn, id)
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to delete history entry: {}", e))
+    }).await.map_err(|e| format!("Failed to delete history entry: {}", e))
 }
 
 /// Clear download history (with optional filter)
 #[command]
-pub async fn clear_download_history(filter_type: Option<String>) -> Result<u32, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
-    let filter = filter_type.as_deref();
-    
-    db_manager.with_connection(|conn| {
-        DownloadHistoryOperations::clear_history(conn, filter)
+pub async fn clear_download_history(filter_type: Option<String>, db: State<'_, SharedDatabase>) -> Result<u32, String> {
+    db.with_connection_async(move |conn| {
+        DownloadHistoryOperations::clear_history(conn, filter_type.as_deref())
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to clear history: {}", e))
+    }).await.map_err(|e| format!("Failed to clear history: {}", e))
 }
 
 /// Re-download from history entry
@@ -106,15 +75,13 @@ pub async fn clear_download_history(filter_type: Option<String>) -> Result<u32,
 pub async fn redownload_from_history(
     history_id: i64,
     new_save_path: Option<String>,
+    db: State<'_, SharedDatabase>,
 ) -> Result<String, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
-    
     // Get the original download entry
-    let entry = db_manager.with_connection(|conn| {
+    let entry = db.with_read_connection_async(move |conn| {
         DownloadHistoryOperations::get_download_by_id(conn, history_id)
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to get history entry: {}", e))?
+    }).await.map_err(|e| format!("Failed to get history entry: {}", e))?
         .ok_or("History entry not found")?;
     
     if !entry.is_redownloadable {
@@ -136,11 +103,10 @@ pub async fn redownload_from_history(
 
 /// Debug command to check database tables
 #[command]
-pub async fn debug_history_database() -> Result<String, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
+pub async fn debug_history_database(db: State<'_, SharedDatabase>) -> Result<String, String> {
+    let db_path = db.db_path().display().to_string();
     
-    db_manager.with_connection(|conn| {
+    db.with_read_connection_async(move |conn| {
         // Get schema version
         let schema_version: i32 = conn.query_row(
             "SELECT value FROM cache_metadata WHERE key = 'schema_version'",
@@ -169,9 +135,9 @@ pub async fn debug_history_database() -> Result<String, String> {
             schema_version,
             table_exists,
             table_names.join(", "),
-            db_manager.db_path().display()
+            db_path
         ))
-    }).map_err(|e| format!("Failed to debug database: {}", e))
+    }).await.map_err(|e| format!("Failed to debug database: {}", e))
 }
 
 /// Add download to history (internal function for integration)
@@ -185,18 +151,17 @@ pub async fn add_download_to_history(
     game_name: Option<String>,
     original_request: Option<String>,
 ) -> Result<i64, String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
+    let db = shared_database().map_err(|e| e.to_string())?;
     
     let mut entry = DownloadHistoryEntry::new(download_id, download_type, source_type, url, save_path);
     entry.app_id = app_id;
     entry.game_name = game_name;
     entry.original_request = original_request;
     
-    db_manager.with_connection(|conn| {
+    db.with_connection_async(move |conn| {
         DownloadHistoryOperations::add_download(conn, &entry)
             .map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to add download to history: {}", e))
+    }).await.map_err(|e| format!("Failed to add download to history: {}", e))
 }
 
 /// Update download completion in history (internal function for integration)
@@ -209,10 +174,9 @@ pub async fn update_download_history_completion(
     file_size: Option<i64>,
     error_message: Option<String>,
 ) -> Result<(), String> {
-    let db_path = get_history_db_path().map_err(|e| e.to_string())?;
-    let db_manager = DatabaseManager::new(db_path).map_err(|e| e.to_string())?;
+    let db = shared_database().map_err(|e| e.to_string())?;
     
-    db_manager.with_connection(|conn| {
+    db.with_connection_async(move |conn| {
         DownloadHistoryOperations::update_download_completion(
             conn,
             &download_id,
@@ -223,5 +187,5 @@ pub async fn update_download_history_completion(
             file_size,
             error_message.as_deref(),
         ).map_err(|e| anyhow::anyhow!("Database error: {}", e))
-    }).map_err(|e| format!("Failed to update download history: {}", e))
+    }).await.map_err(|e| format!("Failed to update download history: {}", e))
 }
@@ -1,5 +1,6 @@
 use rusqlite::{params, Connection, Result};
 use super::history_models::{DownloadHistoryEntry, DownloadHistorySummary, HistoryStats};
+use super::search_operations::build_match_query;
 use chrono::Utc;
 
 pub struct DownloadHistoryOperations;
@@ -230,24 +231,29 @@ impl DownloadHistoryOperations {
         Ok(affected as u32)
     }
 
-    /// Search history by game name or file name
+    /// Ranked prefix search over file names, game names and App IDs (typos are corrected)
     pub fn search_history(
         conn: &Connection,
         search_term: &str,
         limit: Option<u32>,
     ) -> Result<Vec<DownloadHistorySummary>> {
+        let match_query = match build_match_query(conn, "download_history_fts_vocab", search_term)? {
+            Some(match_query) => match_query,
+            None => return Ok(Vec::new()),
+        };
+
         let sql = r#"
-            SELECT * FROM download_history_summary 
-            WHERE (file_name LIKE ?1 OR game_name LIKE ?1 OR app_id LIKE ?1)
-            ORDER BY completed_at DESC
+            SELECT s.* FROM download_history_fts
+            JOIN download_history_summary s ON s.id = download_history_fts.rowid
+            WHERE download_history_fts MATCH ?1
+            ORDER BY bm25(download_history_fts, 1.0, 2.0, 1.0), s.completed_at DESC
             LIMIT ?2
         "#;
 
-        let search_pattern = format!("%{}%", search_term);
         let limit_val = limit.unwrap_or(50);
 
         let mut stmt = conn.prepare(sql)?;
-        let history_iter = stmt.query_map(params![search_pattern, limit_val], |row| {
+        let history_iter = stmt.query_map(params![match_query.expression, limit_val], |row| {
             DownloadHistorySummary::from_row(row)
         })?;
 
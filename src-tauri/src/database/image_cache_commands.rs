@@ -0,0 +1,30 @@
+use tauri::command;
+use super::image_cache_models::{ImageCacheStats, ImageEvictionResult};
+use crate::image_cache::IMAGE_CACHE;
+
+/// Entry count, disk usage and size cap of the local image cache
+#[command]
+pub async fn get_image_cache_stats() -> Result<ImageCacheStats, String> {
+    IMAGE_CACHE
+        .stats()
+        .await
+        .map_err(|e| format!("Failed to get image cache stats: {}", e))
+}
+
+/// Evict least recently used images until the cache fits its size cap
+#[command]
+pub async fn evict_image_cache() -> Result<ImageEvictionResult, String> {
+    IMAGE_CACHE
+        .evict_to_limit()
+        .await
+        .map_err(|e| format!("Failed to evict image cache: {}", e))
+}
+
+/// Delete every cached image and thumbnail
+#[command]
+pub async fn clear_image_cache() -> Result<ImageEvictionResult, String> {
+    IMAGE_CACHE
+        .clear()
+        .await
+        .map_err(|e| format!("Failed to clear image cache: {}", e))
+}
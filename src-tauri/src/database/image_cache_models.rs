@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use super::ttl_config::FieldTtl;
+
+/// Custom URI scheme the webview loads cached images from
+pub const IMAGE_SCHEME: &str = "zenith-image";
+
+/// Thumbnails are scaled to this width (height keeps the aspect ratio)
+pub const THUMBNAIL_WIDTH: u32 = 320;
+pub const THUMBNAIL_QUALITY: u8 = 80;
+
+/// Which TTL an image is cached under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageKind {
+    Header,
+    Banner,
+    Screenshot,
+}
+
+impl ImageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageKind::Header => "header",
+            ImageKind::Banner => "banner",
+            ImageKind::Screenshot => "screenshot",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "header" => Some(ImageKind::Header),
+            "banner" => Some(ImageKind::Banner),
+            "screenshot" => Some(ImageKind::Screenshot),
+            _ => None,
+        }
+    }
+
+    /// `TtlConfig::HEADER_IMAGE`, `BANNER_IMAGE` or `SCREENSHOTS`, with any runtime overrides
+    pub fn ttl(&self) -> i64 {
+        FieldTtl::get_field_ttl(match self {
+            ImageKind::Header => "header_image",
+            ImageKind::Banner => "banner_image",
+            ImageKind::Screenshot => "screenshots",
+        })
+    }
+}
+
+/// A row of `image_cache`; the file lives at `cache/images/<hash[..2]>/<hash>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageCacheEntry {
+    pub url: String,
+    pub hash: String,
+    pub kind: ImageKind,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// 0 if no thumbnail could be generated (the full image is served instead)
+    pub thumbnail_bytes: i64,
+    pub cached_at: i64,
+    pub expires_at: i64,
+    pub last_accessed: i64,
+}
+
+impl ImageCacheEntry {
+    pub fn is_expired(&self) -> bool {
+        chrono::Utc::now().timestamp() > self.expires_at
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageCacheStats {
+    pub entries: i64,
+    /// Distinct files on disk (identical images are stored once)
+    pub files: i64,
+    pub expired: i64,
+    pub total_bytes: i64,
+    pub max_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageEvictionResult {
+    pub entries_removed: usize,
+    pub files_removed: usize,
+    pub bytes_freed: i64,
+}
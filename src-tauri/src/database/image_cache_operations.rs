@@ -0,0 +1,214 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use super::image_cache_models::{ImageCacheEntry, ImageCacheStats, ImageEvictionResult, ImageKind};
+
+/// Bytes on disk, counting each content hash once
+const TOTAL_BYTES_SQL: &str = "
+    SELECT COALESCE(SUM(size_bytes + thumbnail_bytes), 0) FROM (
+        SELECT MAX(size_bytes) AS size_bytes, MAX(thumbnail_bytes) AS thumbnail_bytes
+        FROM image_cache GROUP BY hash
+    )";
+
+pub struct ImageCacheOperations;
+
+impl ImageCacheOperations {
+    pub fn get(conn: &Connection, url: &str) -> Result<Option<ImageCacheEntry>> {
+        Ok(conn
+            .query_row(
+                "SELECT url, hash, kind, content_type, size_bytes, thumbnail_bytes, cached_at, expires_at, last_accessed
+                 FROM image_cache WHERE url = ?1",
+                [url],
+                Self::from_row,
+            )
+            .optional()?)
+    }
+
+    /// Insert or replace an entry. Returns the previous hash if no entry uses it any more.
+    pub fn upsert(conn: &Connection, entry: &ImageCacheEntry) -> Result<Option<String>> {
+        let previous: Option<String> = conn
+            .query_row("SELECT hash FROM image_cache WHERE url = ?1", [&entry.url], |row| row.get(0))
+            .optional()?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO image_cache
+                (url, hash, kind, content_type, size_bytes, thumbnail_bytes, cached_at, expires_at, last_accessed)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                entry.url,
+                entry.hash,
+                entry.kind.as_str(),
+                entry.content_type,
+                entry.size_bytes,
+                entry.thumbnail_bytes,
+                entry.cached_at,
+                entry.expires_at,
+                entry.last_accessed,
+            ],
+        )?;
+
+        match previous {
+            Some(hash) if hash != entry.hash && !Self::is_referenced(conn, &hash)? => Ok(Some(hash)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn touch(conn: &Connection, url: &str, now: i64) -> Result<()> {
+        conn.execute("UPDATE image_cache SET last_accessed = ?2 WHERE url = ?1", params![url, now])?;
+        Ok(())
+    }
+
+    pub fn total_bytes(conn: &Connection) -> Result<i64> {
+        Ok(conn.query_row(TOTAL_BYTES_SQL, [], |row| row.get(0))?)
+    }
+
+    /// Drop least recently used entries until the files fit in `max_bytes`.
+    /// Returns what was removed and the hashes whose files can now be deleted.
+    pub fn evict_lru(conn: &Connection, max_bytes: i64) -> Result<(ImageEvictionResult, Vec<String>)> {
+        let mut result = ImageEvictionResult::default();
+        let mut orphaned = Vec::new();
+
+        let tx = conn.unchecked_transaction()?;
+        let mut total = Self::total_bytes(&tx)?;
+        if total > max_bytes {
+            let candidates: Vec<(String, String, i64)> = tx
+                .prepare("SELECT url, hash, size_bytes + thumbnail_bytes FROM image_cache ORDER BY last_accessed, cached_at")?
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+
+            for (url, hash, bytes) in candidates {
+                if total <= max_bytes {
+                    break;
+                }
+                tx.execute("DELETE FROM image_cache WHERE url = ?1", [&url])?;
+                result.entries_removed += 1;
+
+                // Shared content only frees space once its last entry is gone
+                if !Self::is_referenced(&tx, &hash)? {
+                    total -= bytes;
+                    result.bytes_freed += bytes;
+                    orphaned.push(hash);
+                }
+            }
+        }
+        tx.commit()?;
+
+        result.files_removed = orphaned.len();
+        Ok((result, orphaned))
+    }
+
+    /// Remove every entry, returning all hashes that had files
+    pub fn clear(conn: &Connection) -> Result<(ImageEvictionResult, Vec<String>)> {
+        let tx = conn.unchecked_transaction()?;
+        let bytes_freed = Self::total_bytes(&tx)?;
+        let hashes: Vec<String> = tx
+            .prepare("SELECT DISTINCT hash FROM image_cache")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        let entries_removed = tx.execute("DELETE FROM image_cache", [])?;
+        tx.commit()?;
+
+        let result = ImageEvictionResult {
+            entries_removed,
+            files_removed: hashes.len(),
+            bytes_freed,
+        };
+        Ok((result, hashes))
+    }
+
+    pub fn stats(conn: &Connection, now: i64, max_bytes: u64) -> Result<ImageCacheStats> {
+        let (entries, files, expired): (i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COUNT(DISTINCT hash), COALESCE(SUM(expires_at < ?1), 0) FROM image_cache",
+            [now],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        Ok(ImageCacheStats {
+            entries,
+            files,
+            expired,
+            total_bytes: Self::total_bytes(conn)?,
+            max_bytes,
+        })
+    }
+
+    fn is_referenced(conn: &Connection, hash: &str) -> Result<bool> {
+        Ok(conn.query_row("SELECT EXISTS(SELECT 1 FROM image_cache WHERE hash = ?1)", [hash], |row| row.get(0))?)
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<ImageCacheEntry> {
+        let kind: String = row.get(2)?;
+        Ok(ImageCacheEntry {
+            url: row.get(0)?,
+            hash: row.get(1)?,
+            kind: ImageKind::parse(&kind).unwrap_or(ImageKind::Screenshot),
+            content_type: row.get(3)?,
+            size_bytes: row.get(4)?,
+            thumbnail_bytes: row.get(5)?,
+            cached_at: row.get(6)?,
+            expires_at: row.get(7)?,
+            last_accessed: row.get(8)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+
+    fn entry(url: &str, hash: &str, size_bytes: i64, last_accessed: i64) -> ImageCacheEntry {
+        ImageCacheEntry {
+            url: url.to_string(),
+            hash: hash.to_string(),
+            kind: ImageKind::Header,
+            content_type: "image/jpeg".to_string(),
+            size_bytes,
+            thumbnail_bytes: 0,
+            cached_at: last_accessed,
+            expires_at: last_accessed + 3600,
+            last_accessed,
+        }
+    }
+
+    #[test]
+    fn test_lru_eviction_counts_shared_files_once() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            ImageCacheOperations::upsert(conn, &entry("https://a/1.jpg", "aaa", 100, 1))?;
+            // Same content under a second URL is stored once
+            ImageCacheOperations::upsert(conn, &entry("https://a/2.jpg", "aaa", 100, 5))?;
+            ImageCacheOperations::upsert(conn, &entry("https://a/3.jpg", "bbb", 100, 2))?;
+            ImageCacheOperations::upsert(conn, &entry("https://a/4.jpg", "ccc", 100, 3))?;
+            assert_eq!(ImageCacheOperations::total_bytes(conn)?, 300);
+
+            ImageCacheOperations::touch(conn, "https://a/3.jpg", 10)?;
+            let (result, orphaned) = ImageCacheOperations::evict_lru(conn, 200)?;
+
+            // 1.jpg goes first but its file is still used by 2.jpg; 4.jpg is next oldest
+            assert_eq!(result.entries_removed, 2);
+            assert_eq!(orphaned, vec!["ccc"]);
+            assert_eq!(result.bytes_freed, 100);
+            assert!(ImageCacheOperations::get(conn, "https://a/3.jpg")?.is_some());
+            assert_eq!(ImageCacheOperations::total_bytes(conn)?, 200);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_replacing_content_orphans_old_hash() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            assert_eq!(ImageCacheOperations::upsert(conn, &entry("https://a/1.jpg", "old", 10, 1))?, None);
+            assert_eq!(
+                ImageCacheOperations::upsert(conn, &entry("https://a/1.jpg", "new", 10, 2))?,
+                Some("old".to_string())
+            );
+            assert_eq!(ImageCacheOperations::upsert(conn, &entry("https://a/1.jpg", "new", 10, 3))?, None);
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,82 @@
+use tauri::{command, State};
+use crate::database::{
+    launch_profile_models::*, launch_profile_operations::LaunchProfileOperations,
+    operations::UserLibraryOperations, shared_database, SharedDatabase,
+};
+
+fn open_database() -> Result<SharedDatabase, String> {
+    shared_database().map_err(|e| e.to_string())
+}
+
+/// Get all launch profiles for a game (default first)
+#[command]
+pub async fn get_launch_profiles(app_id: String, db: State<'_, SharedDatabase>) -> Result<Vec<LaunchProfile>, String> {
+    db.with_read_connection_async(move |conn| {
+        LaunchProfileOperations::get_for_app(conn, &app_id)
+    }).await.map_err(|e| format!("Failed to get launch profiles: {}", e))
+}
+
+/// Create a launch profile (the game's first profile becomes its default)
+#[command]
+pub async fn create_launch_profile(profile: LaunchProfileInput, db: State<'_, SharedDatabase>) -> Result<LaunchProfile, String> {
+    profile.validate()?;
+
+    db.with_connection_async(move |conn| {
+        let id = LaunchProfileOperations::create(conn, &profile)?;
+        LaunchProfileOperations::get_by_id(conn, id)?
+            .ok_or_else(|| anyhow::anyhow!("Profile {} vanished after insert", id))
+    }).await.map_err(|e| format!("Failed to create launch profile: {}", e))
+}
+
+/// Update an existing launch profile
+#[command]
+pub async fn update_launch_profile(
+    id: i64,
+    profile: LaunchProfileInput,
+    db: State<'_, SharedDatabase>,
+) -> Result<LaunchProfile, String> {
+    profile.validate()?;
+
+    db.with_connection_async(move |conn| {
+        if !LaunchProfileOperations::update(conn, id, &profile)? {
+            return Err(anyhow::anyhow!("Launch profile {} not found for app {}", id, profile.app_id));
+        }
+        LaunchProfileOperations::get_by_id(conn, id)?
+            .ok_or_else(|| anyhow::anyhow!("Launch profile {} not found", id))
+    }).await.map_err(|e| format!("Failed to update launch profile: {}", e))
+}
+
+/// Delete a launch profile
+#[command]
+pub async fn delete_launch_profile(id: i64, db: State<'_, SharedDatabase>) -> Result<bool, String> {
+    db.with_connection_async(move |conn| {
+        LaunchProfileOperations::delete(conn, id)
+    }).await.map_err(|e| format!("Failed to delete launch profile: {}", e))
+}
+
+/// Make a launch profile the game's default ("run as default")
+#[command]
+pub async fn set_default_launch_profile(id: i64, db: State<'_, SharedDatabase>) -> Result<bool, String> {
+    db.with_connection_async(move |conn| {
+        LaunchProfileOperations::set_default(conn, id)
+    }).await.map_err(|e| format!("Failed to set default launch profile: {}", e))
+}
+
+/// Resolve the profile to launch: the requested one, or the game's default
+/// (internal function for integration)
+pub async fn resolve_launch_profile(app_id: String, profile_id: Option<i64>) -> Result<LaunchProfile, String> {
+    let db = open_database()?;
+
+    db.with_read_connection_async(move |conn| {
+        LaunchProfileOperations::resolve(conn, &app_id, profile_id)
+    }).await.map_err(|e| e.to_string())
+}
+
+/// Mark a library game as accessed (internal function for integration)
+pub async fn record_library_access(app_id: String) -> Result<(), String> {
+    let db = open_database()?;
+
+    db.with_connection_async(move |conn| {
+        UserLibraryOperations::update_access(conn, &app_id)
+    }).await.map_err(|e| format!("Failed to update library access: {}", e))
+}
@@ -0,0 +1,88 @@
+use rusqlite::{types::Type, Row, Result as SqliteResult};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchProfile {
+    pub id: i64,
+    pub app_id: String,
+    pub name: String,
+
+    // Launch configuration
+    pub executable_path: String,
+    pub arguments: Vec<String>,
+    pub env_overrides: HashMap<String, String>,
+    pub working_dir: Option<String>,
+
+    // Hooks (shell command lines)
+    pub pre_launch_hook: Option<String>,
+    pub post_exit_hook: Option<String>,
+
+    pub is_default: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Fields supplied by the frontend when creating or updating a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchProfileInput {
+    pub app_id: String,
+    pub name: String,
+    pub executable_path: String,
+    #[serde(default)]
+    pub arguments: Vec<String>,
+    #[serde(default)]
+    pub env_overrides: HashMap<String, String>,
+    pub working_dir: Option<String>,
+    pub pre_launch_hook: Option<String>,
+    pub post_exit_hook: Option<String>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+impl LaunchProfile {
+    pub fn from_row(row: &Row) -> SqliteResult<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            app_id: row.get("app_id")?,
+            name: row.get("name")?,
+            executable_path: row.get("executable_path")?,
+            arguments: json_column(row, "arguments")?,
+            env_overrides: json_column(row, "env_overrides")?,
+            working_dir: row.get("working_dir")?,
+            pre_launch_hook: row.get("pre_launch_hook")?,
+            post_exit_hook: row.get("post_exit_hook")?,
+            is_default: row.get("is_default")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+/// A JSON text column; malformed JSON is a conversion error rather than silently empty
+fn json_column<T: DeserializeOwned>(row: &Row, column: &str) -> SqliteResult<T> {
+    let json: String = row.get(column)?;
+    serde_json::from_str(&json).map_err(|e| {
+        let index = row.as_ref().column_index(column).unwrap_or_default();
+        rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e))
+    })
+}
+
+impl LaunchProfileInput {
+    /// Validate user supplied values before they are stored
+    pub fn validate(&self) -> Result<(), String> {
+        if self.app_id.trim().is_empty() || !self.app_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid app id: {}", self.app_id));
+        }
+        if self.name.trim().is_empty() {
+            return Err("Profile name cannot be empty".to_string());
+        }
+        if self.executable_path.trim().is_empty() {
+            return Err("Executable path cannot be empty".to_string());
+        }
+        if self.env_overrides.keys().any(|k| k.is_empty() || k.contains('=')) {
+            return Err("Environment variable names cannot be empty or contain '='".to_string());
+        }
+        Ok(())
+    }
+}
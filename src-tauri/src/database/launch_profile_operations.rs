@@ -0,0 +1,321 @@
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use super::launch_profile_models::{LaunchProfile, LaunchProfileInput};
+use super::operations::UserLibraryOperations;
+use chrono::Utc;
+
+pub struct LaunchProfileOperations;
+
+impl LaunchProfileOperations {
+    /// Create a profile for a game in user_library.
+    /// The first profile of a game always becomes its default.
+    pub fn create(conn: &Connection, input: &LaunchProfileInput) -> Result<i64> {
+        let tx = conn.unchecked_transaction()?;
+
+        if !UserLibraryOperations::is_in_library(&tx, &input.app_id)? {
+            return Err(anyhow!("App {} is not in the library", input.app_id));
+        }
+
+        let has_profiles: bool = tx.query_row(
+            "SELECT COUNT(*) FROM launch_profiles WHERE app_id = ?1",
+            params![input.app_id],
+            |row| Ok(row.get::<_, i64>(0)? > 0),
+        )?;
+        let is_default = input.is_default || !has_profiles;
+
+        if is_default {
+            Self::clear_default(&tx, &input.app_id)?;
+        }
+
+        let now = Utc::now().timestamp();
+        tx.execute(
+            "INSERT INTO launch_profiles (
+                app_id, name, executable_path, arguments, env_overrides, working_dir,
+                pre_launch_hook, post_exit_hook, is_default, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+            params![
+                input.app_id,
+                input.name,
+                input.executable_path,
+                serde_json::to_string(&input.arguments).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&input.env_overrides).unwrap_or_else(|_| "{}".to_string()),
+                input.working_dir,
+                input.pre_launch_hook,
+                input.post_exit_hook,
+                is_default,
+                now,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Update an existing profile. The game it belongs to cannot change.
+    pub fn update(conn: &Connection, id: i64, input: &LaunchProfileInput) -> Result<bool> {
+        let tx = conn.unchecked_transaction()?;
+
+        if input.is_default {
+            Self::clear_default(&tx, &input.app_id)?;
+        }
+
+        let affected = tx.execute(
+            "UPDATE launch_profiles
+             SET name = ?3,
+                 executable_path = ?4,
+                 arguments = ?5,
+                 env_overrides = ?6,
+                 working_dir = ?7,
+                 pre_launch_hook = ?8,
+                 post_exit_hook = ?9,
+                 is_default = CASE WHEN ?10 THEN 1 ELSE is_default END,
+                 updated_at = ?11
+             WHERE id = ?1 AND app_id = ?2",
+            params![
+                id,
+                input.app_id,
+                input.name,
+                input.executable_path,
+                serde_json::to_string(&input.arguments).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&input.env_overrides).unwrap_or_else(|_| "{}".to_string()),
+                input.working_dir,
+                input.pre_launch_hook,
+                input.post_exit_hook,
+                input.is_default,
+                Utc::now().timestamp(),
+            ],
+        )?;
+
+        tx.commit()?;
+        Ok(affected > 0)
+    }
+
+    /// Delete a profile. If it was the default, the oldest remaining profile takes over.
+    pub fn delete(conn: &Connection, id: i64) -> Result<bool> {
+        let tx = conn.unchecked_transaction()?;
+
+        let profile = match Self::get_by_id(&tx, id)? {
+            Some(profile) => profile,
+            None => return Ok(false),
+        };
+
+        tx.execute("DELETE FROM launch_profiles WHERE id = ?1", params![id])?;
+
+        if profile.is_default {
+            tx.execute(
+                "UPDATE launch_profiles SET is_default = 1
+                 WHERE id = (SELECT id FROM launch_profiles WHERE app_id = ?1 ORDER BY created_at, id LIMIT 1)",
+                params![profile.app_id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Make a profile the default for its game
+    pub fn set_default(conn: &Connection, id: i64) -> Result<bool> {
+        let tx = conn.unchecked_transaction()?;
+
+        let profile = match Self::get_by_id(&tx, id)? {
+            Some(profile) => profile,
+            None => return Ok(false),
+        };
+
+        Self::clear_default(&tx, &profile.app_id)?;
+        tx.execute(
+            "UPDATE launch_profiles SET is_default = 1, updated_at = ?2 WHERE id = ?1",
+            params![id, Utc::now().timestamp()],
+        )?;
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Get a profile by ID
+    pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<LaunchProfile>> {
+        Ok(conn.query_row(
+            "SELECT * FROM launch_profiles WHERE id = ?1",
+            params![id],
+            LaunchProfile::from_row,
+        ).optional()?)
+    }
+
+    /// Get the default profile for a game
+    pub fn get_default(conn: &Connection, app_id: &str) -> Result<Option<LaunchProfile>> {
+        Ok(conn.query_row(
+            "SELECT * FROM launch_profiles WHERE app_id = ?1 AND is_default = 1",
+            params![app_id],
+            LaunchProfile::from_row,
+        ).optional()?)
+    }
+
+    /// The profile to launch a game with: the requested one, which has to belong to the game,
+    /// or the game's default
+    pub fn resolve(conn: &Connection, app_id: &str, profile_id: Option<i64>) -> Result<LaunchProfile> {
+        let profile = match profile_id {
+            Some(id) => Self::get_by_id(conn, id)?,
+            None => Self::get_default(conn, app_id)?,
+        };
+
+        match profile {
+            Some(profile) if profile.app_id == app_id => Ok(profile),
+            Some(profile) => Err(anyhow!(
+                "Launch profile {} belongs to app {}, not {}",
+                profile.id, profile.app_id, app_id
+            )),
+            None => Err(anyhow!("No launch profile configured for app {}", app_id)),
+        }
+    }
+
+    /// Get all profiles for a game, default first
+    pub fn get_for_app(conn: &Connection, app_id: &str) -> Result<Vec<LaunchProfile>> {
+        let mut stmt = conn.prepare(
+            "SELECT * FROM launch_profiles WHERE app_id = ?1
+             ORDER BY is_default DESC, created_at, id",
+        )?;
+
+        let profiles = stmt.query_map(params![app_id], LaunchProfile::from_row)?;
+        Ok(profiles.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn clear_default(conn: &Connection, app_id: &str) -> Result<()> {
+        conn.execute(
+            "UPDATE launch_profiles SET is_default = 0 WHERE app_id = ?1 AND is_default = 1",
+            params![app_id],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{add_library_game, test_database};
+    use std::collections::HashMap;
+
+    fn input(app_id: &str, name: &str, is_default: bool) -> LaunchProfileInput {
+        LaunchProfileInput {
+            app_id: app_id.to_string(),
+            name: name.to_string(),
+            executable_path: format!("/games/{}/game.exe", app_id),
+            arguments: vec!["-windowed".to_string()],
+            env_overrides: HashMap::from([("DXVK_HUD".to_string(), "fps".to_string())]),
+            working_dir: None,
+            pre_launch_hook: None,
+            post_exit_hook: None,
+            is_default,
+        }
+    }
+
+    fn default_name(conn: &Connection, app_id: &str) -> Result<Option<String>> {
+        Ok(LaunchProfileOperations::get_default(conn, app_id)?.map(|profile| profile.name))
+    }
+
+    #[test]
+    fn test_each_game_keeps_a_single_default() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            // Only library games get profiles, and nothing is added on their behalf
+            let error = LaunchProfileOperations::create(conn, &input("620", "DX12", false)).unwrap_err();
+            assert_eq!(error.to_string(), "App 620 is not in the library");
+            let games: i64 = conn.query_row("SELECT COUNT(*) FROM games", [], |row| row.get(0))?;
+            assert_eq!(games, 0);
+            add_library_game(conn, "620", "Portal 2", None)?;
+
+            // The first profile is the default even when not asked for
+            let dx12 = LaunchProfileOperations::create(conn, &input("620", "DX12", false))?;
+            assert_eq!(default_name(conn, "620")?.as_deref(), Some("DX12"));
+
+            let dx11 = LaunchProfileOperations::create(conn, &input("620", "DX11", false))?;
+            assert_eq!(default_name(conn, "620")?.as_deref(), Some("DX12"));
+            let modded = LaunchProfileOperations::create(conn, &input("620", "Modded", true))?;
+            assert_eq!(default_name(conn, "620")?.as_deref(), Some("Modded"));
+
+            assert!(LaunchProfileOperations::set_default(conn, dx11)?);
+            let names: Vec<_> = LaunchProfileOperations::get_for_app(conn, "620")?
+                .into_iter()
+                .map(|profile| (profile.name, profile.is_default))
+                .collect();
+            assert_eq!(names, [("DX11".to_string(), true), ("DX12".to_string(), false), ("Modded".to_string(), false)]);
+
+            // Updating keeps the default unless asked to take it over; the game cannot change
+            assert!(LaunchProfileOperations::update(conn, dx12, &input("620", "DX12 (Vulkan)", false))?);
+            assert_eq!(default_name(conn, "620")?.as_deref(), Some("DX11"));
+            assert!(LaunchProfileOperations::update(conn, dx12, &input("620", "DX12 (Vulkan)", true))?);
+            assert_eq!(default_name(conn, "620")?.as_deref(), Some("DX12 (Vulkan)"));
+            assert!(!LaunchProfileOperations::update(conn, dx12, &input("440", "Elsewhere", false))?);
+
+            // Deleting the default hands it to the oldest remaining profile
+            assert!(LaunchProfileOperations::delete(conn, dx12)?);
+            assert_eq!(default_name(conn, "620")?.as_deref(), Some("DX11"));
+            assert!(LaunchProfileOperations::delete(conn, dx11)?);
+            assert!(LaunchProfileOperations::delete(conn, modded)?);
+            assert_eq!(default_name(conn, "620")?, None);
+
+            // Two defaults for one game are rejected by the database itself
+            let first = LaunchProfileOperations::create(conn, &input("620", "First", false))?;
+            LaunchProfileOperations::create(conn, &input("620", "Second", false))?;
+            let second_default = conn.execute(
+                "UPDATE launch_profiles SET is_default = 1 WHERE app_id = '620' AND id != ?1",
+                [first],
+            );
+            assert!(second_default.is_err());
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_profiles_are_removed_with_their_game() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            add_library_game(conn, "620", "Portal 2", None)?;
+            add_library_game(conn, "440", "Team Fortress 2", None)?;
+            LaunchProfileOperations::create(conn, &input("620", "Default", false))?;
+            LaunchProfileOperations::create(conn, &input("440", "Default", false))?;
+
+            assert!(UserLibraryOperations::remove_game(conn, "620")?);
+            assert!(LaunchProfileOperations::get_for_app(conn, "620")?.is_empty());
+
+            conn.execute("DELETE FROM games WHERE app_id = '440'", [])?;
+            assert!(LaunchProfileOperations::get_for_app(conn, "440")?.is_empty());
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_launch_resolves_requested_or_default_profile() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            let error = LaunchProfileOperations::resolve(conn, "620", None).unwrap_err();
+            assert_eq!(error.to_string(), "No launch profile configured for app 620");
+
+            add_library_game(conn, "620", "Portal 2", None)?;
+            add_library_game(conn, "440", "Team Fortress 2", None)?;
+            let default = LaunchProfileOperations::create(conn, &input("620", "Default", false))?;
+            let modded = LaunchProfileOperations::create(conn, &input("620", "Modded", false))?;
+            let other = LaunchProfileOperations::create(conn, &input("440", "Default", false))?;
+
+            assert_eq!(LaunchProfileOperations::resolve(conn, "620", None)?.id, default);
+            assert_eq!(LaunchProfileOperations::resolve(conn, "620", Some(modded))?.id, modded);
+
+            // Another game's profile, or one that does not exist, is never used
+            let error = LaunchProfileOperations::resolve(conn, "620", Some(other)).unwrap_err();
+            assert_eq!(error.to_string(), format!("Launch profile {} belongs to app 440, not 620", other));
+            assert!(LaunchProfileOperations::resolve(conn, "620", Some(other + 100)).is_err());
+
+            // Corrupt stored arguments fail the lookup instead of launching without them
+            conn.execute("UPDATE launch_profiles SET arguments = 'not json' WHERE id = ?1", [modded])?;
+            assert!(LaunchProfileOperations::resolve(conn, "620", Some(modded)).is_err());
+            assert_eq!(LaunchProfileOperations::resolve(conn, "620", None)?.id, default);
+            Ok(())
+        })
+        .unwrap();
+    }
+}
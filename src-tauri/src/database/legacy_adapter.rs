@@ -4,7 +4,9 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
-use crate::database::cache_service::SqliteCacheService;
+use crate::app_paths::app_paths;
+use crate::database::cache_service::{SqliteCacheService, SQLITE_CACHE_SERVICE};
+use crate::database::refresh_queue_models::RefreshKind;
 use crate::GameDetail;
 
 /// Adapter that provides the old GameCache interface but uses SQLite underneath
@@ -17,13 +19,10 @@ pub struct LegacyGameCacheAdapter {
 
 impl LegacyGameCacheAdapter {
     pub fn new() -> anyhow::Result<Self> {
-        let cache_dir = dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("zenith-launcher")
-            .join("cache");
+        let cache_dir = app_paths().cache_dir();
 
         Ok(Self {
-            sqlite_service: Arc::new(SqliteCacheService::new()?),
+            sqlite_service: SQLITE_CACHE_SERVICE.clone(),
             _legacy_in_flight: Arc::new(Mutex::new(HashMap::new())),
             _cache_dir: cache_dir,
         })
@@ -35,8 +34,8 @@ impl LegacyGameCacheAdapter {
         self.sqlite_service.get_game_details(app_id).await
     }
 
-    pub fn set_game_details(&self, app_id: String, details: GameDetail) {
-        if let Err(e) = self.sqlite_service.set_game_details(app_id, details) {
+    pub async fn set_game_details(&self, app_id: String, details: GameDetail) {
+        if let Err(e) = self.sqlite_service.set_game_details(app_id, details).await {
             eprintln!("Failed to cache game details: {}", e);
         }
     }
@@ -45,8 +44,8 @@ impl LegacyGameCacheAdapter {
         self.sqlite_service.get_game_name(app_id).await
     }
 
-    pub fn set_game_name(&self, app_id: String, name: String) {
-        if let Err(e) = self.sqlite_service.set_game_name(app_id, name) {
+    pub async fn set_game_name(&self, app_id: String, name: String) {
+        if let Err(e) = self.sqlite_service.set_game_name(app_id, name).await {
             eprintln!("Failed to cache game name: {}", e);
         }
     }
@@ -71,34 +70,34 @@ impl LegacyGameCacheAdapter {
         self.sqlite_service.reset_error_count().await
     }
 
-    pub fn cleanup_expired(&self) {
-        if let Err(e) = self.sqlite_service.cleanup_expired() {
+    pub async fn cleanup_expired(&self) {
+        if let Err(e) = self.sqlite_service.cleanup_expired().await {
             eprintln!("Failed to cleanup expired entries: {}", e);
         }
     }
 
-    pub fn cache_stats(&self) {
-        if let Err(e) = self.sqlite_service.cache_stats() {
+    pub async fn cache_stats(&self) {
+        if let Err(e) = self.sqlite_service.cache_stats().await {
             eprintln!("Failed to get cache stats: {}", e);
         }
     }
 
-    pub fn save_to_disk(&self) {
-        self.sqlite_service.save_to_disk();
+    pub async fn save_to_disk(&self) {
+        self.sqlite_service.save_to_disk().await;
     }
 
-    pub fn load_from_disk(&self) {
-        self.sqlite_service.load_from_disk();
+    pub async fn load_from_disk(&self) {
+        self.sqlite_service.load_from_disk().await;
     }
 
-    pub fn clear_cache(&self) {
-        if let Err(e) = self.sqlite_service.clear_cache() {
+    pub async fn clear_cache(&self) {
+        if let Err(e) = self.sqlite_service.clear_cache().await {
             eprintln!("Failed to clear cache: {}", e);
         }
     }
 
-    pub fn invalidate_game_details(&self, app_id: &str) {
-        if let Err(e) = self.sqlite_service.invalidate_game_details(app_id) {
+    pub async fn invalidate_game_details(&self, app_id: &str) {
+        if let Err(e) = self.sqlite_service.invalidate_game_details(app_id).await {
             eprintln!("Failed to invalidate game details for {}: {}", app_id, e);
         }
     }
@@ -110,19 +109,19 @@ impl LegacyGameCacheAdapter {
 
     // Additional methods needed for compatibility with old GameCache interface
     
-    pub fn queue_for_refresh(&self, app_id: String) {
-        // For SQLite, we don't need explicit queue management as it handles this internally
-        println!("Queue refresh for {}", app_id);
+    /// Queue a game's name for the background refresh worker
+    pub async fn queue_for_refresh(&self, app_id: String) {
+        self.sqlite_service.queue_refresh(&app_id, RefreshKind::Name).await;
     }
 
+    /// Nudge the background refresh worker to process the queue now
     pub async fn process_queue_batch(&self) {
-        // SQLite handles background refreshing automatically, so this is a no-op
-        println!("SQLite: Queue processing handled automatically");
+        self.sqlite_service.wake_refresh_worker();
     }
 
-    pub fn clear_all(&self) {
+    pub async fn clear_all(&self) {
         // Alias for clear_cache for backward compatibility
-        self.clear_cache();
+        self.clear_cache().await;
     }
 
     // Expose circuit breaker field for backward compatibility
@@ -136,13 +135,13 @@ impl LegacyGameCacheAdapter {
         &self.sqlite_service
     }
 
-    /// Batch refresh multiple games safely with rate limiting
-    pub async fn batch_refresh_games(&self, app_ids: Vec<String>) -> anyhow::Result<crate::database::cache_service::BatchRefreshResult> {
+    /// Queue multiple games for the background refresh worker
+    pub async fn batch_refresh_games(&self, app_ids: Vec<String>) -> crate::database::cache_service::BatchRefreshResult {
         self.sqlite_service.batch_refresh_games(app_ids).await
     }
 
     /// Smart refresh library games based on staleness priority
-    pub async fn smart_refresh_library(&self, library_games: Vec<String>) -> anyhow::Result<crate::database::cache_service::BatchRefreshResult> {
+    pub async fn smart_refresh_library(&self, library_games: Vec<String>) -> crate::database::cache_service::BatchRefreshResult {
         self.sqlite_service.smart_refresh_library(library_games).await
     }
 }
@@ -6,7 +6,8 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::fs;
 use serde::{Deserialize, Serialize};
-use crate::database::{DatabaseManager, operations::*};
+use crate::app_paths::app_paths;
+use crate::database::{operations::*, shared_database, SharedDatabase};
 use crate::database::models::{Game, GameDetailDb};
 use crate::GameDetail;
 
@@ -20,20 +21,15 @@ struct CacheEntry<T> {
 
 /// Migrator for converting JSON cache to SQLite
 pub struct CacheMigrator {
-    db: DatabaseManager,
+    db: SharedDatabase,
     cache_dir: PathBuf,
 }
 
 impl CacheMigrator {
     /// Create new migrator
     pub fn new() -> Result<Self> {
-        let cache_dir = dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("zenith-launcher")
-            .join("cache");
-
-        let db_path = cache_dir.join("games.db");
-        let db = DatabaseManager::new(db_path)?;
+        let cache_dir = app_paths().cache_dir();
+        let db = shared_database()?;
 
         Ok(Self {
             db,
@@ -1,378 +1,370 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use rusqlite::Connection;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::collections::HashSet;
+use std::path::Path;
+use super::backup_models::{BackupInfo, BackupKind};
+use super::backup_operations::BackupOperations;
 
-/// Current database schema version
-const CURRENT_SCHEMA_VERSION: i32 = 7;
+/// A schema migration embedded from `migrations/NNNN_name.{up,down}.sql`
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// SHA-1 of the up script, recorded in `schema_migrations` when applied
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha1::digest(self.up.as_bytes()))
+    }
+}
+
+macro_rules! migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("migrations/", $name, ".up.sql")),
+            down: include_str!(concat!("migrations/", $name, ".down.sql")),
+        }
+    };
+}
+
+/// All migrations in the order they are applied.
+/// Only ever append to this list - applied migrations are checksummed.
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_initial"),
+    migration!(2, "0002_granular_ttl"),
+    migration!(3, "0003_user_profile"),
+    migration!(4, "0004_bypass_games"),
+    migration!(5, "0005_profile_ttl_backup"),
+    migration!(6, "0006_drop_profile_bio"),
+    migration!(7, "0007_download_history"),
+    migration!(8, "0008_play_sessions"),
+    migration!(9, "0009_launch_profiles"),
+    migration!(10, "0010_search_fts"),
+    migration!(11, "0011_refresh_queue"),
+    migration!(12, "0012_image_cache"),
+    migration!(13, "0013_catalogue_cache"),
+    migration!(14, "0014_wishlist"),
+];
 
-/// Run all necessary database migrations
+/// Outcome of a migration run
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub from_version: i32,
+    pub to_version: i32,
+    pub applied: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// Row of the `schema_migrations` table
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedMigration {
+    pub version: i32,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: i64,
+}
+
+/// Applied migrations plus what a dry run says is still pending
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaMigrationStatus {
+    pub current_version: i32,
+    pub head_version: i32,
+    pub applied: Vec<AppliedMigration>,
+    pub pending: Vec<String>,
+}
+
+/// Migrations reverted by `rollback_to`, newest first, and the backup taken before them
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaRollbackReport {
+    pub reverted: Vec<String>,
+    pub backup: BackupInfo,
+}
+
+/// Run all pending database migrations
 pub fn run_migrations(conn: &Connection) -> Result<()> {
     // Enable foreign keys
     conn.execute("PRAGMA foreign_keys = ON", [])?;
-    
-    let current_version = get_schema_version(conn)?;
-    
-    if current_version < CURRENT_SCHEMA_VERSION {
-        println!("Migrating database from version {} to {}", current_version, CURRENT_SCHEMA_VERSION);
-        
-        // Run migrations step by step
-        for version in (current_version + 1)..=CURRENT_SCHEMA_VERSION {
-            migrate_to_version(conn, version)?;
-        }
-        
-        // Update schema version
-        set_schema_version(conn, CURRENT_SCHEMA_VERSION)?;
-        println!("Database migration completed successfully");
+
+    let report = migrate(conn, MIGRATIONS, false)?;
+    if !report.applied.is_empty() {
+        println!("Database migration completed successfully (v{} -> v{})", report.from_version, report.to_version);
     }
-    
+
     Ok(())
 }
 
-/// Get current schema version from database
-fn get_schema_version(conn: &Connection) -> Result<i32> {
-    // Check if cache_metadata table exists
-    let table_exists: bool = conn
-        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='cache_metadata'")?
-        .exists([])?;
-    
-    if !table_exists {
-        return Ok(0); // No schema exists yet
+/// Whether an existing (non-empty) database is behind the newest migration
+pub fn has_pending_migrations(conn: &Connection) -> Result<bool> {
+    let version = get_schema_version(conn)?;
+    Ok(version > 0 && version < MIGRATIONS.last().map_or(0, |m| m.version))
+}
+
+/// Apply every pending migration inside a transaction that is always rolled back
+pub fn plan_migrations(conn: &Connection) -> Result<MigrationReport> {
+    migrate(conn, MIGRATIONS, true)
+}
+
+/// Revert applied migrations, newest first, until the schema is at `target_version`.
+/// The down scripts drop tables with their data, so a backup into `backups_dir` has to
+/// succeed first, and the initial schema (version 1) is never reverted.
+pub fn rollback_to(conn: &Connection, backups_dir: &Path, target_version: i32) -> Result<SchemaRollbackReport> {
+    let current_version = get_schema_version(conn)?;
+    if target_version < 1 {
+        return Err(anyhow!("Cannot roll back below version 1"));
     }
-    
-    // Try to get schema version
-    match conn.query_row(
-        "SELECT value FROM cache_metadata WHERE key = 'schema_version'",
-        [],
-        |row| {
-            let value: String = row.get(0)?;
-            Ok(value.parse::<i32>().unwrap_or(0))
-        },
-    ) {
-        Ok(version) => Ok(version),
-        Err(_) => Ok(0), // No version found, assume 0
+    if target_version >= current_version {
+        return Err(anyhow!("Schema is at version {}, nothing to roll back to {}", current_version, target_version));
     }
+
+    let backup = BackupOperations::create_backup(conn, backups_dir, BackupKind::PreMigration)
+        .map_err(|e| anyhow!("Not rolling back, the backup failed: {}", e))?;
+    let reverted = rollback(conn, MIGRATIONS, target_version)?;
+    Ok(SchemaRollbackReport { reverted, backup })
 }
 
-/// Set schema version in database
-fn set_schema_version(conn: &Connection, version: i32) -> Result<()> {
-    conn.execute(
-        "INSERT OR REPLACE INTO cache_metadata (key, value, updated_at) VALUES ('schema_version', ?1, strftime('%s', 'now'))",
-        [version.to_string()],
+/// Current schema state for the migration status command
+pub fn migration_status(conn: &Connection) -> Result<SchemaMigrationStatus> {
+    let plan = plan_migrations(conn)?;
+    Ok(SchemaMigrationStatus {
+        current_version: get_schema_version(conn)?,
+        head_version: MIGRATIONS.last().map_or(0, |m| m.version),
+        applied: applied_migrations(conn)?,
+        pending: plan.applied,
+    })
+}
+
+/// List rows of `schema_migrations` (empty before the table exists)
+pub fn applied_migrations(conn: &Connection) -> Result<Vec<AppliedMigration>> {
+    if !table_exists(conn, "schema_migrations")? {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version",
     )?;
-    Ok(())
+    let rows = stmt.query_map([], |row| {
+        Ok(AppliedMigration {
+            version: row.get(0)?,
+            name: row.get(1)?,
+            checksum: row.get(2)?,
+            applied_at: row.get(3)?,
+        })
+    })?;
+
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+fn migrate(conn: &Connection, migrations: &[Migration], dry_run: bool) -> Result<MigrationReport> {
+    if !dry_run {
+        return migrate_steps(conn, migrations, false);
+    }
+
+    // Each step still runs in its own savepoint; the outer transaction throws all of them away
+    conn.execute_batch("BEGIN")?;
+    let result = migrate_steps(conn, migrations, true);
+    conn.execute_batch("ROLLBACK")?;
+    result
 }
 
-/// Migrate to specific version
-fn migrate_to_version(conn: &Connection, version: i32) -> Result<()> {
-    match version {
-        1 => migrate_to_v1(conn),
-        2 => migrate_to_v2(conn),
-        3 => migrate_to_v3(conn),
-        4 => migrate_to_v4(conn),
-        5 => migrate_to_v5(conn),
-        6 => migrate_to_v6(conn),
-        7 => migrate_to_v7(conn),
-        _ => Err(anyhow::anyhow!("Unknown migration version: {}", version)),
+fn migrate_steps(conn: &Connection, migrations: &[Migration], dry_run: bool) -> Result<MigrationReport> {
+    in_savepoint(conn, || ensure_migrations_table(conn, migrations))?;
+    verify_checksums(conn, migrations)?;
+
+    let from_version = get_schema_version(conn)?;
+    let done: HashSet<i32> = applied_migrations(conn)?.iter().map(|m| m.version).collect();
+
+    let mut applied = Vec::new();
+    for migration in migrations.iter().filter(|m| !done.contains(&m.version)) {
+        if !dry_run {
+            println!("Applying migration {}...", migration.name);
+        }
+
+        in_savepoint(conn, || {
+            conn.execute_batch(migration.up)?;
+            record_migration(conn, migration)
+        })
+        .map_err(|e| anyhow!("Migration {} failed and was rolled back: {}", migration.name, e))?;
+
+        applied.push(migration.name.to_string());
     }
+
+    Ok(MigrationReport {
+        from_version,
+        to_version: get_schema_version(conn)?,
+        applied,
+        dry_run,
+    })
 }
 
-/// Initial schema creation (version 1)
-fn migrate_to_v1(conn: &Connection) -> Result<()> {
-    println!("Creating initial database schema (v1)...");
-    
-    // Read and execute the schema SQL
-    let schema_sql = include_str!("schema.sql");
-    conn.execute_batch(schema_sql)?;
-    
-    println!("Initial schema created successfully");
-    Ok(())
+fn rollback(conn: &Connection, migrations: &[Migration], target_version: i32) -> Result<Vec<String>> {
+    verify_checksums(conn, migrations)?;
+
+    let mut reverted = Vec::new();
+    for applied in applied_migrations(conn)?.iter().rev().filter(|m| m.version > target_version) {
+        let migration = migrations
+            .iter()
+            .find(|m| m.version == applied.version)
+            .ok_or_else(|| anyhow!("No down script for migration {}", applied.name))?;
+
+        println!("Reverting migration {}...", migration.name);
+        in_savepoint(conn, || {
+            conn.execute_batch(migration.down)?;
+            conn.execute("DELETE FROM schema_migrations WHERE version = ?1", [migration.version])?;
+            if table_exists(conn, "cache_metadata")? {
+                set_schema_version(conn, get_schema_version(conn)?)?;
+            }
+            Ok(())
+        })
+        .map_err(|e| anyhow!("Reverting migration {} failed and was rolled back: {}", migration.name, e))?;
+
+        reverted.push(migration.name.to_string());
+    }
+
+    Ok(reverted)
 }
 
-/// Add granular TTL support (version 2)
-fn migrate_to_v2(conn: &Connection) -> Result<()> {
-    println!("Migrating to granular TTL schema (v2)...");
-    
-    // Check if columns already exist before adding them
-    let mut stmt = conn.prepare("PRAGMA table_info(game_details)")?;
-    let column_names: Vec<String> = stmt.query_map([], |row| {
-        Ok(row.get::<_, String>(1)?) // column name is at index 1
-    })?.collect::<Result<Vec<_>, _>>()?;
-    
-    // Add granular TTL columns to game_details table if they don't exist
-    if !column_names.contains(&"dynamic_expires_at".to_string()) {
-        conn.execute(
-            "ALTER TABLE game_details ADD COLUMN dynamic_expires_at INTEGER NOT NULL DEFAULT 0",
-            [],
-        )?;
+/// Run `f` in a savepoint so a failure leaves no partial changes behind
+fn in_savepoint<T>(conn: &Connection, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    conn.execute_batch("SAVEPOINT schema_migration")?;
+    match f() {
+        Ok(value) => {
+            conn.execute_batch("RELEASE schema_migration")?;
+            Ok(value)
+        }
+        Err(e) => {
+            if let Err(rollback_err) = conn.execute_batch("ROLLBACK TO schema_migration; RELEASE schema_migration") {
+                eprintln!("Failed to roll back migration savepoint: {}", rollback_err);
+            }
+            Err(e)
+        }
     }
-    
-    if !column_names.contains(&"semistatic_expires_at".to_string()) {
-        conn.execute(
-            "ALTER TABLE game_details ADD COLUMN semistatic_expires_at INTEGER NOT NULL DEFAULT 0", 
-            [],
-        )?;
+}
+
+/// Create `schema_migrations`, adopting the version recorded by the old hand-coded migrations
+fn ensure_migrations_table(conn: &Connection, migrations: &[Migration]) -> Result<()> {
+    if table_exists(conn, "schema_migrations")? {
+        return Ok(());
     }
-    
-    if !column_names.contains(&"static_expires_at".to_string()) {
-        conn.execute(
-            "ALTER TABLE game_details ADD COLUMN static_expires_at INTEGER NOT NULL DEFAULT 0",
-            [],
-        )?;
+
+    let legacy_version = legacy_schema_version(conn)?;
+    let head_version = migrations.last().map_or(0, |m| m.version);
+    if legacy_version > head_version {
+        return Err(anyhow!(
+            "Database schema version {} is newer than this build supports ({})",
+            legacy_version,
+            head_version
+        ));
     }
-    
-    // Create indexes for new granular TTL columns (ignore if they already exist)
-    let _ = conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_game_details_dynamic_expires ON game_details(dynamic_expires_at)",
-        [],
-    );
-    
-    let _ = conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_game_details_semistatic_expires ON game_details(semistatic_expires_at)",
-        [],
-    );
-    
-    let _ = conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_game_details_static_expires ON game_details(static_expires_at)",
-        [],
-    );
-    
-    // Update existing records with appropriate TTL values
-    let now = chrono::Utc::now().timestamp();
-    
-    // Import TTL config
-    use crate::database::ttl_config::TtlConfig;
-    
-    conn.execute(
-        "UPDATE game_details SET 
-         dynamic_expires_at = ?1,
-         semistatic_expires_at = ?2,
-         static_expires_at = ?3
-         WHERE dynamic_expires_at = 0",
-        [
-            now + TtlConfig::DLC_LIST,
-            now + TtlConfig::GAME_NAME, 
-            now + TtlConfig::SCREENSHOTS,
-        ],
+
+    conn.execute_batch(
+        "CREATE TABLE schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )",
     )?;
-    
-    println!("Granular TTL schema migration completed successfully");
+
+    for migration in migrations.iter().filter(|m| m.version <= legacy_version) {
+        record_migration(conn, migration)?;
+    }
+    if legacy_version > 0 {
+        println!("Adopted legacy schema version {} into schema_migrations", legacy_version);
+    }
+
     Ok(())
 }
 
-/// Migration to version 3: Add user profile table
-fn migrate_to_v3(conn: &Connection) -> Result<()> {
-    println!("Adding user profile table (v3)...");
-    
-    // Check if user_profile table already exists
-    let table_exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='user_profile'",
-        [],
-        |row| Ok(row.get::<_, i32>(0)? > 0)
-    )?;
-    
-    if !table_exists {
-        // Create user_profile table
-        conn.execute(
-            "CREATE TABLE user_profile (
-                id INTEGER PRIMARY KEY DEFAULT 1,
-                name TEXT NOT NULL DEFAULT 'Nazril',
-                steam_id TEXT,
-                banner_path TEXT,
-                avatar_path TEXT,
-                created_at INTEGER DEFAULT (strftime('%s', 'now')),
-                updated_at INTEGER DEFAULT (strftime('%s', 'now'))
-            )",
-            [],
-        )?;
-        
-        // Create index for user_profile
-        let _ = conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_user_profile_updated_at ON user_profile(updated_at)",
-            [],
-        );
-        
-        // Insert default user profile
-        conn.execute(
-            "INSERT INTO user_profile (id, name) VALUES (1, 'User')",
-            [],
-        )?;
+/// Refuse to run when an applied migration was edited or comes from a newer build
+fn verify_checksums(conn: &Connection, migrations: &[Migration]) -> Result<()> {
+    for applied in applied_migrations(conn)? {
+        match migrations.iter().find(|m| m.version == applied.version) {
+            Some(migration) if migration.checksum() != applied.checksum => {
+                return Err(anyhow!(
+                    "Migration {} was modified after it was applied (checksum {} != {})",
+                    migration.name,
+                    migration.checksum(),
+                    applied.checksum
+                ));
+            }
+            Some(_) => {}
+            None => {
+                return Err(anyhow!(
+                    "Database has migration {} ({}) that this build does not know about",
+                    applied.version,
+                    applied.name
+                ));
+            }
+        }
     }
-    
-    println!("User profile table migration completed successfully");
     Ok(())
 }
 
-/// Migration to version 4: Add bypass_games table
-fn migrate_to_v4(conn: &Connection) -> Result<()> {
-    println!("Adding bypass_games table (v4)...");
-    
-    // Check if bypass_games table already exists
-    let table_exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='bypass_games'",
-        [],
-        |row| Ok(row.get::<_, i32>(0)? > 0)
+fn record_migration(conn: &Connection, migration: &Migration) -> Result<()> {
+    conn.execute(
+        "INSERT INTO schema_migrations (version, name, checksum) VALUES (?1, ?2, ?3)",
+        rusqlite::params![migration.version, migration.name, migration.checksum()],
     )?;
-    
-    if !table_exists {
-        // Create bypass_games table
-        conn.execute(
-            "CREATE TABLE bypass_games (
-                app_id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                image TEXT NOT NULL,
-                bypasses TEXT NOT NULL,
-                cached_at INTEGER NOT NULL,
-                expires_at INTEGER NOT NULL,
-                last_updated INTEGER DEFAULT (strftime('%s', 'now'))
-            )",
-            [],
-        )?;
-        
-        // Create indexes for bypass_games
-        let _ = conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bypass_games_name ON bypass_games(name)",
-            [],
-        );
-        
-        let _ = conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bypass_games_cached_at ON bypass_games(cached_at)",
-            [],
-        );
-        
-        let _ = conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_bypass_games_expires_at ON bypass_games(expires_at)",
-            [],
-        );
-        
-        println!("bypass_games table created successfully");
-    } else {
-        println!("bypass_games table already exists, skipping creation");
+
+    // Keep the legacy marker in sync for code that still reads it
+    if table_exists(conn, "cache_metadata")? {
+        set_schema_version(conn, migration.version)?;
     }
-    
-    println!("Bypass games table migration completed successfully");
     Ok(())
 }
 
-/// Migration to version 5: Add TTL and backup support to user profile
-fn migrate_to_v5(conn: &Connection) -> Result<()> {
-    println!("Adding TTL and backup support to user profile (v5)...");
-    
-    // Use transaction for safe migration
-    conn.execute("BEGIN TRANSACTION", [])?;
-    
-    let result = migrate_v5_internal(conn);
-    
-    match result {
-        Ok(_) => {
-            conn.execute("COMMIT", [])?;
-            println!("Migration to v5 completed successfully - Profile TTL and backup support added");
-            Ok(())
-        }
-        Err(e) => {
-            conn.execute("ROLLBACK", [])?;
-            eprintln!("Migration v5 failed, rolled back: {}", e);
-            // Don't fail the entire migration - just log the error
-            println!("⚠️  Migration v5 skipped due to error, continuing with existing schema");
-            Ok(())
-        }
-    }
+fn table_exists(conn: &Connection, name: &str) -> Result<bool> {
+    Ok(conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name=?1")?
+        .exists([name])?)
 }
 
-/// Internal migration logic for v5
-fn migrate_v5_internal(conn: &Connection) -> Result<()> {
-    // Check existing columns in user_profile table
-    let mut stmt = conn.prepare("PRAGMA table_info(user_profile)")?;
-    let column_names: Vec<String> = stmt.query_map([], |row| {
-        Ok(row.get::<_, String>(1)?) // column name is at index 1
-    })?.collect::<Result<Vec<_>, _>>()?;
-    
-    // Get current timestamp for migration
-    let now = chrono::Utc::now().timestamp();
-    let expires_in_year = now + 31536000; // 1 year from now
-    
-    // Add new TTL columns if they don't exist
-    if !column_names.contains(&"cached_at".to_string()) {
-        conn.execute(
-            "ALTER TABLE user_profile ADD COLUMN cached_at INTEGER DEFAULT 0",
-            [],
-        )?;
-        // Update existing records with current timestamp
-        conn.execute(
-            "UPDATE user_profile SET cached_at = ?1 WHERE cached_at = 0",
-            [now],
-        )?;
-    }
-    
-    if !column_names.contains(&"expires_at".to_string()) {
-        conn.execute(
-            "ALTER TABLE user_profile ADD COLUMN expires_at INTEGER DEFAULT 0",
-            [],
-        )?;
-        // Update existing records with future timestamp (1 year)
-        conn.execute(
-            "UPDATE user_profile SET expires_at = ?1 WHERE expires_at = 0",
-            [expires_in_year],
-        )?;
-    }
-    
-    if !column_names.contains(&"is_backed_up".to_string()) {
-        conn.execute(
-            "ALTER TABLE user_profile ADD COLUMN is_backed_up INTEGER DEFAULT 0",
+/// Get current schema version from database
+pub fn get_schema_version(conn: &Connection) -> Result<i32> {
+    if table_exists(conn, "schema_migrations")? {
+        return Ok(conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
             [],
-        )?;
+            |row| row.get(0),
+        )?);
     }
-    
-    if !column_names.contains(&"backup_created_at".to_string()) {
-        conn.execute(
-            "ALTER TABLE user_profile ADD COLUMN backup_created_at INTEGER DEFAULT 0",
-            [],
-        )?;
+
+    legacy_schema_version(conn)
+}
+
+/// Version written to cache_metadata by the hand-coded migrations
+fn legacy_schema_version(conn: &Connection) -> Result<i32> {
+    if !table_exists(conn, "cache_metadata")? {
+        return Ok(0); // No schema exists yet
     }
-    
-    // Create backup table if it doesn't exist
-    let backup_table_exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='user_profile_backup'",
+
+    // Try to get schema version
+    match conn.query_row(
+        "SELECT value FROM cache_metadata WHERE key = 'schema_version'",
         [],
-        |row| Ok(row.get::<_, i32>(0)? > 0)
-    )?;
-    
-    if !backup_table_exists {
-            conn.execute(
-                "CREATE TABLE user_profile_backup (
-                    id INTEGER PRIMARY KEY DEFAULT 1,
-                    name TEXT NOT NULL DEFAULT 'User',
-                    steam_id TEXT,
-                    banner_path TEXT,
-                    avatar_path TEXT,
-                    created_at INTEGER DEFAULT 0,
-                    updated_at INTEGER DEFAULT 0,
-                    cached_at INTEGER DEFAULT 0,
-                    expires_at INTEGER DEFAULT 0,
-                    backup_created_at INTEGER DEFAULT 0,
-                    backup_reason TEXT DEFAULT 'manual'
-                )",
-                [],
-            )?;
-        
-        // Create backup from existing profile data
-        let profile_exists: bool = conn.query_row(
-            "SELECT COUNT(*) FROM user_profile WHERE id = 1",
-            [],
-            |row| Ok(row.get::<_, i32>(0)? > 0)
-        )?;
-        
-        if profile_exists {
-            conn.execute(
-                "INSERT INTO user_profile_backup 
-                 (id, name, steam_id, banner_path, avatar_path, created_at, updated_at, 
-                  cached_at, expires_at, backup_created_at, backup_reason)
-                 SELECT id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
-                        ?1, ?2, ?1, 'migration_v5'
-                 FROM user_profile WHERE id = 1",
-                [now, expires_in_year],
-            )?;
-        }
+        |row| {
+            let value: String = row.get(0)?;
+            Ok(value.parse::<i32>().unwrap_or(0))
+        },
+    ) {
+        Ok(version) => Ok(version),
+        Err(_) => Ok(0), // No version found, assume 0
     }
-    
+}
+
+/// Set schema version in database
+fn set_schema_version(conn: &Connection, version: i32) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO cache_metadata (key, value, updated_at) VALUES ('schema_version', ?1, strftime('%s', 'now'))",
+        [version.to_string()],
+    )?;
     Ok(())
 }
 
@@ -453,137 +445,181 @@ impl std::fmt::Display for DatabaseInfo {
     }
 }
 
-/// Migration to version 6: Remove bio column from user profile tables
-fn migrate_to_v6(conn: &Connection) -> Result<()> {
-    println!("Removing bio column from user profile tables (v6)...");
-    
-    // Check if bio column exists in user_profile table
-    let bio_exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM pragma_table_info('user_profile') WHERE name='bio'",
-        [],
-        |row| Ok(row.get::<_, i32>(0)? > 0)
-    )?;
-    
-    if bio_exists {
-        // SQLite doesn't support DROP COLUMN directly, so we need to recreate the table
-        
-        // 1. Create new table without bio column
-        conn.execute(
-            "CREATE TABLE user_profile_new (
-                id INTEGER PRIMARY KEY DEFAULT 1,
-                name TEXT NOT NULL DEFAULT 'User',
-                steam_id TEXT,
-                banner_path TEXT,
-                avatar_path TEXT,
-                created_at INTEGER DEFAULT 0,
-                updated_at INTEGER DEFAULT 0,
-                cached_at INTEGER DEFAULT 0,
-                expires_at INTEGER DEFAULT 0,
-                is_backed_up INTEGER DEFAULT 0,
-                backup_created_at INTEGER DEFAULT 0
-            )",
-            [],
-        )?;
-        
-        // 2. Copy data from old table to new table (excluding bio)
-        conn.execute(
-            "INSERT INTO user_profile_new 
-             (id, name, steam_id, banner_path, avatar_path, created_at, updated_at, 
-              cached_at, expires_at, is_backed_up, backup_created_at)
-             SELECT id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
-                    cached_at, expires_at, is_backed_up, backup_created_at
-             FROM user_profile",
-            [],
-        )?;
-        
-        // 3. Drop old table
-        conn.execute("DROP TABLE user_profile", [])?;
-        
-        // 4. Rename new table
-        conn.execute("ALTER TABLE user_profile_new RENAME TO user_profile", [])?;
-        
-        // 5. Recreate index
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_user_profile_updated_at ON user_profile(updated_at)",
-            [],
-        )?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const FIXTURES: &[(&str, &str)] = &[
+        ("v1", include_str!("migrations/fixtures/v1.sql")),
+        ("v5_with_bio", include_str!("migrations/fixtures/v5_with_bio.sql")),
+    ];
+
+    fn open(sql: &str) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(sql).unwrap();
+        conn
     }
-    
-    // Do the same for backup table if it exists
-    let backup_bio_exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM pragma_table_info('user_profile_backup') WHERE name='bio'",
-        [],
-        |row| Ok(row.get::<_, i32>(0)? > 0)
-    ).unwrap_or(false);
-    
-    if backup_bio_exists {
-        // Create new backup table without bio
-        conn.execute(
-            "CREATE TABLE user_profile_backup_new (
-                id INTEGER PRIMARY KEY DEFAULT 1,
-                name TEXT NOT NULL DEFAULT 'User',
-                steam_id TEXT,
-                banner_path TEXT,
-                avatar_path TEXT,
-                created_at INTEGER DEFAULT 0,
-                updated_at INTEGER DEFAULT 0,
-                cached_at INTEGER DEFAULT 0,
-                expires_at INTEGER DEFAULT 0,
-                backup_created_at INTEGER DEFAULT 0,
-                backup_reason TEXT DEFAULT 'manual'
-            )",
-            [],
-        )?;
-        
-        // Copy data if backup table has data
-        let has_backup_data: bool = conn.query_row(
-            "SELECT COUNT(*) FROM user_profile_backup",
-            [],
-            |row| Ok(row.get::<_, i32>(0)? > 0)
-        ).unwrap_or(false);
-        
-        if has_backup_data {
-            conn.execute(
-                "INSERT INTO user_profile_backup_new 
-                 (id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
-                  cached_at, expires_at, backup_created_at, backup_reason)
-                 SELECT id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
-                        cached_at, expires_at, backup_created_at, backup_reason
-                 FROM user_profile_backup",
-                [],
-            )?;
+
+    /// Tables/views with their sorted columns, plus index names
+    fn schema_shape(conn: &Connection) -> BTreeMap<String, Vec<String>> {
+        let mut stmt = conn
+            .prepare("SELECT type, name FROM sqlite_master WHERE name NOT LIKE 'sqlite_%' AND name != 'schema_migrations'")
+            .unwrap();
+        let objects: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let mut shape = BTreeMap::new();
+        for (kind, name) in objects {
+            let mut columns: Vec<String> = Vec::new();
+            if kind == "table" || kind == "view" {
+                let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)").unwrap();
+                columns = stmt
+                    .query_map([&name], |row| row.get(0))
+                    .unwrap()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                columns.sort();
+            }
+            shape.insert(format!("{}:{}", kind, name), columns);
         }
-        
-        // Drop and rename backup table
-        conn.execute("DROP TABLE user_profile_backup", [])?;
-        conn.execute("ALTER TABLE user_profile_backup_new RENAME TO user_profile_backup", [])?;
+        shape
     }
-    
-    println!("Bio column removal migration completed successfully");
-    Ok(())
-}
 
-/// Migration to version 7: Add download history table
-fn migrate_to_v7(conn: &Connection) -> Result<()> {
-    println!("Adding download history table (v7)...");
-    
-    // Check if download_history table already exists
-    let table_exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='download_history'",
-        [],
-        |row| Ok(row.get::<_, i32>(0)? > 0)
-    )?;
-    
-    if !table_exists {
-        // Read and execute the history schema SQL
-        let history_schema = include_str!("history_schema.sql");
-        conn.execute_batch(history_schema)?;
-        
-        println!("Download history table created successfully");
-    } else {
-        println!("Download history table already exists, skipping creation");
+    fn head_shape() -> BTreeMap<String, Vec<String>> {
+        let conn = open("");
+        run_migrations(&conn).unwrap();
+        schema_shape(&conn)
+    }
+
+    #[test]
+    fn test_fresh_database_reaches_head() {
+        let conn = open("");
+        run_migrations(&conn).unwrap();
+
+        let head = MIGRATIONS.last().unwrap().version;
+        assert_eq!(get_schema_version(&conn).unwrap(), head);
+        assert_eq!(legacy_schema_version(&conn).unwrap(), head);
+        assert_eq!(applied_migrations(&conn).unwrap().len(), MIGRATIONS.len());
+        assert!(check_database_integrity(&conn).unwrap());
+
+        // Running again is a no-op
+        let report = migrate(&conn, MIGRATIONS, false).unwrap();
+        assert!(report.applied.is_empty());
+    }
+
+    #[test]
+    fn test_fixture_databases_reach_head() {
+        let head = head_shape();
+
+        for (name, sql) in FIXTURES {
+            let conn = open(sql);
+            run_migrations(&conn).unwrap_or_else(|e| panic!("{} failed to migrate: {}", name, e));
+
+            assert_eq!(schema_shape(&conn), head, "{} does not match the head schema", name);
+            assert_eq!(get_schema_version(&conn).unwrap(), MIGRATIONS.last().unwrap().version);
+
+            // User data survives the upgrade
+            let library: i64 = conn.query_row("SELECT COUNT(*) FROM user_library", [], |row| row.get(0)).unwrap();
+            assert_eq!(library, 1, "{} lost its library", name);
+        }
+    }
+
+    #[test]
+    fn test_bio_column_is_dropped_and_profile_kept() {
+        let conn = open(FIXTURES[1].1);
+        run_migrations(&conn).unwrap();
+
+        let (profile_name, steam_id): (String, String) = conn
+            .query_row("SELECT name, steam_id FROM user_profile WHERE id = 1", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(profile_name, "Player");
+        assert_eq!(steam_id, "7656119");
+    }
+
+    #[test]
+    fn test_dry_run_leaves_database_untouched() {
+        let conn = open(FIXTURES[0].1);
+        let before = schema_shape(&conn);
+
+        let report = plan_migrations(&conn).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.applied.len(), MIGRATIONS.len() - 1);
+
+        assert_eq!(schema_shape(&conn), before);
+        assert!(!table_exists(&conn, "schema_migrations").unwrap());
+        assert_eq!(legacy_schema_version(&conn).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let broken = [
+            MIGRATIONS[0],
+            Migration {
+                version: 2,
+                name: "0002_broken",
+                up: "CREATE TABLE half_done (id INTEGER); ALTER TABLE missing_table ADD COLUMN x INTEGER;",
+                down: "DROP TABLE IF EXISTS half_done;",
+            },
+        ];
+
+        let conn = open("");
+        let err = migrate(&conn, &broken, false).unwrap_err();
+        assert!(err.to_string().contains("0002_broken"));
+
+        // The first migration stays, nothing from the broken one is left behind
+        assert_eq!(get_schema_version(&conn).unwrap(), 1);
+        assert!(table_exists(&conn, "games").unwrap());
+        assert!(!table_exists(&conn, "half_done").unwrap());
+        assert_eq!(legacy_schema_version(&conn).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rollback_and_reapply() {
+        let conn = open("");
+        run_migrations(&conn).unwrap();
+        let head = schema_shape(&conn);
+
+        let reverted = rollback(&conn, MIGRATIONS, 0).unwrap();
+        assert_eq!(reverted.len(), MIGRATIONS.len());
+        assert!(schema_shape(&conn).is_empty());
+
+        run_migrations(&conn).unwrap();
+        assert_eq!(schema_shape(&conn), head);
+    }
+
+    #[test]
+    fn test_rollback_is_guarded_and_backed_up() {
+        let backups = tempfile::tempdir().unwrap();
+        let conn = open("");
+        run_migrations(&conn).unwrap();
+        let head = MIGRATIONS.last().unwrap();
+
+        assert!(rollback_to(&conn, backups.path(), 0).is_err());
+        assert!(rollback_to(&conn, backups.path(), head.version).is_err());
+        assert!(BackupOperations::list_backups(backups.path()).unwrap().is_empty());
+
+        let report = rollback_to(&conn, backups.path(), head.version - 1).unwrap();
+        assert_eq!(report.reverted, [head.name]);
+        assert_eq!(report.backup.kind, BackupKind::PreMigration);
+        assert_eq!(get_schema_version(&conn).unwrap(), head.version - 1);
+
+        // The backup still has the reverted tables
+        let backup = Connection::open(&report.backup.path).unwrap();
+        assert_eq!(get_schema_version(&backup).unwrap(), head.version);
+    }
+
+    #[test]
+    fn test_modified_migration_is_rejected() {
+        let conn = open("");
+        run_migrations(&conn).unwrap();
+        conn.execute("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 3", []).unwrap();
+
+        let err = migrate(&conn, MIGRATIONS, false).unwrap_err();
+        assert!(err.to_string().contains("0003_user_profile"));
     }
-    
-    println!("Download history migration completed successfully");
-    Ok(())
 }
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS user_library;
+DROP TABLE IF EXISTS game_details;
+DROP TABLE IF EXISTS games;
+DROP TABLE IF EXISTS cache_metadata;
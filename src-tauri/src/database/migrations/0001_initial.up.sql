@@ -0,0 +1,72 @@
+-- Initial Zenith Launcher schema
+-- Based on actual GameDetail struct and LibraryGame struct
+
+-- Games basic info (from LibraryGame struct)
+CREATE TABLE games (
+    app_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    header_image TEXT,
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    last_updated INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Detailed game information (from GameDetail struct)
+CREATE TABLE game_details (
+    app_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    header_image TEXT,
+    banner_image TEXT,
+    detailed_description TEXT,
+    release_date TEXT,
+    publisher TEXT,
+    trailer TEXT, -- Optional trailer URL
+    screenshots TEXT, -- JSON array of screenshot URLs
+    sysreq_min TEXT, -- JSON array of (String, String) tuples for minimum requirements
+    sysreq_rec TEXT, -- JSON array of (String, String) tuples for recommended requirements
+    pc_requirements TEXT, -- JSON blob for PcRequirements struct (minimum/recommended)
+    dlc TEXT, -- JSON array of DLC AppIDs
+    drm_notice TEXT, -- Optional DRM information
+
+    -- Global cache timestamps
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    last_updated INTEGER DEFAULT (strftime('%s', 'now')),
+
+    FOREIGN KEY (app_id) REFERENCES games(app_id) ON DELETE CASCADE
+);
+
+-- User library tracking (for My Library feature)
+CREATE TABLE user_library (
+    app_id TEXT PRIMARY KEY,
+    added_at INTEGER DEFAULT (strftime('%s', 'now')),
+    last_accessed INTEGER,
+    access_count INTEGER DEFAULT 0,
+    FOREIGN KEY (app_id) REFERENCES games(app_id) ON DELETE CASCADE
+);
+
+-- Cache metadata and application settings
+CREATE TABLE cache_metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Indexes for better query performance
+CREATE INDEX idx_games_name ON games(name);
+CREATE INDEX idx_games_cached_at ON games(cached_at);
+CREATE INDEX idx_games_expires_at ON games(expires_at);
+
+CREATE INDEX idx_game_details_cached_at ON game_details(cached_at);
+CREATE INDEX idx_game_details_expires_at ON game_details(expires_at);
+CREATE INDEX idx_game_details_name ON game_details(name);
+
+CREATE INDEX idx_user_library_added_at ON user_library(added_at);
+CREATE INDEX idx_user_library_last_accessed ON user_library(last_accessed);
+
+CREATE INDEX idx_cache_metadata_key ON cache_metadata(key);
+
+-- Insert initial metadata
+INSERT INTO cache_metadata (key, value) VALUES
+    ('created_at', strftime('%s', 'now')),
+    ('last_cleanup', '0');
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_game_details_dynamic_expires;
+DROP INDEX IF EXISTS idx_game_details_semistatic_expires;
+DROP INDEX IF EXISTS idx_game_details_static_expires;
+
+ALTER TABLE game_details DROP COLUMN dynamic_expires_at;
+ALTER TABLE game_details DROP COLUMN semistatic_expires_at;
+ALTER TABLE game_details DROP COLUMN static_expires_at;
@@ -0,0 +1,17 @@
+-- Granular expiry timestamps for different data categories
+ALTER TABLE game_details ADD COLUMN dynamic_expires_at INTEGER NOT NULL DEFAULT 0;    -- For DLC list
+ALTER TABLE game_details ADD COLUMN semistatic_expires_at INTEGER NOT NULL DEFAULT 0; -- For name, images, trailer
+ALTER TABLE game_details ADD COLUMN static_expires_at INTEGER NOT NULL DEFAULT 0;     -- For screenshots, descriptions, sysreq
+
+-- Indexes for granular TTL queries
+CREATE INDEX idx_game_details_dynamic_expires ON game_details(dynamic_expires_at);
+CREATE INDEX idx_game_details_semistatic_expires ON game_details(semistatic_expires_at);
+CREATE INDEX idx_game_details_static_expires ON game_details(static_expires_at);
+
+-- Give existing records the TtlConfig defaults at the time of the migration
+-- (DLC_LIST = 90 days, GAME_NAME = 180 days, SCREENSHOTS = 180 days)
+UPDATE game_details SET
+    dynamic_expires_at = CAST(strftime('%s', 'now') AS INTEGER) + 7776000,
+    semistatic_expires_at = CAST(strftime('%s', 'now') AS INTEGER) + 15552000,
+    static_expires_at = CAST(strftime('%s', 'now') AS INTEGER) + 15552000
+WHERE dynamic_expires_at = 0;
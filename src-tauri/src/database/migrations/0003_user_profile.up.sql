@@ -0,0 +1,15 @@
+-- User profile data (single profile entry)
+CREATE TABLE user_profile (
+    id INTEGER PRIMARY KEY DEFAULT 1,
+    name TEXT NOT NULL DEFAULT 'User',
+    steam_id TEXT,
+    banner_path TEXT, -- Local file path to banner image
+    avatar_path TEXT, -- Local file path to avatar image
+    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX idx_user_profile_updated_at ON user_profile(updated_at);
+
+-- Insert default user profile
+INSERT INTO user_profile (id, name) VALUES (1, 'User');
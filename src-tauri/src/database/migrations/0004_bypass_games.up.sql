@@ -0,0 +1,14 @@
+-- Bypass games data (static data with monthly TTL)
+CREATE TABLE bypass_games (
+    app_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    image TEXT NOT NULL,
+    bypasses TEXT NOT NULL, -- JSON array of bypass info (type, url)
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL, -- 1 month TTL for static data
+    last_updated INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX idx_bypass_games_name ON bypass_games(name);
+CREATE INDEX idx_bypass_games_cached_at ON bypass_games(cached_at);
+CREATE INDEX idx_bypass_games_expires_at ON bypass_games(expires_at);
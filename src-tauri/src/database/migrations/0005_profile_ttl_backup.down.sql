@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS user_profile_backup;
+
+ALTER TABLE user_profile DROP COLUMN backup_created_at;
+ALTER TABLE user_profile DROP COLUMN is_backed_up;
+ALTER TABLE user_profile DROP COLUMN expires_at;
+ALTER TABLE user_profile DROP COLUMN cached_at;
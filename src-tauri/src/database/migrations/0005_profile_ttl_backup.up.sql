@@ -0,0 +1,32 @@
+-- TTL and backup flags for the user profile
+ALTER TABLE user_profile ADD COLUMN cached_at INTEGER DEFAULT 0;
+ALTER TABLE user_profile ADD COLUMN expires_at INTEGER DEFAULT 0; -- TTL will be set programmatically
+ALTER TABLE user_profile ADD COLUMN is_backed_up INTEGER DEFAULT 0;
+ALTER TABLE user_profile ADD COLUMN backup_created_at INTEGER DEFAULT 0;
+
+-- Existing profiles are cached now and expire in one year
+UPDATE user_profile SET cached_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE cached_at = 0;
+UPDATE user_profile SET expires_at = CAST(strftime('%s', 'now') AS INTEGER) + 31536000 WHERE expires_at = 0;
+
+-- Profile backup table for recovery
+CREATE TABLE user_profile_backup (
+    id INTEGER PRIMARY KEY DEFAULT 1,
+    name TEXT NOT NULL DEFAULT 'User',
+    steam_id TEXT,
+    banner_path TEXT,
+    avatar_path TEXT,
+    created_at INTEGER DEFAULT 0,
+    updated_at INTEGER DEFAULT 0,
+    cached_at INTEGER DEFAULT 0,
+    expires_at INTEGER DEFAULT 0,
+    backup_created_at INTEGER DEFAULT 0,
+    backup_reason TEXT DEFAULT 'manual' -- migration, corruption, manual, etc.
+);
+
+-- Back up the current profile
+INSERT INTO user_profile_backup
+    (id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
+     cached_at, expires_at, backup_created_at, backup_reason)
+SELECT id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
+       cached_at, expires_at, cached_at, 'migration_v5'
+FROM user_profile WHERE id = 1;
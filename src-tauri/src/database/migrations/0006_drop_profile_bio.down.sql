@@ -0,0 +1,3 @@
+-- The bio text itself is not recoverable; only the column comes back
+ALTER TABLE user_profile ADD COLUMN bio TEXT;
+ALTER TABLE user_profile_backup ADD COLUMN bio TEXT;
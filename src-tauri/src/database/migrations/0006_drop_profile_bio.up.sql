@@ -0,0 +1,51 @@
+-- Early builds stored a bio column on both profile tables. Rebuilding the
+-- tables with an explicit column list drops it whether or not it exists.
+
+CREATE TABLE user_profile_new (
+    id INTEGER PRIMARY KEY DEFAULT 1,
+    name TEXT NOT NULL DEFAULT 'User',
+    steam_id TEXT,
+    banner_path TEXT,
+    avatar_path TEXT,
+    created_at INTEGER DEFAULT 0,
+    updated_at INTEGER DEFAULT 0,
+    cached_at INTEGER DEFAULT 0,
+    expires_at INTEGER DEFAULT 0,
+    is_backed_up INTEGER DEFAULT 0,
+    backup_created_at INTEGER DEFAULT 0
+);
+
+INSERT INTO user_profile_new
+    (id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
+     cached_at, expires_at, is_backed_up, backup_created_at)
+SELECT id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
+       cached_at, expires_at, is_backed_up, backup_created_at
+FROM user_profile;
+
+DROP TABLE user_profile;
+ALTER TABLE user_profile_new RENAME TO user_profile;
+CREATE INDEX idx_user_profile_updated_at ON user_profile(updated_at);
+
+CREATE TABLE user_profile_backup_new (
+    id INTEGER PRIMARY KEY DEFAULT 1,
+    name TEXT NOT NULL DEFAULT 'User',
+    steam_id TEXT,
+    banner_path TEXT,
+    avatar_path TEXT,
+    created_at INTEGER DEFAULT 0,
+    updated_at INTEGER DEFAULT 0,
+    cached_at INTEGER DEFAULT 0,
+    expires_at INTEGER DEFAULT 0,
+    backup_created_at INTEGER DEFAULT 0,
+    backup_reason TEXT DEFAULT 'manual'
+);
+
+INSERT INTO user_profile_backup_new
+    (id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
+     cached_at, expires_at, backup_created_at, backup_reason)
+SELECT id, name, steam_id, banner_path, avatar_path, created_at, updated_at,
+       cached_at, expires_at, backup_created_at, backup_reason
+FROM user_profile_backup;
+
+DROP TABLE user_profile_backup;
+ALTER TABLE user_profile_backup_new RENAME TO user_profile_backup;
@@ -0,0 +1,2 @@
+DROP VIEW IF EXISTS download_history_summary;
+DROP TABLE IF EXISTS download_history;
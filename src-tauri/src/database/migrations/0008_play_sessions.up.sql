@@ -0,0 +1,20 @@
+-- Play Sessions Table
+CREATE TABLE IF NOT EXISTS play_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    app_id TEXT,                         -- Steam App ID (NULL if it could not be resolved)
+    executable_path TEXT NOT NULL,       -- Executable that was launched
+    pid INTEGER,                         -- PID of the launched root process
+
+    -- Timestamps (Unix seconds)
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER,                    -- NULL while the game is still running
+
+    -- Result
+    duration_seconds INTEGER DEFAULT 0,  -- Wall-clock time until the whole process tree exited
+    exit_code INTEGER                    -- Exit code of the root process, if known
+);
+
+-- Indexes for performance
+CREATE INDEX IF NOT EXISTS idx_play_sessions_app_id ON play_sessions(app_id);
+CREATE INDEX IF NOT EXISTS idx_play_sessions_started_at ON play_sessions(started_at);
+CREATE INDEX IF NOT EXISTS idx_play_sessions_ended_at ON play_sessions(ended_at);
@@ -0,0 +1,29 @@
+-- Launch Profiles Table
+CREATE TABLE IF NOT EXISTS launch_profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    app_id TEXT NOT NULL,                -- Steam App ID (must be in user_library)
+    name TEXT NOT NULL,                  -- Display name, e.g. 'Default', 'DX11', 'Modded'
+
+    -- Launch configuration
+    executable_path TEXT NOT NULL,
+    arguments TEXT NOT NULL DEFAULT '[]',      -- JSON array of arguments
+    env_overrides TEXT NOT NULL DEFAULT '{}',  -- JSON object of environment variables
+    working_dir TEXT,                          -- Defaults to the executable's folder
+
+    -- Hooks (shell command lines)
+    pre_launch_hook TEXT,                -- Runs before the game; non-zero exit aborts launch
+    post_exit_hook TEXT,                 -- Runs after the game's process tree exits
+
+    is_default INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+
+    FOREIGN KEY (app_id) REFERENCES user_library(app_id) ON DELETE CASCADE
+);
+
+-- Indexes for performance
+CREATE INDEX IF NOT EXISTS idx_launch_profiles_app_id ON launch_profiles(app_id);
+
+-- Only one default profile per game
+CREATE UNIQUE INDEX IF NOT EXISTS idx_launch_profiles_default
+    ON launch_profiles(app_id) WHERE is_default = 1;
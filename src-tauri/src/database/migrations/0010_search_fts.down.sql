@@ -0,0 +1,12 @@
+DROP TABLE IF EXISTS download_history_fts_vocab;
+DROP TABLE IF EXISTS game_details_fts_vocab;
+
+DROP TRIGGER IF EXISTS download_history_fts_delete;
+DROP TRIGGER IF EXISTS download_history_fts_update;
+DROP TRIGGER IF EXISTS download_history_fts_insert;
+DROP TABLE IF EXISTS download_history_fts;
+
+DROP TRIGGER IF EXISTS game_details_fts_delete;
+DROP TRIGGER IF EXISTS game_details_fts_update;
+DROP TRIGGER IF EXISTS game_details_fts_insert;
+DROP TABLE IF EXISTS game_details_fts;
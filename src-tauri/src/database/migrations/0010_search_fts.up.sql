@@ -0,0 +1,66 @@
+-- Full-text search over cached game details and download history
+
+-- Game details: rowid is the numeric Steam App ID, so rows stay in sync even
+-- when game_details is written with INSERT OR REPLACE (which skips delete triggers)
+CREATE VIRTUAL TABLE game_details_fts USING fts5(
+    name,
+    publisher,
+    description,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE TRIGGER game_details_fts_insert AFTER INSERT ON game_details
+WHEN new.app_id NOT GLOB '*[^0-9]*' BEGIN
+    DELETE FROM game_details_fts WHERE rowid = CAST(new.app_id AS INTEGER);
+    INSERT INTO game_details_fts (rowid, name, publisher, description)
+    VALUES (CAST(new.app_id AS INTEGER), new.name, new.publisher, new.detailed_description);
+END;
+
+CREATE TRIGGER game_details_fts_update AFTER UPDATE OF name, publisher, detailed_description ON game_details
+WHEN new.app_id NOT GLOB '*[^0-9]*' BEGIN
+    DELETE FROM game_details_fts WHERE rowid = CAST(new.app_id AS INTEGER);
+    INSERT INTO game_details_fts (rowid, name, publisher, description)
+    VALUES (CAST(new.app_id AS INTEGER), new.name, new.publisher, new.detailed_description);
+END;
+
+CREATE TRIGGER game_details_fts_delete AFTER DELETE ON game_details
+WHEN old.app_id NOT GLOB '*[^0-9]*' BEGIN
+    DELETE FROM game_details_fts WHERE rowid = CAST(old.app_id AS INTEGER);
+END;
+
+-- Download history: rowid is download_history.id
+CREATE VIRTUAL TABLE download_history_fts USING fts5(
+    file_name,
+    game_name,
+    app_id,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE TRIGGER download_history_fts_insert AFTER INSERT ON download_history BEGIN
+    INSERT INTO download_history_fts (rowid, file_name, game_name, app_id)
+    VALUES (new.id, new.file_name, new.game_name, new.app_id);
+END;
+
+CREATE TRIGGER download_history_fts_update AFTER UPDATE OF file_name, game_name, app_id ON download_history BEGIN
+    DELETE FROM download_history_fts WHERE rowid = old.id;
+    INSERT INTO download_history_fts (rowid, file_name, game_name, app_id)
+    VALUES (new.id, new.file_name, new.game_name, new.app_id);
+END;
+
+CREATE TRIGGER download_history_fts_delete AFTER DELETE ON download_history BEGIN
+    DELETE FROM download_history_fts WHERE rowid = old.id;
+END;
+
+-- Term lists used to correct typos in search queries
+CREATE VIRTUAL TABLE game_details_fts_vocab USING fts5vocab(game_details_fts, 'row');
+CREATE VIRTUAL TABLE download_history_fts_vocab USING fts5vocab(download_history_fts, 'row');
+
+-- Backfill existing rows
+INSERT INTO game_details_fts (rowid, name, publisher, description)
+SELECT CAST(app_id AS INTEGER), name, publisher, detailed_description
+FROM game_details WHERE app_id NOT GLOB '*[^0-9]*';
+
+INSERT INTO download_history_fts (rowid, file_name, game_name, app_id)
+SELECT id, file_name, game_name, app_id FROM download_history;
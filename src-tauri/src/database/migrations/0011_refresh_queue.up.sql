@@ -0,0 +1,16 @@
+-- Persistent queue of cache entries waiting for a background refresh
+CREATE TABLE IF NOT EXISTS refresh_queue (
+    app_id TEXT NOT NULL,
+    kind TEXT NOT NULL,                  -- 'name' or 'details'
+    enqueued_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+
+    -- Retry state for failed refreshes
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+
+    -- One pending refresh per game and kind
+    PRIMARY KEY (app_id, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_queue_next_attempt ON refresh_queue(next_attempt_at);
@@ -0,0 +1,16 @@
+-- Local copies of Steam header, banner and screenshot images
+CREATE TABLE IF NOT EXISTS image_cache (
+    url TEXT PRIMARY KEY,                -- Remote (CDN) URL
+    hash TEXT NOT NULL,                  -- SHA-1 of the content; file name under cache/images
+    kind TEXT NOT NULL,                  -- 'header', 'banner' or 'screenshot' (picks the TTL)
+    content_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    thumbnail_bytes INTEGER NOT NULL DEFAULT 0,
+
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    last_accessed INTEGER NOT NULL       -- LRU eviction order
+);
+
+CREATE INDEX IF NOT EXISTS idx_image_cache_hash ON image_cache(hash);
+CREATE INDEX IF NOT EXISTS idx_image_cache_last_accessed ON image_cache(last_accessed);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS metadata_resources;
+DROP TABLE IF EXISTS catalogue_cache;
@@ -0,0 +1,24 @@
+-- Hydra catalogue pages and search results, keyed by the normalized request
+CREATE TABLE IF NOT EXISTS catalogue_cache (
+    cache_key TEXT PRIMARY KEY,          -- Canonical JSON of the normalized query
+    kind TEXT NOT NULL,                  -- 'page' (no title or filters) or 'search'
+    response_json TEXT NOT NULL,         -- CatalogueResponse as returned by the API
+    etag TEXT,
+
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    last_accessed INTEGER NOT NULL       -- Oldest searches are pruned first
+);
+
+CREATE INDEX IF NOT EXISTS idx_catalogue_cache_kind_accessed ON catalogue_cache(kind, last_accessed);
+
+-- The genre, tag, developer and publisher JSON files behind the catalogue filters
+CREATE TABLE IF NOT EXISTS metadata_resources (
+    name TEXT PRIMARY KEY,               -- File name, e.g. 'steam-genres.json'
+    body TEXT NOT NULL,
+    etag TEXT,
+    last_modified TEXT,
+
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL
+);
@@ -0,0 +1,31 @@
+-- Games watched before they are owned, with their latest price and an optional price alert.
+-- Prices are in the currency's smallest unit (cents), as appdetails `price_overview` reports them.
+CREATE TABLE IF NOT EXISTS wishlist (
+    app_id TEXT PRIMARY KEY,
+    name TEXT,
+    added_at INTEGER NOT NULL,
+    target_price INTEGER,                -- Alert when the price reaches this; NULL for no alert
+    target_currency TEXT,                -- Currency of target_price; prices in another never alert
+    notified_price INTEGER,              -- Price last alerted on, so a drop alerts once
+
+    currency TEXT,
+    initial_price INTEGER,
+    final_price INTEGER,
+    discount_percent INTEGER,
+    last_checked_at INTEGER              -- NULL until the first check
+);
+
+CREATE INDEX IF NOT EXISTS idx_wishlist_last_checked ON wishlist(last_checked_at);
+
+-- One row per observed price change of a wishlisted game
+CREATE TABLE IF NOT EXISTS price_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    app_id TEXT NOT NULL REFERENCES wishlist(app_id) ON DELETE CASCADE,
+    recorded_at INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    initial_price INTEGER NOT NULL,
+    final_price INTEGER NOT NULL,
+    discount_percent INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_history_app ON price_history(app_id, recorded_at);
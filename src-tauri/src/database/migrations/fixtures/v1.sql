@@ -0,0 +1,63 @@
+-- Fixture: database created by the first release (schema version 1)
+CREATE TABLE games (
+    app_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    header_image TEXT,
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    last_updated INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE TABLE game_details (
+    app_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    header_image TEXT,
+    banner_image TEXT,
+    detailed_description TEXT,
+    release_date TEXT,
+    publisher TEXT,
+    trailer TEXT,
+    screenshots TEXT,
+    sysreq_min TEXT,
+    sysreq_rec TEXT,
+    pc_requirements TEXT,
+    dlc TEXT,
+    drm_notice TEXT,
+    cached_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    last_updated INTEGER DEFAULT (strftime('%s', 'now')),
+    FOREIGN KEY (app_id) REFERENCES games(app_id) ON DELETE CASCADE
+);
+
+CREATE TABLE user_library (
+    app_id TEXT PRIMARY KEY,
+    added_at INTEGER DEFAULT (strftime('%s', 'now')),
+    last_accessed INTEGER,
+    access_count INTEGER DEFAULT 0,
+    FOREIGN KEY (app_id) REFERENCES games(app_id) ON DELETE CASCADE
+);
+
+CREATE TABLE cache_metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX idx_games_name ON games(name);
+CREATE INDEX idx_games_cached_at ON games(cached_at);
+CREATE INDEX idx_games_expires_at ON games(expires_at);
+CREATE INDEX idx_game_details_cached_at ON game_details(cached_at);
+CREATE INDEX idx_game_details_expires_at ON game_details(expires_at);
+CREATE INDEX idx_game_details_name ON game_details(name);
+CREATE INDEX idx_user_library_added_at ON user_library(added_at);
+CREATE INDEX idx_user_library_last_accessed ON user_library(last_accessed);
+CREATE INDEX idx_cache_metadata_key ON cache_metadata(key);
+
+INSERT INTO cache_metadata (key, value) VALUES
+    ('schema_version', '1'),
+    ('created_at', '1690000000'),
+    ('last_cleanup', '0');
+
+INSERT INTO games (app_id, name, cached_at, expires_at) VALUES ('570', 'Dota 2', 1690000000, 1790000000);
+INSERT INTO game_details (app_id, name, cached_at, expires_at) VALUES ('570', 'Dota 2', 1690000000, 1790000000);
+INSERT INTO user_library (app_id) VALUES ('570');
@@ -1,60 +1,207 @@
 use anyhow::Result;
-use rusqlite::{Connection, OpenFlags};
+use rusqlite::{Connection, DatabaseName, OpenFlags};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use crate::app_paths::app_paths;
+use backup_models::BackupKind;
+use backup_operations::BackupOperations;
+use recovery::RecoveryReport;
 
 pub mod models;
 pub mod migrations;
 pub mod operations;
 pub mod cache_service;
+pub mod cache_metrics;
 pub mod history_models;
 pub mod history_operations;
 pub mod history_commands;
+pub mod playtime_models;
+pub mod playtime_operations;
+pub mod playtime_commands;
+pub mod launch_profile_models;
+pub mod launch_profile_operations;
+pub mod launch_profile_commands;
+pub mod backup_models;
+pub mod backup_operations;
+pub mod backup_commands;
+pub mod recovery;
+pub mod search_models;
+pub mod search_operations;
+pub mod search_commands;
+pub mod user_data_models;
+pub mod user_data_operations;
+pub mod user_data_commands;
+pub mod refresh_queue_models;
+pub mod refresh_queue_operations;
+pub mod image_cache_models;
+pub mod image_cache_operations;
+pub mod image_cache_commands;
+pub mod catalogue_cache_models;
+pub mod catalogue_cache_operations;
+pub mod catalogue_cache_commands;
+pub mod catalogue_enrichment_models;
+pub mod catalogue_enrichment_operations;
+pub mod wishlist_models;
+pub mod wishlist_operations;
+pub mod wishlist_commands;
 pub mod legacy_adapter;
 pub mod migration_utils;
 pub mod commands;
 pub mod ttl_config;
 
-/// Database manager for SQLite operations
+/// Number of read-only connections kept next to the single writer
+const READ_POOL_SIZE: usize = 4;
+
+/// How long a connection waits on a locked database before failing
+const BUSY_TIMEOUT_MS: u32 = 5000;
+
+/// Handle shared by every command through Tauri state
+pub type SharedDatabase = Arc<DatabaseManager>;
+
+static SHARED_DATABASE: OnceLock<SharedDatabase> = OnceLock::new();
+static SHARED_DATABASE_INIT: Mutex<()> = Mutex::new(());
+
+/// Process-wide database handle. Opened (and migrated) once, on first use.
+pub fn shared_database() -> Result<SharedDatabase> {
+    if let Some(db) = SHARED_DATABASE.get() {
+        return Ok(db.clone());
+    }
+
+    // Serialise the first open so migrations never run twice
+    let _guard = SHARED_DATABASE_INIT.lock().unwrap();
+    if let Some(db) = SHARED_DATABASE.get() {
+        return Ok(db.clone());
+    }
+
+    let db = match app_paths().ensure_database_path().and_then(DatabaseManager::new) {
+        Ok(db) => db,
+        Err(e) => {
+            // Keep the app usable (and the cache statics from panicking) even if the disk is unusable
+            eprintln!("🚨 Failed to open games database, falling back to memory: {}", e);
+            DatabaseManager::in_memory(app_paths().database_path(), e.to_string())?
+        }
+    };
+    let db = Arc::new(db);
+    Ok(SHARED_DATABASE.get_or_init(|| db).clone())
+}
+
+/// Database manager for SQLite operations.
+/// Writes go through a single connection; reads are spread over a small pool.
 pub struct DatabaseManager {
-    connection: Arc<Mutex<Connection>>,
+    writer: Mutex<Connection>,
+    readers: Vec<Mutex<Connection>>,
+    next_reader: AtomicUsize,
     db_path: PathBuf,
+    recovery: Option<RecoveryReport>,
 }
 
 impl DatabaseManager {
-    /// Create a new database manager instance
+    /// Open the database, run migrations and fill the read pool
     pub fn new(db_path: PathBuf) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        // Open database with appropriate flags
+        // A damaged file is quarantined and rebuilt with whatever user data can be read
+        let (conn, salvage) = match Self::open_writer(&db_path) {
+            Ok((conn, None)) => (conn, None),
+            Ok((conn, Some(problems))) => {
+                drop(conn);
+                eprintln!("🚨 Database failed quick_check: {}", problems);
+                let salvage = recovery::quarantine_and_salvage(&db_path, problems)?;
+                (Self::open_writer(&db_path)?.0, Some(salvage))
+            }
+            Err(e) if recovery::is_corruption(&e) => {
+                eprintln!("🚨 Database could not be read: {}", e);
+                let salvage = recovery::quarantine_and_salvage(&db_path, e.to_string())?;
+                (Self::open_writer(&db_path)?.0, Some(salvage))
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Snapshot existing data before the schema changes
+        if migrations::has_pending_migrations(&conn)? {
+            let backups_dir = Self::backups_dir_for(&db_path);
+            if let Err(e) = BackupOperations::create_backup(&conn, &backups_dir, BackupKind::PreMigration) {
+                eprintln!("⚠️  Pre-migration backup failed, migrating anyway: {}", e);
+            }
+        }
+
+        // Run migrations before any reader sees the schema
+        migrations::run_migrations(&conn)?;
+        let recovery = salvage.map(|salvage| recovery::restore_salvage(&conn, salvage));
+
+        let readers = (0..READ_POOL_SIZE)
+            .map(|_| Self::open_reader(&db_path).map(Mutex::new))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            writer: Mutex::new(conn),
+            readers,
+            next_reader: AtomicUsize::new(0),
+            db_path,
+            recovery,
+        })
+    }
+
+    /// Non-persistent database used when the file can't be opened or rebuilt.
+    /// Without readers every read goes through the single in-memory connection.
+    pub fn in_memory(db_path: PathBuf, reason: String) -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        migrations::run_migrations(&conn)?;
+
+        Ok(Self {
+            writer: Mutex::new(conn),
+            readers: Vec::new(),
+            next_reader: AtomicUsize::new(0),
+            db_path,
+            recovery: Some(RecoveryReport::in_memory(reason)),
+        })
+    }
+
+    /// Open and configure the writer, returning any problems `quick_check` found
+    fn open_writer(db_path: &Path) -> Result<(Connection, Option<String>)> {
         let conn = Connection::open_with_flags(
-            &db_path,
+            db_path,
             OpenFlags::SQLITE_OPEN_READ_WRITE 
                 | OpenFlags::SQLITE_OPEN_CREATE 
                 | OpenFlags::SQLITE_OPEN_NO_MUTEX,
         )?;
 
         // Configure SQLite for better performance
-        conn.execute_batch("
+        conn.execute_batch(&format!("
             PRAGMA journal_mode = WAL;
             PRAGMA synchronous = NORMAL;
             PRAGMA cache_size = 1000;
             PRAGMA foreign_keys = ON;
             PRAGMA temp_store = MEMORY;
-        ")?;
+            PRAGMA busy_timeout = {};
+        ", BUSY_TIMEOUT_MS))?;
+
+        let problems = recovery::quick_check(&conn)?;
+        Ok((conn, problems))
+    }
 
-        let manager = Self {
-            connection: Arc::new(Mutex::new(conn)),
+    /// Set when the database had to be rebuilt (or replaced by memory) on open
+    pub fn recovery_report(&self) -> Option<&RecoveryReport> {
+        self.recovery.as_ref()
+    }
+
+    fn open_reader(db_path: &Path) -> Result<Connection> {
+        let conn = Connection::open_with_flags(
             db_path,
-        };
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
 
-        // Run migrations
-        manager.run_migrations()?;
+        conn.execute_batch(&format!("
+            PRAGMA cache_size = 1000;
+            PRAGMA temp_store = MEMORY;
+            PRAGMA busy_timeout = {};
+        ", BUSY_TIMEOUT_MS))?;
 
-        Ok(manager)
+        Ok(conn)
     }
 
     /// Get database file path
@@ -62,25 +209,87 @@ impl DatabaseManager {
         &self.db_path
     }
 
-    /// Execute a function with database connection
+    /// Directory holding backups of this database (`<db dir>/backups`)
+    pub fn backups_dir(&self) -> PathBuf {
+        Self::backups_dir_for(&self.db_path)
+    }
+
+    fn backups_dir_for(db_path: &Path) -> PathBuf {
+        db_path.parent().unwrap_or_else(|| Path::new(".")).join("backups")
+    }
+
+    /// Replace the database contents with `src` via the backup API, then migrate it to head
+    pub fn restore_from(&self, src: &Path) -> Result<()> {
+        let mut conn = self.writer.lock().unwrap();
+        conn.restore(DatabaseName::Main, src, None::<fn(rusqlite::backup::Progress)>)?;
+        migrations::run_migrations(&conn)
+    }
+
+    /// Execute a function with the writer connection
     pub fn with_connection<T, F>(&self, f: F) -> Result<T>
     where
         F: FnOnce(&Connection) -> Result<T>,
     {
-        let conn = self.connection.lock().unwrap();
-        f(&*conn)
+        let conn = self.writer.lock().unwrap();
+        f(&conn)
     }
 
-    /// Run database migrations
-    fn run_migrations(&self) -> Result<()> {
-        self.with_connection(|conn| {
-            migrations::run_migrations(conn)
-        })
+    /// Execute a read-only function on a pooled connection
+    pub fn with_read_connection<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T>,
+    {
+        if self.readers.is_empty() {
+            return self.with_connection(f);
+        }
+
+        // Prefer an idle reader, otherwise queue on the next one in turn
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.readers.len() {
+            let index = (start + offset) % self.readers.len();
+            if let Ok(conn) = self.readers[index].try_lock() {
+                return f(&conn);
+            }
+        }
+
+        let conn = self.readers[start % self.readers.len()].lock().unwrap();
+        f(&conn)
+    }
+
+    /// Run blocking database work on Tokio's blocking pool so async callers
+    /// never hold a connection lock on a runtime worker thread
+    pub async fn run_blocking<T, F>(self: &Arc<Self>, f: F) -> Result<T>
+    where
+        F: FnOnce(&DatabaseManager) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = Arc::clone(self);
+        tokio::task::spawn_blocking(move || f(&db))
+            .await
+            .map_err(|e| anyhow::anyhow!("Database task failed: {}", e))?
+    }
+
+    /// Async version of `with_connection` (writer)
+    pub async fn with_connection_async<T, F>(self: &Arc<Self>, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.run_blocking(move |db| db.with_connection(f)).await
+    }
+
+    /// Async version of `with_read_connection` (read pool)
+    pub async fn with_read_connection_async<T, F>(self: &Arc<Self>, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.run_blocking(move |db| db.with_read_connection(f)).await
     }
 
     /// Get database statistics
     pub fn get_stats(&self) -> Result<DatabaseStats> {
-        self.with_connection(|conn| {
+        self.with_read_connection(|conn| {
             let games_count: i64 = conn.query_row(
                 "SELECT COUNT(*) FROM games", 
                 [], 
@@ -118,8 +327,10 @@ impl DatabaseManager {
         self.with_connection(|conn| {
             let now = chrono::Utc::now().timestamp();
             
+            // Games in the user library are kept (deleting them would cascade to user data)
             let games_deleted: usize = conn.execute(
-                "DELETE FROM games WHERE expires_at < ?1",
+                "DELETE FROM games WHERE expires_at < ?1
+                 AND app_id NOT IN (SELECT app_id FROM user_library)",
                 [now],
             )?;
             
@@ -152,7 +363,7 @@ impl DatabaseManager {
 
     /// Close database connection
     pub fn close(self) -> Result<()> {
-        // The Arc<Mutex<Connection>> will be dropped and connection closed
+        // The writer and pooled readers are dropped and their connections closed
         Ok(())
     }
 }
@@ -185,3 +396,98 @@ impl std::fmt::Display for DatabaseStats {
         )
     }
 }
+
+/// Test helper: a migrated database in a fresh temp dir, which lives as long as the `TempDir`
+#[cfg(test)]
+pub(crate) fn test_database() -> (tempfile::TempDir, SharedDatabase) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Arc::new(DatabaseManager::new(dir.path().join("games.db")).unwrap());
+    (dir, db)
+}
+
+/// Test helper: add a game to `games` and the user library
+#[cfg(test)]
+pub(crate) fn add_library_game(conn: &Connection, app_id: &str, name: &str, last_accessed: Option<i64>) -> Result<()> {
+    conn.execute(
+        "INSERT INTO games (app_id, name, cached_at, expires_at) VALUES (?1, ?2, 0, 0)",
+        [app_id, name],
+    )?;
+    conn.execute(
+        "INSERT INTO user_library (app_id, last_accessed) VALUES (?1, ?2)",
+        rusqlite::params![app_id, last_accessed],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readers_see_writes_and_cannot_write() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO cache_metadata (key, value, updated_at) VALUES ('pool_test', 'ok', 0)",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        for _ in 0..READ_POOL_SIZE + 1 {
+            let value: String = db
+                .with_read_connection(|conn| {
+                    Ok(conn.query_row(
+                        "SELECT value FROM cache_metadata WHERE key = 'pool_test'",
+                        [],
+                        |row| row.get(0),
+                    )?)
+                })
+                .unwrap();
+            assert_eq!(value, "ok");
+        }
+
+        let write = db.with_read_connection(|conn| {
+            conn.execute("DELETE FROM cache_metadata", [])?;
+            Ok(())
+        });
+        assert!(write.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_async_access_runs_off_the_runtime() {
+        let (_dir, db) = test_database();
+
+        let runtime_thread = std::thread::current().id();
+        db.with_connection_async(move |conn| {
+            assert_ne!(std::thread::current().id(), runtime_thread);
+            conn.execute(
+                "INSERT INTO cache_metadata (key, value, updated_at) VALUES ('async_test', '1', 0)",
+                [],
+            )?;
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        let reads = (0..8).map(|_| {
+            let db = db.clone();
+            tokio::spawn(async move {
+                db.with_read_connection_async(|conn| {
+                    Ok(conn.query_row(
+                        "SELECT value FROM cache_metadata WHERE key = 'async_test'",
+                        [],
+                        |row| row.get::<_, String>(0),
+                    )?)
+                })
+                .await
+            })
+        });
+
+        for read in reads.collect::<Vec<_>>() {
+            assert_eq!(read.await.unwrap().unwrap(), "1");
+        }
+    }
+}
@@ -95,7 +95,7 @@ impl GameDetailDb {
         let now = Utc::now().timestamp();
         
         // Import TTL config
-        use crate::database::ttl_config::TtlConfig;
+        use crate::database::ttl_config::{TtlCategory, TtlConfig};
         
         Self {
             app_id,
@@ -116,9 +116,9 @@ impl GameDetailDb {
             expires_at: now + TtlConfig::DEFAULT, // Use default for global expiry
             last_updated: now,
             // Granular expiry based on data category
-            dynamic_expires_at: now + TtlConfig::DLC_LIST,
-            semistatic_expires_at: now + TtlConfig::GAME_NAME, // Use shortest semi-static TTL
-            static_expires_at: now + TtlConfig::SCREENSHOTS, // Use shortest static TTL
+            dynamic_expires_at: now + TtlCategory::Dynamic.live_ttl(),
+            semistatic_expires_at: now + TtlCategory::SemiStatic.live_ttl(), // Shortest semi-static TTL unless overridden
+            static_expires_at: now + TtlCategory::Static.live_ttl(), // Shortest static TTL unless overridden
         }
     }
 
@@ -228,23 +228,23 @@ impl GameDetailDb {
     /// Update specific category expiry
     pub fn refresh_category_expiry(&mut self, category: &str) {
         let now = Utc::now().timestamp();
-        use crate::database::ttl_config::TtlConfig;
+        use crate::database::ttl_config::TtlCategory;
 
         match category {
             "dynamic" => {
-                self.dynamic_expires_at = now + TtlConfig::DLC_LIST;
+                self.dynamic_expires_at = now + TtlCategory::Dynamic.live_ttl();
             }
             "semistatic" => {
-                self.semistatic_expires_at = now + TtlConfig::GAME_NAME;
+                self.semistatic_expires_at = now + TtlCategory::SemiStatic.live_ttl();
             }
             "static" => {
-                self.static_expires_at = now + TtlConfig::SCREENSHOTS;
+                self.static_expires_at = now + TtlCategory::Static.live_ttl();
             }
             _ => {
                 // Refresh all categories
-                self.dynamic_expires_at = now + TtlConfig::DLC_LIST;
-                self.semistatic_expires_at = now + TtlConfig::GAME_NAME;
-                self.static_expires_at = now + TtlConfig::SCREENSHOTS;
+                self.dynamic_expires_at = now + TtlCategory::Dynamic.live_ttl();
+                self.semistatic_expires_at = now + TtlCategory::SemiStatic.live_ttl();
+                self.static_expires_at = now + TtlCategory::Static.live_ttl();
             }
         }
         
@@ -7,11 +7,18 @@ pub struct GameOperations;
 
 impl GameOperations {
     /// Insert or update a game
+    /// (updates in place: a REPLACE would cascade-delete the game's user_library row)
     pub fn upsert(conn: &Connection, game: &Game) -> Result<()> {
         conn.execute(
-            "INSERT OR REPLACE INTO games 
+            "INSERT INTO games 
              (app_id, name, header_image, cached_at, expires_at, last_updated) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(app_id) DO UPDATE SET
+                name = excluded.name,
+                header_image = excluded.header_image,
+                cached_at = excluded.cached_at,
+                expires_at = excluded.expires_at,
+                last_updated = excluded.last_updated",
             params![
                 game.app_id,
                 game.name,
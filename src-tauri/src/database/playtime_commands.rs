@@ -0,0 +1,80 @@
+use tauri::{command, State};
+use crate::database::{
+    playtime_models::*, playtime_operations::PlaySessionOperations, shared_database, SharedDatabase,
+};
+use crate::process_monitor::{self, RunningGame};
+
+fn recent_since() -> i64 {
+    chrono::Utc::now().timestamp() - RECENT_PLAYTIME_DAYS * 24 * 60 * 60
+}
+
+/// Get total and recent playtime for a game
+#[command]
+pub async fn get_game_playtime(app_id: String, db: State<'_, SharedDatabase>) -> Result<PlaytimeSummary, String> {
+    let id = app_id.clone();
+    let summary = db.with_read_connection_async(move |conn| {
+        PlaySessionOperations::get_playtime_summary(conn, &id, recent_since())
+            .map_err(|e| anyhow::anyhow!("Database error: {}", e))
+    }).await.map_err(|e| format!("Failed to get playtime: {}", e))?;
+
+    let mut summary = summary.unwrap_or_else(|| PlaytimeSummary::empty(app_id.clone()));
+    summary.is_running = process_monitor::is_app_running(&app_id);
+    Ok(summary)
+}
+
+/// Get total and recent playtime for every game that has been played
+#[command]
+pub async fn get_all_playtime(db: State<'_, SharedDatabase>) -> Result<Vec<PlaytimeSummary>, String> {
+    let mut summaries = db.with_read_connection_async(|conn| {
+        PlaySessionOperations::get_all_playtime(conn, recent_since())
+            .map_err(|e| anyhow::anyhow!("Database error: {}", e))
+    }).await.map_err(|e| format!("Failed to get playtime: {}", e))?;
+
+    for summary in summaries.iter_mut() {
+        summary.is_running = process_monitor::is_app_running(&summary.app_id);
+    }
+    Ok(summaries)
+}
+
+/// Get recent play sessions, optionally for a single game
+#[command]
+pub async fn get_play_sessions(
+    app_id: Option<String>,
+    limit: Option<u32>,
+    db: State<'_, SharedDatabase>,
+) -> Result<Vec<PlaySession>, String> {
+    db.with_read_connection_async(move |conn| {
+        PlaySessionOperations::get_sessions(conn, app_id.as_deref(), limit.unwrap_or(50))
+            .map_err(|e| anyhow::anyhow!("Database error: {}", e))
+    }).await.map_err(|e| format!("Failed to get play sessions: {}", e))
+}
+
+/// Get games that are currently running
+#[command]
+pub async fn get_running_games() -> Result<Vec<RunningGame>, String> {
+    Ok(process_monitor::running_games())
+}
+
+/// Record the start of a play session (internal function for integration)
+pub async fn record_play_session_start(session: PlaySession) -> Result<i64, String> {
+    let db = shared_database().map_err(|e| e.to_string())?;
+
+    db.with_connection_async(move |conn| {
+        PlaySessionOperations::start_session(conn, &session)
+            .map_err(|e| anyhow::anyhow!("Database error: {}", e))
+    }).await.map_err(|e| format!("Failed to record play session start: {}", e))
+}
+
+/// Record the end of a play session (internal function for integration)
+pub async fn record_play_session_end(
+    session_id: i64,
+    ended_at: i64,
+    exit_code: Option<i32>,
+) -> Result<(), String> {
+    let db = shared_database().map_err(|e| e.to_string())?;
+
+    db.with_connection_async(move |conn| {
+        PlaySessionOperations::end_session(conn, session_id, ended_at, exit_code)
+            .map_err(|e| anyhow::anyhow!("Database error: {}", e))
+    }).await.map_err(|e| format!("Failed to record play session end: {}", e))
+}
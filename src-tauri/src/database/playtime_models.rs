@@ -0,0 +1,106 @@
+use chrono::Utc;
+use rusqlite::{Row, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+/// Number of days counted as "recent" playtime (same window Steam uses)
+pub const RECENT_PLAYTIME_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaySession {
+    pub id: Option<i64>,
+    pub app_id: Option<String>,
+    pub executable_path: String,
+    pub pid: Option<i64>,
+
+    // Timestamps (stored as Unix timestamps)
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+
+    // Result
+    pub duration_seconds: i64,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaytimeSummary {
+    pub app_id: String,
+    pub total_seconds: i64,
+    pub recent_seconds: i64,       // Last RECENT_PLAYTIME_DAYS days
+    pub session_count: i64,
+    pub last_played_at: Option<i64>,
+    pub is_running: bool,          // Filled in from the live process tracker
+}
+
+/// Payload of the `game-started` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameStartedEvent {
+    pub session_id: i64,
+    pub app_id: Option<String>,
+    pub executable_path: String,
+    pub pid: u32,
+    pub started_at: i64,
+}
+
+/// Payload of the `game-exited` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameExitedEvent {
+    pub session_id: i64,
+    pub app_id: Option<String>,
+    pub executable_path: String,
+    pub exit_code: Option<i32>,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub duration_seconds: i64,
+}
+
+impl PlaySession {
+    pub fn new(app_id: Option<String>, executable_path: String, pid: Option<i64>) -> Self {
+        Self {
+            id: None,
+            app_id,
+            executable_path,
+            pid,
+            started_at: Utc::now().timestamp(),
+            ended_at: None,
+            duration_seconds: 0,
+            exit_code: None,
+        }
+    }
+
+    pub fn from_row(row: &Row) -> SqliteResult<Self> {
+        Ok(Self {
+            id: Some(row.get("id")?),
+            app_id: row.get("app_id")?,
+            executable_path: row.get("executable_path")?,
+            pid: row.get("pid")?,
+            started_at: row.get("started_at")?,
+            ended_at: row.get("ended_at")?,
+            duration_seconds: row.get("duration_seconds")?,
+            exit_code: row.get("exit_code")?,
+        })
+    }
+}
+
+impl PlaytimeSummary {
+    pub fn from_row(row: &Row) -> SqliteResult<Self> {
+        Ok(Self {
+            app_id: row.get("app_id")?,
+            total_seconds: row.get("total_seconds")?,
+            recent_seconds: row.get("recent_seconds")?,
+            session_count: row.get("session_count")?,
+            last_played_at: row.get("last_played_at")?,
+            is_running: false,
+        })
+    }
+
+    pub fn empty(app_id: String) -> Self {
+        Self {
+            app_id,
+            total_seconds: 0,
+            recent_seconds: 0,
+            session_count: 0,
+            last_played_at: None,
+            is_running: false,
+        }
+    }
+}
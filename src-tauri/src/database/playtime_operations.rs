@@ -0,0 +1,143 @@
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use super::playtime_models::{PlaySession, PlaytimeSummary};
+
+pub struct PlaySessionOperations;
+
+/// Aggregation shared by the single-app and all-apps summaries.
+/// Sessions that never recorded an end (launcher closed mid-game) are ignored.
+const SUMMARY_SELECT: &str = r#"
+    SELECT 
+        app_id,
+        COALESCE(SUM(duration_seconds), 0) as total_seconds,
+        COALESCE(SUM(CASE WHEN started_at >= ?1 THEN duration_seconds END), 0) as recent_seconds,
+        COUNT(*) as session_count,
+        MAX(ended_at) as last_played_at
+    FROM play_sessions
+    WHERE ended_at IS NOT NULL AND app_id IS NOT NULL
+"#;
+
+impl PlaySessionOperations {
+    /// Record the start of a new play session
+    pub fn start_session(conn: &Connection, session: &PlaySession) -> Result<i64> {
+        conn.execute(
+            "INSERT INTO play_sessions (app_id, executable_path, pid, started_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                session.app_id,
+                session.executable_path,
+                session.pid,
+                session.started_at,
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Close a play session once the whole process tree has exited
+    pub fn end_session(
+        conn: &Connection,
+        session_id: i64,
+        ended_at: i64,
+        exit_code: Option<i32>,
+    ) -> Result<()> {
+        conn.execute(
+            "UPDATE play_sessions 
+             SET ended_at = ?2,
+                 duration_seconds = MAX(?2 - started_at, 0),
+                 exit_code = ?3
+             WHERE id = ?1",
+            params![session_id, ended_at, exit_code],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get most recent sessions, optionally for a single app
+    pub fn get_sessions(
+        conn: &Connection,
+        app_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<PlaySession>> {
+        let mut stmt = conn.prepare(
+            "SELECT * FROM play_sessions 
+             WHERE (?1 IS NULL OR app_id = ?1)
+             ORDER BY started_at DESC
+             LIMIT ?2",
+        )?;
+
+        let sessions = stmt.query_map(params![app_id, limit], |row| {
+            PlaySession::from_row(row)
+        })?;
+
+        sessions.collect()
+    }
+
+    /// Get total and recent playtime for a single app
+    pub fn get_playtime_summary(
+        conn: &Connection,
+        app_id: &str,
+        recent_since: i64,
+    ) -> Result<Option<PlaytimeSummary>> {
+        let sql = format!("{} AND app_id = ?2 GROUP BY app_id", SUMMARY_SELECT);
+
+        conn.query_row(&sql, params![recent_since, app_id], |row| {
+            PlaytimeSummary::from_row(row)
+        }).optional()
+    }
+
+    /// Get total and recent playtime for every app, most played first
+    pub fn get_all_playtime(conn: &Connection, recent_since: i64) -> Result<Vec<PlaytimeSummary>> {
+        let sql = format!("{} GROUP BY app_id ORDER BY total_seconds DESC", SUMMARY_SELECT);
+
+        let mut stmt = conn.prepare(&sql)?;
+        let summaries = stmt.query_map(params![recent_since], |row| {
+            PlaytimeSummary::from_row(row)
+        })?;
+
+        summaries.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+
+    fn played(conn: &Connection, app_id: Option<&str>, started_at: i64, ended_at: Option<i64>) -> i64 {
+        let mut session = PlaySession::new(app_id.map(str::to_string), "game.exe".to_string(), None);
+        session.started_at = started_at;
+        let id = PlaySessionOperations::start_session(conn, &session).unwrap();
+        if let Some(ended_at) = ended_at {
+            PlaySessionOperations::end_session(conn, id, ended_at, Some(0)).unwrap();
+        }
+        id
+    }
+
+    #[test]
+    fn test_playtime_totals_skip_open_and_unresolved_sessions() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            played(conn, Some("620"), 1_000, Some(1_600));
+            played(conn, Some("620"), 5_000, Some(5_300));
+            played(conn, Some("620"), 9_000, None); // still running
+            played(conn, Some("220"), 6_000, Some(7_000));
+            played(conn, None, 2_000, Some(9_000));
+            // A clock that went backwards counts as zero seconds, not negative
+            played(conn, Some("220"), 8_000, Some(7_900));
+
+            let portal = PlaySessionOperations::get_playtime_summary(conn, "620", 4_000)?.unwrap();
+            assert_eq!((portal.total_seconds, portal.recent_seconds), (900, 300));
+            assert_eq!(portal.session_count, 2);
+            assert_eq!(portal.last_played_at, Some(5_300));
+            assert!(!portal.is_running);
+            assert!(PlaySessionOperations::get_playtime_summary(conn, "550", 0)?.is_none());
+
+            let all = PlaySessionOperations::get_all_playtime(conn, 4_000)?;
+            let totals: Vec<_> = all.iter().map(|s| (s.app_id.as_str(), s.total_seconds, s.recent_seconds)).collect();
+            assert_eq!(totals, vec![("220", 1_000, 1_000), ("620", 900, 300)]);
+            assert_eq!(all[0].last_played_at, Some(7_900));
+            Ok(())
+        }).unwrap();
+    }
+}
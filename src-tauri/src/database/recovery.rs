@@ -0,0 +1,421 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User data worth salvaging from a corrupted database, in insert order.
+/// Everything else is cache that is downloaded again.
+const SALVAGE_TABLES: &[&str] = &[
+    "user_library",
+    "user_profile",
+    "download_history",
+    "play_sessions",
+    "launch_profiles",
+];
+
+/// Upper bound for rowid probing when a table can't be scanned in one pass
+const MAX_PROBED_ROWID: i64 = 100_000;
+
+/// What happened to one salvaged table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRecovery {
+    pub table: String,
+    pub recovered: usize,
+    /// Row count reported by the damaged file, when it could still be read
+    pub expected: Option<usize>,
+    pub error: Option<String>,
+}
+
+/// Result of rebuilding a damaged database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    pub reason: String,
+    pub quarantined_path: Option<String>,
+    pub tables: Vec<TableRecovery>,
+    /// Nothing could be written to disk; changes are lost on exit
+    pub in_memory_fallback: bool,
+}
+
+impl RecoveryReport {
+    pub fn in_memory(reason: String) -> Self {
+        Self {
+            reason,
+            quarantined_path: None,
+            tables: Vec::new(),
+            in_memory_fallback: true,
+        }
+    }
+
+    /// Human readable lines for the loading screen
+    pub fn summary_lines(&self) -> Vec<String> {
+        if self.in_memory_fallback {
+            return vec![format!(
+                "Database could not be opened ({}); running without saving changes",
+                self.reason
+            )];
+        }
+
+        let mut lines = vec!["Database was damaged and has been rebuilt".to_string()];
+        for table in &self.tables {
+            let line = match (table.expected, &table.error) {
+                (Some(expected), _) if expected > table.recovered => format!(
+                    "{}: recovered {} of {} rows ({} lost)",
+                    table.table, table.recovered, expected, expected - table.recovered
+                ),
+                (None, Some(_)) if table.recovered == 0 => format!("{}: could not be recovered", table.table),
+                (None, Some(_)) => format!("{}: recovered {} rows, some may be lost", table.table, table.recovered),
+                _ => format!("{}: recovered {} rows", table.table, table.recovered),
+            };
+            lines.push(line);
+        }
+        lines.push("Cached game data will be downloaded again".to_string());
+        if let Some(path) = &self.quarantined_path {
+            lines.push(format!("Damaged file kept at {}", path));
+        }
+        lines
+    }
+}
+
+/// Rows read out of a damaged table
+pub struct SalvagedTable {
+    table: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    expected: Option<usize>,
+    error: Option<String>,
+}
+
+/// Data carried from the quarantined file into the rebuilt database
+pub struct Salvage {
+    reason: String,
+    quarantined_path: PathBuf,
+    tables: Vec<SalvagedTable>,
+    /// (app_id, name, header_image) for library games, so library rows satisfy their foreign key
+    library_games: Vec<(String, Option<String>, Option<String>)>,
+}
+
+/// Run `PRAGMA quick_check`. Returns the reported problems, or None when the file is healthy.
+pub fn quick_check(conn: &Connection) -> Result<Option<String>> {
+    let mut stmt = conn.prepare("PRAGMA quick_check(5)")?;
+    let messages = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if messages.len() == 1 && messages[0] == "ok" {
+        Ok(None)
+    } else {
+        Ok(Some(messages.join("; ")))
+    }
+}
+
+/// Whether an error means the file itself is damaged (as opposed to locked, missing, ...)
+pub fn is_corruption(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<rusqlite::Error>()
+        .and_then(|e| e.sqlite_error_code())
+        .map(|code| matches!(code, ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase))
+        .unwrap_or(false)
+}
+
+/// Move the damaged database (and its WAL/SHM files) into `quarantine/` and read what it still holds
+pub fn quarantine_and_salvage(db_path: &Path, reason: String) -> Result<Salvage> {
+    let quarantine_dir = db_path.parent().unwrap_or_else(|| Path::new(".")).join("quarantine");
+    fs::create_dir_all(&quarantine_dir)?;
+
+    let stem = db_path.file_stem().and_then(|s| s.to_str()).unwrap_or("games");
+    let quarantined_path = quarantine_dir.join(format!("{}-{}.db", stem, Utc::now().format("%Y%m%d-%H%M%S")));
+
+    // The WAL must keep the same base name or SQLite won't find it
+    for suffix in ["", "-wal", "-shm"] {
+        let from = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+        if from.exists() {
+            let to = PathBuf::from(format!("{}{}", quarantined_path.display(), suffix));
+            fs::rename(&from, &to)?;
+        }
+    }
+    println!("🚑 Quarantined damaged database at {}", quarantined_path.display());
+
+    let mut salvage = Salvage {
+        reason,
+        quarantined_path: quarantined_path.clone(),
+        tables: Vec::new(),
+        library_games: Vec::new(),
+    };
+
+    let conn = match Connection::open(&quarantined_path) {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Could not open quarantined database: {}", e);
+            return Ok(salvage);
+        }
+    };
+
+    for table in SALVAGE_TABLES {
+        salvage.tables.push(salvage_table(&conn, table));
+    }
+
+    if let Some(library) = salvage.tables.iter().find(|t| t.table == "user_library") {
+        if let Some(app_id_index) = library.columns.iter().position(|c| c == "app_id") {
+            for row in &library.rows {
+                if let Value::Text(app_id) = &row[app_id_index] {
+                    let game = conn
+                        .query_row(
+                            "SELECT name, header_image FROM games WHERE app_id = ?1",
+                            [app_id],
+                            |row| Ok((row.get(0)?, row.get(1)?)),
+                        )
+                        .unwrap_or((None, None));
+                    salvage.library_games.push((app_id.clone(), game.0, game.1));
+                }
+            }
+        }
+    }
+
+    Ok(salvage)
+}
+
+/// Read every readable row of `table`, probing rowids one by one if a full scan fails
+fn salvage_table(conn: &Connection, table: &str) -> SalvagedTable {
+    let mut salvaged = SalvagedTable {
+        table: table.to_string(),
+        columns: Vec::new(),
+        rows: Vec::new(),
+        expected: None,
+        error: None,
+    };
+
+    let columns: Result<Vec<String>> = conn
+        .prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .map_err(Into::into);
+    match columns {
+        Ok(columns) if !columns.is_empty() => salvaged.columns = columns,
+        Ok(_) => return salvaged, // Table didn't exist in this file
+        Err(e) => {
+            salvaged.error = Some(e.to_string());
+            return salvaged;
+        }
+    }
+
+    salvaged.expected = conn
+        .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))
+        .ok()
+        .map(|count| count as usize);
+
+    let column_list = salvaged.columns.join(", ");
+    let column_count = salvaged.columns.len();
+    let read_row = |row: &rusqlite::Row| -> rusqlite::Result<(i64, Vec<Value>)> {
+        let values = (0..column_count).map(|i| row.get(i + 1)).collect::<rusqlite::Result<Vec<Value>>>()?;
+        Ok((row.get(0)?, values))
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    let scan: rusqlite::Result<()> = (|| {
+        let mut stmt = conn.prepare(&format!("SELECT rowid, {} FROM {}", column_list, table))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let (rowid, values) = read_row(row)?;
+            seen.insert(rowid);
+            salvaged.rows.push(values);
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = scan {
+        salvaged.error = Some(e.to_string());
+
+        // Pages that are still intact can be reached by rowid
+        let max_rowid: i64 = conn
+            .query_row(&format!("SELECT MAX(rowid) FROM {}", table), [], |row| row.get(0))
+            .unwrap_or(MAX_PROBED_ROWID);
+        if let Ok(mut stmt) = conn.prepare(&format!("SELECT rowid, {} FROM {} WHERE rowid = ?1", column_list, table)) {
+            for rowid in 1..=max_rowid.min(MAX_PROBED_ROWID) {
+                if seen.contains(&rowid) {
+                    continue;
+                }
+                if let Ok((_, values)) = stmt.query_row([rowid], read_row) {
+                    salvaged.rows.push(values);
+                }
+            }
+        }
+    }
+
+    salvaged
+}
+
+/// Insert salvaged rows into the freshly migrated database
+pub fn restore_salvage(conn: &Connection, salvage: Salvage) -> RecoveryReport {
+    // Placeholders expire immediately so the real metadata is fetched again
+    for (app_id, name, header_image) in &salvage.library_games {
+        let _ = conn.execute(
+            "INSERT OR IGNORE INTO games (app_id, name, header_image, cached_at, expires_at) VALUES (?1, ?2, ?3, 0, 0)",
+            rusqlite::params![app_id, name.clone().unwrap_or_else(|| app_id.clone()), header_image],
+        );
+    }
+
+    let mut tables = Vec::new();
+    for salvaged in salvage.tables {
+        let mut recovery = TableRecovery {
+            table: salvaged.table.clone(),
+            recovered: 0,
+            expected: salvaged.expected,
+            error: salvaged.error.clone(),
+        };
+
+        // Only columns the current schema still has; older files may differ
+        let current: Vec<String> = conn
+            .prepare(&format!("SELECT name FROM pragma_table_info('{}')", salvaged.table))
+            .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+            .unwrap_or_default();
+        let keep: Vec<usize> = (0..salvaged.columns.len())
+            .filter(|i| current.contains(&salvaged.columns[*i]))
+            .collect();
+
+        if !keep.is_empty() && !salvaged.rows.is_empty() {
+            let columns: Vec<&str> = keep.iter().map(|i| salvaged.columns[*i].as_str()).collect();
+            let placeholders: Vec<String> = (1..=keep.len()).map(|i| format!("?{}", i)).collect();
+            // The rebuilt schema inserts a default profile, which the salvaged one replaces
+            let sql = format!(
+                "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+                salvaged.table,
+                columns.join(", "),
+                placeholders.join(", ")
+            );
+
+            for row in &salvaged.rows {
+                let values = keep.iter().map(|i| &row[*i]);
+                match conn.execute(&sql, params_from_iter(values)) {
+                    Ok(_) => recovery.recovered += 1,
+                    Err(e) => {
+                        recovery.error.get_or_insert_with(|| e.to_string());
+                    }
+                }
+            }
+        }
+
+        if recovery.recovered > 0 || recovery.expected.unwrap_or(0) > 0 || recovery.error.is_some() {
+            tables.push(recovery);
+        }
+    }
+
+    let report = RecoveryReport {
+        reason: salvage.reason,
+        quarantined_path: Some(salvage.quarantined_path.to_string_lossy().to_string()),
+        tables,
+        in_memory_fallback: false,
+    };
+    for line in report.summary_lines() {
+        println!("🚑 {}", line);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{add_library_game, test_database, DatabaseManager};
+    use std::io::{Seek, SeekFrom, Write};
+
+    fn seed(db: &DatabaseManager) {
+        db.with_connection(|conn| {
+            for app_id in ["570", "730"] {
+                add_library_game(conn, app_id, &format!("Game {}", app_id), None)?;
+            }
+            conn.execute("UPDATE user_profile SET name = 'Player' WHERE id = 1", [])?;
+            conn.execute(
+                "INSERT INTO download_history (download_id, download_type, source_type, url, save_path, status, started_at)
+                 VALUES ('d1', 'bypass', 'bypass', 'https://example.com/a.zip', '/tmp', 'completed', '2024-01-01')",
+                [],
+            )?;
+            // Enough cache rows to give game_details pages of its own
+            for i in 0..200 {
+                let app_id = format!("9{}", i);
+                conn.execute(
+                    "INSERT INTO games (app_id, name, cached_at, expires_at) VALUES (?1, 'Cached', 0, 0)",
+                    [&app_id],
+                )?;
+                conn.execute(
+                    "INSERT INTO game_details (app_id, name, detailed_description, cached_at, expires_at,
+                        dynamic_expires_at, semistatic_expires_at, static_expires_at)
+                     VALUES (?1, 'Cached', ?2, 0, 0, 0, 0, 0)",
+                    rusqlite::params![app_id, "x".repeat(500)],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_damaged_cache_pages_keep_user_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("games.db");
+
+        let (root_page, page_size) = {
+            let db = DatabaseManager::new(db_path.clone()).unwrap();
+            seed(&db);
+            db.with_connection(|conn| {
+                conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")?;
+                Ok((
+                    conn.query_row("SELECT rootpage FROM sqlite_master WHERE name = 'game_details'", [], |row| row.get::<_, u64>(0))?,
+                    conn.query_row("PRAGMA page_size", [], |row| row.get::<_, u64>(0))?,
+                ))
+            })
+            .unwrap()
+        };
+
+        // Scribble over the root page of a cache table
+        let mut file = fs::OpenOptions::new().write(true).open(&db_path).unwrap();
+        file.seek(SeekFrom::Start((root_page - 1) * page_size)).unwrap();
+        file.write_all(&vec![0xA5; page_size as usize]).unwrap();
+        drop(file);
+
+        let db = DatabaseManager::new(db_path.clone()).unwrap();
+        let report = db.recovery_report().expect("recovery should have run");
+        assert!(!report.in_memory_fallback);
+        assert!(Path::new(report.quarantined_path.as_ref().unwrap()).exists());
+
+        let (library, profile, history): (i64, String, i64) = db
+            .with_read_connection(|conn| {
+                Ok((
+                    conn.query_row("SELECT COUNT(*) FROM user_library", [], |row| row.get(0))?,
+                    conn.query_row("SELECT name FROM user_profile WHERE id = 1", [], |row| row.get(0))?,
+                    conn.query_row("SELECT COUNT(*) FROM download_history", [], |row| row.get(0))?,
+                ))
+            })
+            .unwrap();
+        assert_eq!(library, 2);
+        assert_eq!(profile, "Player");
+        assert_eq!(history, 1);
+        assert!(quick_check(&Connection::open(&db_path).unwrap()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_garbage_file_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("games.db");
+        fs::write(&db_path, vec![0x42; 8192]).unwrap();
+
+        let db = DatabaseManager::new(db_path).unwrap();
+        let report = db.recovery_report().expect("recovery should have run");
+        assert!(report.tables.iter().all(|table| table.recovered == 0 && table.error.is_some()));
+        let lines = report.summary_lines();
+        assert!(lines.iter().any(|line| line.contains("rebuilt")));
+        assert!(lines.iter().any(|line| line == "user_library: could not be recovered"));
+
+        // A fresh schema is in place
+        let profiles: i64 = db
+            .with_read_connection(|conn| Ok(conn.query_row("SELECT COUNT(*) FROM user_profile", [], |row| row.get(0))?))
+            .unwrap();
+        assert_eq!(profiles, 1);
+    }
+
+    #[test]
+    fn test_healthy_database_is_left_alone() {
+        let (dir, db) = test_database();
+        assert!(db.recovery_report().is_none());
+        assert!(!dir.path().join("quarantine").exists());
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use crate::database::cache_service::Freshness;
+use crate::GameDetail;
+
+/// Event emitted to the frontend whenever a queued refresh lands
+pub const GAME_METADATA_UPDATED_EVENT: &str = "game-metadata-updated";
+
+/// Event emitted with the full details whenever a stale entry has been revalidated
+pub const GAME_DETAILS_UPDATED_EVENT: &str = "game-details-updated";
+
+/// Failed refreshes are retried with exponential backoff, then dropped
+pub const MAX_REFRESH_ATTEMPTS: i64 = 5;
+pub const RETRY_BASE_DELAY_SECS: i64 = 60;
+
+/// What a queued refresh fetches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefreshKind {
+    /// Library name only (`games` table)
+    Name,
+    /// Full game details, or just their expired categories if cached
+    Details,
+}
+
+impl RefreshKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefreshKind::Name => "name",
+            RefreshKind::Details => "details",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "name" => Some(RefreshKind::Name),
+            "details" => Some(RefreshKind::Details),
+            _ => None,
+        }
+    }
+}
+
+/// A pending row of `refresh_queue`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedRefresh {
+    pub app_id: String,
+    pub kind: RefreshKind,
+    pub enqueued_at: i64,
+    pub attempts: i64,
+    /// From user_library; recently played games are refreshed first
+    pub last_accessed: Option<i64>,
+}
+
+/// Payload of `game-metadata-updated`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameMetadataUpdate {
+    pub app_id: String,
+    pub kind: RefreshKind,
+    pub name: String,
+    pub header_image: String,
+}
+
+/// Payload of `game-details-updated`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameDetailsUpdate {
+    pub app_id: String,
+    pub freshness: Freshness,
+    pub details: GameDetail,
+}
@@ -0,0 +1,203 @@
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+use crate::connectivity::CONNECTIVITY;
+use crate::image_cache::cached_image_url;
+use super::image_cache_models::ImageKind;
+use super::cache_service::SQLITE_CACHE_SERVICE;
+use super::refresh_queue_models::{
+    GameMetadataUpdate, QueuedRefresh, RefreshKind, GAME_DETAILS_UPDATED_EVENT, GAME_METADATA_UPDATED_EVENT, MAX_REFRESH_ATTEMPTS,
+    RETRY_BASE_DELAY_SECS,
+};
+
+/// How often an idle worker looks for retries that have come due
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct RefreshQueueOperations;
+
+impl RefreshQueueOperations {
+    /// Queue a refresh. Returns false if the same refresh is already pending.
+    pub fn enqueue(conn: &Connection, app_id: &str, kind: RefreshKind) -> Result<bool> {
+        let inserted = conn.execute(
+            "INSERT INTO refresh_queue (app_id, kind, enqueued_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(app_id, kind) DO NOTHING",
+            params![app_id, kind.as_str(), chrono::Utc::now().timestamp()],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Due entries, library games first (most recently accessed first), then oldest queued
+    pub fn next_batch(conn: &Connection, limit: usize, now: i64) -> Result<Vec<QueuedRefresh>> {
+        let mut stmt = conn.prepare(
+            "SELECT q.app_id, q.kind, q.enqueued_at, q.attempts, l.last_accessed
+             FROM refresh_queue q
+             LEFT JOIN user_library l ON l.app_id = q.app_id
+             WHERE q.next_attempt_at <= ?1
+             ORDER BY l.app_id IS NULL, COALESCE(l.last_accessed, 0) DESC, q.enqueued_at, q.app_id
+             LIMIT ?2",
+        )?;
+
+        let rows = stmt.query_map(params![now, limit as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+            ))
+        })?;
+
+        let mut batch = Vec::new();
+        for row in rows {
+            let (app_id, kind, enqueued_at, attempts, last_accessed) = row?;
+            // Unknown kinds can only come from a newer build; leave them alone
+            if let Some(kind) = RefreshKind::parse(&kind) {
+                batch.push(QueuedRefresh { app_id, kind, enqueued_at, attempts, last_accessed });
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Remove a finished (or permanently failed) refresh
+    pub fn complete(conn: &Connection, app_id: &str, kind: RefreshKind) -> Result<()> {
+        conn.execute(
+            "DELETE FROM refresh_queue WHERE app_id = ?1 AND kind = ?2",
+            params![app_id, kind.as_str()],
+        )?;
+        Ok(())
+    }
+
+    /// Back off a failed refresh. Returns false if it ran out of attempts and was dropped.
+    pub fn fail(conn: &Connection, app_id: &str, kind: RefreshKind, error: &str, now: i64) -> Result<bool> {
+        let attempts: i64 = conn.query_row(
+            "SELECT attempts FROM refresh_queue WHERE app_id = ?1 AND kind = ?2",
+            params![app_id, kind.as_str()],
+            |row| row.get(0),
+        )?;
+
+        if attempts + 1 >= MAX_REFRESH_ATTEMPTS {
+            Self::complete(conn, app_id, kind)?;
+            return Ok(false);
+        }
+
+        let delay = RETRY_BASE_DELAY_SECS << attempts;
+        conn.execute(
+            "UPDATE refresh_queue SET attempts = attempts + 1, next_attempt_at = ?3, last_error = ?4
+             WHERE app_id = ?1 AND kind = ?2",
+            params![app_id, kind.as_str(), now + delay, error],
+        )?;
+        Ok(true)
+    }
+
+    /// Number of pending refreshes, including ones waiting to retry
+    pub fn depth(conn: &Connection) -> Result<u64> {
+        let depth: i64 = conn.query_row("SELECT COUNT(*) FROM refresh_queue", [], |row| row.get(0))?;
+        Ok(depth as u64)
+    }
+}
+
+/// Drain the refresh queue for the lifetime of the app, emitting `game-metadata-updated`
+/// as each entry lands. Sleeps until something is queued or a retry comes due, and while
+/// offline until connectivity returns.
+pub async fn run_refresh_worker(app: AppHandle) {
+    let service = SQLITE_CACHE_SERVICE.clone();
+    println!("🔄 Background refresh worker started");
+
+    loop {
+        CONNECTIVITY.wait_until_online().await;
+
+        let emit = |mut update: GameMetadataUpdate| {
+            update.header_image = cached_image_url(ImageKind::Header, &update.header_image, false);
+            let _ = app.emit(GAME_METADATA_UPDATED_EVENT, update);
+        };
+        let processed = match service.process_refresh_queue_batch(&emit).await {
+            Ok(processed) => processed,
+            Err(e) => {
+                eprintln!("⚠️  Refresh queue batch failed: {}", e);
+                0
+            }
+        };
+
+        if processed == 0 {
+            service.wait_for_queued_refresh(IDLE_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Forward revalidated game details to the frontend as `game-details-updated`
+pub async fn forward_details_updates(app: AppHandle) {
+    let mut updates = SQLITE_CACHE_SERVICE.subscribe_details_updates();
+    loop {
+        match updates.recv().await {
+            Ok(mut update) => {
+                update.details = crate::image_cache::localize_game_detail(update.details);
+                let _ = app.emit(GAME_DETAILS_UPDATED_EVENT, update);
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                eprintln!("⚠️  Dropped {} game-details-updated events", skipped);
+            }
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{add_library_game, test_database};
+
+    #[test]
+    fn test_enqueue_is_deduplicated_and_prioritised() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            add_library_game(conn, "10", "Game", Some(100))?;
+            add_library_game(conn, "20", "Game", Some(500))?;
+            add_library_game(conn, "30", "Game", None)?;
+
+            for app_id in ["99", "30", "10", "20"] {
+                assert!(RefreshQueueOperations::enqueue(conn, app_id, RefreshKind::Name)?);
+            }
+            assert!(!RefreshQueueOperations::enqueue(conn, "20", RefreshKind::Name)?);
+            assert!(RefreshQueueOperations::enqueue(conn, "20", RefreshKind::Details)?);
+            assert_eq!(RefreshQueueOperations::depth(conn)?, 5);
+
+            let now = chrono::Utc::now().timestamp();
+            let order: Vec<_> = RefreshQueueOperations::next_batch(conn, 10, now)?
+                .into_iter()
+                .filter(|entry| entry.kind == RefreshKind::Name)
+                .map(|entry| entry.app_id)
+                .collect();
+            // Recently accessed first, library games before anything else
+            assert_eq!(order, vec!["20", "10", "30", "99"]);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_failures_back_off_then_drop() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            let now = 1_000;
+            RefreshQueueOperations::enqueue(conn, "570", RefreshKind::Details)?;
+
+            assert!(RefreshQueueOperations::fail(conn, "570", RefreshKind::Details, "status 500", now)?);
+            assert!(RefreshQueueOperations::next_batch(conn, 10, now)?.is_empty());
+            let retry = RefreshQueueOperations::next_batch(conn, 10, now + RETRY_BASE_DELAY_SECS)?;
+            assert_eq!(retry.len(), 1);
+            assert_eq!(retry[0].attempts, 1);
+
+            for _ in 1..MAX_REFRESH_ATTEMPTS - 1 {
+                assert!(RefreshQueueOperations::fail(conn, "570", RefreshKind::Details, "status 500", now)?);
+            }
+            assert!(!RefreshQueueOperations::fail(conn, "570", RefreshKind::Details, "status 500", now)?);
+            assert_eq!(RefreshQueueOperations::depth(conn)?, 0);
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,18 @@
+use tauri::{command, State};
+use crate::database::{
+    search_models::*, search_operations::SearchOperations, SharedDatabase,
+};
+
+/// Search cached game details by name, publisher and description.
+/// Terms match as prefixes and misspelled terms are corrected.
+#[command]
+pub async fn search_games(
+    query: String,
+    limit: Option<u32>,
+    db: State<'_, SharedDatabase>,
+) -> Result<GameSearchResponse, String> {
+    db.with_read_connection_async(move |conn| {
+        SearchOperations::search_games(conn, &query, limit)
+            .map_err(|e| anyhow::anyhow!("Database error: {}", e))
+    }).await.map_err(|e| format!("Failed to search games: {}", e))
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Default and maximum number of search results
+pub const DEFAULT_SEARCH_LIMIT: u32 = 20;
+pub const MAX_SEARCH_LIMIT: u32 = 100;
+
+/// A cached game matching a search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSearchResult {
+    pub app_id: String,
+    pub name: String,
+    pub header_image: Option<String>,
+    pub publisher: Option<String>,
+    pub release_date: Option<String>,
+    pub score: f64, // bm25, lower is better
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSearchResponse {
+    pub query: String,
+    /// Set when misspelled terms were replaced, e.g. "witcer" -> "witcher"
+    pub corrected_query: Option<String>,
+    pub results: Vec<GameSearchResult>,
+}
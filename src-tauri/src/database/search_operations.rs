@@ -0,0 +1,251 @@
+use rusqlite::{params, Connection, Result};
+use super::search_models::*;
+
+/// Typo correction is only attempted for terms at least this long
+const MIN_CORRECTABLE_LEN: usize = 3;
+
+/// Alternatives tried for one misspelled term
+const MAX_CORRECTIONS_PER_TERM: usize = 3;
+
+/// An FTS5 MATCH expression built from free text
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchQuery {
+    pub expression: String,
+    pub corrected_query: Option<String>,
+}
+
+pub struct SearchOperations;
+
+impl SearchOperations {
+    /// Ranked prefix search over cached game names, publishers and descriptions
+    pub fn search_games(conn: &Connection, query: &str, limit: Option<u32>) -> Result<GameSearchResponse> {
+        let mut response = GameSearchResponse {
+            query: query.to_string(),
+            corrected_query: None,
+            results: Vec::new(),
+        };
+
+        let match_query = match build_match_query(conn, "game_details_fts_vocab", query)? {
+            Some(match_query) => match_query,
+            None => return Ok(response),
+        };
+
+        // Name matches weigh most, then publisher, then description
+        let mut stmt = conn.prepare(
+            "SELECT d.app_id, d.name, d.header_image, d.publisher, d.release_date,
+                    bm25(game_details_fts, 10.0, 4.0, 1.0) AS score
+             FROM game_details_fts
+             JOIN game_details d ON d.app_id = CAST(game_details_fts.rowid AS TEXT)
+             WHERE game_details_fts MATCH ?1
+             ORDER BY score
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![match_query.expression, clamp_limit(limit)], |row| {
+            Ok(GameSearchResult {
+                app_id: row.get(0)?,
+                name: row.get(1)?,
+                header_image: row.get(2)?,
+                publisher: row.get(3)?,
+                release_date: row.get(4)?,
+                score: row.get(5)?,
+            })
+        })?;
+
+        response.results = rows.collect::<Result<Vec<_>>>()?;
+        response.corrected_query = match_query.corrected_query;
+        Ok(response)
+    }
+}
+
+pub fn clamp_limit(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT)
+}
+
+/// Turn free text into an FTS5 expression: every term is a prefix match, and terms
+/// with no match in `vocab_table` are swapped for the closest indexed terms.
+pub fn build_match_query(conn: &Connection, vocab_table: &str, query: &str) -> Result<Option<MatchQuery>> {
+    let terms = query_terms(query);
+    if terms.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parts = Vec::new();
+    let mut corrected_terms = Vec::new();
+    let mut corrected = false;
+
+    for term in terms {
+        let suggestions = if term.chars().count() >= MIN_CORRECTABLE_LEN && !has_prefix_match(conn, vocab_table, &term)? {
+            corrections(conn, vocab_table, &term)?
+        } else {
+            Vec::new()
+        };
+
+        if suggestions.is_empty() {
+            parts.push(prefix_term(&term));
+            corrected_terms.push(term);
+        } else {
+            corrected = true;
+            let alternatives: Vec<String> = suggestions.iter().map(|s| prefix_term(s)).collect();
+            parts.push(format!("({})", alternatives.join(" OR ")));
+            corrected_terms.push(suggestions[0].clone());
+        }
+    }
+
+    Ok(Some(MatchQuery {
+        expression: parts.join(" AND "),
+        corrected_query: corrected.then(|| corrected_terms.join(" ")),
+    }))
+}
+
+/// Lowercased alphanumeric words; everything else separates terms
+fn query_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+fn prefix_term(term: &str) -> String {
+    format!("\"{}\"*", term.replace('"', "\"\""))
+}
+
+fn has_prefix_match(conn: &Connection, vocab_table: &str, term: &str) -> Result<bool> {
+    conn.prepare(&format!("SELECT 1 FROM {} WHERE term >= ?1 AND term < ?2 LIMIT 1", vocab_table))?
+        .exists(params![term, format!("{}\u{10FFFF}", term)])
+}
+
+/// Indexed terms within a small edit distance, closest and most common first.
+/// Candidates share the first letter, which keeps the scan to one slice of the vocabulary.
+fn corrections(conn: &Connection, vocab_table: &str, term: &str) -> Result<Vec<String>> {
+    let first: String = term.chars().take(1).collect();
+    let term_len = term.chars().count();
+    let max_distance = if term_len <= 4 { 1 } else { 2 };
+
+    let mut stmt = conn.prepare(&format!("SELECT term, doc FROM {} WHERE term >= ?1 AND term < ?2", vocab_table))?;
+    let candidates = stmt.query_map(params![first, format!("{}\u{10FFFF}", first)], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })?;
+
+    let mut scored = Vec::new();
+    for candidate in candidates {
+        let (candidate, doc_count) = candidate?;
+        let candidate_len = candidate.chars().count();
+        if candidate_len + max_distance < term_len {
+            continue;
+        }
+
+        // Compare against the whole word and against a word of the same length, so a
+        // typo in a partly typed word ("witc" -> "wicther...") still finds it
+        let truncated: String = candidate.chars().take(term_len).collect();
+        let distance = strsim::damerau_levenshtein(term, &candidate)
+            .min(strsim::damerau_levenshtein(term, &truncated));
+        if distance <= max_distance {
+            scored.push((distance, -doc_count, candidate));
+        }
+    }
+
+    scored.sort();
+    Ok(scored
+        .into_iter()
+        .map(|(_, _, candidate)| candidate)
+        .take(MAX_CORRECTIONS_PER_TERM)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations::run_migrations;
+
+    fn add_game(conn: &Connection, app_id: &str, name: &str, publisher: &str, description: &str) {
+        conn.execute(
+            "INSERT OR REPLACE INTO games (app_id, name, cached_at, expires_at) VALUES (?1, ?2, 0, 0)",
+            params![app_id, name],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO game_details (app_id, name, publisher, detailed_description, cached_at, expires_at,
+                dynamic_expires_at, semistatic_expires_at, static_expires_at)
+             VALUES (?1, ?2, ?3, ?4, 0, 0, 0, 0, 0)",
+            params![app_id, name, publisher, description],
+        )
+        .unwrap();
+    }
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        add_game(&conn, "620", "Portal 2", "Valve", "A puzzle game with portals");
+        add_game(&conn, "292030", "The Witcher 3: Wild Hunt", "CD PROJEKT RED", "An open world RPG");
+        add_game(&conn, "400", "Portal", "Valve", "The original test chamber puzzle");
+        add_game(&conn, "1", "Chamber Tales", "Indie", "Nothing about portals at all, just a portal mention");
+        conn
+    }
+
+    fn app_ids(response: &GameSearchResponse) -> Vec<&str> {
+        response.results.iter().map(|r| r.app_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_prefix_search_ranks_names_first() {
+        let conn = setup();
+        let response = SearchOperations::search_games(&conn, "port", None).unwrap();
+        let ids = app_ids(&response);
+
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids.last(), Some(&"1"), "description-only match should rank last");
+        assert!(response.corrected_query.is_none());
+    }
+
+    #[test]
+    fn test_typos_are_corrected() {
+        let conn = setup();
+        let response = SearchOperations::search_games(&conn, "witcehr wild", None).unwrap();
+
+        assert_eq!(app_ids(&response), vec!["292030"]);
+        assert_eq!(response.corrected_query.as_deref(), Some("witcher wild"));
+    }
+
+    #[test]
+    fn test_index_follows_replace_and_delete() {
+        let conn = setup();
+        add_game(&conn, "620", "Portal Reloaded", "Valve", "");
+        conn.execute("DELETE FROM game_details WHERE app_id = '400'", []).unwrap();
+
+        let response = SearchOperations::search_games(&conn, "portal", None).unwrap();
+        assert_eq!(app_ids(&response), vec!["620", "1"]);
+        assert_eq!(response.results[0].name, "Portal Reloaded");
+    }
+
+    #[test]
+    fn test_existing_history_is_backfilled() {
+        use crate::database::history_operations::DownloadHistoryOperations;
+
+        use crate::database::migrations::rollback_to;
+
+        // History written before 0010_search_fts existed
+        let backups = tempfile::tempdir().unwrap();
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        rollback_to(&conn, backups.path(), 9).unwrap();
+        conn.execute(
+            "INSERT INTO download_history (download_id, download_type, source_type, url, save_path, status, started_at, game_name)
+             VALUES ('d1', 'regular', 'manual', 'https://example.com/dota.zip', '/tmp', 'completed', 1700000000, 'Dota 2')",
+            [],
+        ).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let history = DownloadHistoryOperations::search_history(&conn, "dot", None).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].game_name.as_deref(), Some("Dota 2"));
+    }
+
+    #[test]
+    fn test_query_syntax_is_escaped() {
+        let conn = setup();
+        for query in ["\"", "NEAR(", "port AND OR", "*", ""] {
+            assert!(SearchOperations::search_games(&conn, query, None).is_ok(), "query {:?} failed", query);
+        }
+    }
+}
@@ -1,5 +1,8 @@
-/// TTL Configuration for different types of game data based on their dynamism
-/// Values are in seconds for precise control
+//! TTL Configuration for different types of game data based on their dynamism
+//! Values are in seconds for precise control
+
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
 
 /// TTL Constants based on data dynamism analysis
 pub struct TtlConfig;
@@ -41,6 +44,16 @@ impl TtlConfig {
     /// DRM notice - changes rarely but can happen
     pub const DRM_NOTICE: i64 = 180 * 24 * 3600; // 180 days (6 months)
     
+    // ========== CATALOGUE DATA ==========
+    /// Hydra catalogue pages - new games are added daily
+    pub const CATALOGUE_PAGE: i64 = 6 * 3600; // 6 hours
+
+    /// Hydra search results - more specific queries, refreshed more often
+    pub const CATALOGUE_SEARCH: i64 = 3600; // 1 hour
+
+    /// Genre, tag, developer and publisher lists - regenerated now and then
+    pub const METADATA_RESOURCES: i64 = 7 * 24 * 3600; // 7 days
+
     // ========== PROFILE DATA ==========
     /// Profile data - user-specific, should persist long
     pub const PROFILE_DATA: i64 = 365 * 24 * 3600; // 1 year
@@ -71,6 +84,30 @@ impl TtlCategory {
         }
     }
     
+    /// TTL currently in effect for the category: the runtime override if one is set,
+    /// otherwise the compiled TTL used for its granular expiry column
+    pub fn live_ttl(&self) -> i64 {
+        TtlOverrides::current().category_ttl(*self)
+    }
+
+    /// Key used for the category in `GameDetailDb::get_expired_categories`/`refresh_category_expiry`
+    pub fn key(&self) -> &'static str {
+        match self {
+            TtlCategory::Dynamic => "dynamic",
+            TtlCategory::SemiStatic => "semistatic",
+            TtlCategory::Static => "static",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "dynamic" => Some(TtlCategory::Dynamic),
+            "semistatic" => Some(TtlCategory::SemiStatic),
+            "static" => Some(TtlCategory::Static),
+            _ => None,
+        }
+    }
+
     /// Get category name for logging
     pub fn name(&self) -> &'static str {
         match self {
@@ -81,12 +118,87 @@ impl TtlCategory {
     }
 }
 
+/// Per-category TTLs set at runtime, in seconds. `None` keeps the compiled defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TtlOverrides {
+    pub dynamic: Option<i64>,
+    pub semistatic: Option<i64>,
+    #[serde(rename = "static")]
+    pub static_data: Option<i64>,
+}
+
+lazy_static::lazy_static! {
+    static ref LIVE_TTL_OVERRIDES: RwLock<TtlOverrides> = RwLock::new(TtlOverrides::default());
+}
+
+impl TtlOverrides {
+    /// Shortest and longest TTL accepted for a category
+    pub const MIN_TTL: i64 = 3600; // 1 hour
+    pub const MAX_TTL: i64 = 5 * 365 * 24 * 3600; // 5 years
+
+    /// Overrides currently in effect
+    pub fn current() -> Self {
+        *LIVE_TTL_OVERRIDES.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Make these overrides the live ones
+    pub fn apply(self) {
+        *LIVE_TTL_OVERRIDES.write().unwrap_or_else(|e| e.into_inner()) = self;
+    }
+
+    pub fn get(&self, category: TtlCategory) -> Option<i64> {
+        match category {
+            TtlCategory::Dynamic => self.dynamic,
+            TtlCategory::SemiStatic => self.semistatic,
+            TtlCategory::Static => self.static_data,
+        }
+    }
+
+    /// TTL for a category's granular expiry column under these overrides
+    pub fn category_ttl(&self, category: TtlCategory) -> i64 {
+        self.get(category).unwrap_or(match category {
+            TtlCategory::Dynamic => TtlConfig::DLC_LIST,
+            TtlCategory::SemiStatic => TtlConfig::GAME_NAME,
+            TtlCategory::Static => TtlConfig::SCREENSHOTS,
+        })
+    }
+
+    /// TTL for a GameDetail field under these overrides
+    pub fn field_ttl(&self, field_name: &str) -> i64 {
+        self.get(FieldTtl::get_field_category(field_name))
+            .unwrap_or_else(|| FieldTtl::default_field_ttl(field_name))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for category in [TtlCategory::Dynamic, TtlCategory::SemiStatic, TtlCategory::Static] {
+            if let Some(ttl) = self.get(category) {
+                if !(Self::MIN_TTL..=Self::MAX_TTL).contains(&ttl) {
+                    return Err(format!(
+                        "{} TTL must be between {} and {} seconds (got {})",
+                        category.name(),
+                        Self::MIN_TTL,
+                        Self::MAX_TTL,
+                        ttl
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Field-specific TTL mapping
 pub struct FieldTtl;
 
 impl FieldTtl {
-    /// Get TTL for specific GameDetail field
+    /// Get TTL for specific GameDetail field (a category override applies to all of its fields)
     pub fn get_field_ttl(field_name: &str) -> i64 {
+        TtlOverrides::current().field_ttl(field_name)
+    }
+
+    /// Compiled TTL for a field, ignoring runtime overrides
+    pub fn default_field_ttl(field_name: &str) -> i64 {
         match field_name {
             // Dynamic data
             "dlc" => TtlConfig::DLC_LIST,
@@ -201,6 +313,19 @@ mod tests {
         assert_eq!(FieldTtl::get_field_category("screenshots"), TtlCategory::Static);
     }
     
+    #[test]
+    fn test_overrides_apply_to_whole_category() {
+        let overrides = TtlOverrides { static_data: Some(7 * 24 * 3600), ..Default::default() };
+
+        assert_eq!(overrides.field_ttl("screenshots"), 7 * 24 * 3600);
+        assert_eq!(overrides.field_ttl("publisher"), 7 * 24 * 3600);
+        assert_eq!(overrides.category_ttl(TtlCategory::Static), 7 * 24 * 3600);
+        assert_eq!(overrides.field_ttl("name"), TtlConfig::GAME_NAME);
+        assert_eq!(TtlOverrides::default().field_ttl("screenshots"), TtlConfig::SCREENSHOTS);
+
+        assert!(TtlOverrides { dynamic: Some(60), ..Default::default() }.validate().is_err());
+    }
+
     #[test]
     fn test_smart_ttl_calculation() {
         let fields = vec!["dlc", "screenshots", "name"];
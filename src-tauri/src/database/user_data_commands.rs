@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+use tauri::{command, State};
+use crate::app_paths::app_paths;
+use crate::database::{
+    user_data_models::*, user_data_operations::UserDataOperations, SharedDatabase,
+};
+
+/// Export library, profile, history, play sessions and launch profiles to a zip archive.
+/// `destination` may be a folder, in which case a timestamped file name is chosen.
+#[command]
+pub async fn export_user_data(
+    destination: String,
+    include_cache: Option<bool>,
+    db: State<'_, SharedDatabase>,
+) -> Result<ExportSummary, String> {
+    let profile_dir = app_paths().profile_dir();
+    db.run_blocking(move |db| {
+        db.with_read_connection(|conn| {
+            UserDataOperations::export(conn, &profile_dir, &PathBuf::from(&destination), include_cache.unwrap_or(INCLUDE_CACHE_BY_DEFAULT))
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to export user data: {}", e))
+}
+
+/// Import an export archive. Defaults to a dry run that only reports what would change.
+#[command]
+pub async fn import_user_data(
+    archive_path: String,
+    mode: ImportMode,
+    dry_run: Option<bool>,
+    include_cache: Option<bool>,
+    db: State<'_, SharedDatabase>,
+) -> Result<ImportSummary, String> {
+    let profile_dir = app_paths().profile_dir();
+    db.run_blocking(move |db| {
+        let mut archive = UserDataOperations::read_archive(&PathBuf::from(&archive_path))?;
+        db.with_connection(|conn| {
+            UserDataOperations::import(
+                conn,
+                &mut archive,
+                mode,
+                include_cache.unwrap_or(INCLUDE_CACHE_BY_DEFAULT),
+                &profile_dir,
+                dry_run.unwrap_or(true),
+            )
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to import user data: {}", e))
+}
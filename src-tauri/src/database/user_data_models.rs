@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bumped when the archive layout changes (not the database schema)
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const DATA_DIR: &str = "data";
+pub const PROFILE_DIR: &str = "profile";
+pub const CACHE_SNAPSHOT_FILE: &str = "cache/games.db";
+
+/// Whether export and import carry the cache snapshot when the caller doesn't say
+pub const INCLUDE_CACHE_BY_DEFAULT: bool = false;
+
+/// Profile images saved by the profile commands
+pub const PROFILE_IMAGES: [&str; 2] = ["banner.jpg", "avatar.jpg"];
+
+/// `manifest.json` at the root of an export archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub schema_version: i32,
+    pub created_at: i64,
+    /// Row count per exported table
+    pub tables: BTreeMap<String, usize>,
+    pub profile_images: Vec<String>,
+    pub includes_cache_snapshot: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Add what is missing and keep local rows on conflict
+    Merge,
+    /// Make local user data match the archive
+    Replace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileChange {
+    Unchanged,
+    Replaced,
+    Kept,
+    NotInArchive,
+}
+
+/// Per-table diff between the archive and the local database
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableChange {
+    pub table: String,
+    pub incoming: usize,
+    /// Rows that don't exist locally
+    pub added: usize,
+    /// Local rows overwritten by the archive (replace mode, or newer cache entries)
+    pub replaced: usize,
+    /// Rows already present locally that are left alone (merge mode)
+    pub skipped: usize,
+    /// Local rows missing from the archive that are deleted (replace mode)
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub dry_run: bool,
+    pub mode: ImportMode,
+    pub manifest: ExportManifest,
+    pub tables: Vec<TableChange>,
+    pub profile: ProfileChange,
+    pub profile_images: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub size_bytes: u64,
+    pub manifest: ExportManifest,
+}
@@ -0,0 +1,770 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params_from_iter, Connection, DatabaseName};
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use super::migrations::{self, MIGRATIONS};
+use super::user_data_models::*;
+
+type Row = Map<String, JsonValue>;
+
+/// A user table carried in an archive, with the columns that identify a row across machines
+struct TableSpec {
+    table: &'static str,
+    key: &'static [&'static str],
+    /// Autoincrement id that is only meaningful locally
+    local_id: Option<&'static str>,
+}
+
+/// User tables in insert order (library before the profiles that reference it)
+const USER_TABLES: &[TableSpec] = &[
+    TableSpec { table: "user_library", key: &["app_id"], local_id: None },
+    TableSpec { table: "download_history", key: &["download_id", "started_at"], local_id: Some("id") },
+    TableSpec { table: "play_sessions", key: &["executable_path", "started_at"], local_id: Some("id") },
+    TableSpec { table: "launch_profiles", key: &["app_id", "name"], local_id: Some("id") },
+];
+
+/// Cache tables merged from the optional snapshot, keyed by app_id
+const CACHE_TABLES: &[&str] = &["games", "game_details", "bypass_games"];
+
+/// An opened export archive with a validated manifest. Entries are streamed from the zip as
+/// the import reaches them rather than loaded up front.
+pub struct UserDataArchive {
+    pub manifest: ExportManifest,
+    zip: ZipArchive<File>,
+}
+
+impl UserDataArchive {
+    /// Call `f` with each row of `table` as it is decoded. Tables the archive lacks have no rows.
+    fn for_each_row(&mut self, table: &str, f: impl FnMut(Row) -> Result<()>) -> Result<()> {
+        if !self.manifest.tables.contains_key(table) {
+            return Ok(());
+        }
+        let file = self
+            .zip
+            .by_name(&format!("{}/{}.json", DATA_DIR, table))
+            .map_err(|_| anyhow!("Archive is missing data for {}", table))?;
+        for_each_json_row(file, f)
+    }
+
+    /// Profile images in the archive that this version knows about
+    fn profile_images(&self) -> Vec<&str> {
+        self.manifest
+            .profile_images
+            .iter()
+            .map(String::as_str)
+            .filter(|name| PROFILE_IMAGES.contains(name))
+            .collect()
+    }
+
+    fn extract_profile_image(&mut self, name: &str, destination: &Path) -> Result<()> {
+        let mut file = self.zip.by_name(&format!("{}/{}", PROFILE_DIR, name))?;
+        std::io::copy(&mut file, &mut File::create(destination)?)?;
+        Ok(())
+    }
+
+    /// Unpack the cache snapshot to a temp file SQLite can attach
+    fn extract_cache_snapshot(&mut self) -> Result<Option<tempfile::NamedTempFile>> {
+        if !self.manifest.includes_cache_snapshot {
+            return Ok(None);
+        }
+        let mut snapshot = tempfile::NamedTempFile::new()?;
+        std::io::copy(&mut self.zip.by_name(CACHE_SNAPSHOT_FILE)?, &mut snapshot)?;
+        Ok(Some(snapshot))
+    }
+}
+
+pub struct UserDataOperations;
+
+impl UserDataOperations {
+    /// Write user data (and optionally a snapshot of the cache database) to a zip archive.
+    /// `destination` may be a file path or an existing directory.
+    pub fn export(conn: &Connection, profile_dir: &Path, destination: &Path, include_cache: bool) -> Result<ExportSummary> {
+        let path = if destination.is_dir() {
+            destination.join(format!("zenith-export-{}.zip", Utc::now().format("%Y%m%d-%H%M%S")))
+        } else {
+            destination.to_path_buf()
+        };
+
+        // Read every table in one transaction so the export is consistent
+        let mut tables = BTreeMap::new();
+        {
+            let tx = conn.unchecked_transaction()?;
+            tables.insert("user_profile".to_string(), read_rows(&tx, "SELECT * FROM user_profile WHERE id = 1")?);
+            // Names and images for library games, so the library renders before metadata is refetched
+            tables.insert(
+                "games".to_string(),
+                read_rows(&tx, "SELECT g.* FROM games g JOIN user_library l ON l.app_id = g.app_id")?,
+            );
+            for spec in USER_TABLES {
+                tables.insert(spec.table.to_string(), read_rows(&tx, &format!("SELECT * FROM {}", spec.table))?);
+            }
+            tx.commit()?;
+        }
+
+        let profile_images: Vec<String> = PROFILE_IMAGES
+            .iter()
+            .filter(|name| profile_dir.join(name).is_file())
+            .map(|name| name.to_string())
+            .collect();
+
+        let manifest = ExportManifest {
+            format_version: EXPORT_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: migrations::get_schema_version(conn)?,
+            created_at: Utc::now().timestamp(),
+            tables: tables.iter().map(|(name, rows)| (name.clone(), rows.len())).collect(),
+            profile_images: profile_images.clone(),
+            includes_cache_snapshot: include_cache,
+        };
+
+        // Write next to the target and rename, so a failed export never leaves a truncated archive
+        let tmp_path = PathBuf::from(format!("{}.tmp", path.display()));
+        {
+            let mut zip = ZipWriter::new(File::create(&tmp_path)?);
+            let options = FileOptions::default();
+
+            zip.start_file(MANIFEST_FILE, options)?;
+            zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+            for (table, rows) in &tables {
+                zip.start_file(format!("{}/{}.json", DATA_DIR, table), options)?;
+                zip.write_all(&serde_json::to_vec(rows)?)?;
+            }
+
+            for name in &profile_images {
+                zip.start_file(format!("{}/{}", PROFILE_DIR, name), options)?;
+                zip.write_all(&fs::read(profile_dir.join(name))?)?;
+            }
+
+            if include_cache {
+                let snapshot = tempfile::NamedTempFile::new()?;
+                conn.backup(DatabaseName::Main, snapshot.path(), None)?;
+                zip.start_file(CACHE_SNAPSHOT_FILE, options)?;
+                std::io::copy(&mut File::open(snapshot.path())?, &mut zip)?;
+            }
+
+            zip.finish()?;
+        }
+        fs::rename(&tmp_path, &path)?;
+
+        println!("📦 Exported user data to {}", path.display());
+        Ok(ExportSummary {
+            size_bytes: fs::metadata(&path)?.len(),
+            path: path.to_string_lossy().to_string(),
+            manifest,
+        })
+    }
+
+    /// Open an archive and validate its manifest and the entries it lists
+    pub fn read_archive(path: &Path) -> Result<UserDataArchive> {
+        let mut zip = ZipArchive::new(File::open(path)?)?;
+
+        let manifest: ExportManifest = {
+            let file = zip
+                .by_name(MANIFEST_FILE)
+                .map_err(|_| anyhow!("Not a Zenith export: {} is missing", MANIFEST_FILE))?;
+            serde_json::from_reader(file)?
+        };
+        validate_manifest(&manifest)?;
+
+        let entries: HashSet<&str> = zip.file_names().collect();
+        let missing = |entry: &str| !entries.contains(entry);
+        if let Some(table) = manifest.tables.keys().find(|table| missing(&format!("{}/{}.json", DATA_DIR, table))) {
+            return Err(anyhow!("Archive is missing data for {}", table));
+        }
+        if let Some(name) = manifest.profile_images.iter().find(|name| missing(&format!("{}/{}", PROFILE_DIR, name))) {
+            return Err(anyhow!("Archive is missing profile image {}", name));
+        }
+        if manifest.includes_cache_snapshot && missing(CACHE_SNAPSHOT_FILE) {
+            return Err(anyhow!("Archive is missing its cache snapshot"));
+        }
+
+        Ok(UserDataArchive { manifest, zip })
+    }
+
+    /// Diff the archive against the local database and, unless `dry_run`, apply it in one transaction
+    pub fn import(
+        conn: &Connection,
+        archive: &mut UserDataArchive,
+        mode: ImportMode,
+        include_cache: bool,
+        profile_dir: &Path,
+        dry_run: bool,
+    ) -> Result<ImportSummary> {
+        let snapshot = if include_cache { archive.extract_cache_snapshot()? } else { None };
+        if let Some(snapshot) = &snapshot {
+            attach_snapshot(conn, snapshot.path())?;
+        }
+
+        let result = Self::import_attached(conn, archive, mode, snapshot.is_some(), profile_dir, dry_run);
+
+        if snapshot.is_some() {
+            conn.execute_batch("DETACH DATABASE snapshot")?;
+        }
+        result
+    }
+
+    fn import_attached(
+        conn: &Connection,
+        archive: &mut UserDataArchive,
+        mode: ImportMode,
+        with_cache: bool,
+        profile_dir: &Path,
+        dry_run: bool,
+    ) -> Result<ImportSummary> {
+        let mut changes = Vec::new();
+        let mut pending = Vec::new();
+        for spec in USER_TABLES {
+            let (change, keys) = diff_table(conn, spec, archive, mode)?;
+            changes.push(change);
+            pending.push((spec, keys));
+        }
+
+        if with_cache {
+            for table in CACHE_TABLES {
+                if let Some(change) = diff_cache_table(conn, table, mode)? {
+                    changes.push(change);
+                }
+            }
+        }
+
+        let mut incoming_profile = None;
+        archive.for_each_row("user_profile", |row| {
+            incoming_profile.get_or_insert(row);
+            Ok(())
+        })?;
+        let image_count = archive.profile_images().len();
+        let profile = profile_change(conn, incoming_profile.as_ref(), image_count > 0, mode)?;
+
+        let summary = ImportSummary {
+            dry_run,
+            mode,
+            manifest: archive.manifest.clone(),
+            tables: changes,
+            profile,
+            profile_images: if profile == ProfileChange::Replaced { image_count } else { 0 },
+        };
+        if dry_run {
+            return Ok(summary);
+        }
+
+        let tx = conn.unchecked_transaction()?;
+
+        if mode == ImportMode::Replace {
+            for spec in USER_TABLES.iter().rev() {
+                tx.execute(&format!("DELETE FROM {}", spec.table), [])?;
+            }
+        }
+
+        // Library rows need a games row for their foreign key
+        archive.for_each_row("games", |row| {
+            insert_row(&tx, "games", &row, None, "ON CONFLICT(app_id) DO NOTHING").map(drop)
+        })?;
+
+        for (spec, mut keys) in pending {
+            archive.for_each_row(spec.table, |mut row| {
+                // Only the first copy of each row picked by the diff
+                if !keys.remove(&row_key(&row, spec.key)) {
+                    return Ok(());
+                }
+                if spec.table == "user_library" {
+                    tx.execute(
+                        "INSERT INTO games (app_id, name, cached_at, expires_at) VALUES (?1, ?1, 0, 0)
+                         ON CONFLICT(app_id) DO NOTHING",
+                        [row.get("app_id").and_then(JsonValue::as_str).unwrap_or_default()],
+                    )?;
+                }
+
+                if spec.table == "launch_profiles" && has_default_profile(&tx, &row)? {
+                    // Only one default per game; the local one wins
+                    row.insert("is_default".to_string(), JsonValue::from(0));
+                }
+                insert_row(&tx, spec.table, &row, spec.local_id, "").map(drop)
+            })?;
+        }
+
+        if let (ProfileChange::Replaced, Some(incoming)) = (profile, &incoming_profile) {
+            import_profile(&tx, incoming, archive, profile_dir)?;
+        }
+
+        if with_cache {
+            for table in CACHE_TABLES {
+                merge_cache_table(&tx, table, mode)?;
+            }
+        }
+
+        tx.commit()?;
+        println!("📥 Imported user data ({:?} mode)", mode);
+        Ok(summary)
+    }
+}
+
+fn validate_manifest(manifest: &ExportManifest) -> Result<()> {
+    if manifest.format_version != EXPORT_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Unsupported export format version {} (expected {})",
+            manifest.format_version,
+            EXPORT_FORMAT_VERSION
+        ));
+    }
+
+    let head_version = MIGRATIONS.last().map_or(0, |m| m.version);
+    if manifest.schema_version < 1 || manifest.schema_version > head_version {
+        return Err(anyhow!(
+            "Archive uses database schema v{} but this version supports v1 to v{}; update Zenith before importing",
+            manifest.schema_version,
+            head_version
+        ));
+    }
+    Ok(())
+}
+
+/// Count what an import would do to one table and return the keys of the rows to insert
+fn diff_table(
+    conn: &Connection,
+    spec: &TableSpec,
+    archive: &mut UserDataArchive,
+    mode: ImportMode,
+) -> Result<(TableChange, HashSet<String>)> {
+    let local_keys: HashSet<String> = read_rows(conn, &format!("SELECT {} FROM {}", spec.key.join(", "), spec.table))?
+        .iter()
+        .map(|row| row_key(row, spec.key))
+        .collect();
+
+    let mut change = TableChange {
+        table: spec.table.to_string(),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let mut keys = HashSet::new();
+
+    archive.for_each_row(spec.table, |row| {
+        change.incoming += 1;
+        let key = row_key(&row, spec.key);
+        if !seen.insert(key.clone()) {
+            return Ok(()); // Duplicate inside the archive
+        }
+
+        match (local_keys.contains(&key), mode) {
+            (false, _) => {
+                change.added += 1;
+                keys.insert(key);
+            }
+            (true, ImportMode::Merge) => change.skipped += 1,
+            (true, ImportMode::Replace) => {
+                change.replaced += 1;
+                keys.insert(key);
+            }
+        }
+        Ok(())
+    })?;
+
+    if mode == ImportMode::Replace {
+        change.removed = local_keys.difference(&seen).count();
+    }
+    Ok((change, keys))
+}
+
+fn profile_change(conn: &Connection, incoming: Option<&Row>, has_images: bool, mode: ImportMode) -> Result<ProfileChange> {
+    let incoming = match incoming {
+        Some(incoming) => incoming,
+        None => return Ok(ProfileChange::NotInArchive),
+    };
+    let local = read_rows(conn, "SELECT name, steam_id, banner_path, avatar_path FROM user_profile WHERE id = 1")?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+
+    let same = ["name", "steam_id"].iter().all(|column| local.get(*column) == incoming.get(*column));
+    if same && !has_images {
+        return Ok(ProfileChange::Unchanged);
+    }
+
+    // Merging only fills in a profile that was never customised
+    let is_default = local.get("name").and_then(JsonValue::as_str) == Some("User")
+        && ["steam_id", "banner_path", "avatar_path"]
+            .iter()
+            .all(|column| local.get(*column).map_or(true, JsonValue::is_null));
+
+    Ok(match mode {
+        ImportMode::Replace => ProfileChange::Replaced,
+        ImportMode::Merge if is_default => ProfileChange::Replaced,
+        ImportMode::Merge => ProfileChange::Kept,
+    })
+}
+
+/// Write profile images into this machine's profile folder and point the profile at them
+fn import_profile(conn: &Connection, incoming: &Row, archive: &mut UserDataArchive, profile_dir: &Path) -> Result<()> {
+    let images: Vec<String> = archive.profile_images().into_iter().map(str::to_string).collect();
+    if !images.is_empty() {
+        fs::create_dir_all(profile_dir)?;
+    }
+    for name in &images {
+        archive.extract_profile_image(name, &profile_dir.join(name))?;
+    }
+
+    let image_path = |name: &str| {
+        images
+            .iter()
+            .any(|image| image == name)
+            .then(|| profile_dir.join(name).to_string_lossy().to_string())
+    };
+
+    conn.execute(
+        "UPDATE user_profile SET name = ?1, steam_id = ?2, banner_path = ?3, avatar_path = ?4, updated_at = ?5
+         WHERE id = 1",
+        rusqlite::params![
+            incoming.get("name").and_then(JsonValue::as_str).unwrap_or("User"),
+            incoming.get("steam_id").and_then(JsonValue::as_str),
+            image_path(PROFILE_IMAGES[0]),
+            image_path(PROFILE_IMAGES[1]),
+            Utc::now().timestamp(),
+        ],
+    )?;
+    Ok(())
+}
+
+fn has_default_profile(conn: &Connection, row: &Row) -> Result<bool> {
+    if row.get("is_default").and_then(JsonValue::as_i64) != Some(1) {
+        return Ok(false);
+    }
+    Ok(conn
+        .prepare("SELECT 1 FROM launch_profiles WHERE app_id = ?1 AND is_default = 1")?
+        .exists([row.get("app_id").and_then(JsonValue::as_str).unwrap_or_default()])?)
+}
+
+fn attach_snapshot(conn: &Connection, path: &Path) -> Result<()> {
+    conn.execute("ATTACH DATABASE ?1 AS snapshot", [path.to_string_lossy()])?;
+
+    let check: String = conn.query_row("PRAGMA snapshot.quick_check", [], |row| row.get(0))?;
+    if check != "ok" {
+        conn.execute_batch("DETACH DATABASE snapshot")?;
+        return Err(anyhow!("Cache snapshot in the archive is damaged: {}", check));
+    }
+    Ok(())
+}
+
+/// Columns present in both the live table and the snapshot (which may be an older schema)
+fn shared_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let snapshot: HashSet<String> = table_columns(conn, table, "snapshot")?.into_iter().collect();
+    Ok(table_columns(conn, table, "main")?
+        .into_iter()
+        .filter(|column| snapshot.contains(column))
+        .collect())
+}
+
+fn diff_cache_table(conn: &Connection, table: &str, mode: ImportMode) -> Result<Option<TableChange>> {
+    let columns = shared_columns(conn, table)?;
+    if !columns.iter().any(|c| c == "app_id") {
+        return Ok(None); // Not in the snapshot
+    }
+
+    let newer = if mode == ImportMode::Merge && columns.iter().any(|c| c == "cached_at") {
+        " AND s.cached_at > m.cached_at"
+    } else {
+        ""
+    };
+    let count = |sql: String| -> Result<usize> { Ok(conn.query_row(&sql, [], |row| row.get::<_, i64>(0))? as usize) };
+
+    let incoming = count(format!("SELECT COUNT(*) FROM snapshot.{}", table))?;
+    let added = count(format!(
+        "SELECT COUNT(*) FROM snapshot.{t} s WHERE NOT EXISTS (SELECT 1 FROM main.{t} m WHERE m.app_id = s.app_id)",
+        t = table
+    ))?;
+    let replaced = count(format!(
+        "SELECT COUNT(*) FROM snapshot.{t} s WHERE EXISTS (SELECT 1 FROM main.{t} m WHERE m.app_id = s.app_id{newer})",
+        t = table,
+        newer = newer
+    ))?;
+
+    Ok(Some(TableChange {
+        table: table.to_string(),
+        incoming,
+        added,
+        replaced,
+        skipped: incoming - added - replaced,
+        removed: 0,
+    }))
+}
+
+/// Upsert cache rows from the snapshot. Merge keeps whichever copy was cached last.
+fn merge_cache_table(conn: &Connection, table: &str, mode: ImportMode) -> Result<()> {
+    let columns = shared_columns(conn, table)?;
+    if !columns.iter().any(|c| c == "app_id") {
+        return Ok(());
+    }
+
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|c| c.as_str() != "app_id")
+        .map(|c| format!("{c} = excluded.{c}", c = c))
+        .collect();
+    let condition = if mode == ImportMode::Merge && columns.iter().any(|c| c == "cached_at") {
+        format!(" WHERE excluded.cached_at > {}.cached_at", table)
+    } else {
+        String::new()
+    };
+
+    // Upsert rather than REPLACE: deleting a games row would cascade to the library
+    conn.execute(
+        &format!(
+            "INSERT INTO main.{t} ({cols}) SELECT {cols} FROM snapshot.{t} WHERE true
+             ON CONFLICT(app_id) DO UPDATE SET {updates}{condition}",
+            t = table,
+            cols = columns.join(", "),
+            updates = updates.join(", "),
+            condition = condition
+        ),
+        [],
+    )?;
+    Ok(())
+}
+
+fn table_columns(conn: &Connection, table: &str, schema: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1, ?2)")?;
+    let columns = stmt
+        .query_map([table, schema], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(columns)
+}
+
+/// Insert the columns of `row` that the live table has (archives may come from an older schema)
+fn insert_row(conn: &Connection, table: &str, row: &Row, skip: Option<&str>, conflict: &str) -> Result<usize> {
+    let columns: Vec<String> = table_columns(conn, table, "main")?
+        .into_iter()
+        .filter(|column| row.contains_key(column) && Some(column.as_str()) != skip)
+        .collect();
+    if columns.is_empty() {
+        return Ok(0);
+    }
+
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    let values: Vec<Value> = columns.iter().map(|column| json_to_sql(&row[column])).collect();
+    Ok(conn.execute(
+        &format!(
+            "INSERT INTO {} ({}) VALUES ({}) {}",
+            table,
+            columns.join(", "),
+            placeholders.join(", "),
+            conflict
+        ),
+        params_from_iter(values),
+    )?)
+}
+
+/// Decode a JSON array of rows one row at a time, handing each to `f`
+fn for_each_json_row<R: Read>(reader: R, f: impl FnMut(Row) -> Result<()>) -> Result<()> {
+    struct RowVisitor<F>(F);
+
+    impl<'de, F: FnMut(Row) -> Result<()>> Visitor<'de> for RowVisitor<F> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an array of rows")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> std::result::Result<(), A::Error> {
+            while let Some(row) = seq.next_element::<Row>()? {
+                (self.0)(row).map_err(de::Error::custom)?;
+            }
+            Ok(())
+        }
+    }
+
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+    (&mut deserializer).deserialize_seq(RowVisitor(f))?;
+    deserializer.end()?;
+    Ok(())
+}
+
+fn read_rows(conn: &Connection, sql: &str) -> Result<Vec<Row>> {
+    let mut stmt = conn.prepare(sql)?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+
+    let mut rows = stmt.query([])?;
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut map = Map::new();
+        for (i, column) in columns.iter().enumerate() {
+            map.insert(column.clone(), sql_to_json(row.get_ref(i)?));
+        }
+        result.push(map);
+    }
+    Ok(result)
+}
+
+fn row_key(row: &Row, key: &[&str]) -> String {
+    let values: Vec<&JsonValue> = key.iter().map(|column| row.get(*column).unwrap_or(&JsonValue::Null)).collect();
+    serde_json::to_string(&values).unwrap_or_default()
+}
+
+fn sql_to_json(value: ValueRef) -> JsonValue {
+    match value {
+        ValueRef::Null => JsonValue::Null,
+        ValueRef::Integer(i) => JsonValue::from(i),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f).map(JsonValue::Number).unwrap_or(JsonValue::Null),
+        ValueRef::Text(text) => JsonValue::String(String::from_utf8_lossy(text).to_string()),
+        ValueRef::Blob(bytes) => JsonValue::from(bytes.to_vec()),
+    }
+}
+
+fn json_to_sql(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(b) => Value::Integer(*b as i64),
+        JsonValue::Number(n) => n.as_i64().map(Value::Integer).unwrap_or_else(|| Value::Real(n.as_f64().unwrap_or(0.0))),
+        JsonValue::String(s) => Value::Text(s.clone()),
+        JsonValue::Array(items) => Value::Blob(items.iter().filter_map(|b| b.as_u64().map(|b| b as u8)).collect()),
+        JsonValue::Object(_) => Value::Text(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{add_library_game, DatabaseManager};
+
+    fn count(db: &DatabaseManager, table: &str) -> i64 {
+        db.with_read_connection(|conn| Ok(conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))?))
+            .unwrap()
+    }
+
+    fn source_archive(dir: &Path) -> PathBuf {
+        let db = DatabaseManager::new(dir.join("source").join("games.db")).unwrap();
+        let profile_dir = dir.join("source_profile");
+        fs::create_dir_all(&profile_dir).unwrap();
+        fs::write(profile_dir.join("banner.jpg"), b"jpeg").unwrap();
+
+        db.with_connection(|conn| add_library_game(conn, "570", "Dota 2", None)).unwrap();
+        db.with_connection(|conn| add_library_game(conn, "730", "Counter-Strike 2", None)).unwrap();
+        db.with_connection(|conn| {
+            conn.execute("UPDATE user_profile SET name = 'Player', banner_path = 'C:/old/banner.jpg' WHERE id = 1", [])?;
+            conn.execute(
+                "INSERT INTO download_history (download_id, download_type, source_type, url, save_path, status, started_at)
+                 VALUES ('d1', 'bypass', 'bypass', 'https://example.com/a.zip', '/tmp', 'completed', 1700000000)",
+                [],
+            )?;
+            conn.execute(
+                "INSERT INTO launch_profiles (app_id, name, executable_path, is_default) VALUES ('570', 'Default', 'dota2.exe', 1)",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let summary = db
+            .with_read_connection(|conn| UserDataOperations::export(conn, &profile_dir, &dir.join("export.zip"), true))
+            .unwrap();
+        assert_eq!(summary.manifest.tables["user_library"], 2);
+        PathBuf::from(summary.path)
+    }
+
+    fn change<'a>(summary: &'a ImportSummary, table: &str) -> &'a TableChange {
+        summary.tables.iter().find(|c| c.table == table).unwrap()
+    }
+
+    #[test]
+    fn test_merge_import_with_dry_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = source_archive(dir.path());
+
+        let target = DatabaseManager::new(dir.path().join("target").join("games.db")).unwrap();
+        let profile_dir = dir.path().join("target_profile");
+        target.with_connection(|conn| add_library_game(conn, "440", "Team Fortress 2", None)).unwrap();
+        target.with_connection(|conn| add_library_game(conn, "570", "Dota 2", None)).unwrap();
+
+        let mut archive = UserDataOperations::read_archive(&archive_path).unwrap();
+        let mut import = |dry_run| {
+            target.with_connection(|conn| {
+                UserDataOperations::import(conn, &mut archive, ImportMode::Merge, true, &profile_dir, dry_run)
+            })
+        };
+
+        let preview = import(true).unwrap();
+        assert_eq!(change(&preview, "user_library").added, 1);
+        assert_eq!(change(&preview, "user_library").skipped, 1);
+        assert_eq!(preview.profile, ProfileChange::Replaced);
+        assert_eq!(count(&target, "user_library"), 2, "dry run must not change anything");
+
+        import(false).unwrap();
+        assert_eq!(count(&target, "user_library"), 3);
+        assert_eq!(count(&target, "download_history"), 1);
+        assert_eq!(count(&target, "launch_profiles"), 1);
+
+        let (name, banner): (String, String) = target
+            .with_read_connection(|conn| {
+                Ok(conn.query_row("SELECT name, banner_path FROM user_profile WHERE id = 1", [], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?)
+            })
+            .unwrap();
+        assert_eq!(name, "Player");
+        assert_eq!(PathBuf::from(banner), profile_dir.join("banner.jpg"));
+        assert!(profile_dir.join("banner.jpg").is_file());
+
+        // Importing again changes nothing
+        let again = import(true).unwrap();
+        assert!(again.tables.iter().all(|c| c.added == 0));
+        assert_eq!(again.profile, ProfileChange::Kept);
+    }
+
+    #[test]
+    fn test_replace_import_removes_local_only_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = source_archive(dir.path());
+
+        let target = DatabaseManager::new(dir.path().join("target").join("games.db")).unwrap();
+        target.with_connection(|conn| add_library_game(conn, "440", "Team Fortress 2", None)).unwrap();
+
+        let mut archive = UserDataOperations::read_archive(&archive_path).unwrap();
+        let summary = target
+            .with_connection(|conn| {
+                UserDataOperations::import(conn, &mut archive, ImportMode::Replace, false, &dir.path().join("p"), false)
+            })
+            .unwrap();
+
+        assert_eq!(change(&summary, "user_library").removed, 1);
+        assert_eq!(count(&target, "user_library"), 2);
+        // The cache snapshot was skipped, but library games still got their names
+        let name: String = target
+            .with_read_connection(|conn| Ok(conn.query_row("SELECT name FROM games WHERE app_id = '730'", [], |row| row.get(0))?))
+            .unwrap();
+        assert_eq!(name, "Counter-Strike 2");
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("future.zip");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        zip.start_file(MANIFEST_FILE, FileOptions::default()).unwrap();
+        zip.write_all(
+            serde_json::json!({
+                "format_version": EXPORT_FORMAT_VERSION,
+                "app_version": "99.0.0",
+                "schema_version": 999,
+                "created_at": 0,
+                "tables": {},
+                "profile_images": [],
+                "includes_cache_snapshot": false
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+        zip.finish().unwrap();
+
+        let err = UserDataOperations::read_archive(&path).err().unwrap();
+        assert!(err.to_string().contains("update Zenith"));
+    }
+}
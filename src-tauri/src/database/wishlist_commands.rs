@@ -0,0 +1,126 @@
+use tauri::{command, State};
+use crate::database::{
+    wishlist_models::*, wishlist_operations::WishlistOperations, SharedDatabase,
+};
+use crate::price_watch::wake_price_watch;
+
+fn validate_app_id(app_id: &str) -> Result<String, String> {
+    let app_id = app_id.trim();
+    if app_id.is_empty() || !app_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid app id: {}", app_id));
+    }
+    Ok(app_id.to_string())
+}
+
+fn validate_target_price(target_price: Option<i64>) -> Result<(), String> {
+    match target_price {
+        Some(price) if price < 0 => Err(format!("Invalid target price: {}", price)),
+        _ => Ok(()),
+    }
+}
+
+/// ISO 4217 code, as `price_overview` reports it
+fn validate_currency(currency: Option<String>) -> Result<Option<String>, String> {
+    match currency.map(|currency| currency.trim().to_ascii_uppercase()) {
+        Some(currency) if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Err(format!("Invalid currency: {}", currency))
+        }
+        currency => Ok(currency),
+    }
+}
+
+/// Set or clear the target of a wishlisted game. Without an explicit `currency` the target is
+/// taken to be in the currency of the last checked price.
+fn apply_target_price(
+    conn: &rusqlite::Connection,
+    app_id: &str,
+    target_price: Option<i64>,
+    currency: Option<&str>,
+) -> anyhow::Result<WishlistEntry> {
+    let entry = WishlistOperations::get(conn, app_id)?
+        .ok_or_else(|| anyhow::anyhow!("App {} is not on the wishlist", app_id))?;
+    let target = match target_price {
+        Some(price) => {
+            let currency = currency.or(entry.currency.as_deref()).ok_or_else(|| {
+                anyhow::anyhow!("App {} has no checked price yet; pass the target's currency", app_id)
+            })?;
+            Some((price, currency))
+        }
+        None => None,
+    };
+    WishlistOperations::set_target_price(conn, app_id, target)?;
+    WishlistOperations::get(conn, app_id)?
+        .ok_or_else(|| anyhow::anyhow!("App {} is not on the wishlist", app_id))
+}
+
+/// Add a game to the wishlist, optionally with a price alert (in the smallest unit of
+/// `currency`). Its price is checked in the background shortly after.
+#[command]
+pub async fn add_to_wishlist(
+    app_id: String,
+    name: Option<String>,
+    target_price: Option<i64>,
+    currency: Option<String>,
+    db: State<'_, SharedDatabase>,
+) -> Result<WishlistEntry, String> {
+    let app_id = validate_app_id(&app_id)?;
+    validate_target_price(target_price)?;
+    let currency = validate_currency(currency)?;
+
+    let entry = db.with_connection_async(move |conn| {
+        WishlistOperations::add(conn, &app_id, name.as_deref(), chrono::Utc::now().timestamp())?;
+        if target_price.is_some() {
+            apply_target_price(conn, &app_id, target_price, currency.as_deref())
+        } else {
+            WishlistOperations::get(conn, &app_id)?
+                .ok_or_else(|| anyhow::anyhow!("Wishlist entry {} vanished after insert", app_id))
+        }
+    }).await.map_err(|e| format!("Failed to add to wishlist: {}", e))?;
+
+    wake_price_watch();
+    Ok(entry)
+}
+
+/// Remove a game and its price history from the wishlist
+#[command]
+pub async fn remove_from_wishlist(app_id: String, db: State<'_, SharedDatabase>) -> Result<bool, String> {
+    let app_id = validate_app_id(&app_id)?;
+    db.with_connection_async(move |conn| {
+        WishlistOperations::remove(conn, &app_id)
+    }).await.map_err(|e| format!("Failed to remove from wishlist: {}", e))
+}
+
+/// Get the wishlist with each game's latest known price
+#[command]
+pub async fn get_wishlist(db: State<'_, SharedDatabase>) -> Result<Vec<WishlistEntry>, String> {
+    db.with_read_connection_async(WishlistOperations::list)
+        .await
+        .map_err(|e| format!("Failed to get wishlist: {}", e))
+}
+
+/// Set or clear (`None`) the price a wishlisted game has to drop below to raise an alert. The
+/// target is in `currency`, or in the currency of the last checked price when omitted.
+#[command]
+pub async fn set_wishlist_price_alert(
+    app_id: String,
+    target_price: Option<i64>,
+    currency: Option<String>,
+    db: State<'_, SharedDatabase>,
+) -> Result<WishlistEntry, String> {
+    let app_id = validate_app_id(&app_id)?;
+    validate_target_price(target_price)?;
+    let currency = validate_currency(currency)?;
+
+    db.with_connection_async(move |conn| {
+        apply_target_price(conn, &app_id, target_price, currency.as_deref())
+    }).await.map_err(|e| format!("Failed to set price alert: {}", e))
+}
+
+/// Get the recorded price changes of a wishlisted game, oldest first
+#[command]
+pub async fn get_price_history(app_id: String, db: State<'_, SharedDatabase>) -> Result<Vec<PricePoint>, String> {
+    let app_id = validate_app_id(&app_id)?;
+    db.with_read_connection_async(move |conn| {
+        WishlistOperations::price_history(conn, &app_id)
+    }).await.map_err(|e| format!("Failed to get price history: {}", e))
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// Event emitted when a wishlisted game's price drops below its target
+pub const PRICE_ALERT_EVENT: &str = "wishlist-price-alert";
+
+/// How long a checked price is trusted before it is fetched again
+pub const PRICE_CHECK_INTERVAL_SECS: i64 = 6 * 60 * 60;
+
+/// A game on the wishlist. Prices are in the currency's smallest unit (e.g. cents) and are
+/// `None` until the first check, or when Steam lists no price (free or unreleased games).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WishlistEntry {
+    pub app_id: String,
+    pub name: Option<String>,
+    pub added_at: i64,
+    pub target_price: Option<i64>,
+    /// The currency `target_price` is in. Steam picks the currency by region, so prices
+    /// reported in any other currency are not compared against the target.
+    pub target_currency: Option<String>,
+    pub currency: Option<String>,
+    pub initial_price: Option<i64>,
+    pub final_price: Option<i64>,
+    pub discount_percent: Option<i64>,
+    pub last_checked_at: Option<i64>,
+}
+
+/// A recorded price change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    pub recorded_at: i64,
+    pub currency: String,
+    pub initial_price: i64,
+    pub final_price: i64,
+    pub discount_percent: i64,
+}
+
+/// The `price_overview` object of an appdetails response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceOverview {
+    pub currency: String,
+    pub initial: i64,
+    #[serde(rename = "final")]
+    pub final_price: i64,
+    pub discount_percent: i64,
+    #[serde(default)]
+    pub final_formatted: String,
+}
+
+/// Payload of `wishlist-price-alert`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceAlert {
+    pub app_id: String,
+    pub name: Option<String>,
+    pub currency: String,
+    pub final_price: i64,
+    pub final_formatted: String,
+    pub target_price: i64,
+    pub discount_percent: i64,
+}
@@ -0,0 +1,246 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use super::wishlist_models::{PriceAlert, PriceOverview, PricePoint, WishlistEntry, PRICE_CHECK_INTERVAL_SECS};
+
+const ENTRY_COLUMNS: &str = "app_id, name, added_at, target_price, target_currency, currency, initial_price,
+                             final_price, discount_percent, last_checked_at";
+
+pub struct WishlistOperations;
+
+impl WishlistOperations {
+    /// Add a game, or update the name of one already on the wishlist
+    pub fn add(conn: &Connection, app_id: &str, name: Option<&str>, now: i64) -> Result<()> {
+        conn.execute(
+            "INSERT INTO wishlist (app_id, name, added_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(app_id) DO UPDATE SET name = COALESCE(excluded.name, wishlist.name)",
+            params![app_id, name, now],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a game and its price history. Returns false if it was not on the wishlist.
+    pub fn remove(conn: &Connection, app_id: &str) -> Result<bool> {
+        Ok(conn.execute("DELETE FROM wishlist WHERE app_id = ?1", [app_id])? > 0)
+    }
+
+    pub fn get(conn: &Connection, app_id: &str) -> Result<Option<WishlistEntry>> {
+        Ok(conn
+            .query_row(
+                &format!("SELECT {} FROM wishlist WHERE app_id = ?1", ENTRY_COLUMNS),
+                [app_id],
+                Self::from_row,
+            )
+            .optional()?)
+    }
+
+    /// Every wishlisted game, most recently added first
+    pub fn list(conn: &Connection) -> Result<Vec<WishlistEntry>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM wishlist ORDER BY added_at DESC, app_id",
+            ENTRY_COLUMNS
+        ))?;
+        let entries = stmt.query_map([], Self::from_row)?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// Set the price alert to a `(price, currency)` target, or clear it. A new target can alert
+    /// again even at a price already alerted on. Returns false if the game is not on the
+    /// wishlist.
+    pub fn set_target_price(conn: &Connection, app_id: &str, target: Option<(i64, &str)>) -> Result<bool> {
+        let (target_price, target_currency) = target.unzip();
+        Ok(conn.execute(
+            "UPDATE wishlist SET target_price = ?2, target_currency = ?3, notified_price = NULL WHERE app_id = ?1",
+            params![app_id, target_price, target_currency],
+        )? > 0)
+    }
+
+    /// Games never checked or last checked more than `PRICE_CHECK_INTERVAL_SECS` ago,
+    /// longest waiting first
+    pub fn due_for_check(conn: &Connection, now: i64, limit: usize) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare(
+            "SELECT app_id FROM wishlist
+             WHERE last_checked_at IS NULL OR last_checked_at <= ?1
+             ORDER BY last_checked_at IS NOT NULL, last_checked_at, app_id
+             LIMIT ?2",
+        )?;
+        let app_ids = stmt
+            .query_map(params![now - PRICE_CHECK_INTERVAL_SECS, limit as i64], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(app_ids)
+    }
+
+    /// Store a checked price (`None` when Steam lists none), adding it to the history if it
+    /// changed. Returns an alert if the price is in the target's currency, below the target
+    /// (strictly) and lower than any price already alerted on; rising back to or above the
+    /// target re-arms the alert.
+    pub fn record_price(
+        conn: &Connection,
+        app_id: &str,
+        price: Option<&PriceOverview>,
+        now: i64,
+    ) -> Result<Option<PriceAlert>> {
+        let current = match Self::get(conn, app_id)? {
+            Some(entry) => entry,
+            // Removed while its price was being fetched
+            None => return Ok(None),
+        };
+
+        let price = match price {
+            Some(price) => price,
+            None => {
+                conn.execute(
+                    "UPDATE wishlist SET currency = NULL, initial_price = NULL, final_price = NULL,
+                         discount_percent = NULL, last_checked_at = ?2
+                     WHERE app_id = ?1",
+                    params![app_id, now],
+                )?;
+                return Ok(None);
+            }
+        };
+
+        let changed = current.currency.as_deref() != Some(price.currency.as_str())
+            || current.initial_price != Some(price.initial)
+            || current.final_price != Some(price.final_price)
+            || current.discount_percent != Some(price.discount_percent);
+        if changed {
+            conn.execute(
+                "INSERT INTO price_history
+                    (app_id, recorded_at, currency, initial_price, final_price, discount_percent)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![app_id, now, price.currency, price.initial, price.final_price, price.discount_percent],
+            )?;
+        }
+
+        let notified_price: Option<i64> =
+            conn.query_row("SELECT notified_price FROM wishlist WHERE app_id = ?1", [app_id], |row| row.get(0))?;
+        let in_target_currency = current.target_currency.as_deref() == Some(price.currency.as_str());
+        let (alert, notified_price) = match current.target_price {
+            Some(target) if in_target_currency && price.final_price < target => {
+                if notified_price.map_or(true, |notified| price.final_price < notified) {
+                    let alert = PriceAlert {
+                        app_id: app_id.to_string(),
+                        name: current.name.clone(),
+                        currency: price.currency.clone(),
+                        final_price: price.final_price,
+                        final_formatted: price.final_formatted.clone(),
+                        target_price: target,
+                        discount_percent: price.discount_percent,
+                    };
+                    (Some(alert), Some(price.final_price))
+                } else {
+                    (None, notified_price)
+                }
+            }
+            _ => (None, None),
+        };
+
+        conn.execute(
+            "UPDATE wishlist SET currency = ?2, initial_price = ?3, final_price = ?4, discount_percent = ?5,
+                 last_checked_at = ?6, notified_price = ?7
+             WHERE app_id = ?1",
+            params![
+                app_id,
+                price.currency,
+                price.initial,
+                price.final_price,
+                price.discount_percent,
+                now,
+                notified_price,
+            ],
+        )?;
+        Ok(alert)
+    }
+
+    /// Recorded price changes, oldest first
+    pub fn price_history(conn: &Connection, app_id: &str) -> Result<Vec<PricePoint>> {
+        let mut stmt = conn.prepare(
+            "SELECT recorded_at, currency, initial_price, final_price, discount_percent
+             FROM price_history WHERE app_id = ?1 ORDER BY recorded_at, id",
+        )?;
+        let history = stmt
+            .query_map([app_id], |row| {
+                Ok(PricePoint {
+                    recorded_at: row.get(0)?,
+                    currency: row.get(1)?,
+                    initial_price: row.get(2)?,
+                    final_price: row.get(3)?,
+                    discount_percent: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(history)
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<WishlistEntry> {
+        Ok(WishlistEntry {
+            app_id: row.get(0)?,
+            name: row.get(1)?,
+            added_at: row.get(2)?,
+            target_price: row.get(3)?,
+            target_currency: row.get(4)?,
+            currency: row.get(5)?,
+            initial_price: row.get(6)?,
+            final_price: row.get(7)?,
+            discount_percent: row.get(8)?,
+            last_checked_at: row.get(9)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+
+    fn price(final_price: i64, discount_percent: i64) -> PriceOverview {
+        PriceOverview {
+            currency: "USD".to_string(),
+            initial: 1999,
+            final_price,
+            discount_percent,
+            final_formatted: format!("${}.{:02}", final_price / 100, final_price % 100),
+        }
+    }
+
+    #[test]
+    fn test_price_alert_fires_once_per_drop() {
+        let (_dir, db) = test_database();
+
+        db.with_connection(|conn| {
+            WishlistOperations::add(conn, "620", Some("Portal 2"), 0)?;
+            WishlistOperations::set_target_price(conn, "620", Some((1000, "USD")))?;
+
+            assert_eq!(WishlistOperations::record_price(conn, "620", Some(&price(1999, 0)), 1)?, None);
+            let alert = WishlistOperations::record_price(conn, "620", Some(&price(999, 50)), 2)?.unwrap();
+            assert_eq!((alert.final_price, alert.target_price), (999, 1000));
+            assert_eq!(alert.final_formatted, "$9.99");
+
+            // Same price again: recorded as checked, no new history and no second alert
+            assert_eq!(WishlistOperations::record_price(conn, "620", Some(&price(999, 50)), 3)?, None);
+            assert!(WishlistOperations::record_price(conn, "620", Some(&price(499, 75)), 4)?.is_some());
+
+            // Back at the target is not a drop below it, but re-arms the alert
+            assert_eq!(WishlistOperations::record_price(conn, "620", Some(&price(1000, 50)), 5)?, None);
+            assert!(WishlistOperations::record_price(conn, "620", Some(&price(999, 50)), 6)?.is_some());
+
+            let history = WishlistOperations::price_history(conn, "620")?;
+            let prices: Vec<i64> = history.iter().map(|point| point.final_price).collect();
+            assert_eq!(prices, [1999, 999, 499, 1000, 999]);
+
+            let entry = WishlistOperations::get(conn, "620")?.unwrap();
+            assert_eq!(entry.final_price, Some(999));
+            assert_eq!(entry.last_checked_at, Some(6));
+
+            // A price in another currency is not compared with the target
+            let mut euros = price(799, 60);
+            euros.currency = "EUR".to_string();
+            assert_eq!(WishlistOperations::record_price(conn, "620", Some(&euros), 7)?, None);
+            assert_eq!(WishlistOperations::get(conn, "620")?.unwrap().currency.as_deref(), Some("EUR"));
+
+            assert!(WishlistOperations::remove(conn, "620")?);
+            assert!(WishlistOperations::price_history(conn, "620")?.is_empty());
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,908 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// How deep below the game folder to look (Unreal games keep the real exe in Game/Binaries/Win64)
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Resource sections larger than this are not read (version info sits in the first few KB anyway)
+const MAX_RESOURCE_SECTION_BYTES: u32 = 32 * 1024 * 1024;
+
+/// File names of redistributables, installers and helper tools that are never the game
+const SKIPPED_NAME_PATTERNS: &[&str] = &[
+    "unitycrashhandler",
+    "crashhandler",
+    "crashreport",
+    "crashpad",
+    "vcredist",
+    "vc_redist",
+    "dxsetup",
+    "dxwebsetup",
+    "dotnetfx",
+    "ndp4",
+    "physx",
+    "oalinst",
+    "uninstall",
+    "ue4prereqsetup",
+    "ueprereqsetup",
+    "easyanticheat_setup",
+    "easyanticheat_eos_setup",
+];
+
+/// Whole file stems of installers; `unins000`-style uninstallers and `*installer` stems are
+/// matched separately so game names merely containing these words are kept
+const SKIPPED_STEMS: &[&str] = &["setup", "install", "installer"];
+
+/// Extensions of native Linux game binaries (most ship without one)
+const ELF_EXTENSIONS: &[&str] = &["", "x86_64", "x86", "bin"];
+
+/// Section headers and dynamic sections larger than this are not read
+const MAX_ELF_TABLE_BYTES: u64 = 1024 * 1024;
+
+/// Shared libraries that mark an ELF binary as a windowed application rather than a CLI tool
+const ELF_GUI_LIBRARIES: &[&str] = &[
+    "libx11", "libxcb", "libwayland", "libsdl", "libglfw", "libgl.", "libegl", "libvulkan", "libgtk",
+];
+
+/// Folders that only contain redistributables
+const SKIPPED_FOLDER_NAMES: &[&str] = &[
+    "_commonredist",
+    "commonredist",
+    "redist",
+    "redistributables",
+    "__installer",
+    "directx",
+    "vcredist",
+    "dotnet",
+];
+
+/// Version resource values that identify redistributables and helpers
+const SKIPPED_PRODUCT_PATTERNS: &[&str] = &[
+    "visual c++",
+    "directx",
+    "redistributable",
+    "crash handler",
+    "crash reporter",
+    "uninstall",
+    "setup",
+];
+
+/// Metadata read from an executable's headers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutableMetadata {
+    pub format: String,                    // "pe" or "elf"
+    pub architecture: Option<String>,      // "x64", "x86", "arm64"
+    pub subsystem: Option<String>,         // "gui" or "console"; for ELF, whether windowing libraries are linked
+    pub product_name: Option<String>,
+    pub file_description: Option<String>,
+    pub company_name: Option<String>,
+    pub original_filename: Option<String>,
+}
+
+/// An executable candidate with its ranking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedExecutable {
+    pub name: String,
+    pub path: String,
+    pub size_mb: f64,
+    pub score: i32,
+    pub reasons: Vec<String>,
+    pub metadata: ExecutableMetadata,
+}
+
+/// Scan a game folder for executables, drop redistributables/helpers and rank the rest.
+/// `hint` is an executable name recommended elsewhere (e.g. a bypass note); it is never skipped.
+pub fn rank_executables(game_path: &str, game_name: &str, hint: Option<&str>) -> Vec<RankedExecutable> {
+    let root = Path::new(game_path);
+    let mut candidates = Vec::new();
+
+    for entry in WalkDir::new(root).max_depth(MAX_SCAN_DEPTH).into_iter().filter_map(Result::ok) {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let is_hinted = hint.is_some_and(|h| h.eq_ignore_ascii_case(&file_name));
+
+        // Only offer what the launcher can start
+        let metadata = match inspect_executable(path) {
+            Some(metadata) => metadata,
+            None => continue,
+        };
+
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        if !is_hinted && should_skip(&file_name, relative, &metadata) {
+            println!("⏭️ Skipping helper/redistributable: {}", relative.display());
+            continue;
+        }
+
+        let size_bytes = path.metadata().map(|m| m.len()).unwrap_or(0);
+        let (score, reasons) = score_executable(&file_name, relative, size_bytes, &metadata, game_name, is_hinted);
+
+        candidates.push(RankedExecutable {
+            name: file_name,
+            path: path.to_string_lossy().to_string(),
+            size_mb: size_bytes as f64 / 1_048_576.0,
+            score,
+            reasons,
+            metadata,
+        });
+    }
+
+    // Best first, alphabetical as tie-breaker
+    candidates.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    candidates
+}
+
+/// Whether the launcher can start this file: a Windows executable, or a native ELF binary
+/// outside Windows
+pub fn is_launchable(path: &Path) -> bool {
+    inspect_executable(path).is_some()
+}
+
+/// Read PE or ELF metadata; returns None for files that are not executables on this platform
+pub fn inspect_executable(path: &Path) -> Option<ExecutableMetadata> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let mut file = File::open(path).ok()?;
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic).ok()?;
+
+    if extension == "exe" && &magic[..2] == b"MZ" {
+        return Some(parse_pe(&mut file).unwrap_or_else(|| ExecutableMetadata {
+            format: "pe".to_string(),
+            ..Default::default()
+        }));
+    }
+
+    // ELF binaries are only launchable outside Windows
+    if !cfg!(target_os = "windows") && magic == *b"\x7fELF" && ELF_EXTENSIONS.contains(&extension.as_str()) {
+        return parse_elf(&mut file);
+    }
+
+    None
+}
+
+fn should_skip(file_name: &str, relative: &Path, metadata: &ExecutableMetadata) -> bool {
+    let lower_name = file_name.to_lowercase();
+    if SKIPPED_NAME_PATTERNS.iter().any(|p| lower_name.contains(p)) {
+        return true;
+    }
+    if is_installer_stem(&lower_name) {
+        return true;
+    }
+
+    let in_redist_folder = relative
+        .parent()
+        .map(|parent| {
+            parent.components().any(|c| {
+                let folder = c.as_os_str().to_string_lossy().to_lowercase();
+                SKIPPED_FOLDER_NAMES.contains(&folder.as_str())
+            })
+        })
+        .unwrap_or(false);
+    if in_redist_folder {
+        return true;
+    }
+
+    [&metadata.product_name, &metadata.file_description]
+        .iter()
+        .filter_map(|v| v.as_deref())
+        .map(|v| v.to_lowercase())
+        .any(|v| SKIPPED_PRODUCT_PATTERNS.iter().any(|p| v.contains(p)))
+}
+
+/// `setup.exe`, `unins000.exe`, `GameInstaller.exe` and the like (`lower_name` is lowercase)
+fn is_installer_stem(lower_name: &str) -> bool {
+    let stem = Path::new(lower_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let is_uninstaller = stem
+        .strip_prefix("unins")
+        .is_some_and(|digits| digits.len() == 3 && digits.chars().all(|c| c.is_ascii_digit()));
+
+    SKIPPED_STEMS.contains(&stem.as_str()) || is_uninstaller || stem.ends_with("installer")
+}
+
+fn score_executable(
+    file_name: &str,
+    relative: &Path,
+    size_bytes: u64,
+    metadata: &ExecutableMetadata,
+    game_name: &str,
+    is_hinted: bool,
+) -> (i32, Vec<String>) {
+    let mut score = 0;
+    let mut reasons = Vec::new();
+
+    if is_hinted {
+        score += 100;
+        reasons.push("Recommended by bypass note".to_string());
+    }
+
+    // Name similarity: best of file name, ProductName and FileDescription
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut best_match: Option<(f64, String)> = None;
+    for (label, value) in [
+        ("File name", Some(stem.as_str())),
+        ("ProductName", metadata.product_name.as_deref()),
+        ("FileDescription", metadata.file_description.as_deref()),
+    ] {
+        if let Some(value) = value {
+            let similarity = name_similarity(game_name, value);
+            if best_match.as_ref().map_or(true, |(best, _)| similarity > *best) {
+                best_match = Some((similarity, format!("{} '{}' matches game name ({:.0}%)", label, value, similarity * 100.0)));
+            }
+        }
+    }
+    if let Some((similarity, reason)) = best_match {
+        if similarity >= 0.5 {
+            score += (similarity * 40.0).round() as i32;
+            reasons.push(reason);
+        }
+    }
+
+    // Size: real game binaries are rarely tiny
+    let size_mb = size_bytes as f64 / 1_048_576.0;
+    if size_mb >= 50.0 {
+        score += 25;
+        reasons.push(format!("Large executable ({:.0} MB)", size_mb));
+    } else if size_mb >= 10.0 {
+        score += 15;
+        reasons.push(format!("Sizeable executable ({:.0} MB)", size_mb));
+    } else if size_mb >= 2.0 {
+        score += 8;
+    } else if size_mb < 0.5 {
+        score -= 10;
+        reasons.push("Very small executable".to_string());
+    }
+
+    match metadata.subsystem.as_deref() {
+        Some("gui") => {
+            score += 10;
+            reasons.push(if metadata.format == "elf" { "Links windowing libraries" } else { "Windows GUI application" }.to_string());
+        }
+        Some("console") => {
+            score -= 15;
+            reasons.push("Console application".to_string());
+        }
+        _ => {}
+    }
+
+    if matches!(metadata.architecture.as_deref(), Some("x64") | Some("arm64")) {
+        score += 5;
+        reasons.push("64-bit build".to_string());
+    }
+
+    let lower_name = file_name.to_lowercase();
+    if lower_name.contains("-shipping") {
+        score += 10;
+        reasons.push("Unreal Engine shipping build".to_string());
+    }
+    if lower_name.contains("launcher") {
+        score -= 5;
+        reasons.push("Looks like a launcher".to_string());
+    }
+
+    if relative.components().count() == 1 {
+        score += 10;
+        reasons.push("In game root folder".to_string());
+    }
+
+    (score, reasons)
+}
+
+/// Similarity between two names in 0.0..=1.0, ignoring case, spaces and punctuation
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(|c| c.to_lowercase())
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let similarity = strsim::normalized_levenshtein(&a, &b);
+    // "witcher3" vs "thewitcher3wildhunt" should still count as a strong match
+    if a.contains(&b) || b.contains(&a) {
+        let shorter = a.len().min(b.len()) as f64;
+        let longer = a.len().max(b.len()) as f64;
+        return similarity.max(0.6 + 0.4 * shorter / longer);
+    }
+    similarity
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Parse PE headers and the RT_VERSION resource
+fn parse_pe(file: &mut File) -> Option<ExecutableMetadata> {
+    let mut headers = vec![0u8; 4096];
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let read = file.read(&mut headers).ok()?;
+    headers.truncate(read);
+
+    let pe_offset = read_u32(&headers, 0x3C)? as usize;
+    if headers.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+
+    let coff = pe_offset + 4;
+    let machine = read_u16(&headers, coff)?;
+    let section_count = read_u16(&headers, coff + 2)? as usize;
+    let optional_header_size = read_u16(&headers, coff + 16)? as usize;
+    let optional = coff + 20;
+
+    let magic = read_u16(&headers, optional)?;
+    let subsystem = read_u16(&headers, optional + 68)?;
+    // Data directories start at 96 (PE32) or 112 (PE32+); resources are entry #2
+    let data_directories = if magic == 0x20b { optional + 112 } else { optional + 96 };
+    let resource_rva = read_u32(&headers, data_directories + 2 * 8).unwrap_or(0);
+
+    let mut metadata = ExecutableMetadata {
+        format: "pe".to_string(),
+        architecture: match machine {
+            0x8664 => Some("x64".to_string()),
+            0x014c => Some("x86".to_string()),
+            0xAA64 => Some("arm64".to_string()),
+            _ => None,
+        },
+        subsystem: match subsystem {
+            2 => Some("gui".to_string()),
+            3 => Some("console".to_string()),
+            _ => None,
+        },
+        ..Default::default()
+    };
+
+    if resource_rva == 0 {
+        return Some(metadata);
+    }
+
+    // Find the section holding the resource directory
+    let sections = optional + optional_header_size;
+    for i in 0..section_count {
+        let section = sections + i * 40;
+        let virtual_size = read_u32(&headers, section + 8)?;
+        let virtual_address = read_u32(&headers, section + 12)?;
+        let raw_size = read_u32(&headers, section + 16)?;
+        let raw_pointer = read_u32(&headers, section + 20)?;
+
+        let span = virtual_size.max(raw_size);
+        if resource_rva < virtual_address || resource_rva >= virtual_address.saturating_add(span) {
+            continue;
+        }
+        if raw_size > MAX_RESOURCE_SECTION_BYTES {
+            break;
+        }
+
+        let mut section_data = vec![0u8; raw_size as usize];
+        file.seek(SeekFrom::Start(raw_pointer as u64)).ok()?;
+        file.read_exact(&mut section_data).ok()?;
+
+        let directory = (resource_rva - virtual_address) as usize;
+        if let Some(strings) = read_version_strings(&section_data, directory, virtual_address) {
+            for (key, value) in strings {
+                let slot = match key.as_str() {
+                    "ProductName" => &mut metadata.product_name,
+                    "FileDescription" => &mut metadata.file_description,
+                    "CompanyName" => &mut metadata.company_name,
+                    "OriginalFilename" => &mut metadata.original_filename,
+                    _ => continue,
+                };
+                if slot.is_none() && !value.is_empty() {
+                    *slot = Some(value);
+                }
+            }
+        }
+        break;
+    }
+
+    Some(metadata)
+}
+
+/// Walk the resource tree (type → name → language) to RT_VERSION and parse its string table
+fn read_version_strings(section: &[u8], root: usize, section_rva: u32) -> Option<Vec<(String, String)>> {
+    const RT_VERSION: u32 = 16;
+
+    // Returns (is_directory, offset) of the matching (or first) entry of a directory
+    let find_entry = |directory: usize, id: Option<u32>| -> Option<(bool, usize)> {
+        let named = read_u16(section, root + directory + 12)? as usize;
+        let ids = read_u16(section, root + directory + 14)? as usize;
+        for i in 0..named + ids {
+            let entry = root + directory + 16 + i * 8;
+            let name = read_u32(section, entry)?;
+            let target = read_u32(section, entry + 4)?;
+            if id.map_or(true, |id| name == id) {
+                return Some((target & 0x8000_0000 != 0, (target & 0x7FFF_FFFF) as usize));
+            }
+        }
+        None
+    };
+
+    let (is_dir, names) = find_entry(0, Some(RT_VERSION))?;
+    if !is_dir {
+        return None;
+    }
+    let (is_dir, languages) = find_entry(names, None)?;
+    if !is_dir {
+        return None;
+    }
+    let (is_dir, data_entry) = find_entry(languages, None)?;
+    if is_dir {
+        return None;
+    }
+
+    let data_rva = read_u32(section, root + data_entry)?;
+    let data_size = read_u32(section, root + data_entry + 4)? as usize;
+    let start = data_rva.checked_sub(section_rva)? as usize;
+    let data = section.get(start..start + data_size)?;
+
+    Some(parse_version_info(data))
+}
+
+/// One node of a VS_VERSIONINFO tree
+struct VersionBlock {
+    key: String,
+    value_start: usize,
+    value_len: usize,
+    children_start: usize,
+    end: usize,
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn read_utf16_z(data: &[u8], start: usize, max_end: usize) -> (String, usize) {
+    let mut units = Vec::new();
+    let mut pos = start;
+    while pos + 1 < max_end {
+        let unit = u16::from_le_bytes([data[pos], data[pos + 1]]);
+        pos += 2;
+        if unit == 0 {
+            break;
+        }
+        units.push(unit);
+    }
+    (String::from_utf16_lossy(&units), pos)
+}
+
+fn read_version_block(data: &[u8], start: usize) -> Option<VersionBlock> {
+    let length = read_u16(data, start)? as usize;
+    if length < 6 || start + length > data.len() {
+        return None;
+    }
+    let end = start + length;
+    let value_length = read_u16(data, start + 2)? as usize;
+    let is_text = read_u16(data, start + 4)? == 1;
+
+    let (key, key_end) = read_utf16_z(data, start + 6, end);
+    let value_start = align4(key_end);
+    let value_len = if is_text { value_length * 2 } else { value_length };
+
+    Some(VersionBlock {
+        key,
+        value_start,
+        value_len,
+        children_start: align4(value_start + value_len).min(end),
+        end,
+    })
+}
+
+fn child_blocks(data: &[u8], parent: &VersionBlock) -> Vec<VersionBlock> {
+    let mut blocks = Vec::new();
+    let mut pos = parent.children_start;
+    while pos < parent.end {
+        match read_version_block(data, pos) {
+            Some(block) => {
+                pos = align4(block.end);
+                blocks.push(block);
+            }
+            None => break,
+        }
+    }
+    blocks
+}
+
+/// Extract key/value pairs from every StringTable of a VS_VERSIONINFO resource
+fn parse_version_info(data: &[u8]) -> Vec<(String, String)> {
+    let mut strings = Vec::new();
+    let root = match read_version_block(data, 0) {
+        Some(root) if root.key == "VS_VERSION_INFO" => root,
+        _ => return strings,
+    };
+
+    for file_info in child_blocks(data, &root) {
+        if file_info.key != "StringFileInfo" {
+            continue;
+        }
+        for table in child_blocks(data, &file_info) {
+            for entry in child_blocks(data, &table) {
+                let value_end = (entry.value_start + entry.value_len).min(entry.end);
+                let (value, _) = read_utf16_z(data, entry.value_start, value_end);
+                strings.push((entry.key, value.trim().to_string()));
+            }
+        }
+    }
+    strings
+}
+
+/// Parse the ELF header and the `DT_NEEDED` libraries of its dynamic section
+fn parse_elf(file: &mut File) -> Option<ExecutableMetadata> {
+    let mut header = [0u8; 64];
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_exact(&mut header[..52]).ok()?;
+    let _ = file.read(&mut header[52..]);
+
+    // Only little-endian executables (ET_EXEC) and position-independent ones (ET_DYN)
+    let is_64 = match header[4] {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    if header[5] != 1 {
+        return None;
+    }
+    let elf_type = read_u16(&header, 16)?;
+    if elf_type != 2 && elf_type != 3 {
+        return None;
+    }
+
+    let architecture = match read_u16(&header, 18)? {
+        0x3E => Some("x64".to_string()),
+        0x03 => Some("x86".to_string()),
+        0xB7 => Some("arm64".to_string()),
+        _ => None,
+    };
+
+    // Statically linked binaries have no dynamic section to judge by
+    let subsystem = elf_needed_libraries(file, &header, is_64).map(|libraries| {
+        let gui = libraries
+            .iter()
+            .map(|library| library.to_lowercase())
+            .any(|library| ELF_GUI_LIBRARIES.iter().any(|p| library.starts_with(p)));
+        if gui { "gui" } else { "console" }.to_string()
+    });
+
+    Some(ExecutableMetadata {
+        format: "elf".to_string(),
+        architecture,
+        subsystem,
+        ..Default::default()
+    })
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// Read `len` bytes at `offset`, refusing tables too large to be real
+fn read_elf_table(file: &mut File, offset: u64, len: u64) -> Option<Vec<u8>> {
+    if len > MAX_ELF_TABLE_BYTES {
+        return None;
+    }
+    let mut data = vec![0u8; len as usize];
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(&mut data).ok()?;
+    Some(data)
+}
+
+/// Libraries named by `DT_NEEDED` entries, found through the `SHT_DYNAMIC` section and the
+/// string table it links to
+fn elf_needed_libraries(file: &mut File, header: &[u8], is_64: bool) -> Option<Vec<String>> {
+    const SHT_DYNAMIC: u32 = 6;
+    const DT_NEEDED: u64 = 1;
+
+    let (section_offset, entry_size, count) = if is_64 {
+        (read_u64(header, 0x28)?, read_u16(header, 0x3A)? as u64, read_u16(header, 0x3C)? as u64)
+    } else {
+        (read_u32(header, 0x20)? as u64, read_u16(header, 0x2E)? as u64, read_u16(header, 0x30)? as u64)
+    };
+    if entry_size < if is_64 { 64 } else { 40 } {
+        return None;
+    }
+    let sections = read_elf_table(file, section_offset, entry_size * count)?;
+
+    // (type, offset, size, link) of section `index`
+    let section = |index: usize| -> Option<(u32, u64, u64, usize)> {
+        let start = index * entry_size as usize;
+        let kind = read_u32(&sections, start + 4)?;
+        if is_64 {
+            Some((kind, read_u64(&sections, start + 24)?, read_u64(&sections, start + 32)?, read_u32(&sections, start + 40)? as usize))
+        } else {
+            Some((kind, read_u32(&sections, start + 16)? as u64, read_u32(&sections, start + 20)? as u64, read_u32(&sections, start + 24)? as usize))
+        }
+    };
+
+    let (_, dynamic_offset, dynamic_size, strings_index) =
+        (0..count as usize).filter_map(section).find(|(kind, ..)| *kind == SHT_DYNAMIC)?;
+    let (_, strings_offset, strings_size, _) = section(strings_index)?;
+    let dynamic = read_elf_table(file, dynamic_offset, dynamic_size)?;
+    let strings = read_elf_table(file, strings_offset, strings_size)?;
+
+    let entry_len = if is_64 { 16 } else { 8 };
+    let mut libraries = Vec::new();
+    for entry in dynamic.chunks_exact(entry_len) {
+        let (tag, value) = if is_64 {
+            (read_u64(entry, 0)?, read_u64(entry, 8)? as usize)
+        } else {
+            (read_u32(entry, 0)? as u64, read_u32(entry, 4)? as usize)
+        };
+        match tag {
+            0 => break, // DT_NULL
+            DT_NEEDED => {
+                let name = strings.get(value..)?.split(|b| *b == 0).next()?;
+                libraries.push(String::from_utf8_lossy(name).to_string());
+            }
+            _ => {}
+        }
+    }
+    Some(libraries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skips_redistributables_and_ranks_game_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("_CommonRedist")).unwrap();
+
+        let exe = |size: usize| {
+            let mut bytes = vec![0u8; size];
+            bytes[..2].copy_from_slice(b"MZ");
+            bytes
+        };
+        std::fs::write(root.join("HollowKnight.exe"), exe(2_000_000)).unwrap();
+        std::fs::write(root.join("Tool.exe"), exe(200_000)).unwrap();
+        std::fs::write(root.join("UnityCrashHandler64.exe"), exe(1_500_000)).unwrap();
+        std::fs::write(root.join("unins000.exe"), exe(1_000_000)).unwrap();
+        std::fs::write(root.join("_CommonRedist").join("vc.exe"), exe(5_000_000)).unwrap();
+
+        let ranked = rank_executables(&root.to_string_lossy(), "Hollow Knight", None);
+        let names: Vec<&str> = ranked.iter().map(|r| r.name.as_str()).collect();
+
+        assert_eq!(names, vec!["HollowKnight.exe", "Tool.exe"]);
+        assert!(ranked[0].reasons.iter().any(|r| r.contains("matches game name")));
+    }
+
+    #[test]
+    fn test_hint_is_never_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut bytes = vec![0u8; 1024];
+        bytes[..2].copy_from_slice(b"MZ");
+        std::fs::write(dir.path().join("Installer.exe"), &bytes).unwrap();
+
+        let ranked = rank_executables(&dir.path().to_string_lossy(), "Game", Some("installer.exe"));
+        assert_eq!(ranked.len(), 1);
+        assert!(ranked[0].score >= 100);
+    }
+
+    #[test]
+    fn test_only_launchable_non_installer_files_are_ranked() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut pe = vec![0u8; 1024];
+        pe[..2].copy_from_slice(b"MZ");
+        for name in ["Installer Tycoon.exe", "GameInstaller.exe", "setup.exe", "unins001.exe"] {
+            std::fs::write(dir.path().join(name), &pe).unwrap();
+        }
+        // An ELF magic without a valid header is not an executable on any platform
+        let mut elf = vec![0u8; 1024];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        std::fs::write(dir.path().join("game.x86_64"), &elf).unwrap();
+
+        let ranked = rank_executables(&dir.path().to_string_lossy(), "Installer Tycoon", None);
+        let names: Vec<&str> = ranked.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Installer Tycoon.exe"]);
+    }
+
+    /// UTF-16LE with a terminating null
+    fn utf16z(text: &str) -> Vec<u8> {
+        text.encode_utf16().chain([0]).flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    fn pad4(bytes: &mut Vec<u8>) {
+        while bytes.len() % 4 != 0 {
+            bytes.push(0);
+        }
+    }
+
+    /// One version-resource block: wLength, wValueLength, wType, key, value, children
+    fn version_block(key: &str, value: Option<&str>, binary: &[u8], children: &[Vec<u8>]) -> Vec<u8> {
+        let (value_bytes, value_length, kind) = match value {
+            Some(text) => (utf16z(text), text.encode_utf16().count() + 1, 1u16),
+            None => (binary.to_vec(), binary.len(), if binary.is_empty() { 1 } else { 0 }),
+        };
+        let mut block = vec![0u8; 6];
+        block.extend(utf16z(key));
+        pad4(&mut block);
+        block.extend(value_bytes);
+        for child in children {
+            pad4(&mut block);
+            block.extend(child);
+        }
+        let length = block.len() as u16;
+        block[0..2].copy_from_slice(&length.to_le_bytes());
+        block[2..4].copy_from_slice(&(value_length as u16).to_le_bytes());
+        block[4..6].copy_from_slice(&kind.to_le_bytes());
+        block
+    }
+
+    /// A 64-bit GUI PE whose .rsrc section holds a VS_VERSIONINFO resource
+    fn pe_with_version_info(strings: &[(&str, &str)]) -> Vec<u8> {
+        const SECTION_RVA: u32 = 0x1000;
+        const SECTION_FILE_OFFSET: usize = 0x400;
+        const VERSION_DATA: usize = 0x58;
+
+        let entries: Vec<Vec<u8>> = strings.iter().map(|(k, v)| version_block(k, Some(v), &[], &[])).collect();
+        let table = version_block("040904B0", None, &[], &entries);
+        let string_file_info = version_block("StringFileInfo", None, &[], &[table]);
+        let version_info = version_block("VS_VERSION_INFO", None, &[0u8; 52], &[string_file_info]);
+
+        // Resource tree: type 16 (RT_VERSION) -> name 1 -> language 0x409 -> data entry
+        let mut rsrc = vec![0u8; VERSION_DATA];
+        let mut directory = |at: usize, id: u32, target: u32| {
+            rsrc[at + 14..at + 16].copy_from_slice(&1u16.to_le_bytes());
+            rsrc[at + 16..at + 20].copy_from_slice(&id.to_le_bytes());
+            rsrc[at + 20..at + 24].copy_from_slice(&target.to_le_bytes());
+        };
+        directory(0x00, 16, 0x8000_0018);
+        directory(0x18, 1, 0x8000_0030);
+        directory(0x30, 0x409, 0x48);
+        rsrc[0x48..0x4C].copy_from_slice(&(SECTION_RVA + VERSION_DATA as u32).to_le_bytes());
+        rsrc[0x4C..0x50].copy_from_slice(&(version_info.len() as u32).to_le_bytes());
+        rsrc.extend(&version_info);
+        pad4(&mut rsrc);
+
+        let mut pe = vec![0u8; SECTION_FILE_OFFSET];
+        pe[..2].copy_from_slice(b"MZ");
+        pe[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        pe[0x80..0x84].copy_from_slice(b"PE\0\0");
+        let coff = 0x84;
+        pe[coff..coff + 2].copy_from_slice(&0x8664u16.to_le_bytes());
+        pe[coff + 2..coff + 4].copy_from_slice(&1u16.to_le_bytes());
+        pe[coff + 16..coff + 18].copy_from_slice(&240u16.to_le_bytes());
+        let optional = coff + 20;
+        pe[optional..optional + 2].copy_from_slice(&0x20bu16.to_le_bytes());
+        pe[optional + 68..optional + 70].copy_from_slice(&2u16.to_le_bytes());
+        let resource_directory = optional + 112 + 2 * 8;
+        pe[resource_directory..resource_directory + 4].copy_from_slice(&SECTION_RVA.to_le_bytes());
+        pe[resource_directory + 4..resource_directory + 8].copy_from_slice(&(rsrc.len() as u32).to_le_bytes());
+        let section = optional + 240;
+        pe[section..section + 5].copy_from_slice(b".rsrc");
+        pe[section + 8..section + 12].copy_from_slice(&(rsrc.len() as u32).to_le_bytes());
+        pe[section + 12..section + 16].copy_from_slice(&SECTION_RVA.to_le_bytes());
+        pe[section + 16..section + 20].copy_from_slice(&(rsrc.len() as u32).to_le_bytes());
+        pe[section + 20..section + 24].copy_from_slice(&(SECTION_FILE_OFFSET as u32).to_le_bytes());
+        pe.extend(rsrc);
+        pe
+    }
+
+    #[test]
+    fn test_version_resource_names_the_game() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.exe");
+        let pe = pe_with_version_info(&[
+            ("CompanyName", "Team Cherry"),
+            ("FileDescription", "Hollow Knight Game"),
+            ("ProductName", "Hollow Knight"),
+            ("OriginalFilename", "hollow_knight.exe"),
+        ]);
+        std::fs::write(&path, &pe).unwrap();
+
+        let metadata = inspect_executable(&path).unwrap();
+        assert_eq!(metadata.format, "pe");
+        assert_eq!(metadata.architecture.as_deref(), Some("x64"));
+        assert_eq!(metadata.subsystem.as_deref(), Some("gui"));
+        assert_eq!(metadata.product_name.as_deref(), Some("Hollow Knight"));
+        assert_eq!(metadata.file_description.as_deref(), Some("Hollow Knight Game"));
+        assert_eq!(metadata.company_name.as_deref(), Some("Team Cherry"));
+        assert_eq!(metadata.original_filename.as_deref(), Some("hollow_knight.exe"));
+
+        // The file name says nothing; the product name carries the match
+        let ranked = rank_executables(&dir.path().to_string_lossy(), "Hollow Knight", None);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].score, 40 - 10 + 10 + 5 + 10);
+        assert_eq!(
+            ranked[0].reasons,
+            vec![
+                "ProductName 'Hollow Knight' matches game name (100%)",
+                "Very small executable",
+                "Windows GUI application",
+                "64-bit build",
+                "In game root folder",
+            ]
+        );
+    }
+
+    /// A 64-bit little-endian ELF executable whose dynamic section needs `libraries`
+    #[cfg(not(target_os = "windows"))]
+    fn elf_needing(libraries: &[&str]) -> Vec<u8> {
+        let mut strings = vec![0u8];
+        let mut dynamic = Vec::new();
+        for library in libraries {
+            dynamic.extend(1u64.to_le_bytes());
+            dynamic.extend((strings.len() as u64).to_le_bytes());
+            strings.extend(library.as_bytes());
+            strings.push(0);
+        }
+        dynamic.extend([0u8; 16]);
+
+        let strings_offset = 64u64;
+        let dynamic_offset = strings_offset + strings.len() as u64;
+        let sections_offset = dynamic_offset + dynamic.len() as u64;
+
+        let mut elf = vec![0u8; 64];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[16..18].copy_from_slice(&3u16.to_le_bytes());
+        elf[18..20].copy_from_slice(&0x3Eu16.to_le_bytes());
+        elf[0x28..0x30].copy_from_slice(&sections_offset.to_le_bytes());
+        elf[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes());
+        elf[0x3C..0x3E].copy_from_slice(&3u16.to_le_bytes());
+        elf.extend(&strings);
+        elf.extend(&dynamic);
+
+        // Section headers: null, .dynstr (SHT_STRTAB), .dynamic (SHT_DYNAMIC linked to 1)
+        let mut section = |kind: u32, offset: u64, size: u64, link: u32| {
+            let mut header = vec![0u8; 64];
+            header[4..8].copy_from_slice(&kind.to_le_bytes());
+            header[24..32].copy_from_slice(&offset.to_le_bytes());
+            header[32..40].copy_from_slice(&size.to_le_bytes());
+            header[40..44].copy_from_slice(&link.to_le_bytes());
+            elf.extend(header);
+        };
+        section(0, 0, 0, 0);
+        section(3, strings_offset, strings.len() as u64, 0);
+        section(6, dynamic_offset, dynamic.len() as u64, 1);
+        elf
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_native_elf_binaries_are_ranked_by_linked_libraries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("game.x86_64"), elf_needing(&["libSDL2-2.0.so.0", "libc.so.6"])).unwrap();
+        std::fs::write(dir.path().join("server"), elf_needing(&["libc.so.6"])).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"\x7fELF").unwrap();
+
+        let game = inspect_executable(&dir.path().join("game.x86_64")).unwrap();
+        assert_eq!(game.format, "elf");
+        assert_eq!(game.architecture.as_deref(), Some("x64"));
+        assert_eq!(game.subsystem.as_deref(), Some("gui"));
+        let server = inspect_executable(&dir.path().join("server")).unwrap();
+        assert_eq!(server.subsystem.as_deref(), Some("console"));
+
+        let ranked = rank_executables(&dir.path().to_string_lossy(), "Some Game", None);
+        let names: Vec<&str> = ranked.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["game.x86_64", "server"]);
+        assert!(ranked[0].reasons.contains(&"Links windowing libraries".to_string()));
+        assert!(is_launchable(&dir.path().join("server")));
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::command;
+
+use crate::database::launch_profile_commands::{record_library_access, resolve_launch_profile};
+use crate::database::launch_profile_models::LaunchProfile;
+use crate::process_monitor::{track_game_process, OnGameExit};
+
+/// Launch a game using one of its launch profiles (or its default profile)
+#[command]
+pub async fn launch_game(
+    app_id: String,
+    profile_id: Option<i64>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let profile = resolve_launch_profile(app_id.clone(), profile_id).await?;
+    println!("🚀 Launching app {} with profile '{}'", app_id, profile.name);
+
+    if !Path::new(&profile.executable_path).exists() {
+        let error_msg = format!("Game executable not found: {}", profile.executable_path);
+        println!("❌ {}", error_msg);
+        return Err(error_msg);
+    }
+
+    let working_dir = profile_working_dir(&profile);
+
+    // Pre-launch hook must succeed before the game is started
+    if let Some(hook) = profile.pre_launch_hook.as_deref().filter(|h| !h.trim().is_empty()) {
+        println!("🪝 Running pre-launch hook: {}", hook);
+        let command = hook_command(hook, &working_dir, &profile.env_overrides, &app_id);
+        let status = tokio::process::Command::from(command)
+            .status()
+            .await
+            .map_err(|e| format!("Failed to run pre-launch hook: {}", e))?;
+        if !status.success() {
+            let error_msg = format!("Pre-launch hook failed with {}", status);
+            println!("❌ {}", error_msg);
+            return Err(error_msg);
+        }
+    }
+
+    let child = Command::new(&profile.executable_path)
+        .args(&profile.arguments)
+        .envs(&profile.env_overrides)
+        .current_dir(&working_dir)
+        .spawn()
+        .map_err(|e| {
+            let error_msg = format!("Failed to launch game: {}", e);
+            println!("❌ {}", error_msg);
+            error_msg
+        })?;
+
+    println!("✅ Game process started (PID {})", child.id());
+
+    if let Err(e) = record_library_access(app_id.clone()).await {
+        println!("⚠️ {}", e);
+    }
+
+    // Post-exit hook runs once the whole process tree has exited
+    let on_exit = profile
+        .post_exit_hook
+        .clone()
+        .filter(|h| !h.trim().is_empty())
+        .map(|hook| {
+            let env_overrides = profile.env_overrides.clone();
+            let app_id = app_id.clone();
+            let working_dir = working_dir.clone();
+            Box::new(move |exit_code: Option<i32>| {
+                println!("🪝 Running post-exit hook: {}", hook);
+                let mut command = hook_command(&hook, &working_dir, &env_overrides, &app_id);
+                if let Some(code) = exit_code {
+                    command.env("ZENITH_EXIT_CODE", code.to_string());
+                }
+                match command.status() {
+                    Ok(status) if !status.success() => {
+                        println!("⚠️ Post-exit hook failed with {}", status)
+                    }
+                    Err(e) => println!("⚠️ Failed to run post-exit hook: {}", e),
+                    _ => {}
+                }
+            }) as OnGameExit
+        });
+
+    track_game_process(
+        app,
+        child,
+        Some(app_id),
+        profile.executable_path.clone(),
+        on_exit,
+    )
+    .await?;
+
+    Ok(format!("{} launched with profile '{}'", profile.executable_path, profile.name))
+}
+
+/// Working directory for a profile: explicit override or the executable's folder
+fn profile_working_dir(profile: &LaunchProfile) -> PathBuf {
+    match profile.working_dir.as_deref().filter(|d| !d.trim().is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(&profile.executable_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(".")),
+    }
+}
+
+/// Build a shell command for a hook command line
+fn hook_command(
+    hook: &str,
+    working_dir: &Path,
+    env_overrides: &HashMap<String, String>,
+    app_id: &str,
+) -> Command {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", hook]);
+        command
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.args(["-c", hook]);
+        command
+    };
+
+    command
+        .current_dir(working_dir)
+        .envs(env_overrides)
+        .env("ZENITH_APP_ID", app_id);
+    command
+}
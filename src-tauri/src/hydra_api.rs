@@ -1,8 +1,26 @@
 use anyhow::Result;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Serialize, Deserialize)]
+use std::fmt;
+use std::sync::Arc;
+use crate::connectivity::{ConnectivityMonitor, OfflineError};
+use crate::database::cache_metrics::{self, CACHE_METRICS};
+use crate::database::cache_service::Freshness;
+use crate::database::catalogue_cache_models::{CachedCatalogueResponse, CatalogueCacheKind};
+use crate::database::catalogue_cache_operations::CatalogueCacheOperations;
+use crate::database::SharedDatabase;
+
+/// Largest page the catalogue can be asked for
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// The API rejects a smaller `take`, so smaller pages are fetched at this size and trimmed
+const MIN_API_TAKE: u32 = 5;
+
+/// Most results a sorted listing will fetch; the API cannot sort, so every match is fetched
+/// (in `MAX_PAGE_SIZE` chunks, each cached) and sorted here
+pub const MAX_SORTED_RESULTS: u32 = 2000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SearchFilters {
     pub title: String,
     pub genres: Vec<String>,
@@ -12,10 +30,105 @@ pub struct SearchFilters {
     pub download_source_fingerprints: Vec<String>,
 }
 
+/// Why a catalogue request was rejected or failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogueError {
+    /// Pages start at 1
+    InvalidPage(u32),
+    /// Page sizes run from 1 to `MAX_PAGE_SIZE`
+    InvalidPageSize(u32),
+    /// A tag filter that is not a numeric Steam tag id
+    InvalidTag(String),
+    /// The API answered with an error status
+    Status(StatusCode),
+    Request(String),
+    Parse(String),
+    /// Not attempted because the app is offline
+    Offline(OfflineError),
+    /// A non-relevance sort over more than `MAX_SORTED_RESULTS` matches
+    TooManyToSort(u32),
+}
+
+impl fmt::Display for CatalogueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogueError::InvalidPage(page) => write!(f, "Invalid page {}: pages start at 1", page),
+            CatalogueError::InvalidPageSize(size) => {
+                write!(f, "Invalid page size {}: must be between 1 and {}", size, MAX_PAGE_SIZE)
+            }
+            CatalogueError::InvalidTag(tag) => write!(f, "Invalid tag filter '{}': expected a numeric tag id", tag),
+            CatalogueError::Status(status) => write!(f, "Hydra API returned error status: {}", status),
+            CatalogueError::Request(e) => write!(f, "Hydra API request failed: {}", e),
+            CatalogueError::Parse(e) => write!(f, "Failed to parse Hydra API response: {}", e),
+            CatalogueError::Offline(e) => write!(f, "{}", e),
+            CatalogueError::TooManyToSort(count) => write!(
+                f,
+                "Cannot sort {} results: narrow the search to at most {} games or sort by relevance",
+                count, MAX_SORTED_RESULTS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CatalogueError {}
+
+impl From<OfflineError> for CatalogueError {
+    fn from(e: OfflineError) -> Self {
+        CatalogueError::Offline(e)
+    }
+}
+
+impl From<reqwest::Error> for CatalogueError {
+    fn from(e: reqwest::Error) -> Self {
+        CatalogueError::Request(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CatalogueError {
+    fn from(e: serde_json::Error) -> Self {
+        CatalogueError::Parse(e.to_string())
+    }
+}
+
+/// Order of catalogue results. The search API takes no sort parameter, so every order but
+/// relevance sorts the full set of matches before it is paged; ties go by `objectId`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogueSort {
+    /// The API's own order
+    #[default]
+    Relevance,
+    /// A to Z
+    Title,
+    /// Newest first; games without a release date last
+    ReleaseDate,
+    /// Most popular first; games without a popularity last
+    Popularity,
+}
+
+impl CatalogueSort {
+    /// Sort `games` into this order; relevance leaves them as the API returned them
+    fn sort(&self, games: &mut [HydraGame]) {
+        match self {
+            CatalogueSort::Relevance => {}
+            CatalogueSort::Title => games.sort_by_cached_key(|game| (game.title.to_lowercase(), game.object_id.clone())),
+            CatalogueSort::ReleaseDate => games.sort_by(|a, b| {
+                b.release_date.cmp(&a.release_date).then_with(|| a.object_id.cmp(&b.object_id))
+            }),
+            CatalogueSort::Popularity => games.sort_by(|a, b| {
+                b.popularity.cmp(&a.popularity).then_with(|| a.object_id.cmp(&b.object_id))
+            }),
+        }
+    }
+}
+
 // Hydra API client for fetching game catalogue data
 pub struct HydraApi {
     client: Client,
     base_url: String,
+    connectivity: Arc<ConnectivityMonitor>,
+    // Pages and searches are cached here when set
+    cache: Option<SharedDatabase>,
 }
 
 // Data structures matching Hydra API responses
@@ -29,10 +142,15 @@ pub struct HydraGame {
     pub genres: Vec<String>,
     #[serde(rename = "libraryImageUrl")]
     pub library_image_url: Option<String>,
+    /// ISO date (YYYY-MM-DD), so it sorts as a string
+    #[serde(rename = "releaseDate", default)]
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub popularity: Option<u64>,
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CatalogueResponse {
     pub edges: Vec<HydraGame>,
     pub count: u32,
@@ -64,8 +182,170 @@ impl Default for CatalogueSearchPayload {
     }
 }
 
+/// A catalogue query with its filters normalized, so equivalent requests share a cache entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CatalogueQuery {
+    pub title: String,
+    pub genres: Vec<String>,
+    pub tags: Vec<u32>,
+    pub developers: Vec<String>,
+    pub publishers: Vec<String>,
+    pub download_source_fingerprints: Vec<String>,
+    /// Applied after the fetch, so every order shares the cached responses
+    #[serde(skip)]
+    pub sort: CatalogueSort,
+    pub take: u32,
+    pub skip: u32,
+}
+
+impl CatalogueQuery {
+    pub fn new(filters: &SearchFilters, take: u32, skip: u32) -> Result<Self, CatalogueError> {
+        let mut tags = normalize_list(&filters.tags)
+            .into_iter()
+            .map(|tag| tag.parse::<u32>().map_err(|_| CatalogueError::InvalidTag(tag)))
+            .collect::<Result<Vec<_>, _>>()?;
+        tags.sort_unstable();
+        tags.dedup();
+
+        Ok(Self {
+            title: filters.title.split_whitespace().collect::<Vec<_>>().join(" "),
+            genres: normalize_list(&filters.genres),
+            tags,
+            developers: normalize_list(&filters.developers),
+            publishers: normalize_list(&filters.publishers),
+            download_source_fingerprints: normalize_list(&filters.download_source_fingerprints),
+            sort: CatalogueSort::default(),
+            take,
+            skip,
+        })
+    }
+
+    pub fn with_sort(mut self, sort: CatalogueSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Canonical JSON of the normalized query
+    pub fn cache_key(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn kind(&self) -> CatalogueCacheKind {
+        let unfiltered = self.title.is_empty()
+            && self.genres.is_empty()
+            && self.tags.is_empty()
+            && self.developers.is_empty()
+            && self.publishers.is_empty()
+            && self.download_source_fingerprints.is_empty();
+        if unfiltered {
+            CatalogueCacheKind::Page
+        } else {
+            CatalogueCacheKind::Search
+        }
+    }
+
+    fn payload(&self) -> serde_json::Value {
+        serde_json::json!({
+            "title": self.title,
+            "take": self.take,
+            "skip": self.skip,
+            "downloadSourceFingerprints": self.download_source_fingerprints,
+            "publishers": self.publishers,
+            "genres": self.genres,
+            "developers": self.developers,
+            "tags": self.tags,
+        })
+    }
+}
+
+/// Trimmed, sorted and deduplicated, without empty entries
+fn normalize_list(values: &[String]) -> Vec<String> {
+    let mut values: Vec<String> = values
+        .iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// A validated page of a catalogue listing or search
+#[derive(Debug, Clone, PartialEq)]
+pub struct CataloguePageRequest {
+    pub query: CatalogueQuery,
+    /// 1-based
+    pub page: u32,
+    pub items_per_page: u32,
+}
+
+impl CataloguePageRequest {
+    pub fn new(
+        filters: &SearchFilters,
+        page: u32,
+        items_per_page: u32,
+        sort: CatalogueSort,
+    ) -> Result<Self, CatalogueError> {
+        if page == 0 {
+            return Err(CatalogueError::InvalidPage(page));
+        }
+        if items_per_page == 0 || items_per_page > MAX_PAGE_SIZE {
+            return Err(CatalogueError::InvalidPageSize(items_per_page));
+        }
+        let skip = (page - 1).checked_mul(items_per_page).ok_or(CatalogueError::InvalidPage(page))?;
+
+        let query = CatalogueQuery::new(filters, items_per_page.max(MIN_API_TAKE), skip)?.with_sort(sort);
+        Ok(Self { query, page, items_per_page })
+    }
+
+    /// The same listing one page further on
+    pub fn next_page(&self) -> Option<Self> {
+        let page = self.page.checked_add(1)?;
+        let skip = self.query.skip.checked_add(self.items_per_page)?;
+        Some(Self {
+            query: CatalogueQuery { skip, ..self.query.clone() },
+            page,
+            items_per_page: self.items_per_page,
+        })
+    }
+
+    /// The page of a response starting at this page's first game, trimmed to the requested size
+    fn paginate(&self, result: CatalogueResult) -> PaginatedCatalogueResponse {
+        let count = result.response.count;
+        let total_pages = count.div_ceil(self.items_per_page);
+
+        let mut edges = result.response.edges;
+        edges.truncate(self.items_per_page as usize);
+
+        PaginatedCatalogueResponse {
+            games: edges.into_iter().map(crate::catalogue_commands::CatalogueGame::from).collect(),
+            pagination: PaginationInfo {
+                current_page: self.page,
+                total_pages,
+                total_items: count,
+                items_per_page: self.items_per_page,
+                has_next_page: self.query.skip.saturating_add(self.items_per_page) < count,
+                has_prev_page: self.page > 1,
+            },
+            freshness: result.freshness,
+            offline: result.offline,
+        }
+    }
+}
+
+/// A catalogue response and where it came from
+#[derive(Debug)]
+pub struct CatalogueResult {
+    pub response: CatalogueResponse,
+    pub freshness: Freshness,
+    /// Answered from the cache only because the app is offline
+    pub offline: bool,
+}
+
 impl HydraApi {
-    pub fn new() -> Self {
+    /// Client for the Hydra API at `base_url`, reporting reachability to `connectivity`.
+    /// The app's instance comes from `catalogue_backend::catalogue_provider`.
+    pub fn with_base_url(base_url: &str, connectivity: Arc<ConnectivityMonitor>) -> Self {
         let client = Client::builder()
             .user_agent("Zenith-Launcher/1.0")
             .timeout(std::time::Duration::from_secs(30))
@@ -74,211 +354,254 @@ impl HydraApi {
 
         Self {
             client,
-            base_url: "https://hydra-api-us-east-1.losbroxas.org".to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            connectivity,
+            cache: None,
         }
     }
 
-    /// Fetch catalogue list (all games or filtered)
-    /// Based on your hydra-test/endpoints/catalogue-list.js implementation
-    pub async fn get_catalogue_list(
-        &self,
-        limit: Option<u32>,
-        offset: Option<u32>,
-    ) -> Result<CatalogueResponse> {
-        let mut payload = CatalogueSearchPayload::default();
-        
-        // Ensure minimum 5 items as per API requirement
-        payload.take = std::cmp::max(limit.unwrap_or(20), 5);
-        payload.skip = offset.unwrap_or(0);
+    /// Cache pages and search results in `db`
+    pub fn with_cache(mut self, db: SharedDatabase) -> Self {
+        self.cache = Some(db);
+        self
+    }
 
-        println!("📚 Fetching catalogue list (limit: {}, offset: {})", payload.take, payload.skip);
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// POST to `/catalogue/search`, failing fast while offline. Error statuses other than 304
+    /// are returned as `CatalogueError::Status`.
+    async fn post_search<T: Serialize + ?Sized>(
+        &self,
+        payload: &T,
+        etag: Option<&str>,
+    ) -> Result<reqwest::Response, CatalogueError> {
+        self.connectivity.ensure_online("the Hydra catalogue")?;
 
-        let response = self
+        let mut request = self
             .client
-            .post(&format!("{}/catalogue/search", self.base_url))
-            .json(&payload)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Hydra API returned error status: {}",
-                response.status()
-            ));
+            .post(format!("{}/catalogue/search", self.base_url))
+            .json(payload);
+        if let Some(etag) = etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
         }
 
-        let catalogue_response: CatalogueResponse = response.json().await?;
-
-        println!(
-            "✅ Catalogue games: {} games found (total: {})",
-            catalogue_response.edges.len(),
-            catalogue_response.count
-        );
+        let response = match request.send().await {
+            Ok(response) => {
+                self.connectivity.record_success();
+                response
+            }
+            Err(e) => {
+                self.connectivity.record_failure(&e);
+                return Err(e.into());
+            }
+        };
 
-        // Debug: Log first game's raw data from API
-        if let Some(first_game) = catalogue_response.edges.first() {
-            println!(
-                "🔍 First game from API: title='{}', objectId='{}', libraryImageUrl='{:?}'",
-                first_game.title, first_game.object_id, first_game.library_image_url
-            );
+        let status = response.status();
+        if status.is_success() || status == StatusCode::NOT_MODIFIED {
+            Ok(response)
+        } else {
+            Err(CatalogueError::Status(status))
         }
-
-        Ok(catalogue_response)
     }
 
-    /// Get paginated catalogue (like Hydra's pagination system)
-    pub async fn get_paginated_catalogue(
-        &self,
-        page: u32,
-        items_per_page: u32,
-    ) -> Result<PaginatedCatalogueResponse> {
-        let page = std::cmp::max(page, 1); // Ensure page is at least 1
-        let items_per_page = std::cmp::max(items_per_page, 5); // API minimum
-        let offset = (page - 1) * items_per_page;
-
-        println!(
-            "📄 Getting catalogue page {} ({} items per page)...",
-            page, items_per_page
-        );
-
-        let results = self.get_catalogue_list(Some(items_per_page), Some(offset)).await?;
+    /// Answer a query from the cache while it is fresh, otherwise revalidate it (sending the
+    /// cached ETag). An expired entry is still served offline or when the request fails.
+    pub async fn search_cached(&self, query: &CatalogueQuery) -> Result<CatalogueResult, CatalogueError> {
+        let cache_key = query.cache_key();
+        let cached = self.cached_response(&cache_key).await;
+
+        if let Some((entry, response)) = &cached {
+            if !entry.is_expired() {
+                CACHE_METRICS.record_hit(cache_metrics::CATALOGUE, false);
+                self.touch_cached(&cache_key).await;
+                return Ok(CatalogueResult { response: response.clone(), freshness: Freshness::Fresh, offline: false });
+            }
+        }
 
-        let total_pages = if results.count > 0 {
-            (results.count as f64 / items_per_page as f64).ceil() as u32
-        } else {
-            1
+        let fetched = match self.connectivity.ensure_online("the Hydra catalogue") {
+            Ok(()) => {
+                let etag = cached.as_ref().and_then(|(entry, _)| entry.etag.clone());
+                self.fetch_and_cache(query, &cache_key, etag.as_deref(), cached.is_some()).await
+            }
+            Err(offline) => Err(offline.into()),
         };
 
-        println!(
-            "📊 Page {} of {} (Total games: {})",
-            page, total_pages, results.count
-        );
-
-        Ok(PaginatedCatalogueResponse {
-            games: results.edges.into_iter()
-                .map(|hydra_game| crate::catalogue_commands::CatalogueGame::from(hydra_game))
-                .collect(),
-            pagination: PaginationInfo {
-                current_page: page,
-                total_pages,
-                total_items: results.count,
-                items_per_page,
-                has_next_page: page < total_pages,
-                has_prev_page: page > 1,
-            },
-        })
+        match (fetched, cached) {
+            (Ok(Some(response)), _) => {
+                CACHE_METRICS.record_miss(cache_metrics::CATALOGUE);
+                Ok(CatalogueResult { response, freshness: Freshness::Fresh, offline: false })
+            }
+            // 304: the cached copy is current again
+            (Ok(None), Some((_, response))) => {
+                CACHE_METRICS.record_hit(cache_metrics::CATALOGUE, false);
+                Ok(CatalogueResult { response, freshness: Freshness::Fresh, offline: false })
+            }
+            (Ok(None), None) => Err(CatalogueError::Status(StatusCode::NOT_MODIFIED)),
+            (Err(e), Some((_, response))) => {
+                if !matches!(e, CatalogueError::Offline(_)) {
+                    eprintln!("⚠️  Serving cached catalogue after request failed: {}", e);
+                }
+                CACHE_METRICS.record_hit(cache_metrics::CATALOGUE, true);
+                self.touch_cached(&cache_key).await;
+                Ok(CatalogueResult {
+                    response,
+                    freshness: Freshness::Stale,
+                    offline: self.connectivity.is_offline(),
+                })
+            }
+            (Err(e), None) => {
+                CACHE_METRICS.record_miss(cache_metrics::CATALOGUE);
+                Err(e)
+            }
+        }
     }
 
-    /// Search games with query and filters
-    pub async fn search_games(
+    /// POST the query and cache the response. Returns None if the server answered 304.
+    async fn fetch_and_cache(
         &self,
-        query: String,
-        filters: Option<SearchFilters>,
-        limit: Option<u32>,
-        offset: Option<u32>,
-    ) -> Result<CatalogueResponse> {
-        let mut payload = CatalogueSearchPayload::default();
-        
-        payload.title = query;
-        payload.take = std::cmp::max(limit.unwrap_or(20), 5);
-        payload.skip = offset.unwrap_or(0);
-
-        if let Some(filters) = filters {
-            payload.genres = filters.genres;
-            payload.developers = filters.developers;
-            payload.publishers = filters.publishers;
-            payload.download_source_fingerprints = filters.download_source_fingerprints;
+        query: &CatalogueQuery,
+        cache_key: &str,
+        etag: Option<&str>,
+        have_cached: bool,
+    ) -> Result<Option<CatalogueResponse>, CatalogueError> {
+        let response = self.post_search(&query.payload(), etag).await?;
+        let now = chrono::Utc::now().timestamp();
+        let kind = query.kind();
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if !have_cached {
+                return Err(CatalogueError::Status(StatusCode::NOT_MODIFIED));
+            }
+            let key = cache_key.to_string();
+            self.with_cache_db(move |conn| CatalogueCacheOperations::revalidated(conn, &key, now, now + kind.ttl()))
+                .await;
+            return Ok(None);
         }
 
-        println!("🔍 Searching games: \"{}\"", payload.title);
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+        let catalogue: CatalogueResponse = serde_json::from_str(&body)?;
+
+        let entry = CachedCatalogueResponse {
+            cache_key: cache_key.to_string(),
+            kind,
+            response_json: body,
+            etag,
+            cached_at: now,
+            expires_at: now + kind.ttl(),
+            last_accessed: now,
+        };
+        self.with_cache_db(move |conn| {
+            CatalogueCacheOperations::upsert(conn, &entry)?;
+            CatalogueCacheOperations::prune(conn, entry.kind, entry.kind.max_cached())?;
+            Ok(())
+        })
+        .await;
 
-        let response = self
-            .client
-            .post(&format!("{}/catalogue/search", self.base_url))
-            .json(&payload)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Hydra API search failed with status: {}",
-                response.status()
-            ));
-        }
+        Ok(Some(catalogue))
+    }
 
-        let search_response: CatalogueResponse = response.json().await?;
+    /// The cached entry for a key and its parsed response; unreadable entries count as missing
+    async fn cached_response(&self, cache_key: &str) -> Option<(CachedCatalogueResponse, CatalogueResponse)> {
+        let key = cache_key.to_string();
+        let entry = self.with_cache_db(move |conn| CatalogueCacheOperations::get(conn, &key)).await??;
+        match serde_json::from_str(&entry.response_json) {
+            Ok(response) => Some((entry, response)),
+            Err(e) => {
+                eprintln!("⚠️  Ignoring unreadable cached catalogue response: {}", e);
+                None
+            }
+        }
+    }
 
-        println!(
-            "✅ Search results: {} games found (total: {})",
-            search_response.edges.len(),
-            search_response.count
-        );
+    async fn touch_cached(&self, cache_key: &str) {
+        let key = cache_key.to_string();
+        let now = chrono::Utc::now().timestamp();
+        self.with_cache_db(move |conn| CatalogueCacheOperations::touch(conn, &key, now)).await;
+    }
 
-        Ok(search_response)
+    /// Run `f` against the cache database, if there is one. Cache errors are logged, not returned.
+    async fn with_cache_db<T, F>(&self, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&rusqlite::Connection) -> Result<T> + Send + 'static,
+    {
+        let db = self.cache.as_ref()?;
+        match db.with_connection_async(f).await {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("⚠️  Catalogue cache error: {}", e);
+                None
+            }
+        }
     }
 
-    /// Search games with pagination (like get_paginated_catalogue but with search query)
-    pub async fn search_games_paginated(
+    /// Fetch catalogue list (all games), bypassing the cache. Returns at most `limit` games
+    /// (default 20), even below the API's minimum page size.
+    pub async fn get_catalogue_list(
         &self,
-        query: String,
-        page: u32,
-        items_per_page: u32,
-    ) -> Result<PaginatedCatalogueResponse> {
-        let page = std::cmp::max(page, 1); // Ensure page is at least 1
-        let items_per_page = std::cmp::max(items_per_page, 5); // API minimum
-        let offset = (page - 1) * items_per_page;
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<CatalogueResponse, CatalogueError> {
+        let limit = limit.unwrap_or(20);
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(CatalogueError::InvalidPageSize(limit));
+        }
 
-        let mut payload = CatalogueSearchPayload::default();
-        payload.title = query.clone();
-        payload.take = items_per_page;
-        payload.skip = offset;
+        let payload = CatalogueSearchPayload {
+            take: limit.max(MIN_API_TAKE),
+            skip: offset.unwrap_or(0),
+            ..Default::default()
+        };
 
-        println!("🔍 Searching games with query: '{}' (page: {})", query, page);
+        let response = self.post_search(&payload, None).await?;
+        let mut catalogue_response: CatalogueResponse = serde_json::from_str(&response.text().await?)?;
+        catalogue_response.edges.truncate(limit as usize);
+        Ok(catalogue_response)
+    }
 
-        let response = self
-            .client
-            .post(&format!("{}/catalogue/search", self.base_url))
-            .json(&payload)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Hydra API search failed with status: {}",
-                response.status()
-            ));
+    /// One page of a listing or search (like Hydra's pagination), cached
+    pub async fn search_page(&self, request: &CataloguePageRequest) -> Result<PaginatedCatalogueResponse, CatalogueError> {
+        if request.query.sort == CatalogueSort::Relevance {
+            let result = self.search_cached(&request.query).await?;
+            return Ok(request.paginate(result));
         }
 
-        let results: CatalogueResponse = response.json().await?;
+        let mut result = self.search_all(&request.query).await?;
+        request.query.sort.sort(&mut result.response.edges);
+        result.response.edges.drain(..(request.query.skip as usize).min(result.response.edges.len()));
+        Ok(request.paginate(result))
+    }
 
-        // Debug: Log first search result from API
-        if let Some(first_game) = results.edges.first() {
-            println!(
-                "🔍 First search result from API: title='{}', objectId='{}', libraryImageUrl='{:?}'",
-                first_game.title, first_game.object_id, first_game.library_image_url
-            );
+    /// Every match for the query's filters, in the API's order, fetched in cached
+    /// `MAX_PAGE_SIZE` chunks. Stale or offline if any chunk was.
+    async fn search_all(&self, query: &CatalogueQuery) -> Result<CatalogueResult, CatalogueError> {
+        let mut chunk = CatalogueQuery { sort: CatalogueSort::Relevance, take: MAX_PAGE_SIZE, skip: 0, ..query.clone() };
+        let mut all = self.search_cached(&chunk).await?;
+        let count = all.response.count;
+        if count > MAX_SORTED_RESULTS {
+            return Err(CatalogueError::TooManyToSort(count));
         }
 
-        let total_pages = ((results.count as f64) / (items_per_page as f64)).ceil() as u32;
-
-        println!(
-            "📊 Search page {} of {} (Total games: {})",
-            page, total_pages, results.count
-        );
-
-        Ok(PaginatedCatalogueResponse {
-            games: results.edges.into_iter()
-                .map(|hydra_game| crate::catalogue_commands::CatalogueGame::from(hydra_game))
-                .collect(),
-            pagination: PaginationInfo {
-                current_page: page,
-                total_pages,
-                total_items: results.count,
-                items_per_page,
-                has_next_page: page < total_pages,
-                has_prev_page: page > 1,
-            },
-        })
+        while (all.response.edges.len() as u32) < count {
+            chunk.skip += MAX_PAGE_SIZE;
+            let next = self.search_cached(&chunk).await?;
+            if next.response.edges.is_empty() {
+                break;
+            }
+            all.response.edges.extend(next.response.edges);
+            if next.freshness == Freshness::Stale {
+                all.freshness = Freshness::Stale;
+            }
+            all.offline |= next.offline;
+        }
+        Ok(all)
     }
 }
 
@@ -287,6 +610,10 @@ impl HydraApi {
 pub struct PaginatedCatalogueResponse {
     pub games: Vec<crate::catalogue_commands::CatalogueGame>,
     pub pagination: PaginationInfo,
+    /// Stale if an expired cached page was served because the API could not be reached
+    pub freshness: Freshness,
+    /// Answered from the cache only because the app is offline
+    pub offline: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -299,54 +626,94 @@ pub struct PaginationInfo {
     pub has_prev_page: bool,
 }
 
-impl HydraApi {
-    /// Search games with filters and pagination (like Hydra)
-    pub async fn search_games_paginated_with_filters(
-        &self,
-        filters: SearchFilters,
-        page: u32,
-        items_per_page: u32,
-    ) -> Result<PaginatedCatalogueResponse> {
-        let skip = (page - 1) * items_per_page;
-
-        let payload = serde_json::json!({
-            "title": filters.title,
-            "take": items_per_page,
-            "skip": skip,
-            "downloadSourceFingerprints": filters.download_source_fingerprints,
-            "publishers": filters.publishers,
-            "genres": filters.genres,
-            "developers": filters.developers,
-            "tags": filters.tags.iter().map(|t| t.parse::<u32>().unwrap_or(0)).collect::<Vec<u32>>()
-        });
-
-        println!("🔍 Searching with filters: {:?}", payload);
-
-        let response = self
-            .client
-            .post(&format!("{}/catalogue/search", self.base_url))
-            .json(&payload)
-            .send()
-            .await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectivity::{closed_port_url, FAILURES_BEFORE_OFFLINE};
+    use crate::database::test_database;
+
+    #[tokio::test]
+    async fn test_unreachable_api_switches_to_offline() {
+        let connectivity = Arc::new(ConnectivityMonitor::with_probe_url(&closed_port_url()));
+        let api = HydraApi::with_base_url(&closed_port_url(), connectivity.clone());
+
+        // Repeated refused connections are what take the monitor offline...
+        for _ in 0..FAILURES_BEFORE_OFFLINE {
+            assert!(connectivity.is_online());
+            assert!(api.get_catalogue_list(Some(5), Some(0)).await.is_err());
+        }
+        assert!(connectivity.is_offline());
 
-        let results: CatalogueResponse = response.json().await?;
+        // ...after which requests are not attempted at all
+        let error = api.get_catalogue_list(Some(5), Some(0)).await.unwrap_err();
+        assert!(matches!(error, CatalogueError::Offline(_)));
+    }
 
-        Ok(PaginatedCatalogueResponse {
-            games: results.edges.into_iter()
-                .map(|hydra_game| crate::catalogue_commands::CatalogueGame::from(hydra_game))
-                .collect(),
-            pagination: PaginationInfo {
-                current_page: page,
-                total_pages: (results.count as f64 / items_per_page as f64).ceil() as u32,
-                total_items: results.count,
-                items_per_page,
-                has_next_page: skip + items_per_page < results.count,
-                has_prev_page: page > 1,
-            },
-        })
+    #[tokio::test]
+    async fn test_expired_search_is_served_when_unreachable() {
+        let (_dir, db) = test_database();
+        let connectivity = Arc::new(ConnectivityMonitor::with_probe_url(&closed_port_url()));
+        let api = HydraApi::with_base_url(&closed_port_url(), connectivity.clone()).with_cache(db.clone());
+
+        let filters = |genres: &[&str]| SearchFilters {
+            title: "  half   life ".to_string(),
+            genres: genres.iter().map(|g| g.to_string()).collect(),
+            ..Default::default()
+        };
+        let query = CatalogueQuery::new(&filters(&["Action", "Shooter"]), 20, 0).unwrap();
+        assert_eq!(query, CatalogueQuery::new(&filters(&["Shooter ", "Action", "Action"]), 20, 0).unwrap());
+        assert_eq!(query.kind(), CatalogueCacheKind::Search);
+
+        let entry = CachedCatalogueResponse {
+            cache_key: query.cache_key(),
+            kind: query.kind(),
+            response_json: r#"{"edges":[],"count":42}"#.to_string(),
+            etag: Some("\"v1\"".to_string()),
+            cached_at: 0,
+            expires_at: 0,
+            last_accessed: 0,
+        };
+        db.with_connection(|conn| CatalogueCacheOperations::upsert(conn, &entry)).unwrap();
+
+        // The request is refused, so the expired copy is served, and marked offline once
+        // enough refusals took the monitor offline
+        for attempt in 1..=FAILURES_BEFORE_OFFLINE {
+            let result = api.search_cached(&query).await.unwrap();
+            assert_eq!(result.response.count, 42);
+            assert_eq!(result.freshness, Freshness::Stale);
+            assert_eq!(result.offline, attempt == FAILURES_BEFORE_OFFLINE);
+        }
+
+        // Anything not cached fails with the offline error
+        let uncached = CatalogueQuery::new(&SearchFilters::default(), 20, 0).unwrap();
+        let error = api.search_cached(&uncached).await.unwrap_err();
+        assert!(matches!(error, CatalogueError::Offline(_)));
     }
-}
 
-// Lazy static instance for reuse
-use std::sync::LazyLock;
-pub static HYDRA_API: LazyLock<HydraApi> = LazyLock::new(HydraApi::new);
+    #[test]
+    fn test_page_requests_are_validated() {
+        let filters = SearchFilters::default();
+        let sort = CatalogueSort::default();
+
+        assert_eq!(CataloguePageRequest::new(&filters, 0, 20, sort), Err(CatalogueError::InvalidPage(0)));
+        assert_eq!(CataloguePageRequest::new(&filters, u32::MAX, 20, sort), Err(CatalogueError::InvalidPage(u32::MAX)));
+        assert_eq!(CataloguePageRequest::new(&filters, 1, 0, sort), Err(CatalogueError::InvalidPageSize(0)));
+        assert_eq!(
+            CataloguePageRequest::new(&filters, 1, MAX_PAGE_SIZE + 1, sort),
+            Err(CatalogueError::InvalidPageSize(MAX_PAGE_SIZE + 1))
+        );
+
+        let tagged = SearchFilters { tags: vec!["492".to_string(), "indie".to_string()], ..Default::default() };
+        assert_eq!(
+            CataloguePageRequest::new(&tagged, 1, 20, sort),
+            Err(CatalogueError::InvalidTag("indie".to_string()))
+        );
+
+        // Small pages are fetched at the API minimum; the offset still follows the page size
+        let request = CataloguePageRequest::new(&filters, 3, 2, CatalogueSort::Popularity).unwrap();
+        assert_eq!((request.query.take, request.query.skip), (MIN_API_TAKE, 4));
+        assert!(request.query.payload().get("sortBy").is_none());
+        assert_eq!(request.query.cache_key(), CatalogueQuery::new(&filters, MIN_API_TAKE, 4).unwrap().cache_key());
+        assert_eq!(request.next_page().unwrap().query.skip, 6);
+    }
+}
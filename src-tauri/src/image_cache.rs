@@ -0,0 +1,484 @@
+//! Local copies of Steam header, banner and screenshot images, served to the webview
+//! through the `zenith-image` URI scheme so the library keeps its artwork offline
+
+use anyhow::{anyhow, Result};
+use image::codecs::jpeg::JpegEncoder;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::http::Response;
+
+use crate::app_paths::app_paths;
+use crate::connectivity::CONNECTIVITY;
+use crate::database::cache_service::CacheConfig;
+use crate::database::image_cache_models::{
+    ImageCacheEntry, ImageCacheStats, ImageEvictionResult, ImageKind, IMAGE_SCHEME, THUMBNAIL_QUALITY, THUMBNAIL_WIDTH,
+};
+use crate::database::image_cache_operations::ImageCacheOperations;
+use crate::database::{shared_database, SharedDatabase};
+use crate::{GameDetail, HTTP_CLIENT};
+
+/// Image bytes ready to hand to the webview
+pub struct CachedImage {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+/// A parsed `zenith-image` request: `/<full|thumb>/<kind>/<percent-encoded remote URL>`
+#[derive(Debug, PartialEq)]
+pub struct ImageRequest {
+    pub kind: ImageKind,
+    pub thumbnail: bool,
+    pub url: String,
+}
+
+/// Content-addressed image store under `cache/images`, indexed by the `image_cache` table
+pub struct ImageCache {
+    db: SharedDatabase,
+    root: PathBuf,
+    // One lock per URL being downloaded, so concurrent requests share a single download
+    downloads: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl ImageCache {
+    pub fn new(root: PathBuf) -> Result<Self> {
+        Ok(Self::with_database(shared_database()?, root))
+    }
+
+    fn with_database(db: SharedDatabase, root: PathBuf) -> Self {
+        Self {
+            db,
+            root,
+            downloads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn file_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[..2]).join(hash)
+    }
+
+    fn thumbnail_path(&self, hash: &str) -> PathBuf {
+        self.root.join(&hash[..2]).join(format!("{}.thumb.jpg", hash))
+    }
+
+    /// Size cap from the saved cache config
+    fn max_bytes(conn: &rusqlite::Connection) -> u64 {
+        CacheConfig::load(conn).unwrap_or_default().image_cache_max_mb * 1024 * 1024
+    }
+
+    /// Cached image for `url`, downloading it first if it is missing or expired.
+    /// An expired copy is still served when offline or when the download fails.
+    pub async fn load(&self, url: &str, kind: ImageKind, thumbnail: bool) -> Result<CachedImage> {
+        let entry = match self.lookup(url).await? {
+            Some(entry) if self.is_servable(&entry) => entry,
+            _ => self.download_once(url, kind).await?,
+        };
+
+        let touched_url = url.to_string();
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = self.db.with_connection_async(move |conn| {
+            ImageCacheOperations::touch(conn, &touched_url, now)
+        }).await {
+            eprintln!("Failed to update image access time: {}", e);
+        }
+
+        self.read(&entry, thumbnail).await
+    }
+
+    async fn lookup(&self, url: &str) -> Result<Option<ImageCacheEntry>> {
+        let lookup_url = url.to_string();
+        self.db.with_read_connection_async(move |conn| ImageCacheOperations::get(conn, &lookup_url)).await
+    }
+
+    /// Fresh (or, offline, any) entry whose file is still on disk
+    fn is_servable(&self, entry: &ImageCacheEntry) -> bool {
+        (!entry.is_expired() || CONNECTIVITY.is_offline()) && self.file_path(&entry.hash).exists()
+    }
+
+    /// Download `url`, or wait for a download of it already in flight and use its result
+    async fn download_once(&self, url: &str, kind: ImageKind) -> Result<ImageCacheEntry> {
+        let lock = self.downloads.lock().unwrap_or_else(|e| e.into_inner()).entry(url.to_string()).or_default().clone();
+        let result = {
+            let _guard = lock.lock().await;
+            self.download_or_fall_back(url, kind).await
+        };
+
+        // The last request for this URL removes its lock
+        let mut downloads = self.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        if Arc::strong_count(&lock) == 2 {
+            downloads.remove(url);
+        }
+        result
+    }
+
+    /// Download `url` unless the request ahead of us just stored it. Falls back on the expired
+    /// copy if the download fails.
+    async fn download_or_fall_back(&self, url: &str, kind: ImageKind) -> Result<ImageCacheEntry> {
+        let stale = match self.lookup(url).await? {
+            Some(entry) if self.is_servable(&entry) => return Ok(entry),
+            stale => stale,
+        };
+
+        match self.download(url, kind).await {
+            Ok(entry) => Ok(entry),
+            Err(e) => match stale {
+                Some(entry) if self.file_path(&entry.hash).exists() => {
+                    #[cfg(debug_assertions)]
+                    println!("Serving expired image for {}: {}", url, e);
+                    Ok(entry)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    async fn read(&self, entry: &ImageCacheEntry, thumbnail: bool) -> Result<CachedImage> {
+        if thumbnail && entry.thumbnail_bytes > 0 {
+            if let Ok(bytes) = tokio::fs::read(self.thumbnail_path(&entry.hash)).await {
+                return Ok(CachedImage { bytes, content_type: "image/jpeg".to_string() });
+            }
+        }
+
+        let bytes = tokio::fs::read(self.file_path(&entry.hash)).await?;
+        Ok(CachedImage { bytes, content_type: entry.content_type.clone() })
+    }
+
+    async fn download(&self, url: &str, kind: ImageKind) -> Result<ImageCacheEntry> {
+        CONNECTIVITY.ensure_online("image download")?;
+        let response = HTTP_CLIENT
+            .get(url)
+            .send()
+            .await
+            .inspect_err(|e| CONNECTIVITY.record_failure(e))?;
+        CONNECTIVITY.record_success();
+        if !response.status().is_success() {
+            return Err(anyhow!("Image request failed with status {}", response.status()));
+        }
+        let bytes = response.bytes().await?.to_vec();
+        let content_type = sniff_content_type(&bytes).ok_or_else(|| anyhow!("{} is not an image", url))?;
+
+        let hash = hex::encode(Sha1::digest(&bytes));
+        let (file, thumbnail) = (self.file_path(&hash), self.thumbnail_path(&hash));
+        let size_bytes = bytes.len() as i64;
+        let thumbnail_bytes = tokio::task::spawn_blocking(move || store_files(&file, &thumbnail, &bytes))
+            .await
+            .map_err(|e| anyhow!("Image write task failed: {}", e))??;
+
+        let now = chrono::Utc::now().timestamp();
+        let entry = ImageCacheEntry {
+            url: url.to_string(),
+            hash,
+            kind,
+            content_type: content_type.to_string(),
+            size_bytes,
+            thumbnail_bytes,
+            cached_at: now,
+            expires_at: now + kind.ttl(),
+            last_accessed: now,
+        };
+
+        let to_store = entry.clone();
+        let replaced = self.db.with_connection_async(move |conn| ImageCacheOperations::upsert(conn, &to_store)).await?;
+        if let Some(hash) = replaced {
+            self.remove_files(&hash);
+        }
+
+        if let Err(e) = self.evict_to_limit().await {
+            eprintln!("⚠️  Image cache eviction failed: {}", e);
+        }
+        Ok(entry)
+    }
+
+    /// Drop least recently used images until the cache fits its size cap
+    pub async fn evict_to_limit(&self) -> Result<ImageEvictionResult> {
+        let (result, orphaned) = self.db.with_connection_async(|conn| {
+            ImageCacheOperations::evict_lru(conn, Self::max_bytes(conn) as i64)
+        }).await?;
+
+        for hash in &orphaned {
+            self.remove_files(hash);
+        }
+        if result.entries_removed > 0 {
+            println!(
+                "🧹 Evicted {} cached images ({:.1} MB)",
+                result.entries_removed,
+                result.bytes_freed as f64 / 1024.0 / 1024.0
+            );
+        }
+        Ok(result)
+    }
+
+    pub async fn clear(&self) -> Result<ImageEvictionResult> {
+        let (result, hashes) = self.db.with_connection_async(ImageCacheOperations::clear).await?;
+        for hash in &hashes {
+            self.remove_files(hash);
+        }
+        Ok(result)
+    }
+
+    pub async fn stats(&self) -> Result<ImageCacheStats> {
+        let now = chrono::Utc::now().timestamp();
+        self.db.with_read_connection_async(move |conn| {
+            ImageCacheOperations::stats(conn, now, Self::max_bytes(conn))
+        }).await
+    }
+
+    fn remove_files(&self, hash: &str) {
+        for path in [self.file_path(hash), self.thumbnail_path(hash)] {
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    eprintln!("Failed to remove cached image {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+}
+
+/// Write the image (once per hash) and its thumbnail. Returns the thumbnail size, 0 if none.
+fn store_files(file: &Path, thumbnail: &Path, bytes: &[u8]) -> Result<i64> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if !file.exists() {
+        write_atomic(file, bytes)?;
+    }
+
+    if let Ok(metadata) = fs::metadata(thumbnail) {
+        return Ok(metadata.len() as i64);
+    }
+    match make_thumbnail(bytes) {
+        Ok(thumb) => {
+            write_atomic(thumbnail, &thumb)?;
+            Ok(thumb.len() as i64)
+        }
+        Err(e) => {
+            #[cfg(debug_assertions)]
+            println!("No thumbnail for {:?}: {}", file, e);
+            Ok(0)
+        }
+    }
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, bytes)?;
+    // A concurrent download of the same content may have won the rename
+    if let Err(e) = fs::rename(&tmp, path) {
+        if !path.exists() {
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+/// JPEG scaled down to `THUMBNAIL_WIDTH`
+pub fn make_thumbnail(bytes: &[u8]) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(bytes)?;
+    let thumbnail = image.thumbnail(THUMBNAIL_WIDTH, u32::MAX).to_rgb8();
+
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, THUMBNAIL_QUALITY).encode_image(&thumbnail)?;
+    Ok(out)
+}
+
+/// Content type from the file signature; None if it isn't an image we serve
+fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Only Steam's image CDNs can be fetched through the scheme
+fn is_cacheable_url(url: &str) -> bool {
+    url::Url::parse(url)
+        .ok()
+        .filter(|parsed| matches!(parsed.scheme(), "https" | "http"))
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+        .map(|host| host.ends_with(".steamstatic.com") || host.ends_with(".akamaihd.net"))
+        .unwrap_or(false)
+}
+
+/// Origin the webview uses for custom schemes (Windows maps them onto http)
+#[cfg(windows)]
+fn scheme_origin() -> String {
+    format!("http://{}.localhost", IMAGE_SCHEME)
+}
+
+#[cfg(not(windows))]
+fn scheme_origin() -> String {
+    format!("{}://localhost", IMAGE_SCHEME)
+}
+
+/// URL the webview should load instead of a Steam CDN URL. Other URLs are returned unchanged.
+pub fn cached_image_url(kind: ImageKind, remote_url: &str, thumbnail: bool) -> String {
+    if !is_cacheable_url(remote_url) {
+        return remote_url.to_string();
+    }
+    format!(
+        "{}/{}/{}/{}",
+        scheme_origin(),
+        if thumbnail { "thumb" } else { "full" },
+        kind.as_str(),
+        urlencoding::encode(remote_url)
+    )
+}
+
+/// Point a GameDetail's header, banner and screenshots at the local cache
+pub fn localize_game_detail(mut detail: GameDetail) -> GameDetail {
+    detail.header_image = cached_image_url(ImageKind::Header, &detail.header_image, false);
+    detail.banner_image = cached_image_url(ImageKind::Banner, &detail.banner_image, false);
+    detail.screenshots = detail.screenshots
+        .iter()
+        .map(|url| cached_image_url(ImageKind::Screenshot, url, false))
+        .collect();
+    detail
+}
+
+pub fn parse_image_request(path: &str) -> Option<ImageRequest> {
+    let mut parts = path.trim_start_matches('/').splitn(3, '/');
+    let thumbnail = match parts.next()? {
+        "full" => false,
+        "thumb" => true,
+        _ => return None,
+    };
+    let kind = ImageKind::parse(parts.next()?)?;
+    let url = urlencoding::decode(parts.next()?).ok()?.into_owned();
+
+    is_cacheable_url(&url).then_some(ImageRequest { kind, thumbnail, url })
+}
+
+/// Handle a `zenith-image` request
+pub async fn respond(path: &str) -> Response<Vec<u8>> {
+    let request = match parse_image_request(path) {
+        Some(request) => request,
+        None => return error_response(400, "Invalid image request"),
+    };
+
+    match IMAGE_CACHE.load(&request.url, request.kind, request.thumbnail).await {
+        Ok(image) => Response::builder()
+            .status(200)
+            .header("Content-Type", &image.content_type)
+            .header("Cache-Control", "max-age=3600")
+            .body(image.bytes)
+            .unwrap_or_else(|_| Response::new(Vec::new())),
+        Err(e) => {
+            eprintln!("Failed to load image {}: {}", request.url, e);
+            error_response(502, "Image unavailable")
+        }
+    }
+}
+
+fn error_response(status: u16, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/plain")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+lazy_static::lazy_static! {
+    pub static ref IMAGE_CACHE: ImageCache = {
+        ImageCache::new(app_paths().cache_dir().join("images")).expect("Failed to initialize image cache")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+    use crate::mock_catalogue::read_request;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = image::RgbImage::from_pixel(width, height, image::Rgb([200, 40, 40]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image).write_to(&mut png, image::ImageOutputFormat::Png).unwrap();
+        png.into_inner()
+    }
+
+    /// Serve `body` as a PNG to every request after a short delay, counting the requests
+    async fn start_image_server(body: Vec<u8>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/steam/apps/570/header.jpg", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        tokio::spawn({
+            let requests = requests.clone();
+            async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let (requests, body) = (requests.clone(), body.clone());
+                    tokio::spawn(async move {
+                        if read_request(&mut stream).await.is_none() {
+                            return;
+                        }
+                        requests.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                        let head = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        );
+                        let _ = stream.write_all(head.as_bytes()).await;
+                        let _ = stream.write_all(&body).await;
+                        let _ = stream.shutdown().await;
+                    });
+                }
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_loads_share_one_download() {
+        let (url, requests) = start_image_server(png(460, 215)).await;
+        let (dir, db) = test_database();
+        let cache = ImageCache::with_database(db, dir.path().join("images"));
+
+        let loads = (0..4).map(|_| cache.load(&url, ImageKind::Header, false));
+        for image in futures::future::join_all(loads).await {
+            assert_eq!(image.unwrap().content_type, "image/png");
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(cache.downloads.lock().unwrap().is_empty());
+
+        // Later loads are served from disk
+        cache.load(&url, ImageKind::Header, true).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_image_request_round_trip() {
+        let remote = "https://cdn.akamai.steamstatic.com/steam/apps/570/header.jpg?t=1";
+        let local = cached_image_url(ImageKind::Header, remote, true);
+        let path = &local[scheme_origin().len()..];
+
+        assert_eq!(
+            parse_image_request(path),
+            Some(ImageRequest { kind: ImageKind::Header, thumbnail: true, url: remote.to_string() })
+        );
+    }
+
+    #[test]
+    fn test_only_steam_cdn_urls_are_cached() {
+        assert_eq!(cached_image_url(ImageKind::Banner, "https://example.com/a.jpg", false), "https://example.com/a.jpg");
+        let encoded = urlencoding::encode("http://127.0.0.1/secret");
+        assert_eq!(parse_image_request(&format!("/full/header/{}", encoded)), None);
+        assert_eq!(parse_image_request("/full/unknown/x"), None);
+    }
+
+    #[test]
+    fn test_thumbnail_is_scaled_jpeg() {
+        let thumbnail = make_thumbnail(&png(920, 430)).unwrap();
+        assert_eq!(sniff_content_type(&thumbnail), Some("image/jpeg"));
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (THUMBNAIL_WIDTH, 150));
+    }
+}
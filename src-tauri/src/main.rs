@@ -1,7 +1,9 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod app_paths;
 mod commands;
+mod connectivity;
 mod database;
 mod models;
 mod bypass;
@@ -9,10 +11,26 @@ mod steam_utils;
 mod download;
 mod hydra_api;
 mod catalogue_commands;
+mod catalogue_backend;
 mod metadata_service;
-
-use crate::steam_utils::{find_steam_config_path, find_steam_executable_path, update_lua_files};
+mod metadata_index;
+mod process_monitor;
+mod game_launcher;
+mod exe_detection;
+mod steam_process;
+mod steam_store;
+mod image_cache;
+mod price_watch;
+#[cfg(test)]
+mod mock_catalogue;
+
+use crate::steam_utils::{find_steam_config_path, update_lua_files};
+use crate::connectivity::CONNECTIVITY;
 use crate::download::{DownloadManagerState};
+use crate::database::cache_metrics::CACHE_METRICS;
+use crate::database::cache_service::Freshness;
+use crate::database::image_cache_models::{ImageKind, IMAGE_SCHEME};
+use crate::database::ttl_config::TtlCategory;
 use futures::stream::{self, StreamExt};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -20,7 +38,6 @@ use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::Cursor;
 use std::path::PathBuf;
-use std::process::Command;
 use std::time::Duration;
 use tauri::command;
 use tempfile::TempDir;
@@ -101,6 +118,16 @@ struct GameDetail {
     drm_notice: Option<String>, // DRM information from Steam API
 }
 
+/// `get_game_details` response: the details plus how current they are
+#[derive(Debug, Serialize, Clone)]
+struct GameDetailsResponse {
+    #[serde(flatten)]
+    details: GameDetail,
+    freshness: Freshness,
+    /// Answered from the cache only because the app is offline
+    offline: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct PcRequirements {
     minimum: Option<String>,
@@ -368,7 +395,7 @@ struct InitProgress {
 }
 
 #[command]
-async fn initialize_app(_app: tauri::AppHandle) -> Result<Vec<InitProgress>, String> {
+async fn initialize_app(_app: tauri::AppHandle, db: tauri::State<'_, database::SharedDatabase>) -> Result<Vec<InitProgress>, String> {
     let mut progress_steps = Vec::new();
 
     // Step 1: Check Steam installation
@@ -391,7 +418,35 @@ async fn initialize_app(_app: tauri::AppHandle) -> Result<Vec<InitProgress>, Str
         }
     }
 
-    // Step 2: Database ready
+    // Step 2: Database ready (report a data move requested for this launch, and anything lost
+    // if the database had to be rebuilt)
+    match app_paths::last_migration_outcome() {
+        Some(Ok(report)) => progress_steps.push(InitProgress {
+            step: match report.skipped_reason {
+                Some(reason) => format!("Data migration skipped: {}", reason),
+                None => format!("Moved {} files to the data directory", report.files_moved),
+            },
+            progress: 45.0,
+            completed: true,
+        }),
+        Some(Err(e)) => progress_steps.push(InitProgress {
+            step: format!("Data migration failed, it will be retried on next launch: {}", e),
+            progress: 45.0,
+            completed: false,
+        }),
+        None => {}
+    }
+
+    if let Some(report) = db.recovery_report() {
+        for line in report.summary_lines() {
+            progress_steps.push(InitProgress {
+                step: line,
+                progress: 45.0,
+                completed: true,
+            });
+        }
+    }
+
     progress_steps.push(InitProgress {
         step: "Database initialization complete".to_string(),
         progress: 45.0,
@@ -426,8 +481,8 @@ async fn initialize_app(_app: tauri::AppHandle) -> Result<Vec<InitProgress>, Str
     }
 
     // Cleanup any expired cache entries
-    GAME_CACHE.cleanup_expired();
-    GAME_CACHE.cache_stats();
+    GAME_CACHE.cleanup_expired().await;
+    GAME_CACHE.cache_stats().await;
 
     progress_steps.push(InitProgress {
         step: "SQLite cache system ready".to_string(),
@@ -537,15 +592,11 @@ async fn initialize_app(_app: tauri::AppHandle) -> Result<Vec<InitProgress>, Str
                 // Queue remaining games for background processing
                 if !background_ids.is_empty() {
                     for app_id in background_ids {
-                        GAME_CACHE.queue_for_refresh(app_id);
+                        GAME_CACHE.queue_for_refresh(app_id).await;
                     }
 
-                    // Start background processing (non-blocking)
-                    tokio::spawn(async {
-                        // Small delay to let app finish initializing
-                        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                        GAME_CACHE.process_queue_batch().await;
-                    });
+                    // The refresh worker picks these up in library order
+                    GAME_CACHE.process_queue_batch().await;
 
                     println!("🔄 Background cache warming started");
                 }
@@ -594,7 +645,7 @@ async fn check_game_in_library(app_id: String) -> Result<bool, String> {
 #[command]
 async fn get_library_games() -> Result<Vec<LibraryGame>, String> {
     // Display cache statistics
-    GAME_CACHE.cache_stats();
+    GAME_CACHE.cache_stats().await;
 
     let steam_config_path = find_steam_config_path().map_err(|e| e.to_string())?;
     let stplugin_dir = steam_config_path.join("stplug-in");
@@ -719,10 +770,10 @@ async fn get_library_games() -> Result<Vec<LibraryGame>, String> {
             let background_count = background_ids.len();
 
             for app_id in &background_ids {
-                GAME_CACHE.queue_for_refresh(app_id.clone());
+                GAME_CACHE.queue_for_refresh(app_id.clone()).await;
             }
 
-            // Add placeholders for background games (will be updated later)
+            // Add placeholders for background games (replaced via game-metadata-updated)
             for app_id in &background_ids {
                 games.push(LibraryGame {
                     app_id: app_id.clone(),
@@ -731,11 +782,8 @@ async fn get_library_games() -> Result<Vec<LibraryGame>, String> {
                 });
             }
 
-            // Trigger background processing
-            tokio::spawn(async {
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                GAME_CACHE.process_queue_batch().await;
-            });
+            // Wake the refresh worker in case everything was already queued
+            GAME_CACHE.process_queue_batch().await;
 
             println!(
                 "🔄 Queued {} games for background loading",
@@ -744,6 +792,11 @@ async fn get_library_games() -> Result<Vec<LibraryGame>, String> {
         }
     }
 
+    // Artwork is served from the local image cache
+    for game in &mut games {
+        game.header_image = image_cache::cached_image_url(ImageKind::Header, &game.header_image, false);
+    }
+
     // Sort games by name alphabetically
     games.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
 
@@ -766,7 +819,7 @@ async fn get_library_games() -> Result<Vec<LibraryGame>, String> {
     }
 
     // Final cache stats
-    GAME_CACHE.cache_stats();
+    GAME_CACHE.cache_stats().await;
     Ok(games)
 }
 
@@ -779,87 +832,6 @@ fn header_image_for(app_id: &str) -> String {
 
 // Helper function to extract AppID from Steam URLs
 
-async fn fetch_game_details_background(app_id: &str) -> Option<GameDetail> {
-    // Background refresh for game details (no user-facing errors)
-    let url = format!(
-        "https://store.steampowered.com/api/appdetails?appids={}",
-        app_id
-    );
-
-    match HTTP_CLIENT.get(&url).send().await {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                match resp.json::<serde_json::Value>().await {
-                    Ok(v) => {
-                        if let Some(data) = v.get(app_id).and_then(|x| x.get("data")) {
-                            // Parse game detail (simplified for background refresh)
-                            let name = data
-                                .get("name")
-                                .and_then(|x| x.as_str())
-                                .unwrap_or("")
-                                .to_string();
-                            let header_image = header_image_for(app_id);
-                            let banner_image = data
-                                .get("background")
-                                .and_then(|x| x.as_str())
-                                .or_else(|| data.get("background_raw").and_then(|x| x.as_str()))
-                                .unwrap_or(&header_image)
-                                .to_string();
-
-                            let game_detail = GameDetail {
-                                app_id: app_id.to_string(),
-                                name,
-                                header_image,
-                                banner_image,
-                                detailed_description: data
-                                    .get("detailed_description")
-                                    .and_then(|x| x.as_str())
-                                    .unwrap_or("")
-                                    .to_string(),
-                                release_date: data
-                                    .get("release_date")
-                                    .and_then(|x| x.get("date"))
-                                    .and_then(|x| x.as_str())
-                                    .unwrap_or("")
-                                    .to_string(),
-                                publisher: data
-                                    .get("publishers")
-                                    .and_then(|x| x.get(0))
-                                    .and_then(|x| x.as_str())
-                                    .unwrap_or("")
-                                    .to_string(),
-                                trailer: data
-                                    .get("movies")
-                                    .and_then(|arr| arr.get(0))
-                                    .and_then(|item| item.get("mp4"))
-                                    .and_then(|mp4| mp4.get("max"))
-                                    .and_then(|x| x.as_str())
-                                    .map(|s| s.to_string()),
-                                screenshots: Vec::new(), // Skip screenshots for background refresh
-                                sysreq_min: Vec::new(),
-                                sysreq_rec: Vec::new(),
-                                pc_requirements: None,
-                                dlc: Vec::new(),
-                                drm_notice: data
-                                    .get("drm_notice")
-                                    .and_then(|x| x.as_str())
-                                    .map(|s| s.to_string()),
-                            };
-
-                            // Cache the refreshed data
-                            GAME_CACHE.set_game_details(app_id.to_string(), game_detail.clone());
-                            return Some(game_detail);
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
-        }
-        Err(_) => {}
-    }
-    None
-}
-
 async fn fetch_game_name_simple(app_id: &str) -> Option<String> {
     // Check cache first (including stale-while-revalidate)
     if let Some(cached_name) = GAME_CACHE.get_game_name(app_id).await {
@@ -869,8 +841,8 @@ async fn fetch_game_name_simple(app_id: &str) -> Option<String> {
     // Check circuit breaker
     let is_circuit_open = GAME_CACHE.is_circuit_breaker_open().await;
 
-    if is_circuit_open {
-        // Return placeholder when circuit is open
+    if is_circuit_open || CONNECTIVITY.is_offline() {
+        // Return placeholder when circuit is open or we are offline
         return Some(format!("Game {}", app_id));
     }
 
@@ -896,11 +868,17 @@ async fn fetch_game_name_simple(app_id: &str) -> Option<String> {
     println!("Fetching game name: {}", app_id);
 
     let result = async {
-        match HTTP_CLIENT.get(url).send().await {
+        let started = std::time::Instant::now();
+        let response = HTTP_CLIENT.get(url).send().await;
+        CACHE_METRICS.observe_latency("appdetails_name", started.elapsed());
+
+        match response {
             Ok(resp) => {
+                CONNECTIVITY.record_success();
                 if !resp.status().is_success() {
                     if resp.status().as_u16() == 429 {
                         println!("Rate limited by Steam API (429)");
+                        CACHE_METRICS.record_rate_limited();
                         GAME_CACHE.record_error().await;
                     }
                     return None;
@@ -912,7 +890,7 @@ async fn fetch_game_name_simple(app_id: &str) -> Option<String> {
                             if let Some(name) = data.get("name").and_then(|x| x.as_str()) {
                                 let name = name.to_string();
                                 // Cache the result
-                                GAME_CACHE.set_game_name(app_id.to_string(), name.clone());
+                                GAME_CACHE.set_game_name(app_id.to_string(), name.clone()).await;
                                 // Reset error count on success
                                 GAME_CACHE.reset_error_count().await;
                                 return Some(name);
@@ -928,6 +906,7 @@ async fn fetch_game_name_simple(app_id: &str) -> Option<String> {
             }
             Err(e) => {
                 println!("Request failed: {}", e);
+                CONNECTIVITY.record_failure(&e);
                 GAME_CACHE.record_error().await;
                 None
             }
@@ -942,185 +921,50 @@ async fn fetch_game_name_simple(app_id: &str) -> Option<String> {
 }
 
 #[command]
-async fn get_game_details(app_id: String) -> Result<GameDetail, String> {
-    // Check cache first
-    if let Some(cached_details) = GAME_CACHE.get_game_details(&app_id).await {
-        return Ok(cached_details);
+async fn get_game_details(app_id: String) -> Result<GameDetailsResponse, String> {
+    // Serve whatever is cached right away; stale entries are revalidated in the background
+    let (cached, freshness) = GAME_CACHE.sqlite_service().get_game_details_with_freshness(&app_id).await;
+    if let Some(details) = cached {
+        let details = image_cache::localize_game_detail(details);
+        return Ok(GameDetailsResponse { details, freshness, offline: CONNECTIVITY.is_offline() });
     }
 
     // Throttle request to avoid rate limiting
     GAME_CACHE.throttle_request().await;
 
-    let url = format!(
-        "https://store.steampowered.com/api/appdetails?appids={}",
-        app_id
-    );
     // Only log in debug mode and when not cached
     #[cfg(debug_assertions)]
     println!("Fetching game details from Steam API: {}", app_id);
 
-    let resp = match HTTP_CLIENT.get(&url).send().await {
-        Ok(r) => r,
+    let fetched = match steam_store::fetch_app_details(&app_id, &[]).await {
+        Ok(fetched) => fetched,
         Err(e) => {
-            GAME_CACHE.record_error().await;
-            return Err(format!("Request failed: {}", e));
-        }
-    };
-
-    if !resp.status().is_success() {
-        if resp.status().as_u16() == 429 {
-            GAME_CACHE.record_error().await;
-            return Err(
-                "Rate limited by Steam API (429). Please wait before trying again.".to_string(),
-            );
-        }
-        return Err(format!("status {}", resp.status()));
-    }
-
-    let v: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
-
-    // Only log if there's an issue, not the full response
-    #[cfg(debug_assertions)]
-    if let Some(app_data) = v.get(&app_id) {
-        if let Some(success) = app_data.get("success").and_then(|s| s.as_bool()) {
-            if !success {
+            if matches!(e, steam_store::AppDetailsError::RateLimited | steam_store::AppDetailsError::Request(_)) {
+                GAME_CACHE.record_error().await;
+            }
+            #[cfg(debug_assertions)]
+            if matches!(e, steam_store::AppDetailsError::Unsuccessful(_)) {
                 println!("Steam API returned success=false for app ID {}", app_id);
             }
+            return Err(e.to_string());
         }
-    }
-
-    // Check if the app exists and was successful
-    let app_data = v
-        .get(&app_id)
-        .ok_or_else(|| format!("App ID {} not found in Steam API response", app_id))?;
-
-    if let Some(success) = app_data.get("success").and_then(|s| s.as_bool()) {
-        if !success {
-            return Err(format!("Steam API returned success=false for app ID {} (game might not exist or be private)", app_id));
-        }
-    }
-
-    let data = app_data.get("data").ok_or_else(|| {
-        format!(
-            "No data field for app ID {} (app might not exist or be private)",
-            app_id
-        )
-    })?;
+    };
 
     // Reset error count on successful request
     GAME_CACHE.reset_error_count().await;
-
-    let name = data
-        .get("name")
-        .and_then(|x| x.as_str())
-        .unwrap_or("")
-        .to_string();
-    // Always use our consistent header image format instead of Steam API's variable quality images
-    let header_image = header_image_for(&app_id);
-
-    // Use background image for banner (higher resolution)
-    let banner_image = data
-        .get("background")
-        .and_then(|x| x.as_str())
-        .or_else(|| data.get("background_raw").and_then(|x| x.as_str()))
-        .unwrap_or(&header_image)
-        .to_string();
-
-    let detailed_description = data
-        .get("detailed_description")
-        .and_then(|x| x.as_str())
-        .unwrap_or("")
-        .to_string();
-    let release_date = data
-        .get("release_date")
-        .and_then(|x| x.get("date"))
-        .and_then(|x| x.as_str())
-        .unwrap_or("")
-        .to_string();
-    let publisher = data
-        .get("publishers")
-        .and_then(|x| x.get(0))
-        .and_then(|x| x.as_str())
-        .unwrap_or("")
-        .to_string();
-
-    let trailer = data
-        .get("movies")
-        .and_then(|arr| arr.get(0))
-        .and_then(|item| item.get("mp4"))
-        .and_then(|mp4| mp4.get("max"))
-        .and_then(|x| x.as_str())
-        .map(|s| s.to_string());
-    let mut screenshots = Vec::new();
-    if let Some(arr) = data.get("screenshots").and_then(|x| x.as_array()) {
-        for s in arr.iter().take(6) {
-            // Use full resolution image instead of thumbnail
-            if let Some(url) = s.get("path_full").and_then(|x| x.as_str()) {
-                screenshots.push(url.to_string());
-            }
-        }
-    }
-
-    // Parse system requirements
-    let mut sysreq_min = Vec::new();
-    let mut sysreq_rec = Vec::new();
-    let mut pc_requirements = None;
-
-    if let Some(pc_req) = data.get("pc_requirements") {
-        let minimum = pc_req
-            .get("minimum")
-            .and_then(|x| x.as_str())
-            .map(|s| s.to_string());
-        let recommended = pc_req
-            .get("recommended")
-            .and_then(|x| x.as_str())
-            .map(|s| s.to_string());
-
-        if minimum.is_some() || recommended.is_some() {
-            pc_requirements = Some(PcRequirements {
-                minimum: minimum.clone(),
-                recommended: recommended.clone(),
-            });
-        }
-
-        if let Some(min_str) = minimum.as_ref() {
-            sysreq_min = parse_sysreq_html(min_str);
-        }
-        if let Some(rec_str) = recommended.as_ref() {
-            sysreq_rec = parse_sysreq_html(rec_str);
-        }
-    }
+    GAME_CACHE.sqlite_service().record_full_fetch(fetched.response_bytes);
 
     // DLC will be loaded separately when needed (lazy loading)
-    let dlc = Vec::new();
-
-    // Extract DRM notice
-    let drm_notice = data
-        .get("drm_notice")
-        .and_then(|x| x.as_str())
-        .map(|s| s.to_string());
-
-    let game_detail = GameDetail {
-        app_id: app_id.clone(),
-        name,
-        header_image,
-        banner_image,
-        detailed_description,
-        release_date,
-        publisher,
-        trailer,
-        screenshots,
-        sysreq_min,
-        sysreq_rec,
-        pc_requirements,
-        dlc,
-        drm_notice,
-    };
+    let game_detail = steam_store::parse_game_detail(&app_id, &fetched.data);
 
     // Cache the result
-    GAME_CACHE.set_game_details(app_id, game_detail.clone());
+    GAME_CACHE.set_game_details(app_id, game_detail.clone()).await;
 
-    Ok(game_detail)
+    Ok(GameDetailsResponse {
+        details: image_cache::localize_game_detail(game_detail),
+        freshness: Freshness::Fresh,
+        offline: false,
+    })
 }
 
 fn parse_sysreq_html(html: &str) -> Vec<(String, String)> {
@@ -1261,113 +1105,29 @@ fn greet(name: &str) -> String {
 async fn restart_steam() -> Result<String, String> {
     println!("Attempting to restart Steam...");
 
-    #[cfg(target_os = "windows")]
-    {
-        // First, terminate the Steam process
-        let kill_result = Command::new("taskkill")
-            .args(&["/F", "/IM", "steam.exe"])
-            .output();
-
-        match kill_result {
-            Ok(output) => {
-                if output.status.success() {
-                    println!("Steam process terminated successfully");
-                } else {
-                    println!("Steam process might not be running");
-                }
-            }
-            Err(e) => {
-                println!("Failed to terminate Steam: {}", e);
-            }
-        }
-
-        // Wait a moment for the process to fully terminate
-        tokio::time::sleep(Duration::from_millis(1000)).await;
-
-        // Find and restart Steam
-        match find_steam_executable_path() {
-            Ok(steam_path) => match Command::new(&steam_path).spawn() {
-                Ok(_) => {
-                    println!("Steam restarted successfully");
-                    Ok("Steam has been restarted successfully".to_string())
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to restart Steam: {}", e);
-                    println!("{}", error_msg);
-                    Err(error_msg)
-                }
-            },
-            Err(e) => {
-                let error_msg = format!("Steam executable not found: {}", e);
-                println!("{}", error_msg);
-                Err(error_msg)
-            }
-        }
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        // For Linux/macOS, try to restart Steam
-        match Command::new("pkill").arg("steam").output() {
-            Ok(_) => println!("Steam process terminated"),
-            Err(_) => println!("Steam might not be running"),
-        }
-
-        tokio::time::sleep(Duration::from_millis(1000)).await;
-
-        match Command::new("steam").spawn() {
-            Ok(_) => {
-                println!("Steam restarted successfully");
-                Ok("Steam has been restarted successfully".to_string())
-            }
-            Err(e) => {
-                let error_msg = format!("Failed to restart Steam: {}", e);
-                println!("{}", error_msg);
-                Err(error_msg)
-            }
+    match steam_process::restart(steam_process::SHUTDOWN_TIMEOUT).await {
+        Ok(_) => Ok("Steam has been restarted successfully".to_string()),
+        Err(e) => {
+            let error_msg = format!("Failed to restart Steam: {}", e);
+            println!("{}", error_msg);
+            Err(error_msg)
         }
     }
 }
 
 #[command]
 async fn check_steam_status() -> Result<bool, String> {
-    #[cfg(target_os = "windows")]
-    {
-        match Command::new("tasklist")
-            .args(&["/FI", "IMAGENAME eq steam.exe"])
-            .output()
-        {
-            Ok(output) => {
-                let output_str = String::from_utf8_lossy(&output.stdout);
-                let is_running = output_str.contains("steam.exe");
-                println!(
-                    "Steam status check: {}",
-                    if is_running { "Running" } else { "Not running" }
-                );
-                Ok(is_running)
-            }
-            Err(e) => {
-                println!("Failed to check Steam status: {}", e);
-                Err(format!("Failed to check Steam status: {}", e))
-            }
+    match steam_process::is_running() {
+        Ok(is_running) => {
+            println!(
+                "Steam status check: {}",
+                if is_running { "Running" } else { "Not running" }
+            );
+            Ok(is_running)
         }
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        match Command::new("pgrep").arg("steam").output() {
-            Ok(output) => {
-                let is_running = !output.stdout.is_empty();
-                println!(
-                    "Steam status check: {}",
-                    if is_running { "Running" } else { "Not running" }
-                );
-                Ok(is_running)
-            }
-            Err(e) => {
-                println!("Failed to check Steam status: {}", e);
-                Err(format!("Failed to check Steam status: {}", e))
-            }
+        Err(e) => {
+            println!("{}", e);
+            Err(e)
         }
     }
 }
@@ -1390,55 +1150,29 @@ async fn get_game_dlc_list(app_id: String) -> Result<Vec<String>, String> {
         }
     }
 
-    // Fetch DLC data from Steam API
-    let url = format!(
-        "https://store.steampowered.com/api/appdetails?appids={}",
-        app_id
-    );
+    // Fetch only the DLC list from Steam API
     // Only log actual API calls, not cache hits
     // println!("Fetching DLC data from Steam API for: {}", app_id);
-
-    let resp = HTTP_CLIENT
-        .get(&url)
-        .send()
+    let fetched = steam_store::fetch_app_details(&app_id, steam_store::category_filters(TtlCategory::Dynamic))
         .await
-        .map_err(|e| e.to_string())?;
-    if !resp.status().is_success() {
-        return Err(format!("Steam API returned status {}", resp.status()));
-    }
+        .map_err(|e| match e {
+            steam_store::AppDetailsError::Status(status) => format!("Steam API returned status {}", status),
+            steam_store::AppDetailsError::NotFound(_) | steam_store::AppDetailsError::Unsuccessful(_) => "no data".to_string(),
+            e => e.to_string(),
+        })?;
 
-    let v: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
-    let data = v
-        .get(&app_id)
-        .and_then(|x| x.get("data"))
-        .ok_or("no data")?;
-
-    // Extract DLC information
-    let dlc = if let Some(dlc_data) = data.get("dlc") {
-        match dlc_data {
-            serde_json::Value::Array(arr) => arr
-                .iter()
-                .filter_map(|v| v.as_u64())
-                .map(|id| id.to_string())
-                .collect(),
-            serde_json::Value::Object(obj) => obj
-                .values()
-                .filter_map(|v| v.as_u64())
-                .map(|id| id.to_string())
-                .collect(),
-            _ => Vec::new(),
-        }
-    } else {
-        Vec::new()
-    };
+    let dlc = steam_store::parse_dlc(&fetched.data);
 
     if !dlc.is_empty() {
         println!("Found {} DLCs for game {}", dlc.len(), app_id);
 
-        // Update cached game details with DLC info
-        if let Some(mut cached_details) = GAME_CACHE.get_game_details(&app_id).await {
-            cached_details.dlc = dlc.clone();
-            GAME_CACHE.set_game_details(app_id.clone(), cached_details);
+        // Merge the DLC list into cached game details, bumping only the dynamic expiry
+        if let Err(e) = GAME_CACHE
+            .sqlite_service()
+            .apply_partial_refresh(&app_id, &fetched.data, &[TtlCategory::Dynamic])
+            .await
+        {
+            eprintln!("Failed to cache DLC list for {}: {}", app_id, e);
         }
     }
 
@@ -1450,27 +1184,20 @@ async fn get_batch_game_details(app_ids: Vec<String>) -> Result<Vec<GameDetail>,
     // Batch details logging reduced to prevent spam
     // println!("Fetching batch details for {} DLCs", app_ids.len());
     let mut details_list = Vec::new();
-    let mut _cache_hits = 0;
-    let mut _api_calls = 0;
 
     // Process in smaller batches to avoid overwhelming the API
     for chunk in app_ids.chunks(5) {
         let mut batch_futures = Vec::new();
 
+        // Cache hits and API calls are counted by the metrics registry (get_cache_metrics)
         for app_id in chunk {
-            // Check cache first to count hits
-            if GAME_CACHE.get_game_details(app_id).await.is_some() {
-                _cache_hits += 1;
-            } else {
-                _api_calls += 1;
-            }
             batch_futures.push(get_game_details(app_id.clone()));
         }
 
         // Wait for all in this batch
         for future in batch_futures {
             match future.await {
-                Ok(details) => details_list.push(details),
+                Ok(response) => details_list.push(response.details),
                 Err(e) => println!("Could not fetch details for AppID: {}", e),
             }
         }
@@ -1481,14 +1208,7 @@ async fn get_batch_game_details(app_ids: Vec<String>) -> Result<Vec<GameDetail>,
         }
     }
 
-    // Batch completion logging reduced to prevent spam
-    // println!(
-    //     "Batch DLC fetch completed: {} cache hits, {} API calls, {} total results",
-    //     cache_hits,
-    //     api_calls,
-    //     details_list.len()
-    // );
-    GAME_CACHE.cache_stats();
+    GAME_CACHE.cache_stats().await;
     Ok(details_list)
 }
 
@@ -1612,63 +1332,34 @@ async fn sync_dlcs_in_lua(
 
 #[command]
 async fn clear_cache() -> Result<String, String> {
-    GAME_CACHE.clear_all();
+    GAME_CACHE.clear_all().await;
     Ok("Cache cleared successfully".to_string())
 }
 
 #[command]
 async fn refresh_dlc_cache(app_id: String) -> Result<String, String> {
-    // Force clear the specific game's DLC cache
-    GAME_CACHE.invalidate_game_details(&app_id);
-    
-    // Fetch fresh DLC data from Steam API
-    let url = format!(
-        "https://store.steampowered.com/api/appdetails?appids={}",
-        app_id
-    );
-    
-    let resp = HTTP_CLIENT
-        .get(&url)
-        .send()
+    // Fetch fresh DLC data from Steam API; the rest of the cached record is kept
+    let fetched = steam_store::fetch_app_details(&app_id, steam_store::category_filters(TtlCategory::Dynamic))
         .await
-        .map_err(|e| format!("Failed to fetch from Steam API: {}", e))?;
-        
-    if !resp.status().is_success() {
-        return Err(format!("Steam API returned status {}", resp.status()));
-    }
+        .map_err(|e| match e {
+            steam_store::AppDetailsError::Request(e) => format!("Failed to fetch from Steam API: {}", e),
+            steam_store::AppDetailsError::Status(status) => format!("Steam API returned status {}", status),
+            steam_store::AppDetailsError::NotFound(_) | steam_store::AppDetailsError::Unsuccessful(_) => {
+                "No data found in Steam API response".to_string()
+            }
+            e => e.to_string(),
+        })?;
 
-    let v: serde_json::Value = resp.json().await
-        .map_err(|e| format!("Failed to parse Steam API response: {}", e))?;
-        
-    let data = v
-        .get(&app_id)
-        .and_then(|x| x.get("data"))
-        .ok_or("No data found in Steam API response")?;
-
-    // Extract DLC information
-    let dlc = if let Some(dlc_data) = data.get("dlc") {
-        match dlc_data {
-            serde_json::Value::Array(arr) => arr
-                .iter()
-                .filter_map(|v| v.as_u64())
-                .map(|id| id.to_string())
-                .collect(),
-            serde_json::Value::Object(obj) => obj
-                .values()
-                .filter_map(|v| v.as_u64())
-                .map(|id| id.to_string())
-                .collect(),
-            _ => Vec::new(),
-        }
-    } else {
-        Vec::new()
-    };
+    let dlc = steam_store::parse_dlc(&fetched.data);
 
     if !dlc.is_empty() {
         // Update cached game details with fresh DLC info
-        if let Some(mut cached_details) = GAME_CACHE.get_game_details(&app_id).await {
-            cached_details.dlc = dlc.clone();
-            GAME_CACHE.set_game_details(app_id.clone(), cached_details);
+        if let Err(e) = GAME_CACHE
+            .sqlite_service()
+            .apply_partial_refresh(&app_id, &fetched.data, &[TtlCategory::Dynamic])
+            .await
+        {
+            eprintln!("Failed to cache DLC list for {}: {}", app_id, e);
         }
         
         Ok(format!("Successfully refreshed {} DLCs for game {}", dlc.len(), app_id))
@@ -1679,10 +1370,8 @@ async fn refresh_dlc_cache(app_id: String) -> Result<String, String> {
 
 #[command]
 async fn refresh_cache_background() -> Result<String, String> {
-    // Trigger background cache refresh
-    tokio::spawn(async {
-        GAME_CACHE.process_queue_batch().await;
-    });
+    // Wake the refresh worker to drain the queue
+    GAME_CACHE.process_queue_batch().await;
     Ok("Background cache refresh started".to_string())
 }
 
@@ -1778,8 +1467,48 @@ struct CacheDebugInfo {
 
 // ====================== UPDATER FUNCTIONS ======================
 
+/// Event carrying the result of an update check that was deferred while offline
+const UPDATE_CHECK_COMPLETED_EVENT: &str = "update-check-completed";
+
+/// Set while a deferred update check is waiting for connectivity
+static UPDATE_CHECK_DEFERRED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateCheckCompleted {
+    success: bool,
+    message: String,
+}
+
 #[command]
 async fn check_for_updates(app: tauri::AppHandle) -> Result<String, String> {
+    if CONNECTIVITY.is_offline() {
+        defer_update_check(app);
+        return Err("Offline: the update check will run when connectivity returns".to_string());
+    }
+    run_update_check(&app).await
+}
+
+/// Run the update check once connectivity returns and report it as `update-check-completed`
+fn defer_update_check(app: tauri::AppHandle) {
+    use std::sync::atomic::Ordering;
+    use tauri::Emitter;
+
+    if UPDATE_CHECK_DEFERRED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        CONNECTIVITY.wait_until_online().await;
+        UPDATE_CHECK_DEFERRED.store(false, Ordering::SeqCst);
+
+        let (success, message) = match run_update_check(&app).await {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+        let _ = app.emit(UPDATE_CHECK_COMPLETED_EVENT, UpdateCheckCompleted { success, message });
+    });
+}
+
+async fn run_update_check(app: &tauri::AppHandle) -> Result<String, String> {
     match app.updater() {
         Ok(updater) => {
             match updater.check().await {
@@ -1809,6 +1538,8 @@ async fn check_for_updates(app: tauri::AppHandle) -> Result<String, String> {
 
 #[command]
 async fn install_update(app: tauri::AppHandle) -> Result<String, String> {
+    CONNECTIVITY.ensure_online("the update download").map_err(|e| e.to_string())?;
+
     match app.updater() {
         Ok(updater) => {
             match updater.check().await {
@@ -1870,6 +1601,11 @@ struct ChangelogItem {
 
 #[command]
 async fn get_changelog() -> Result<ChangelogEntry, String> {
+    // Offline, the bundled changelog is all there is
+    if CONNECTIVITY.is_offline() {
+        return get_local_changelog();
+    }
+
     // Try to get changelog from GitHub releases first
     match fetch_changelog_from_github().await {
         Ok(changelog) => Ok(changelog),
@@ -1900,6 +1636,10 @@ async fn fetch_changelog_from_github() -> Result<ChangelogEntry, String> {
             }
             Err(e) => {
                 println!("❌ Failed to fetch from {}: {}", repo, e);
+                // No point trying the other repositories without a connection
+                if CONNECTIVITY.is_offline() {
+                    break;
+                }
                 continue;
             }
         }
@@ -1916,7 +1656,10 @@ async fn try_fetch_changelog(url: &str) -> Result<ChangelogEntry, String> {
         .timeout(std::time::Duration::from_secs(10))
         .send()
         .await
-        .map_err(|e| format!("Network error: {}", e))?;
+        .map_err(|e| {
+            CONNECTIVITY.record_failure(&e);
+            format!("Network error: {}", e)
+        })?;
     
     if !resp.status().is_success() {
         return Err(format!("HTTP {}: {}", 
@@ -2080,15 +1823,38 @@ fn get_local_changelog() -> Result<ChangelogEntry, String> {
 // ====================== END CHANGELOG FUNCTIONS ======================
 
 fn main() {
+    // Must run before anything opens the database
+    app_paths::app_paths().run_pending_migration();
+
+    // One shared handle for every command; migrations run here, once
+    let database = database::shared_database().expect("Failed to open games database");
+    let backup_database = database.clone();
+    let price_watch_database = database.clone();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
         .manage(DownloadManagerState::new())
-        .setup(|_app| {
+        .manage(database)
+        .setup(move |app| {
             // Database initialization moved to initialize_app function
             // to provide proper loading screen feedback
+            tauri::async_runtime::spawn(database::backup_operations::run_daily_backups(backup_database));
+            tauri::async_runtime::spawn(database::refresh_queue_operations::run_refresh_worker(app.handle().clone()));
+            tauri::async_runtime::spawn(database::refresh_queue_operations::forward_details_updates(app.handle().clone()));
+            connectivity::load_offline_mode();
+            tauri::async_runtime::spawn(connectivity::run_connectivity_monitor());
+            tauri::async_runtime::spawn(connectivity::forward_connectivity_changes(app.handle().clone()));
+            tauri::async_runtime::spawn(price_watch::run_price_watch(app.handle().clone(), price_watch_database));
             Ok(())
         })
+        .register_asynchronous_uri_scheme_protocol(IMAGE_SCHEME, |_ctx, request, responder| {
+            let path = request.uri().path().to_string();
+            tauri::async_runtime::spawn(async move {
+                responder.respond(image_cache::respond(&path).await);
+            });
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             download_game,
@@ -2126,6 +1892,17 @@ fn main() {
             // SQLite Database Management Commands
             database::commands::migrate_json_to_sqlite,
             database::commands::get_migration_status,
+            database::commands::get_schema_migration_status,
+            database::commands::rollback_schema_migrations,
+            // Search Commands
+            database::search_commands::search_games,
+            // Database Backup Commands
+            database::backup_commands::list_backups,
+            database::backup_commands::create_database_backup,
+            database::backup_commands::restore_backup,
+            // User Data Export/Import Commands
+            database::user_data_commands::export_user_data,
+            database::user_data_commands::import_user_data,
             database::commands::get_database_stats,
             database::commands::cleanup_expired_cache,
             database::commands::vacuum_database,
@@ -2135,6 +1912,30 @@ fn main() {
             database::commands::batch_refresh_games,
             database::commands::smart_refresh_library,
             database::commands::get_cache_config,
+            database::commands::set_cache_config,
+            database::commands::get_partial_refresh_stats,
+            database::commands::get_cache_metrics,
+            database::commands::get_cache_metrics_prometheus,
+            // Image Cache Commands
+            database::image_cache_commands::get_image_cache_stats,
+            database::image_cache_commands::evict_image_cache,
+            database::image_cache_commands::clear_image_cache,
+            // Catalogue Cache Commands
+            database::catalogue_cache_commands::get_catalogue_cache_stats,
+            database::catalogue_cache_commands::clear_catalogue_cache,
+            // Wishlist Commands
+            database::wishlist_commands::add_to_wishlist,
+            database::wishlist_commands::remove_from_wishlist,
+            database::wishlist_commands::get_wishlist,
+            database::wishlist_commands::set_wishlist_price_alert,
+            database::wishlist_commands::get_price_history,
+            // Connectivity Commands
+            connectivity::get_connectivity_status,
+            connectivity::set_offline_mode,
+            connectivity::check_connectivity,
+            // Data Directory Commands
+            app_paths::get_app_paths,
+            app_paths::schedule_data_migration,
             // Bypass Games Cache Commands
             database::commands::get_bypass_games_cached,
             database::commands::refresh_bypass_games_cache,
@@ -2172,16 +1973,32 @@ fn main() {
             database::history_commands::clear_download_history,
             database::history_commands::redownload_from_history,
             database::history_commands::debug_history_database,
+            // Playtime Commands
+            database::playtime_commands::get_game_playtime,
+            database::playtime_commands::get_all_playtime,
+            database::playtime_commands::get_play_sessions,
+            database::playtime_commands::get_running_games,
+            // Launch Profile Commands
+            database::launch_profile_commands::get_launch_profiles,
+            database::launch_profile_commands::create_launch_profile,
+            database::launch_profile_commands::update_launch_profile,
+            database::launch_profile_commands::delete_launch_profile,
+            database::launch_profile_commands::set_default_launch_profile,
+            game_launcher::launch_game,
             // Catalogue Commands (Hydra API)
             catalogue_commands::get_catalogue_list,
             catalogue_commands::get_paginated_catalogue,
             catalogue_commands::search_catalogue_games,
             catalogue_commands::get_sample_catalogue_games,
             catalogue_commands::test_hydra_connection,
+            // Catalogue Endpoint Commands
+            catalogue_backend::get_catalogue_endpoints,
+            catalogue_backend::set_catalogue_endpoints,
             // Metadata Commands
             metadata_service::get_metadata_resources,
             metadata_service::get_filter_metadata,
             metadata_service::test_metadata_connection,
+            metadata_index::search_filter_values,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
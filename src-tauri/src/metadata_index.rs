@@ -0,0 +1,413 @@
+//! Filter values for the catalogue UI, ranked by popularity: how many cached catalogue games
+//! use each genre, tag, developer and publisher. Developers and publishers run into the
+//! thousands, so every filter is also indexed by label for prefix search.
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::command;
+
+use crate::catalogue_backend::{catalogue_pages, catalogue_provider, metadata_service};
+use crate::database::catalogue_cache_operations::CatalogueCacheOperations;
+use crate::database::shared_database;
+use crate::hydra_api::{
+    CatalogueError, CataloguePageRequest, CatalogueSort, PaginationInfo, SearchFilters, MAX_PAGE_SIZE,
+};
+use crate::metadata_service::{FilterItem, FilterMetadata, MetadataResources};
+
+/// How long a built index is reused before the cache is counted again
+const INDEX_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// With fewer cached games than this, the first catalogue pages are fetched (and so cached)
+/// before counting
+const SEED_MIN_GAMES: usize = 200;
+const SEED_PAGES: usize = 5;
+
+/// Which filter values to search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    Genres,
+    Tags,
+    Developers,
+    Publishers,
+}
+
+/// The parts of a cached catalogue game that are counted
+#[derive(Deserialize)]
+struct IndexedGame {
+    #[serde(rename = "objectId")]
+    object_id: String,
+    shop: String,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    tags: Vec<u64>,
+    #[serde(default)]
+    developers: Vec<String>,
+    #[serde(default)]
+    publishers: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct IndexedResponse {
+    edges: Vec<IndexedGame>,
+}
+
+/// One filter's values, most popular first
+struct RankedValues {
+    items: Vec<FilterItem>,
+    /// Lowercase labels with their position in `items`, sorted by label
+    by_label: Vec<(String, usize)>,
+}
+
+impl RankedValues {
+    /// `(label, value)` pairs ranked by the count of their value; ties go alphabetically
+    fn new(values: impl IntoIterator<Item = (String, String)>, counts: &HashMap<String, u32>) -> Self {
+        let mut seen = HashSet::new();
+        let mut items: Vec<FilterItem> = values
+            .into_iter()
+            .filter(|(_, value)| seen.insert(value.clone()))
+            .map(|(label, value)| FilterItem {
+                count: Some(counts.get(&value).copied().unwrap_or(0)),
+                label,
+                value,
+            })
+            .collect();
+        items.sort_by_cached_key(|item| (Reverse(item.count), item.label.to_lowercase()));
+
+        let mut by_label: Vec<(String, usize)> = items
+            .iter()
+            .enumerate()
+            .map(|(position, item)| (item.label.to_lowercase(), position))
+            .collect();
+        by_label.sort();
+
+        Self { items, by_label }
+    }
+
+    /// Values whose label starts with `prefix` (case-insensitive), most popular first
+    fn matching(&self, prefix: &str) -> Vec<&FilterItem> {
+        let prefix = prefix.trim().to_lowercase();
+        if prefix.is_empty() {
+            return self.items.iter().collect();
+        }
+
+        let start = self.by_label.partition_point(|(label, _)| label.as_str() < prefix.as_str());
+        let mut positions: Vec<usize> = self.by_label[start..]
+            .iter()
+            .take_while(|(label, _)| label.starts_with(&prefix))
+            .map(|(_, position)| *position)
+            .collect();
+        positions.sort_unstable();
+        positions.into_iter().map(|position| &self.items[position]).collect()
+    }
+}
+
+/// A page of `search_filter_values` results
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FilterValuesPage {
+    pub items: Vec<FilterItem>,
+    pub pagination: PaginationInfo,
+    /// Built from cached metadata only because the app is offline
+    pub offline: bool,
+}
+
+pub struct MetadataIndex {
+    genres: RankedValues,
+    tags: RankedValues,
+    developers: RankedValues,
+    publishers: RankedValues,
+    /// Distinct catalogue games the counts come from
+    pub games_counted: usize,
+    pub offline: bool,
+}
+
+impl MetadataIndex {
+    /// Index `resources` in `language` (English where it has no translation), counting each
+    /// distinct game across the cached catalogue `responses` (raw API bodies) once
+    pub fn build(resources: &MetadataResources, language: &str, responses: &[String]) -> Self {
+        let mut seen = HashSet::new();
+        let mut genre_counts = HashMap::new();
+        let mut tag_counts = HashMap::new();
+        let mut developer_counts = HashMap::new();
+        let mut publisher_counts = HashMap::new();
+        let count = |counts: &mut HashMap<String, u32>, values: Vec<String>| {
+            for value in values {
+                *counts.entry(value).or_insert(0) += 1;
+            }
+        };
+
+        for body in responses {
+            let response: IndexedResponse = match serde_json::from_str(body) {
+                Ok(response) => response,
+                Err(_) => continue,
+            };
+            for game in response.edges {
+                if !seen.insert((game.shop, game.object_id)) {
+                    continue;
+                }
+                count(&mut genre_counts, game.genres);
+                count(&mut tag_counts, game.tags.iter().map(u64::to_string).collect());
+                count(&mut developer_counts, game.developers);
+                count(&mut publisher_counts, game.publishers);
+            }
+        }
+
+        // The API filters on English genre names; translations list the same genres in order
+        let english_genres = resources.genres.get("en");
+        let genres = localized(&resources.genres, language)
+            .map(|labels| {
+                labels
+                    .iter()
+                    .enumerate()
+                    .map(|(i, label)| {
+                        let value = english_genres.and_then(|en| en.get(i)).unwrap_or(label);
+                        (label.clone(), value.clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let tags = localized(&resources.tags, language)
+            .map(|tags| tags.iter().map(|(name, id)| (name.clone(), id.to_string())).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let names = |names: &[String]| names.iter().map(|name| (name.clone(), name.clone())).collect::<Vec<_>>();
+
+        Self {
+            genres: RankedValues::new(genres, &genre_counts),
+            tags: RankedValues::new(tags, &tag_counts),
+            developers: RankedValues::new(names(&resources.developers), &developer_counts),
+            publishers: RankedValues::new(names(&resources.publishers), &publisher_counts),
+            games_counted: seen.len(),
+            offline: resources.offline,
+        }
+    }
+
+    fn values(&self, kind: FilterKind) -> &RankedValues {
+        match kind {
+            FilterKind::Genres => &self.genres,
+            FilterKind::Tags => &self.tags,
+            FilterKind::Developers => &self.developers,
+            FilterKind::Publishers => &self.publishers,
+        }
+    }
+
+    /// The first values of each filter, for the filter panel
+    pub fn filter_metadata(&self) -> FilterMetadata {
+        let top = |kind, limit| self.values(kind).items.iter().take(limit).cloned().collect();
+        FilterMetadata {
+            genres: top(FilterKind::Genres, 20),
+            tags: top(FilterKind::Tags, 30),
+            developers: top(FilterKind::Developers, 50),
+            publishers: top(FilterKind::Publishers, 50),
+            offline: self.offline,
+        }
+    }
+
+    /// One page of `kind` values whose label starts with `query`, in ranked order
+    pub fn search(
+        &self,
+        kind: FilterKind,
+        query: &str,
+        page: u32,
+        items_per_page: u32,
+    ) -> Result<FilterValuesPage, CatalogueError> {
+        if page == 0 {
+            return Err(CatalogueError::InvalidPage(page));
+        }
+        if items_per_page == 0 || items_per_page > MAX_PAGE_SIZE {
+            return Err(CatalogueError::InvalidPageSize(items_per_page));
+        }
+
+        let matches = self.values(kind).matching(query);
+        let total_items = matches.len() as u32;
+        let skip = (page as usize - 1).saturating_mul(items_per_page as usize);
+
+        Ok(FilterValuesPage {
+            items: matches.into_iter().skip(skip).take(items_per_page as usize).cloned().collect(),
+            pagination: PaginationInfo {
+                current_page: page,
+                total_pages: total_items.div_ceil(items_per_page),
+                total_items,
+                items_per_page,
+                has_next_page: skip.saturating_add(items_per_page as usize) < total_items as usize,
+                has_prev_page: page > 1,
+            },
+            offline: self.offline,
+        })
+    }
+}
+
+fn localized<'a, T>(resource: &'a HashMap<String, T>, language: &str) -> Option<&'a T> {
+    resource.get(language).or_else(|| resource.get("en"))
+}
+
+struct BuiltIndex {
+    language: String,
+    built_at: Instant,
+    index: Arc<MetadataIndex>,
+}
+
+lazy_static::lazy_static! {
+    static ref INDEX: Mutex<Option<BuiltIndex>> = Mutex::new(None);
+}
+
+/// Forget the built index, e.g. when the catalogue backend changes
+pub fn invalidate_index() {
+    *INDEX.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// The index for `language`, rebuilt from the metadata files and the catalogue cache once it
+/// is older than `INDEX_TTL`
+pub async fn metadata_index(language: &str) -> Result<Arc<MetadataIndex>, String> {
+    if let Some(built) = INDEX.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        if built.language == language && built.built_at.elapsed() < INDEX_TTL {
+            return Ok(built.index.clone());
+        }
+    }
+
+    let resources = metadata_service().fetch_all_metadata().await.map_err(|e| {
+        eprintln!("❌ Error fetching metadata: {}", e);
+        format!("Failed to fetch metadata: {}", e)
+    })?;
+
+    let mut index = build_from_cache(&resources, language).await;
+    if index.games_counted < SEED_MIN_GAMES && !index.offline {
+        seed_catalogue_cache().await;
+        index = build_from_cache(&resources, language).await;
+    }
+    println!("🗂️ Indexed filter metadata from {} cached games", index.games_counted);
+
+    let index = Arc::new(index);
+    *INDEX.lock().unwrap_or_else(|e| e.into_inner()) = Some(BuiltIndex {
+        language: language.to_string(),
+        built_at: Instant::now(),
+        index: index.clone(),
+    });
+    Ok(index)
+}
+
+/// Count filter values across the catalogue cache. Without a cache every count is zero.
+async fn build_from_cache(resources: &MetadataResources, language: &str) -> MetadataIndex {
+    let responses = match shared_database() {
+        Ok(db) => db.with_read_connection_async(CatalogueCacheOperations::response_bodies).await,
+        Err(e) => Err(e),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("⚠️  Filter counts unavailable: {}", e);
+        Vec::new()
+    });
+
+    let (resources, language) = (resources.clone(), language.to_string());
+    tokio::task::spawn_blocking(move || MetadataIndex::build(&resources, &language, &responses))
+        .await
+        .unwrap_or_else(|_| MetadataIndex::build(&MetadataResources::default(), "en", &[]))
+}
+
+/// Fetch the first catalogue pages so they are in the cache to be counted
+async fn seed_catalogue_cache() {
+    let first = match CataloguePageRequest::new(&SearchFilters::default(), 1, MAX_PAGE_SIZE, CatalogueSort::default()) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("⚠️  Cannot seed filter counts: {}", e);
+            return;
+        }
+    };
+
+    let provider = catalogue_provider();
+    let pages: Vec<_> = catalogue_pages(provider.as_ref(), first).take(SEED_PAGES).collect().await;
+    if let Some(Err(e)) = pages.last() {
+        eprintln!("⚠️  Stopped seeding filter counts: {}", e);
+    }
+}
+
+/// Search genre, tag, developer or publisher values by label prefix, most popular first
+#[command]
+pub async fn search_filter_values(
+    kind: FilterKind,
+    query: Option<String>,
+    page: Option<u32>,
+    items_per_page: Option<u32>,
+    language: Option<String>,
+) -> Result<FilterValuesPage, String> {
+    let index = metadata_index(language.as_deref().unwrap_or("en")).await?;
+    index
+        .search(kind, query.as_deref().unwrap_or(""), page.unwrap_or(1), items_per_page.unwrap_or(50))
+        .map_err(|e| format!("Failed to search filter values: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_resources() -> MetadataResources {
+        MetadataResources {
+            genres: serde_json::from_str(include_str!("../tests/fixtures/hydra/steam-genres.json")).unwrap(),
+            tags: serde_json::from_str(include_str!("../tests/fixtures/hydra/steam-user-tags.json")).unwrap(),
+            developers: serde_json::from_str(include_str!("../tests/fixtures/hydra/steam-developers.json")).unwrap(),
+            publishers: serde_json::from_str(include_str!("../tests/fixtures/hydra/steam-publishers.json")).unwrap(),
+            offline: false,
+        }
+    }
+
+    /// The fixture catalogue as two cached responses that overlap
+    fn fixture_responses() -> Vec<String> {
+        let games: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("../tests/fixtures/hydra/catalogue.json")).unwrap();
+        vec![
+            serde_json::json!({ "edges": &games[..8], "count": games.len() }).to_string(),
+            serde_json::json!({ "edges": &games[4..], "count": games.len() }).to_string(),
+        ]
+    }
+
+    fn labels(items: &[FilterItem]) -> Vec<&str> {
+        items.iter().map(|item| item.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filters_are_ranked_by_catalogue_counts() {
+        let index = MetadataIndex::build(&fixture_resources(), "pt-BR", &fixture_responses());
+        assert_eq!(index.games_counted, 12);
+
+        let metadata = index.filter_metadata();
+        assert_eq!(labels(&metadata.genres), ["Ação", "Indie", "Aventura", "RPG", "Estratégia", "Simulação"]);
+        assert_eq!(metadata.genres[0].value, "Action");
+        assert_eq!(metadata.genres[0].count, Some(7));
+
+        assert_eq!(labels(&metadata.tags[..5]), ["Um jogador", "Ação", "Cooperativo", "Indie", "Mundo Aberto"]);
+        assert_eq!(metadata.tags[0].value, "4182");
+        assert_eq!(metadata.tags[0].count, Some(5));
+        assert_eq!(labels(&metadata.developers[..3]), ["Valve", "CD PROJEKT RED", "ConcernedApe"]);
+        assert_eq!(metadata.developers[0].count, Some(4));
+        assert_eq!(labels(&metadata.publishers[..3]), ["Valve", "CD PROJEKT RED", "2K"]);
+        assert_eq!(metadata.publishers[2].count, Some(1));
+    }
+
+    #[test]
+    fn test_prefix_search_is_paginated() {
+        let index = MetadataIndex::build(&fixture_resources(), "en", &fixture_responses());
+
+        let page = index.search(FilterKind::Developers, " c", 1, 10).unwrap();
+        assert_eq!(labels(&page.items), ["CD PROJEKT RED", "ConcernedApe"]);
+        assert!(!page.pagination.has_next_page);
+
+        let page = index.search(FilterKind::Developers, "", 2, 3).unwrap();
+        assert_eq!(labels(&page.items), ["Firaxis Games", "Iron Gate AB", "Re-Logic"]);
+        assert_eq!(page.pagination.total_items, 8);
+        assert_eq!(page.pagination.total_pages, 3);
+        assert!(page.pagination.has_next_page && page.pagination.has_prev_page);
+
+        assert!(index.search(FilterKind::Publishers, "zzz", 1, 10).unwrap().items.is_empty());
+        assert_eq!(
+            index.search(FilterKind::Tags, "", 0, 10).map(|page| page.items.len()),
+            Err(CatalogueError::InvalidPage(0))
+        );
+        assert_eq!(
+            index.search(FilterKind::Tags, "", 1, 500).map(|page| page.items.len()),
+            Err(CatalogueError::InvalidPageSize(500))
+        );
+    }
+}
@@ -1,14 +1,28 @@
-use reqwest::Client;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tauri::command;
+use crate::catalogue_backend::metadata_service;
+use crate::connectivity::ConnectivityMonitor;
+use crate::database::cache_metrics::{self, CACHE_METRICS};
+use crate::database::catalogue_cache_models::CachedMetadataResource;
+use crate::database::catalogue_cache_operations::CatalogueCacheOperations;
+use crate::database::ttl_config::TtlConfig;
+use crate::database::SharedDatabase;
+use crate::metadata_index::metadata_index;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct MetadataResources {
     pub genres: HashMap<String, Vec<String>>,
     pub tags: HashMap<String, HashMap<String, u32>>,
     pub developers: Vec<String>,
     pub publishers: Vec<String>,
+    /// Answered from the cache only because the app is offline
+    #[serde(default)]
+    pub offline: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,9 +31,11 @@ pub struct FilterMetadata {
     pub tags: Vec<FilterItem>,
     pub developers: Vec<FilterItem>,
     pub publishers: Vec<FilterItem>,
+    #[serde(default)]
+    pub offline: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilterItem {
     pub label: String,
     pub value: String,
@@ -29,21 +45,146 @@ pub struct FilterItem {
 pub struct MetadataService {
     client: Client,
     base_url: String,
+    connectivity: Arc<ConnectivityMonitor>,
+    // The JSON files are cached here when set
+    cache: Option<SharedDatabase>,
 }
 
 impl MetadataService {
-    pub fn new() -> Self {
+    /// Service reading the assets host at `base_url`, reporting reachability to `connectivity`.
+    /// The app's instance comes from `catalogue_backend::metadata_service`.
+    pub fn with_base_url(base_url: &str, connectivity: Arc<ConnectivityMonitor>) -> Self {
         Self {
             client: Client::new(),
-            base_url: "https://assets.hydralauncher.gg".to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            connectivity,
+            cache: None,
+        }
+    }
+
+    /// Cache the JSON files in `db`
+    pub fn with_cache(mut self, db: SharedDatabase) -> Self {
+        self.cache = Some(db);
+        self
+    }
+
+    /// A metadata JSON file, from the cache while it is fresh, otherwise revalidated with its
+    /// ETag/Last-Modified. An expired copy is still used offline or when the request fails.
+    async fn fetch_resource<T: DeserializeOwned>(&self, name: &str) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        let cached = self.cached_resource(name).await;
+        if let Some(resource) = cached.as_ref().filter(|resource| !resource.is_expired()) {
+            if let Ok(value) = serde_json::from_str(&resource.body) {
+                CACHE_METRICS.record_hit(cache_metrics::METADATA_RESOURCES, false);
+                return Ok(value);
+            }
+        }
+
+        match self.download_resource(name, cached.as_ref()).await {
+            Ok(body) => {
+                CACHE_METRICS.record_miss(cache_metrics::METADATA_RESOURCES);
+                Ok(serde_json::from_str(&body)?)
+            }
+            Err(e) => match cached {
+                Some(resource) => {
+                    if self.connectivity.is_online() {
+                        eprintln!("⚠️  Using cached {} after request failed: {}", name, e);
+                    }
+                    CACHE_METRICS.record_hit(cache_metrics::METADATA_RESOURCES, true);
+                    Ok(serde_json::from_str(&resource.body)?)
+                }
+                None => {
+                    CACHE_METRICS.record_miss(cache_metrics::METADATA_RESOURCES);
+                    Err(e)
+                }
+            },
+        }
+    }
+
+    /// Conditional GET of a resource, caching the new body. Returns the current body.
+    async fn download_resource(&self, name: &str, cached: Option<&CachedMetadataResource>) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/{}", self.base_url, name);
+        let mut request = self.client.get(&url);
+        if let Some(cached) = cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = self.send(request).await?;
+        let now = chrono::Utc::now().timestamp();
+        let expires_at = now + TtlConfig::METADATA_RESOURCES;
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            let name = name.to_string();
+            self.with_cache_db(move |conn| CatalogueCacheOperations::resource_revalidated(conn, &name, now, expires_at))
+                .await;
+            return Ok(cached.body.clone());
+        }
+        if !response.status().is_success() {
+            return Err(format!("{} returned status {}", url, response.status()).into());
+        }
+
+        let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let body = response.text().await?;
+        // Only cache bodies that parse
+        serde_json::from_str::<serde_json::Value>(&body)?;
+
+        let resource = CachedMetadataResource {
+            name: name.to_string(),
+            body: body.clone(),
+            etag,
+            last_modified,
+            cached_at: now,
+            expires_at,
+        };
+        self.with_cache_db(move |conn| CatalogueCacheOperations::upsert_resource(conn, &resource)).await;
+        Ok(body)
+    }
+
+    async fn cached_resource(&self, name: &str) -> Option<CachedMetadataResource> {
+        let name = name.to_string();
+        self.with_cache_db(move |conn| CatalogueCacheOperations::get_resource(conn, &name)).await?
+    }
+
+    /// Run `f` against the cache database, if there is one. Cache errors are logged, not returned.
+    async fn with_cache_db<T, F>(&self, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&rusqlite::Connection) -> anyhow::Result<T> + Send + 'static,
+    {
+        let db = self.cache.as_ref()?;
+        match db.with_connection_async(f).await {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("⚠️  Metadata cache error: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Send a request, failing fast while offline
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+        self.connectivity.ensure_online("filter metadata")?;
+
+        match request.send().await {
+            Ok(response) => {
+                self.connectivity.record_success();
+                Ok(response)
+            }
+            Err(e) => {
+                self.connectivity.record_failure(&e);
+                Err(e.into())
+            }
         }
     }
 
     /// Fetch Steam genres from external resources
     pub async fn fetch_steam_genres(&self) -> Result<HashMap<String, Vec<String>>, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/steam-genres.json", self.base_url);
-        let response = self.client.get(&url).send().await?;
-        let genres: HashMap<String, Vec<String>> = response.json().await?;
+        let genres: HashMap<String, Vec<String>> = self.fetch_resource("steam-genres.json").await?;
         
         println!("✅ Fetched {} language genres", genres.keys().len());
         Ok(genres)
@@ -51,9 +192,7 @@ impl MetadataService {
 
     /// Fetch Steam user tags from external resources
     pub async fn fetch_steam_tags(&self) -> Result<HashMap<String, HashMap<String, u32>>, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/steam-user-tags.json", self.base_url);
-        let response = self.client.get(&url).send().await?;
-        let tags: HashMap<String, HashMap<String, u32>> = response.json().await?;
+        let tags: HashMap<String, HashMap<String, u32>> = self.fetch_resource("steam-user-tags.json").await?;
         
         println!("✅ Fetched {} language tags", tags.keys().len());
         Ok(tags)
@@ -61,9 +200,7 @@ impl MetadataService {
 
     /// Fetch Steam developers from external resources
     pub async fn fetch_steam_developers(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/steam-developers.json", self.base_url);
-        let response = self.client.get(&url).send().await?;
-        let developers: Vec<String> = response.json().await?;
+        let developers: Vec<String> = self.fetch_resource("steam-developers.json").await?;
         
         println!("✅ Fetched {} developers", developers.len());
         Ok(developers)
@@ -71,9 +208,7 @@ impl MetadataService {
 
     /// Fetch Steam publishers from external resources
     pub async fn fetch_steam_publishers(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-        let url = format!("{}/steam-publishers.json", self.base_url);
-        let response = self.client.get(&url).send().await?;
-        let publishers: Vec<String> = response.json().await?;
+        let publishers: Vec<String> = self.fetch_resource("steam-publishers.json").await?;
         
         println!("✅ Fetched {} publishers", publishers.len());
         Ok(publishers)
@@ -95,81 +230,18 @@ impl MetadataService {
             tags,
             developers,
             publishers,
+            offline: self.connectivity.is_offline(),
         };
 
         println!("✅ All metadata resources fetched successfully");
         Ok(metadata)
     }
-
-    /// Generate filter metadata for UI (popular/top items)
-    pub fn generate_filter_metadata(&self, metadata: &MetadataResources, language: &str) -> FilterMetadata {
-        let default_lang = "en";
-        let lang = if metadata.genres.contains_key(language) { language } else { default_lang };
-
-        // Process genres
-        let genres = metadata.genres.get(lang)
-            .unwrap_or(&Vec::new())
-            .iter()
-            .take(20) // Top 20 genres
-            .map(|genre| FilterItem {
-                label: genre.clone(),
-                value: genre.clone(),
-                count: None,
-            })
-            .collect();
-
-        // Process tags
-        let tags = metadata.tags.get(lang)
-            .unwrap_or(&HashMap::new())
-            .iter()
-            .take(30) // Top 30 tags
-            .map(|(name, id)| FilterItem {
-                label: name.clone(),
-                value: id.to_string(),
-                count: None,
-            })
-            .collect();
-
-        // Process developers (top 50)
-        let developers = metadata.developers
-            .iter()
-            .take(50)
-            .map(|dev| FilterItem {
-                label: dev.clone(),
-                value: dev.clone(),
-                count: None,
-            })
-            .collect();
-
-        // Process publishers (top 50)
-        let publishers = metadata.publishers
-            .iter()
-            .take(50)
-            .map(|pub_name| FilterItem {
-                label: pub_name.clone(),
-                value: pub_name.clone(),
-                count: None,
-            })
-            .collect();
-
-        FilterMetadata {
-            genres,
-            tags,
-            developers,
-            publishers,
-        }
-    }
-}
-
-// Global metadata service instance
-lazy_static::lazy_static! {
-    static ref METADATA_SERVICE: MetadataService = MetadataService::new();
 }
 
 /// Tauri command to fetch all metadata
 #[command]
 pub async fn get_metadata_resources() -> Result<MetadataResources, String> {
-    METADATA_SERVICE
+    metadata_service()
         .fetch_all_metadata()
         .await
         .map_err(|e| {
@@ -182,16 +254,8 @@ pub async fn get_metadata_resources() -> Result<MetadataResources, String> {
 #[command]
 pub async fn get_filter_metadata(language: Option<String>) -> Result<FilterMetadata, String> {
     let lang = language.as_deref().unwrap_or("en");
-    
-    let metadata = METADATA_SERVICE
-        .fetch_all_metadata()
-        .await
-        .map_err(|e| {
-            eprintln!("❌ Error fetching metadata: {}", e);
-            format!("Failed to fetch metadata: {}", e)
-        })?;
 
-    let filter_metadata = METADATA_SERVICE.generate_filter_metadata(&metadata, lang);
+    let filter_metadata = metadata_index(lang).await?.filter_metadata();
     
     println!("✅ Generated filter metadata for language: {}", lang);
     Ok(filter_metadata)
@@ -202,7 +266,7 @@ pub async fn get_filter_metadata(language: Option<String>) -> Result<FilterMetad
 pub async fn test_metadata_connection() -> Result<String, String> {
     println!("🔍 Testing metadata connection...");
     
-    match METADATA_SERVICE.fetch_steam_genres().await {
+    match metadata_service().fetch_steam_genres().await {
         Ok(genres) => {
             let genre_count = genres.values().map(|v| v.len()).sum::<usize>();
             Ok(format!("✅ Metadata connection successful! Found {} genres across {} languages", 
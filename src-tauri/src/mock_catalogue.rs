@@ -0,0 +1,549 @@
+//! In-process stand-in for the Hydra catalogue API and the metadata assets host, serving the
+//! fixtures in `tests/fixtures/hydra` so catalogue code can be tested without the live services.
+//! The checked-in fixtures are hand-written in the shape of the real responses; the ignored
+//! `record_hydra_fixtures` test replaces them with recorded ones.
+//!
+//! `POST /catalogue/search` filters the fixture catalogue the way the API does: the title is a
+//! case-insensitive substring, every requested genre and tag must match and any requested
+//! developer or publisher matches, in fixture order; there is no sort parameter. A `take` below
+//! 5 is rejected with 400.
+//! Every 200 carries an ETag, and a matching `If-None-Match` gets 304.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const CATALOGUE: &str = include_str!("../tests/fixtures/hydra/catalogue.json");
+
+const METADATA_FILES: &[(&str, &str)] = &[
+    ("steam-genres.json", include_str!("../tests/fixtures/hydra/steam-genres.json")),
+    ("steam-user-tags.json", include_str!("../tests/fixtures/hydra/steam-user-tags.json")),
+    ("steam-developers.json", include_str!("../tests/fixtures/hydra/steam-developers.json")),
+    ("steam-publishers.json", include_str!("../tests/fixtures/hydra/steam-publishers.json")),
+];
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub if_none_match: Option<String>,
+    /// JSON body, `Null` if there was none
+    pub body: Value,
+}
+
+#[derive(Default)]
+struct MockState {
+    requests: Vec<RecordedRequest>,
+    fail_with: Option<u16>,
+}
+
+pub struct MockCatalogueServer {
+    base_url: String,
+    state: Arc<Mutex<MockState>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockCatalogueServer {
+    /// Listen on a free localhost port until dropped
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind mock catalogue server");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let task = tokio::spawn({
+            let state = state.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, state.clone()));
+                }
+            }
+        });
+
+        Self { base_url, state, task }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Answer every request with `status` until called again with `None`
+    pub fn fail_with(&self, status: Option<u16>) {
+        self.state.lock().unwrap().fail_with = status;
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockCatalogueServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Handle one request on a `Connection: close` connection
+async fn serve(mut stream: TcpStream, state: Arc<Mutex<MockState>>) {
+    let request = match read_request(&mut stream).await {
+        Some(request) => request,
+        None => return,
+    };
+
+    let fail_with = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+        state.fail_with
+    };
+    let (status, body, etag) = match fail_with {
+        Some(status) => (status, json!({ "message": "injected failure" }).to_string(), None),
+        None => respond(&request),
+    };
+
+    write_response(&mut stream, status, &body, etag.as_deref()).await;
+}
+
+/// Write a JSON response and close the connection
+pub(crate) async fn write_response(stream: &mut TcpStream, status: u16, body: &str, etag: Option<&str>) {
+    let reason = match status {
+        200 => "OK",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Error",
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        reason,
+        body.len()
+    );
+    if let Some(etag) = etag {
+        head.push_str(&format!("ETag: {}\r\n", etag));
+    }
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+pub(crate) async fn read_request(stream: &mut TcpStream) -> Option<RecordedRequest> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let mut content_length = 0;
+    let mut if_none_match = None;
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "if-none-match" => if_none_match = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    while buf.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+    let body = serde_json::from_slice(&buf[header_end..header_end + content_length]).unwrap_or(Value::Null);
+
+    Some(RecordedRequest { method, path, if_none_match, body })
+}
+
+/// Status, body and ETag for a request
+fn respond(request: &RecordedRequest) -> (u16, String, Option<String>) {
+    let body = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/catalogue/search") => match search(&request.body) {
+            Ok(body) => body,
+            Err(message) => return (400, json!({ "message": message }).to_string(), None),
+        },
+        ("GET", path) => {
+            let name = path.trim_start_matches('/');
+            match METADATA_FILES.iter().find(|(file, _)| *file == name) {
+                Some((_, body)) => body.to_string(),
+                None => return (404, json!({ "message": "Not found" }).to_string(), None),
+            }
+        }
+        _ => return (404, json!({ "message": "Not found" }).to_string(), None),
+    };
+
+    let etag = format!("\"{}\"", &hex::encode(Sha1::digest(body.as_bytes()))[..16]);
+    if request.if_none_match.as_deref() == Some(etag.as_str()) {
+        return (304, String::new(), Some(etag));
+    }
+    (200, body, Some(etag))
+}
+
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct SearchPayload {
+    title: String,
+    take: u32,
+    skip: u32,
+    genres: Vec<String>,
+    tags: Vec<u64>,
+    developers: Vec<String>,
+    publishers: Vec<String>,
+}
+
+impl Default for SearchPayload {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            take: 20,
+            skip: 0,
+            genres: Vec::new(),
+            tags: Vec::new(),
+            developers: Vec::new(),
+            publishers: Vec::new(),
+        }
+    }
+}
+
+fn search(body: &Value) -> Result<String, String> {
+    let payload: SearchPayload = serde_json::from_value(body.clone()).map_err(|e| e.to_string())?;
+    if payload.take < 5 {
+        return Err("take must be at least 5".to_string());
+    }
+
+    let strings = |game: &Value, field: &str| -> Vec<String> {
+        game[field]
+            .as_array()
+            .map(|values| values.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+            .unwrap_or_default()
+    };
+    let title = payload.title.to_lowercase();
+
+    let games: Vec<Value> = serde_json::from_str(CATALOGUE).expect("Invalid catalogue fixture");
+    let matching: Vec<Value> = games
+        .into_iter()
+        .filter(|game| game["title"].as_str().unwrap_or_default().to_lowercase().contains(&title))
+        .filter(|game| {
+            let genres = strings(game, "genres");
+            payload.genres.iter().all(|genre| genres.contains(genre))
+        })
+        .filter(|game| {
+            let tags: Vec<u64> = game["tags"].as_array().map(|t| t.iter().filter_map(Value::as_u64).collect()).unwrap_or_default();
+            payload.tags.iter().all(|tag| tags.contains(tag))
+        })
+        .filter(|game| {
+            let developers = strings(game, "developers");
+            payload.developers.is_empty() || payload.developers.iter().any(|d| developers.contains(d))
+        })
+        .filter(|game| {
+            let publishers = strings(game, "publishers");
+            payload.publishers.is_empty() || payload.publishers.iter().any(|p| publishers.contains(p))
+        })
+        .collect();
+
+    let count = matching.len();
+    let edges: Vec<Value> = matching.into_iter().skip(payload.skip as usize).take(payload.take as usize).collect();
+    Ok(json!({ "edges": edges, "count": count }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogue_backend::{catalogue_pages, CatalogueEndpointSettings, CatalogueEndpoints, CatalogueProvider};
+    use crate::connectivity::{closed_port_url, ConnectivityMonitor};
+    use crate::database::cache_service::Freshness;
+    use crate::database::{test_database, SharedDatabase};
+    use crate::hydra_api::{
+        CatalogueError, CataloguePageRequest, CatalogueQuery, CatalogueSort, HydraApi, SearchFilters, MAX_PAGE_SIZE,
+    };
+    use futures::StreamExt;
+    use reqwest::StatusCode;
+    use crate::metadata_service::MetadataService;
+
+    fn searches(server: &MockCatalogueServer) -> Vec<RecordedRequest> {
+        server.requests().into_iter().filter(|r| r.path == "/catalogue/search").collect()
+    }
+
+    fn page(filters: &SearchFilters, page: u32, items_per_page: u32) -> CataloguePageRequest {
+        CataloguePageRequest::new(filters, page, items_per_page, CatalogueSort::default()).unwrap()
+    }
+
+    fn expire(db: &SharedDatabase, table: &'static str) {
+        db.with_connection(move |conn| {
+            conn.execute(&format!("UPDATE {} SET expires_at = 0", table), [])?;
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_catalogue_pagination() {
+        let server = MockCatalogueServer::start().await;
+        let (_dir, db) = test_database();
+        let connectivity = Arc::new(ConnectivityMonitor::with_probe_url(&closed_port_url()));
+        let provider: Arc<dyn CatalogueProvider> =
+            Arc::new(HydraApi::with_base_url(server.base_url(), connectivity).with_cache(db));
+
+        let first = provider.search(&page(&SearchFilters::default(), 1, 5)).await.unwrap();
+        assert_eq!(first.games.len(), 5);
+        assert_eq!(first.games[0].title, "Half-Life 2");
+        assert_eq!(first.pagination.total_items, 12);
+        assert_eq!(first.pagination.total_pages, 3);
+        assert!(first.pagination.has_next_page && !first.pagination.has_prev_page);
+
+        let last = provider.search(&page(&SearchFilters::default(), 3, 5)).await.unwrap();
+        assert_eq!(last.games.len(), 2);
+        assert_eq!(last.games[1].title, "Factorio");
+        assert!(!last.pagination.has_next_page && last.pagination.has_prev_page);
+
+        // Pages come from the cache until they expire
+        provider.search(&page(&SearchFilters::default(), 1, 5)).await.unwrap();
+        assert_eq!(searches(&server).len(), 2);
+
+        // Pages smaller than the API minimum are fetched at the minimum and trimmed
+        let listing = provider.catalogue_list(Some(2), None).await.unwrap();
+        assert_eq!(listing.edges.len(), 2);
+        assert_eq!(searches(&server).last().unwrap().body["take"], 5);
+
+        let small = provider.search(&page(&SearchFilters::default(), 2, 3)).await.unwrap();
+        let titles: Vec<_> = small.games.into_iter().map(|g| g.title).collect();
+        assert_eq!(titles, vec!["Left 4 Dead 2", "Stardew Valley", "Hollow Knight"]);
+        assert_eq!(small.pagination.total_pages, 4);
+    }
+
+    #[tokio::test]
+    async fn test_catalogue_pages_stream_in_sort_order() {
+        let server = MockCatalogueServer::start().await;
+        let (_dir, db) = test_database();
+        let connectivity = Arc::new(ConnectivityMonitor::with_probe_url(&closed_port_url()));
+        let provider = HydraApi::with_base_url(server.base_url(), connectivity).with_cache(db);
+
+        let by_title = CataloguePageRequest::new(&SearchFilters::default(), 1, 5, CatalogueSort::Title).unwrap();
+        let pages: Vec<_> = catalogue_pages(&provider, by_title).collect().await;
+        assert_eq!(pages.len(), 3);
+        let pages: Vec<Vec<String>> = pages
+            .into_iter()
+            .map(|page| page.unwrap().games.into_iter().map(|g| g.title).collect())
+            .collect();
+        // The whole catalogue is sorted before it is paged, not each page on its own
+        assert_eq!(pages[0], vec!["Cyberpunk 2077", "Factorio", "Half-Life 2", "Half-Life: Alyx", "Hollow Knight"]);
+        let titles: Vec<String> = pages.concat();
+        let mut sorted = titles.clone();
+        sorted.sort_by_key(|title| title.to_lowercase());
+        assert_eq!(titles, sorted);
+        assert_eq!(titles.len(), 12);
+        assert!(searches(&server).iter().all(|r| r.body.get("sortBy").is_none() && r.body["take"] == MAX_PAGE_SIZE));
+
+        // Every order is served from the same cached chunk
+        let requests = searches(&server).len();
+        let first_titles = |sort: CatalogueSort| {
+            let request = CataloguePageRequest::new(&SearchFilters::default(), 1, 3, sort).unwrap();
+            let provider = &provider;
+            async move { provider.search(&request).await.unwrap().games.into_iter().map(|g| g.title).collect::<Vec<_>>() }
+        };
+        assert_eq!(first_titles(CatalogueSort::ReleaseDate).await, vec!["Valheim", "Cyberpunk 2077", "Factorio"]);
+        assert_eq!(first_titles(CatalogueSort::Popularity).await, vec!["Terraria", "Stardew Valley", "Portal 2"]);
+        let second = CataloguePageRequest::new(&SearchFilters::default(), 2, 3, CatalogueSort::Popularity).unwrap();
+        let titles: Vec<_> = provider.search(&second).await.unwrap().games.into_iter().map(|g| g.title).collect();
+        assert_eq!(titles, vec!["Hollow Knight", "The Witcher 3: Wild Hunt", "Left 4 Dead 2"]);
+        assert_eq!(searches(&server).len(), requests);
+
+        // Relevance keeps the API's order
+        let relevance = CataloguePageRequest::new(&SearchFilters::default(), 1, 5, CatalogueSort::Relevance).unwrap();
+        let first = provider.search(&relevance).await.unwrap();
+        assert_eq!(first.games[0].title, "Half-Life 2");
+        assert_eq!(first.games[3].title, "Left 4 Dead 2");
+
+        // A failing page ends the stream with its error
+        server.fail_with(Some(503));
+        let portal = SearchFilters { title: "Portal".to_string(), ..Default::default() };
+        let uncached = CataloguePageRequest::new(&portal, 1, 7, CatalogueSort::Title).unwrap();
+        let pages: Vec<_> = catalogue_pages(&provider, uncached).collect().await;
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].as_ref().unwrap_err(), &CatalogueError::Status(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[tokio::test]
+    async fn test_catalogue_filters() {
+        let server = MockCatalogueServer::start().await;
+        let connectivity = Arc::new(ConnectivityMonitor::with_probe_url(&closed_port_url()));
+        let provider: Arc<dyn CatalogueProvider> = Arc::new(HydraApi::with_base_url(server.base_url(), connectivity));
+
+        let indie = SearchFilters {
+            genres: vec!["Indie".to_string()],
+            tags: vec!["492".to_string()],
+            ..Default::default()
+        };
+        let titles: Vec<_> = provider.search(&page(&indie, 1, 20)).await.unwrap().games.into_iter().map(|g| g.title).collect();
+        assert_eq!(titles, vec!["Stardew Valley", "Hollow Knight", "Terraria", "Factorio"]);
+
+        let witcher = SearchFilters {
+            title: "  witcher ".to_string(),
+            developers: vec!["CD PROJEKT RED".to_string(), " CD PROJEKT RED".to_string()],
+            ..Default::default()
+        };
+        let response = provider.search(&page(&witcher, 1, 20)).await.unwrap();
+        assert_eq!(response.pagination.total_items, 1);
+        assert_eq!(response.games[0].object_id, "292030");
+
+        // Filters reach the API normalized, with tags as numbers
+        let sent: Vec<_> = searches(&server).into_iter().map(|r| r.body).collect();
+        assert_eq!(sent[0]["tags"], json!([492]));
+        assert_eq!(sent[1]["title"], "witcher");
+        assert_eq!(sent[1]["developers"], json!(["CD PROJEKT RED"]));
+
+        let none = SearchFilters { title: "half".to_string(), genres: vec!["RPG".to_string()], ..Default::default() };
+        let response = provider.search(&page(&none, 1, 20)).await.unwrap();
+        assert!(response.games.is_empty());
+        assert_eq!(response.pagination.total_items, 0);
+    }
+
+    #[tokio::test]
+    async fn test_catalogue_errors_fall_back_to_cache() {
+        let server = MockCatalogueServer::start().await;
+        let (_dir, db) = test_database();
+        let connectivity = Arc::new(ConnectivityMonitor::with_probe_url(&closed_port_url()));
+        let api = HydraApi::with_base_url(server.base_url(), connectivity.clone()).with_cache(db.clone());
+        let query = CatalogueQuery::new(&SearchFilters::default(), 5, 0).unwrap();
+
+        // Server errors fail uncached queries but say nothing about connectivity
+        server.fail_with(Some(500));
+        let error = api.search_cached(&query).await.unwrap_err();
+        assert_eq!(error, CatalogueError::Status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(connectivity.is_online());
+
+        // The API rejects pages smaller than 5
+        server.fail_with(None);
+        let too_small = CatalogueQuery::new(&SearchFilters::default(), 2, 0).unwrap();
+        let error = api.search_cached(&too_small).await.unwrap_err();
+        assert_eq!(error, CatalogueError::Status(StatusCode::BAD_REQUEST));
+
+        api.search_cached(&query).await.unwrap();
+        expire(&db, "catalogue_cache");
+
+        // An expired page is served stale while the server is failing...
+        server.fail_with(Some(503));
+        let stale = api.search_cached(&query).await.unwrap();
+        assert_eq!(stale.freshness, Freshness::Stale);
+        assert!(!stale.offline);
+        assert_eq!(stale.response.edges.len(), 5);
+
+        // ...and revalidated with its ETag once it recovers
+        server.fail_with(None);
+        let revalidated = api.search_cached(&query).await.unwrap();
+        assert_eq!(revalidated.freshness, Freshness::Fresh);
+        assert_eq!(revalidated.response.count, 12);
+        assert!(searches(&server).last().unwrap().if_none_match.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_metadata_resources_revalidate() {
+        let server = MockCatalogueServer::start().await;
+        let (_dir, db) = test_database();
+        let connectivity = Arc::new(ConnectivityMonitor::with_probe_url(&closed_port_url()));
+        let service = MetadataService::with_base_url(server.base_url(), connectivity.clone()).with_cache(db.clone());
+
+        let metadata = service.fetch_all_metadata().await.unwrap();
+        assert_eq!(metadata.genres["en"].len(), 6);
+        assert_eq!(metadata.tags["en"]["Indie"], 492);
+        assert_eq!(metadata.developers.len(), 8);
+        assert!(server.requests().iter().all(|r| r.if_none_match.is_none()));
+
+        expire(&db, "metadata_resources");
+        let revalidated = service.fetch_all_metadata().await.unwrap();
+        assert_eq!(revalidated.publishers, metadata.publishers);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 8);
+        assert!(requests[4..].iter().all(|r| r.if_none_match.is_some()));
+
+        // A failing host falls back to the expired copies; without them the error surfaces
+        server.fail_with(Some(503));
+        expire(&db, "metadata_resources");
+        assert_eq!(service.fetch_all_metadata().await.unwrap().developers, metadata.developers);
+
+        server.fail_with(Some(404));
+        let uncached = MetadataService::with_base_url(server.base_url(), connectivity);
+        let error = uncached.fetch_all_metadata().await.unwrap_err();
+        assert!(error.to_string().contains("404"), "{}", error);
+    }
+
+    /// Overwrites `tests/fixtures/hydra` with responses from the live services (or the
+    /// `ZENITH_HYDRA_API_URL` / `ZENITH_METADATA_URL` overrides). See the README there.
+    #[tokio::test]
+    #[ignore = "needs network; run with ZENITH_RECORD_HYDRA_FIXTURES=1 and --ignored"]
+    async fn record_hydra_fixtures() {
+        if std::env::var_os("ZENITH_RECORD_HYDRA_FIXTURES").is_none() {
+            return;
+        }
+        let endpoints = CatalogueEndpoints::resolve(&CatalogueEndpointSettings::default());
+        let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hydra");
+        let client = reqwest::Client::new();
+
+        // The first 12 games of the unfiltered catalogue, as the API returns them
+        let response: Value = client
+            .post(format!("{}/catalogue/search", endpoints.hydra_api_url))
+            .json(&json!({
+                "title": "", "take": 12, "skip": 0, "downloadSourceFingerprints": [],
+                "publishers": [], "genres": [], "developers": [], "tags": [],
+            }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .expect("Catalogue search failed")
+            .json()
+            .await
+            .expect("Catalogue search is not JSON");
+        let games = response["edges"].as_array().expect("Catalogue search has no edges").clone();
+        let named = |field: &str| -> Vec<Value> {
+            games.iter().filter_map(|game| game[field].as_array()).flatten().cloned().collect()
+        };
+        let (developers, publishers) = (named("developers"), named("publishers"));
+
+        for (name, _) in METADATA_FILES {
+            let mut body: Value = client
+                .get(format!("{}/{}", endpoints.metadata_url, name))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .unwrap_or_else(|e| panic!("Failed to fetch {}: {}", name, e))
+                .json()
+                .await
+                .unwrap_or_else(|e| panic!("{} is not JSON: {}", name, e));
+            // The full developer and publisher lists are huge; keep the recorded games' ones
+            if let Some(keep) = match *name {
+                "steam-developers.json" => Some(&developers),
+                "steam-publishers.json" => Some(&publishers),
+                _ => None,
+            } {
+                body.as_array_mut().expect("Expected a list").retain(|value| keep.contains(value));
+            }
+            std::fs::write(fixtures.join(name), serde_json::to_string_pretty(&body).unwrap()).unwrap();
+        }
+        std::fs::write(fixtures.join("catalogue.json"), serde_json::to_string_pretty(&games).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,275 @@
+//! Background price checks for the wishlist. Prices come from appdetails `price_overview` in
+//! batches; a game dropping below its target price raises `wishlist-price-alert` and a desktop
+//! notification.
+
+use anyhow::Result;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Notify;
+
+use crate::connectivity::CONNECTIVITY;
+use crate::database::wishlist_models::{PriceAlert, PRICE_ALERT_EVENT};
+use crate::database::wishlist_operations::WishlistOperations;
+use crate::database::SharedDatabase;
+use crate::steam_store::{fetch_price_overviews, APPDETAILS_URL};
+
+/// App ids per appdetails request
+const BATCH_SIZE: usize = 50;
+
+/// Pause between batches, to stay clear of the store's rate limit
+const BATCH_DELAY: Duration = Duration::from_secs(2);
+
+/// How often the watcher looks for prices due a check
+const POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+lazy_static::lazy_static! {
+    static ref WAKE: Notify = Notify::new();
+}
+
+/// Look for due prices now instead of at the next poll, e.g. after a game was wishlisted
+pub fn wake_price_watch() {
+    WAKE.notify_one();
+}
+
+pub struct PriceWatcher {
+    appdetails_url: String,
+}
+
+impl PriceWatcher {
+    /// Watcher querying the appdetails endpoint at `appdetails_url`
+    pub fn with_url(appdetails_url: &str) -> Self {
+        Self {
+            appdetails_url: appdetails_url.to_string(),
+        }
+    }
+
+    /// Fetch and record the price of every wishlisted game due a check, passing each alert
+    /// raised to `on_alert`. Stops at the first failed request; the remaining games stay due.
+    /// Returns how many games were checked.
+    pub async fn check_due_prices<F>(&self, db: &SharedDatabase, on_alert: &F) -> Result<usize>
+    where
+        F: Fn(PriceAlert) + Sync,
+    {
+        let mut checked = 0;
+        loop {
+            let now = chrono::Utc::now().timestamp();
+            let app_ids = db
+                .with_read_connection_async(move |conn| WishlistOperations::due_for_check(conn, now, BATCH_SIZE))
+                .await?;
+            if app_ids.is_empty() {
+                return Ok(checked);
+            }
+            if checked > 0 {
+                tokio::time::sleep(BATCH_DELAY).await;
+            }
+
+            let prices = fetch_price_overviews(&self.appdetails_url, &app_ids).await?;
+            checked += app_ids.len();
+            let alerts = db
+                .with_connection_async(move |conn| {
+                    let mut alerts = Vec::new();
+                    for app_id in &app_ids {
+                        // Apps Steam does not know are recorded as checked without a price
+                        let price = prices.get(app_id).cloned().flatten();
+                        alerts.extend(WishlistOperations::record_price(conn, app_id, price.as_ref(), now)?);
+                    }
+                    Ok(alerts)
+                })
+                .await?;
+            alerts.into_iter().for_each(on_alert);
+        }
+    }
+}
+
+/// Check wishlist prices for the lifetime of the app, announcing alerts as they are raised.
+/// Waits while offline until connectivity returns.
+pub async fn run_price_watch(app: AppHandle, db: SharedDatabase) {
+    let watcher = PriceWatcher::with_url(APPDETAILS_URL);
+    println!("💰 Wishlist price watch started");
+
+    loop {
+        CONNECTIVITY.wait_until_online().await;
+
+        let announce = |alert: PriceAlert| announce_price_alert(&app, alert);
+        if let Err(e) = watcher.check_due_prices(&db, &announce).await {
+            eprintln!("⚠️  Wishlist price check failed: {}", e);
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            _ = WAKE.notified() => {}
+        }
+    }
+}
+
+fn announce_price_alert(app: &AppHandle, alert: PriceAlert) {
+    let name = alert.name.clone().unwrap_or_else(|| format!("App {}", alert.app_id));
+    let price = if alert.final_formatted.is_empty() {
+        format!("{} {}", alert.final_price, alert.currency)
+    } else {
+        alert.final_formatted.clone()
+    };
+    let body = if alert.discount_percent > 0 {
+        format!("Now {} ({}% off)", price, alert.discount_percent)
+    } else {
+        format!("Now {}", price)
+    };
+    println!("💰 Price alert for {}: {}", name, body);
+
+    if let Err(e) = app.notification().builder().title(format!("{} dropped below your target price", name)).body(body).show() {
+        eprintln!("⚠️  Failed to show price alert notification: {}", e);
+    }
+    let _ = app.emit(PRICE_ALERT_EVENT, alert);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database;
+    use crate::mock_catalogue::{read_request, write_response};
+    use serde_json::{json, Map, Value};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    /// Stand-in for the store's appdetails endpoint, answering `price_overview` requests from
+    /// a table of prices. Unlisted apps get `success: false`, listed ones without a price
+    /// `"data": []` (free games).
+    struct StubSteamStore {
+        url: String,
+        prices: Arc<Mutex<HashMap<String, Option<Value>>>>,
+        requests: Arc<Mutex<Vec<String>>>,
+        task: tokio::task::JoinHandle<()>,
+    }
+
+    impl StubSteamStore {
+        async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/api/appdetails", listener.local_addr().unwrap());
+            let prices = Arc::new(Mutex::new(HashMap::new()));
+            let requests = Arc::new(Mutex::new(Vec::new()));
+
+            let task = tokio::spawn({
+                let (prices, requests) = (prices.clone(), requests.clone());
+                async move {
+                    while let Ok((mut stream, _)) = listener.accept().await {
+                        let request = match read_request(&mut stream).await {
+                            Some(request) => request,
+                            None => continue,
+                        };
+                        requests.lock().unwrap().push(request.path.clone());
+                        let body = respond(&request.path, &prices.lock().unwrap());
+                        write_response(&mut stream, 200, &body, None).await;
+                    }
+                }
+            });
+
+            Self { url, prices, requests, task }
+        }
+
+        fn set_price(&self, app_id: &str, final_price: Option<i64>) {
+            let overview = final_price.map(|price| {
+                json!({
+                    "currency": "USD",
+                    "initial": 1999,
+                    "final": price,
+                    "discount_percent": 100 - price * 100 / 1999,
+                    "initial_formatted": "$19.99",
+                    "final_formatted": format!("${}.{:02}", price / 100, price % 100),
+                })
+            });
+            self.prices.lock().unwrap().insert(app_id.to_string(), overview);
+        }
+    }
+
+    impl Drop for StubSteamStore {
+        fn drop(&mut self) {
+            self.task.abort();
+        }
+    }
+
+    fn respond(path: &str, prices: &HashMap<String, Option<Value>>) -> String {
+        let query = path.split_once('?').map(|(_, query)| query).unwrap_or("");
+        let app_ids = query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("appids="))
+            .unwrap_or("");
+
+        let mut body = Map::new();
+        for app_id in app_ids.split(',') {
+            let entry = match prices.get(app_id) {
+                Some(Some(price)) => json!({ "success": true, "data": { "price_overview": price } }),
+                Some(None) => json!({ "success": true, "data": [] }),
+                None => json!({ "success": false }),
+            };
+            body.insert(app_id.to_string(), entry);
+        }
+        Value::Object(body).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_price_watch_records_prices_and_alerts_on_drop() {
+        let store = StubSteamStore::start().await;
+        store.set_price("620", Some(1999));
+        store.set_price("440", None);
+        // Missing everything but the currency, so it cannot be parsed
+        store.prices.lock().unwrap().insert("730".to_string(), Some(json!({ "currency": "USD" })));
+
+        let (_dir, db) = test_database();
+        db.with_connection(|conn| {
+            WishlistOperations::add(conn, "620", Some("Portal 2"), 0)?;
+            WishlistOperations::set_target_price(conn, "620", Some((1000, "USD")))?;
+            WishlistOperations::add(conn, "440", Some("Team Fortress 2"), 0)?;
+            WishlistOperations::add(conn, "730", Some("Counter-Strike 2"), 0)?;
+            WishlistOperations::add(conn, "999999", None, 0)
+        })
+        .unwrap();
+
+        let watcher = PriceWatcher::with_url(&store.url);
+        let alerts = Mutex::new(Vec::new());
+        let collect = |alert: PriceAlert| alerts.lock().unwrap().push(alert);
+
+        assert_eq!(watcher.check_due_prices(&db, &collect).await.unwrap(), 4);
+        assert!(alerts.lock().unwrap().is_empty());
+        let requests = store.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("filters=price_overview"));
+
+        // Everything was just checked, so nothing is due
+        assert_eq!(watcher.check_due_prices(&db, &collect).await.unwrap(), 0);
+        assert_eq!(store.requests.lock().unwrap().len(), 1);
+
+        store.set_price("620", Some(799));
+        db.with_connection(|conn| {
+            conn.execute("UPDATE wishlist SET last_checked_at = 0", [])?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(watcher.check_due_prices(&db, &collect).await.unwrap(), 4);
+
+        let alerts = alerts.into_inner().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].app_id, "620");
+        assert_eq!(alerts[0].final_formatted, "$7.99");
+
+        db.with_read_connection(|conn| {
+            let prices: Vec<i64> = WishlistOperations::price_history(conn, "620")?
+                .iter()
+                .map(|point| point.final_price)
+                .collect();
+            assert_eq!(prices, [1999, 799]);
+
+            let free = WishlistOperations::get(conn, "440")?.unwrap();
+            assert_eq!(free.final_price, None);
+            assert!(free.last_checked_at.is_some());
+
+            // An unreadable price is skipped without failing the rest of the batch
+            let unreadable = WishlistOperations::get(conn, "730")?.unwrap();
+            assert_eq!(unreadable.final_price, None);
+            assert!(unreadable.last_checked_at.is_some());
+            Ok(())
+        })
+        .unwrap();
+    }
+}
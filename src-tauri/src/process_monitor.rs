@@ -0,0 +1,367 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::Child;
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::playtime_commands::{record_play_session_end, record_play_session_start};
+use crate::database::playtime_models::{GameExitedEvent, GameStartedEvent, PlaySession};
+
+/// How often the process tree of a running game is re-scanned
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A game launched by Zenith that is still running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningGame {
+    pub session_id: i64,
+    pub app_id: Option<String>,
+    pub executable_path: String,
+    pub pid: u32,
+    pub started_at: i64,
+}
+
+lazy_static::lazy_static! {
+    static ref RUNNING_GAMES: Mutex<HashMap<i64, RunningGame>> = Mutex::new(HashMap::new());
+}
+
+/// Snapshot entry for a single live process
+#[derive(Debug, Clone, Copy)]
+struct ProcessEntry {
+    parent_pid: u32,
+    /// Process start time, used to detect PID reuse (0 where unavailable)
+    start_marker: u64,
+}
+
+/// Games currently being tracked
+pub fn running_games() -> Vec<RunningGame> {
+    let games = RUNNING_GAMES.lock().unwrap();
+    let mut list: Vec<RunningGame> = games.values().cloned().collect();
+    list.sort_by_key(|g| g.started_at);
+    list
+}
+
+/// Whether any tracked session belongs to this app
+pub fn is_app_running(app_id: &str) -> bool {
+    RUNNING_GAMES
+        .lock()
+        .unwrap()
+        .values()
+        .any(|g| g.app_id.as_deref() == Some(app_id))
+}
+
+/// Callback run (on a blocking thread) after a tracked game has fully exited
+pub type OnGameExit = Box<dyn FnOnce(Option<i32>) + Send>;
+
+/// Record a play session for a freshly spawned game and watch it until the
+/// launched process and every child it spawned have exited.
+/// Emits `game-started` immediately and `game-exited` once the tree is gone.
+pub async fn track_game_process(
+    app: AppHandle,
+    child: Child,
+    app_id: Option<String>,
+    executable_path: String,
+    on_exit: Option<OnGameExit>,
+) -> Result<i64, String> {
+    let pid = child.id();
+    let session = PlaySession::new(app_id.clone(), executable_path.clone(), Some(pid as i64));
+    let started_at = session.started_at;
+    let session_id = record_play_session_start(session).await?;
+
+    RUNNING_GAMES.lock().unwrap().insert(
+        session_id,
+        RunningGame {
+            session_id,
+            app_id: app_id.clone(),
+            executable_path: executable_path.clone(),
+            pid,
+            started_at,
+        },
+    );
+
+    println!("⏱️ Tracking play session {} (PID {})", session_id, pid);
+    let _ = app.emit(
+        "game-started",
+        GameStartedEvent {
+            session_id,
+            app_id: app_id.clone(),
+            executable_path: executable_path.clone(),
+            pid,
+            started_at,
+        },
+    );
+
+    tokio::spawn(async move {
+        let exit_code = wait_for_process_tree(child, POLL_INTERVAL).await;
+        let ended_at = chrono::Utc::now().timestamp();
+
+        if let Err(e) = record_play_session_end(session_id, ended_at, exit_code).await {
+            println!("⚠️ {}", e);
+        }
+        RUNNING_GAMES.lock().unwrap().remove(&session_id);
+
+        let duration_seconds = (ended_at - started_at).max(0);
+        println!(
+            "🏁 Play session {} ended after {}s (exit code: {:?})",
+            session_id, duration_seconds, exit_code
+        );
+        if let Some(on_exit) = on_exit {
+            let _ = tokio::task::spawn_blocking(move || on_exit(exit_code)).await;
+        }
+
+        let _ = app.emit(
+            "game-exited",
+            GameExitedEvent {
+                session_id,
+                app_id,
+                executable_path,
+                exit_code,
+                started_at,
+                ended_at,
+                duration_seconds,
+            },
+        );
+    });
+
+    Ok(session_id)
+}
+
+/// Wait until `child` and all of its descendants have exited.
+/// Returns the exit code of `child` itself, if one was reported.
+///
+/// Descendants are discovered by polling, so launchers that start the real
+/// game and exit straight away are covered as long as the game is still a
+/// child of the launcher on the first poll after it was spawned.
+pub async fn wait_for_process_tree(mut child: Child, poll_interval: Duration) -> Option<i32> {
+    let root_pid = child.id();
+    let mut tracked: HashMap<u32, u64> = HashMap::new();
+    let mut root_exited = false;
+    let mut exit_code = None;
+
+    loop {
+        if !root_exited {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    exit_code = status.code();
+                    root_exited = true;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    println!("⚠️ Failed to poll game process {}: {}", root_pid, e);
+                    root_exited = true;
+                }
+            }
+        }
+
+        let snapshot = tokio::task::spawn_blocking(snapshot_processes)
+            .await
+            .unwrap_or_default();
+
+        // Forget processes that exited (or whose PID was reused)
+        tracked.retain(|pid, marker| {
+            snapshot
+                .get(pid)
+                .is_some_and(|entry| entry.start_marker == *marker)
+        });
+
+        // Pick up any new descendants of the processes we are tracking
+        let mut parents: HashSet<u32> = tracked.keys().copied().collect();
+        if !root_exited {
+            parents.insert(root_pid);
+        }
+        loop {
+            let new_children: Vec<(u32, u64)> = snapshot
+                .iter()
+                .filter(|(pid, entry)| !parents.contains(pid) && parents.contains(&entry.parent_pid))
+                .map(|(pid, entry)| (*pid, entry.start_marker))
+                .collect();
+            if new_children.is_empty() {
+                break;
+            }
+            for (pid, marker) in new_children {
+                parents.insert(pid);
+                tracked.insert(pid, marker);
+            }
+        }
+
+        if root_exited && tracked.is_empty() {
+            break;
+        }
+
+        tokio::time::sleep(poll_interval).await;
+    }
+
+    exit_code
+}
+
+/// Snapshot of all live processes keyed by PID
+#[cfg(target_os = "linux")]
+fn snapshot_processes() -> HashMap<u32, ProcessEntry> {
+    let mut processes = HashMap::new();
+
+    let entries = match std::fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return processes,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let pid = match entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) {
+            Some(pid) => pid,
+            None => continue,
+        };
+        let stat = match std::fs::read_to_string(entry.path().join("stat")) {
+            Ok(stat) => stat,
+            Err(_) => continue,
+        };
+
+        // The command name may contain spaces, so parse after the closing paren
+        let fields: Vec<&str> = match stat.rfind(')') {
+            Some(pos) => stat[pos + 1..].split_whitespace().collect(),
+            None => continue,
+        };
+        // fields[0] = state, fields[1] = ppid, fields[19] = starttime
+        if fields.len() < 20 || fields[0] == "Z" || fields[0] == "X" {
+            continue;
+        }
+        if let Ok(parent_pid) = fields[1].parse::<u32>() {
+            processes.insert(
+                pid,
+                ProcessEntry {
+                    parent_pid,
+                    start_marker: fields[19].parse().unwrap_or(0),
+                },
+            );
+        }
+    }
+
+    processes
+}
+
+/// Snapshot of all live processes keyed by PID, from a Toolhelp32 snapshot
+#[cfg(target_os = "windows")]
+fn snapshot_processes() -> HashMap<u32, ProcessEntry> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+    };
+
+    let mut processes = HashMap::new();
+
+    // SAFETY: the snapshot handle is checked and closed, and `entry.dwSize` is set as the API requires
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return processes;
+        }
+
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        let mut more = Process32FirstW(snapshot, &mut entry) != 0;
+        while more {
+            processes.insert(
+                entry.th32ProcessID,
+                ProcessEntry {
+                    parent_pid: entry.th32ParentProcessID,
+                    start_marker: process_creation_time(entry.th32ProcessID),
+                },
+            );
+            more = Process32NextW(snapshot, &mut entry) != 0;
+        }
+
+        CloseHandle(snapshot);
+    }
+
+    processes
+}
+
+/// Creation time of a process as a FILETIME tick count, 0 if it cannot be queried
+#[cfg(target_os = "windows")]
+fn process_creation_time(pid: u32) -> u64 {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::Threading::{GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    // SAFETY: the process handle is checked before use and closed afterwards
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process == 0 {
+            return 0;
+        }
+
+        let empty = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
+        let (mut created, mut exited, mut kernel, mut user) = (empty, empty, empty, empty);
+        let ok = GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user) != 0;
+        CloseHandle(process);
+
+        if ok {
+            ((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64
+        } else {
+            0
+        }
+    }
+}
+
+/// Snapshot of all live processes keyed by PID
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn snapshot_processes() -> HashMap<u32, ProcessEntry> {
+    match Command::new("ps").args(["-A", "-o", "pid=", "-o", "ppid="]).output() {
+        Ok(output) => parse_process_list(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// Parse "pid ppid [start]" lines
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn parse_process_list(output: &str) -> HashMap<u32, ProcessEntry> {
+    let mut processes = HashMap::new();
+
+    for line in output.lines() {
+        let mut parts = line.split_whitespace();
+        let pid = parts.next().and_then(|s| s.parse::<u32>().ok());
+        let parent_pid = parts.next().and_then(|s| s.parse::<u32>().ok());
+        let start_marker = parts.next().and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+
+        if let (Some(pid), Some(parent_pid)) = (pid, parent_pid) {
+            processes.insert(pid, ProcessEntry { parent_pid, start_marker });
+        }
+    }
+
+    processes
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_waits_for_children_after_root_exits() {
+        // Root shell exits with code 3 while its background child keeps running
+        let child = Command::new("sh")
+            .args(["-c", "sleep 1.5 & sleep 0.3; exit 3"])
+            .spawn()
+            .expect("failed to spawn dummy game");
+
+        let start = Instant::now();
+        let exit_code = wait_for_process_tree(child, Duration::from_millis(100)).await;
+
+        assert_eq!(exit_code, Some(3));
+        assert!(start.elapsed() >= Duration::from_millis(1400));
+    }
+
+    #[tokio::test]
+    async fn test_returns_once_single_process_exits() {
+        let child = Command::new("sh")
+            .args(["-c", "exit 0"])
+            .spawn()
+            .expect("failed to spawn dummy game");
+
+        let start = Instant::now();
+        let exit_code = wait_for_process_tree(child, Duration::from_millis(50)).await;
+
+        assert_eq!(exit_code, Some(0));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
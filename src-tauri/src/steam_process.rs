@@ -0,0 +1,292 @@
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+use crate::steam_utils::find_steam_executable_path;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// How long Steam gets to exit after a graceful shutdown request
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// How long to wait for the process to disappear after a force kill
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Process name of the Steam client (`/proc/<pid>/comm` on Linux)
+#[cfg(target_os = "linux")]
+const STEAM_PROCESS_NAME: &str = "steam";
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+const STEAM_PROCESS_NAME: &str = "steam_osx";
+
+/// How a shutdown request ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownOutcome {
+    NotRunning,
+    Graceful,
+    Forced,
+}
+
+/// Check whether the Steam client is currently running
+pub fn is_running() -> Result<bool, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let output = Command::new("tasklist")
+            .args(["/FI", "IMAGENAME eq steam.exe", "/NH"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("Failed to check Steam status: {}", e))?;
+        let output_str = String::from_utf8_lossy(&output.stdout).to_lowercase();
+        Ok(output_str.contains("steam.exe"))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // The pid file is the cheap path; fall back to scanning procfs
+        if let Some(pid) = read_pid_file() {
+            if is_steam_pid(pid) {
+                return Ok(true);
+            }
+        }
+        Ok(!scan_proc_for_steam().is_empty())
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let output = Command::new("pgrep")
+            .args(["-x", STEAM_PROCESS_NAME])
+            .output()
+            .map_err(|e| format!("Failed to check Steam status: {}", e))?;
+        Ok(!output.stdout.is_empty())
+    }
+}
+
+/// Start the Steam client
+pub fn start() -> Result<(), String> {
+    steam_command()?
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start Steam: {}", e))
+}
+
+/// Ask Steam to exit, wait up to `timeout`, then force kill it if it is still running
+pub async fn shutdown(timeout: Duration) -> Result<ShutdownOutcome, String> {
+    if !is_running()? {
+        println!("Steam is not running");
+        return Ok(ShutdownOutcome::NotRunning);
+    }
+
+    match request_shutdown() {
+        Ok(()) => {
+            println!("Requested Steam shutdown, waiting up to {}s", timeout.as_secs());
+            if wait_for_exit(timeout).await? {
+                println!("Steam exited gracefully");
+                return Ok(ShutdownOutcome::Graceful);
+            }
+            println!("Steam did not exit within {}s, forcing it to close", timeout.as_secs());
+        }
+        Err(e) => println!("Graceful shutdown failed ({}), forcing Steam to close", e),
+    }
+
+    force_kill()?;
+    if wait_for_exit(KILL_TIMEOUT).await? {
+        println!("Steam process terminated");
+        Ok(ShutdownOutcome::Forced)
+    } else {
+        Err("Steam is still running after being force killed".to_string())
+    }
+}
+
+/// Shut Steam down (gracefully when possible) and start it again
+pub async fn restart(timeout: Duration) -> Result<ShutdownOutcome, String> {
+    let outcome = shutdown(timeout).await?;
+
+    // Give Steam a moment to release its IPC pipes before it is started again
+    if outcome != ShutdownOutcome::NotRunning {
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+    }
+
+    start()?;
+    println!("Steam restarted successfully");
+    Ok(outcome)
+}
+
+/// Poll until Steam has exited. Returns false if it is still running after `timeout`.
+pub async fn wait_for_exit(timeout: Duration) -> Result<bool, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !is_running()? {
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Send `-shutdown` to the running client (steam://exit on macOS)
+fn request_shutdown() -> Result<(), String> {
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    let command = {
+        let mut command = Command::new("open");
+        command.arg("steam://exit");
+        command
+    };
+
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    let command = {
+        let mut command = steam_command()?;
+        command.arg("-shutdown");
+        command
+    };
+
+    // The shutdown helper hands the request to the running client and exits;
+    // tokio reaps it in the background so no zombie is left behind
+    tokio::process::Command::from(command)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to request Steam shutdown: {}", e))
+}
+
+fn force_kill() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        Command::new("taskkill")
+            .args(["/F", "/IM", "steam.exe"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to terminate Steam: {}", e))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut pids = scan_proc_for_steam();
+        if let Some(pid) = read_pid_file().filter(|pid| is_steam_pid(*pid)) {
+            if !pids.contains(&pid) {
+                pids.push(pid);
+            }
+        }
+        if pids.is_empty() {
+            return Ok(());
+        }
+
+        Command::new("kill")
+            .arg("-9")
+            .args(pids.iter().map(|pid| pid.to_string()))
+            .output()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to terminate Steam: {}", e))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        Command::new("pkill")
+            .args(["-9", "-x", STEAM_PROCESS_NAME])
+            .output()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to terminate Steam: {}", e))
+    }
+}
+
+/// Command that runs the Steam client executable
+fn steam_command() -> Result<Command, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let steam_path = find_steam_executable_path()
+            .map_err(|e| format!("Steam executable not found: {}", e))?;
+        Ok(Command::new(steam_path))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if is_on_path("steam") {
+            return Ok(Command::new("steam"));
+        }
+
+        // Installs without a `steam` launcher on PATH (e.g. manual bootstrap)
+        let steam_sh = dirs::home_dir()
+            .map(|home| home.join(".steam").join("steam").join("steam.sh"))
+            .filter(|path| path.exists())
+            .ok_or_else(|| "Steam executable not found: no `steam` on PATH or ~/.steam/steam/steam.sh".to_string())?;
+        Ok(Command::new(steam_sh))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    {
+        let mut command = Command::new("open");
+        command.args(["-a", "Steam"]);
+        Ok(command)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+fn pid_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".steam").join("steam.pid"))
+}
+
+/// PID written by the Steam client to ~/.steam/steam.pid (may be stale)
+#[cfg(target_os = "linux")]
+fn read_pid_file() -> Option<u32> {
+    let content = std::fs::read_to_string(pid_file_path()?).ok()?;
+    parse_pid(&content)
+}
+
+/// Stale pid files are common after crashes, so the pid must still belong to Steam
+#[cfg(target_os = "linux")]
+fn is_steam_pid(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|comm| comm.trim() == STEAM_PROCESS_NAME)
+        .unwrap_or(false)
+}
+
+/// All PIDs whose process name is exactly `steam` (helpers like steamwebhelper are excluded)
+#[cfg(target_os = "linux")]
+fn scan_proc_for_steam() -> Vec<u32> {
+    let entries = match std::fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str().and_then(parse_pid))
+        .filter(|pid| is_steam_pid(*pid))
+        .collect()
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_pid(content: &str) -> Option<u32> {
+    content.trim().parse::<u32>().ok().filter(|pid| *pid > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pid() {
+        assert_eq!(parse_pid("12345\n"), Some(12345));
+        assert_eq!(parse_pid("  42  "), Some(42));
+        assert_eq!(parse_pid(""), None);
+        assert_eq!(parse_pid("0"), None);
+        assert_eq!(parse_pid("self"), None);
+    }
+}
@@ -0,0 +1,349 @@
+//! Steam store `appdetails` requests and parsing, shared by full fetches and per-category refreshes
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Instant;
+
+use crate::connectivity::CONNECTIVITY;
+use crate::database::cache_metrics::CACHE_METRICS;
+use crate::database::models::{GameDetailDb, PcRequirements as PcRequirementsDb};
+use crate::database::wishlist_models::PriceOverview;
+use crate::database::ttl_config::TtlCategory;
+use crate::{header_image_for, parse_sysreq_html, GameDetail, PcRequirements, HTTP_CLIENT};
+
+pub const APPDETAILS_URL: &str = "https://store.steampowered.com/api/appdetails";
+
+/// Top-level appdetails keys holding each TTL category's fields (header_image is built locally)
+pub fn category_filters(category: TtlCategory) -> &'static [&'static str] {
+    match category {
+        TtlCategory::Dynamic => &["dlc"],
+        TtlCategory::SemiStatic => &["name", "background", "background_raw", "movies", "drm_notice"],
+        TtlCategory::Static => &["screenshots", "detailed_description", "pc_requirements", "publishers", "release_date"],
+    }
+}
+
+#[derive(Debug)]
+pub enum AppDetailsError {
+    Request(String),
+    RateLimited,
+    Status(reqwest::StatusCode),
+    Parse(String),
+    NotFound(String),
+    Unsuccessful(String),
+    /// Not attempted because the app is offline
+    Offline,
+}
+
+impl fmt::Display for AppDetailsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppDetailsError::Request(e) => write!(f, "Request failed: {}", e),
+            AppDetailsError::RateLimited => write!(f, "Rate limited by Steam API (429). Please wait before trying again."),
+            AppDetailsError::Status(status) => write!(f, "status {}", status),
+            AppDetailsError::Parse(e) => write!(f, "Failed to parse Steam API response: {}", e),
+            AppDetailsError::NotFound(app_id) => write!(f, "App ID {} not found in Steam API response", app_id),
+            AppDetailsError::Unsuccessful(app_id) => write!(
+                f,
+                "Steam API returned success=false for app ID {} (game might not exist or be private)",
+                app_id
+            ),
+            AppDetailsError::Offline => write!(f, "Offline: Steam store details are unavailable until connectivity returns"),
+        }
+    }
+}
+
+impl std::error::Error for AppDetailsError {}
+
+/// The `data` object of an appdetails response and the size of the body it came in
+pub struct AppDetails {
+    pub data: Value,
+    pub response_bytes: usize,
+}
+
+/// Fetch appdetails for one app. An empty `filters` requests everything.
+pub async fn fetch_app_details(app_id: &str, filters: &[&str]) -> Result<AppDetails, AppDetailsError> {
+    let mut url = format!("{}?appids={}", APPDETAILS_URL, app_id);
+    if !filters.is_empty() {
+        url.push_str("&filters=");
+        url.push_str(&filters.join(","));
+    }
+
+    let endpoint = if filters.is_empty() { "appdetails" } else { "appdetails_filtered" };
+    let (mut v, response_bytes) = get_appdetails(&url, endpoint).await?;
+
+    let app_data = v
+        .get_mut(app_id)
+        .map(Value::take)
+        .ok_or_else(|| AppDetailsError::NotFound(app_id.to_string()))?;
+    if app_data.get("success").and_then(|s| s.as_bool()) == Some(false) {
+        return Err(AppDetailsError::Unsuccessful(app_id.to_string()));
+    }
+
+    // A filtered request whose keys are all absent comes back as `"data": []`
+    let data = match app_data.get("data") {
+        Some(Value::Object(_)) => app_data["data"].clone(),
+        Some(Value::Array(_)) if !filters.is_empty() => Value::Object(Default::default()),
+        _ => return Err(AppDetailsError::NotFound(app_id.to_string())),
+    };
+
+    Ok(AppDetails {
+        data,
+        response_bytes,
+    })
+}
+
+/// `price_overview` for several apps in one request; Steam only accepts more than one app id
+/// with this filter. Apps without a price (free or unreleased), or with one that cannot be
+/// parsed, map to `None`; apps Steam does not know are left out.
+pub async fn fetch_price_overviews(
+    appdetails_url: &str,
+    app_ids: &[String],
+) -> Result<HashMap<String, Option<PriceOverview>>, AppDetailsError> {
+    let url = format!("{}?appids={}&filters=price_overview", appdetails_url, app_ids.join(","));
+    let (body, _) = get_appdetails(&url, "appdetails_prices").await?;
+
+    let mut prices = HashMap::new();
+    for app_id in app_ids {
+        let app_data = match body.get(app_id) {
+            Some(app_data) if app_data.get("success").and_then(|s| s.as_bool()) == Some(true) => app_data,
+            _ => continue,
+        };
+        // One malformed entry must not fail the batch, or the same batch would fail forever
+        let price = match app_data.get("data").and_then(|data| data.get("price_overview")) {
+            Some(price) => serde_json::from_value(price.clone())
+                .inspect_err(|e| eprintln!("⚠️  Skipping unreadable price of app {}: {}", app_id, e))
+                .ok(),
+            None => None,
+        };
+        prices.insert(app_id.clone(), price);
+    }
+    Ok(prices)
+}
+
+/// GET an appdetails URL, tracking connectivity, latency and rate limiting. Returns the
+/// parsed body and its size.
+async fn get_appdetails(url: &str, endpoint: &str) -> Result<(Value, usize), AppDetailsError> {
+    if CONNECTIVITY.is_offline() {
+        return Err(AppDetailsError::Offline);
+    }
+
+    let started = Instant::now();
+    let resp = HTTP_CLIENT
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| {
+            CONNECTIVITY.record_failure(&e);
+            AppDetailsError::Request(e.to_string())
+        })?;
+    CONNECTIVITY.record_success();
+    CACHE_METRICS.observe_latency(endpoint, started.elapsed());
+    if resp.status().as_u16() == 429 {
+        CACHE_METRICS.record_rate_limited();
+        return Err(AppDetailsError::RateLimited);
+    }
+    if !resp.status().is_success() {
+        return Err(AppDetailsError::Status(resp.status()));
+    }
+
+    let body = resp.bytes().await.map_err(|e| AppDetailsError::Request(e.to_string()))?;
+    let v: Value = serde_json::from_slice(&body).map_err(|e| AppDetailsError::Parse(e.to_string()))?;
+    Ok((v, body.len()))
+}
+
+/// Build a GameDetail from a full appdetails `data` object. DLC is loaded lazily and left empty.
+pub fn parse_game_detail(app_id: &str, data: &Value) -> GameDetail {
+    // Always use our consistent header image format instead of Steam API's variable quality images
+    let header_image = header_image_for(app_id);
+    let banner_image = parse_banner(data).unwrap_or_else(|| header_image.clone());
+    let (pc_requirements, sysreq_min, sysreq_rec) = parse_requirements(data).unwrap_or_default();
+
+    GameDetail {
+        app_id: app_id.to_string(),
+        name: str_field(data, "name").unwrap_or_default(),
+        header_image,
+        banner_image,
+        detailed_description: str_field(data, "detailed_description").unwrap_or_default(),
+        release_date: parse_release_date(data).unwrap_or_default(),
+        publisher: parse_publisher(data).unwrap_or_default(),
+        trailer: parse_trailer(data),
+        screenshots: parse_screenshots(data),
+        sysreq_min,
+        sysreq_rec,
+        pc_requirements,
+        dlc: Vec::new(),
+        drm_notice: str_field(data, "drm_notice"),
+    }
+}
+
+/// Copy the fields of `categories` from a (possibly filtered) appdetails response into a cached row
+/// and bump only those categories' expiry. Fields Steam always sends are kept when missing.
+pub fn merge_categories(detail: &mut GameDetailDb, data: &Value, categories: &[TtlCategory]) {
+    for category in categories {
+        match category {
+            TtlCategory::Dynamic => {
+                detail.dlc = parse_dlc(data);
+            }
+            TtlCategory::SemiStatic => {
+                if let Some(name) = str_field(data, "name").filter(|name| !name.is_empty()) {
+                    detail.name = name;
+                }
+                if let Some(banner) = parse_banner(data) {
+                    detail.banner_image = banner;
+                }
+                detail.trailer = parse_trailer(data);
+                detail.drm_notice = str_field(data, "drm_notice");
+            }
+            TtlCategory::Static => {
+                detail.screenshots = parse_screenshots(data);
+                if let Some(description) = str_field(data, "detailed_description") {
+                    detail.detailed_description = description;
+                }
+                if let Some(release_date) = parse_release_date(data) {
+                    detail.release_date = release_date;
+                }
+                if let Some(publisher) = parse_publisher(data) {
+                    detail.publisher = publisher;
+                }
+                if let Some((pc_requirements, sysreq_min, sysreq_rec)) = parse_requirements(data) {
+                    detail.pc_requirements = pc_requirements.map(|req| PcRequirementsDb {
+                        minimum: req.minimum,
+                        recommended: req.recommended,
+                    });
+                    detail.sysreq_min = sysreq_min;
+                    detail.sysreq_rec = sysreq_rec;
+                }
+            }
+        }
+        detail.refresh_category_expiry(category.key());
+    }
+    detail.last_updated = chrono::Utc::now().timestamp();
+}
+
+/// DLC app ids; Steam sends either an array or an object of ids
+pub fn parse_dlc(data: &Value) -> Vec<String> {
+    match data.get("dlc") {
+        Some(Value::Array(arr)) => arr.iter().filter_map(|v| v.as_u64()).map(|id| id.to_string()).collect(),
+        Some(Value::Object(obj)) => obj.values().filter_map(|v| v.as_u64()).map(|id| id.to_string()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn str_field(data: &Value, key: &str) -> Option<String> {
+    data.get(key).and_then(|x| x.as_str()).map(|s| s.to_string())
+}
+
+/// Background image (higher resolution than the header)
+fn parse_banner(data: &Value) -> Option<String> {
+    str_field(data, "background").or_else(|| str_field(data, "background_raw"))
+}
+
+fn parse_release_date(data: &Value) -> Option<String> {
+    data.get("release_date")
+        .and_then(|x| x.get("date"))
+        .and_then(|x| x.as_str())
+        .map(|s| s.to_string())
+}
+
+fn parse_publisher(data: &Value) -> Option<String> {
+    data.get("publishers")
+        .and_then(|x| x.get(0))
+        .and_then(|x| x.as_str())
+        .map(|s| s.to_string())
+}
+
+fn parse_trailer(data: &Value) -> Option<String> {
+    data.get("movies")
+        .and_then(|arr| arr.get(0))
+        .and_then(|item| item.get("mp4"))
+        .and_then(|mp4| mp4.get("max"))
+        .and_then(|x| x.as_str())
+        .map(|s| s.to_string())
+}
+
+fn parse_screenshots(data: &Value) -> Vec<String> {
+    data.get("screenshots")
+        .and_then(|x| x.as_array())
+        .map(|arr| {
+            arr.iter()
+                .take(6)
+                // Use full resolution image instead of thumbnail
+                .filter_map(|s| s.get("path_full").and_then(|x| x.as_str()))
+                .map(|url| url.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+type Requirements = (Option<PcRequirements>, Vec<(String, String)>, Vec<(String, String)>);
+
+/// PC requirements and the parsed minimum/recommended lists, if the response has them
+fn parse_requirements(data: &Value) -> Option<Requirements> {
+    let pc_req = data.get("pc_requirements")?;
+    let minimum = str_field(pc_req, "minimum");
+    let recommended = str_field(pc_req, "recommended");
+
+    let sysreq_min = minimum.as_deref().map(parse_sysreq_html).unwrap_or_default();
+    let sysreq_rec = recommended.as_deref().map(parse_sysreq_html).unwrap_or_default();
+    let pc_requirements = (minimum.is_some() || recommended.is_some()).then_some(PcRequirements { minimum, recommended });
+
+    Some((pc_requirements, sysreq_min, sysreq_rec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cached_detail() -> GameDetailDb {
+        let mut detail: GameDetailDb = parse_game_detail(
+            "570",
+            &json!({
+                "name": "Dota 2",
+                "background": "https://example.com/bg.jpg",
+                "detailed_description": "Old description",
+                "publishers": ["Valve"],
+                "screenshots": [{ "path_full": "https://example.com/1.jpg" }],
+            }),
+        )
+        .into();
+        detail.dynamic_expires_at = 0;
+        detail.semistatic_expires_at = 0;
+        detail.static_expires_at = 0;
+        detail
+    }
+
+    #[test]
+    fn test_merge_only_touches_requested_categories() {
+        let mut detail = cached_detail();
+        let fresh = json!({ "dlc": [1001, 1002], "detailed_description": "New description" });
+
+        merge_categories(&mut detail, &fresh, &[TtlCategory::Dynamic]);
+
+        assert_eq!(detail.dlc, vec!["1001", "1002"]);
+        assert_eq!(detail.detailed_description, "Old description");
+        assert!(detail.dynamic_expires_at > 0);
+        assert_eq!(detail.static_expires_at, 0);
+    }
+
+    #[test]
+    fn test_merge_keeps_fields_missing_from_filtered_response() {
+        let mut detail = cached_detail();
+        let fresh = json!({ "screenshots": [{ "path_full": "https://example.com/2.jpg" }] });
+
+        merge_categories(&mut detail, &fresh, &[TtlCategory::SemiStatic, TtlCategory::Static]);
+
+        assert_eq!(detail.name, "Dota 2");
+        assert_eq!(detail.banner_image, "https://example.com/bg.jpg");
+        assert_eq!(detail.publisher, "Valve");
+        assert_eq!(detail.screenshots, vec!["https://example.com/2.jpg"]);
+        assert!(detail.semistatic_expires_at > 0 && detail.static_expires_at > 0);
+    }
+
+    #[test]
+    fn test_dlc_object_form() {
+        assert_eq!(parse_dlc(&json!({ "dlc": { "0": 10, "1": 20 } })), vec!["10", "20"]);
+        assert!(parse_dlc(&json!({})).is_empty());
+    }
+}
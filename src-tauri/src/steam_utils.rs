@@ -1,9 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use regex::Regex;
 use walkdir::WalkDir;
 
+/// How long a scan for installed apps is reused before the Steam libraries are read again
+const INSTALLED_APPS_TTL: Duration = Duration::from_secs(30);
+
+/// A finished `installed_app_ids` scan: Steam path, when it ran, and what it found
+type InstalledAppsScan = (String, Instant, Arc<HashSet<String>>);
+
+lazy_static::lazy_static! {
+    static ref INSTALLED_APPS: Mutex<Option<InstalledAppsScan>> = Mutex::new(None);
+}
+
 #[cfg(target_os = "windows")]
 use winreg::{enums::*, RegKey};
 
@@ -32,7 +44,8 @@ pub fn find_steam_installation_path() -> Result<String, String> {
     Err("Steam installation not found".to_string())
 }
 
-pub async fn find_game_folder_from_acf(app_id: &str, steam_path: &str) -> Option<String> {
+/// `steamapps` folders of every Steam library: the main install plus those in `libraryfolders.vdf`
+fn steam_library_paths(steam_path: &str) -> Vec<String> {
     let steamapps_path = format!("{}/steamapps", steam_path);
     let library_folders_path = format!("{}/libraryfolders.vdf", steamapps_path);
 
@@ -44,11 +57,14 @@ pub async fn find_game_folder_from_acf(app_id: &str, steam_path: &str) -> Option
             library_paths.push(format!("{}\\steamapps", &cap[1].replace("\\\\", "\\")));
         }
     }
+    library_paths
+}
 
-    for path in library_paths {
+pub async fn find_game_folder_from_acf(app_id: &str, steam_path: &str) -> Option<String> {
+    let re = Regex::new(r#""installdir"\s+"([^"]+)""#).unwrap();
+    for path in steam_library_paths(steam_path) {
         let acf_file = format!("{}/appmanifest_{}.acf", path, app_id);
         if let Ok(content) = fs::read_to_string(&acf_file) {
-            let re = Regex::new(r#""installdir"\s+"([^"]+)""#).unwrap();
             if let Some(cap) = re.captures(&content) {
                 return Some(cap[1].to_string());
             }
@@ -223,3 +239,112 @@ pub fn find_lua_file_for_appid(
 
     Ok(None)
 }
+
+/// App ids with an `appmanifest_<id>.acf` in any Steam library, i.e. installed through Steam
+pub fn installed_app_ids(steam_path: &str) -> HashSet<String> {
+    let mut installed = HashSet::new();
+    for path in steam_library_paths(steam_path) {
+        let entries = match fs::read_dir(&path) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if let Some(app_id) = file_name.strip_prefix("appmanifest_").and_then(|s| s.strip_suffix(".acf")) {
+                installed.insert(app_id.to_string());
+            }
+        }
+    }
+    installed
+}
+
+/// `installed_app_ids`, reusing the last scan of the same Steam path for `INSTALLED_APPS_TTL`
+pub fn cached_installed_app_ids(steam_path: &str) -> Arc<HashSet<String>> {
+    if let Some((path, scanned_at, installed)) = &*INSTALLED_APPS.lock().unwrap_or_else(|e| e.into_inner()) {
+        if path == steam_path && scanned_at.elapsed() < INSTALLED_APPS_TTL {
+            return installed.clone();
+        }
+    }
+
+    let installed = Arc::new(installed_app_ids(steam_path));
+    *INSTALLED_APPS.lock().unwrap_or_else(|e| e.into_inner()) =
+        Some((steam_path.to_string(), Instant::now(), installed.clone()));
+    installed
+}
+
+/// Resolve the Steam app id owning an executable under `steamapps/common/<installdir>`
+// Only launched games are tracked, and launching is Windows-only
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn find_app_id_for_executable(executable_path: &str) -> Option<String> {
+    let normalized = executable_path.replace('\\', "/");
+    // ASCII-only lowercasing keeps byte offsets valid for slicing `normalized`
+    let lower = normalized.to_ascii_lowercase();
+    let marker = "/steamapps/common/";
+    let marker_pos = lower.find(marker)?;
+
+    let steamapps_dir = &normalized[..marker_pos + "/steamapps".len()];
+    let install_dir = normalized[marker_pos + marker.len()..].split('/').next()?;
+
+    let re = Regex::new(r#""installdir"\s+"([^"]+)""#).ok()?;
+    for entry in fs::read_dir(steamapps_dir).ok()?.filter_map(Result::ok) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let app_id = match file_name
+            .strip_prefix("appmanifest_")
+            .and_then(|s| s.strip_suffix(".acf"))
+        {
+            Some(app_id) => app_id.to_string(),
+            None => continue,
+        };
+
+        if let Ok(content) = fs::read_to_string(entry.path()) {
+            if let Some(cap) = re.captures(&content) {
+                if cap[1].eq_ignore_ascii_case(install_dir) {
+                    return Some(app_id);
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_id_for_executable_under_non_ascii_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let steamapps = dir.path().join("İstanbul Oyunlar").join("SteamApps");
+        fs::create_dir_all(steamapps.join("common").join("Half-Life 2")).unwrap();
+        fs::write(
+            steamapps.join("appmanifest_220.acf"),
+            "\"AppState\"\n{\n\t\"appid\"\t\t\"220\"\n\t\"installdir\"\t\t\"Half-Life 2\"\n}\n",
+        )
+        .unwrap();
+
+        let exe = steamapps.join("common").join("Half-Life 2").join("hl2.exe");
+        assert_eq!(find_app_id_for_executable(&exe.to_string_lossy()), Some("220".to_string()));
+        assert_eq!(find_app_id_for_executable("/games/İ/hl2.exe"), None);
+    }
+
+    #[test]
+    fn test_installed_app_ids_from_app_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let steamapps = dir.path().join("steamapps");
+        fs::create_dir_all(steamapps.join("common")).unwrap();
+        for file_name in ["appmanifest_220.acf", "appmanifest_620.acf", "appmanifest_570.acf.tmp", "libraryfolders.vdf"] {
+            fs::write(steamapps.join(file_name), "").unwrap();
+        }
+
+        let steam_path = dir.path().to_string_lossy().to_string();
+        let expected: HashSet<String> = ["220", "620"].iter().map(|id| id.to_string()).collect();
+        assert_eq!(installed_app_ids(&steam_path), expected);
+
+        // A new install shows up once the cached scan expires
+        assert_eq!(*cached_installed_app_ids(&steam_path), expected);
+        fs::write(steamapps.join("appmanifest_570.acf"), "").unwrap();
+        assert_eq!(*cached_installed_app_ids(&steam_path), expected);
+        assert_eq!(installed_app_ids(&steam_path).len(), 3);
+        assert!(installed_app_ids(&dir.path().join("missing").to_string_lossy()).is_empty());
+    }
+}